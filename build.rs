@@ -2,4 +2,5 @@ extern crate build;
 fn main() {
     build::link("shell32", true);
     build::link("d2d1", true);
+    build::link("dwrite", true);
 }
\ No newline at end of file
@@ -2,11 +2,12 @@
     Simple example on how to use a nwg canvas.
 */
 
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use nwg::{Event, EventArgs, Ui, fatal_message, dispatch_events};
-use nwg::constants as nwgc;
+#[cfg(windows)] use nwg::{Event, EventArgs, Ui, fatal_message, dispatch_events};
+#[cfg(windows)] use nwg::constants as nwgc;
 
+#[cfg(windows)]
 #[derive(Debug, Clone, Hash)]
 pub enum CanvasId {
     // Controls
@@ -22,8 +23,9 @@ pub enum CanvasId {
     DashedPen(u8)
 }
 
-use CanvasId::*;
+#[cfg(windows)] use CanvasId::*;
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_ui<CanvasId>,
     controls: [
@@ -71,6 +73,7 @@ nwg_template!(
     values: []
 );
 
+#[cfg(windows)]
 fn setup_canvas_resources(app: &Ui<CanvasId>) {
     let mut canvas = nwg_get_mut!(app; (Canvas, nwg::Canvas<CanvasId>));
 
@@ -93,6 +96,7 @@ fn setup_canvas_resources(app: &Ui<CanvasId>) {
     canvas.create_pen(&DashedPen(0), &p1).expect("Failed to create pen");
 }
 
+#[cfg(windows)]
 fn main() {
     let app: Ui<CanvasId>;
 
@@ -109,3 +113,8 @@ fn main() {
 
     dispatch_events();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
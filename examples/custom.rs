@@ -10,27 +10,30 @@
     For simplicity, the custom control source is in the same source file.
     It is a better idea to have a single extern module for every custom control.
 */
-extern crate user32;
-extern crate winapi;
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg(windows)] extern crate user32;
+#[cfg(windows)] extern crate winapi;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use std::any::TypeId;
-use std::hash::Hash;
+#[cfg(windows)] use std::any::TypeId;
+#[cfg(windows)] use std::hash::Hash;
 
-use nwg::custom::{Control, ControlT, AnyHandle, SysclassParams, build_sysclass, WindowParams, build_window};
-use nwg::{Error, Event, Ui, simple_message, fatal_message, dispatch_events};
+#[cfg(windows)] use nwg::custom::{Control, ControlT, AnyHandle, SysclassParams, build_sysclass, WindowParams, build_window};
+#[cfg(windows)] use nwg::{Error, Event, Ui, simple_message, fatal_message, dispatch_events};
 
-use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT};
+#[cfg(windows)] use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT};
 
 // The control template. Aka the configuration object that is sent to an UI.
+#[cfg(windows)]
 pub struct MyCustomWindowT;
 
 // The actual control. The object that is saved and managed in NWG.
 // A reference to this object is returned when nwg_get is used
+#[cfg(windows)]
 pub struct MyCustomWindow {
     handle: HWND
 }
 
+#[cfg(windows)]
 impl<ID: Hash+Clone> ControlT<ID> for MyCustomWindowT {
     fn resource_type_id(&self) -> TypeId { 
         // This method must return the TypeID of the associated control
@@ -89,6 +92,7 @@ impl<ID: Hash+Clone> ControlT<ID> for MyCustomWindowT {
 
 }
 
+#[cfg(windows)]
 impl Control for MyCustomWindow {
     fn handle(&self) -> AnyHandle {
         // Must return the handle of the control
@@ -107,6 +111,7 @@ impl Control for MyCustomWindow {
 }
 
 // The custom window proc
+#[cfg(windows)]
 #[allow(unused_variables)]
 unsafe extern "system" fn custom_window_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
     use winapi::{WM_CREATE, WM_CLOSE};
@@ -129,6 +134,7 @@ unsafe extern "system" fn custom_window_sysproc(hwnd: HWND, msg: UINT, w: WPARAM
     }
 }
 
+#[cfg(windows)]
 fn main() {
     let app: Ui<&'static str>;
 
@@ -151,3 +157,8 @@ fn main() {
 
     dispatch_events();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
@@ -5,12 +5,13 @@
 */
 
 
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use nwg::{Event, Ui, simple_message, fatal_message, dispatch_events};
-use nwg::constants::HTextAlign;
+#[cfg(windows)] use nwg::{Event, Ui, simple_message, fatal_message, dispatch_events};
+#[cfg(windows)] use nwg::constants::HTextAlign;
 
 // Identifiers for static controls/resources/values
+#[cfg(windows)]
 #[derive(Debug, Clone, Hash)]
 pub enum UiId {
     // Static controls
@@ -38,8 +39,9 @@ pub enum UiId {
     ClearCallback
 }
 
-use UiId::*;
+#[cfg(windows)] use UiId::*;
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_ui<UiId>,
     controls: [
@@ -118,6 +120,7 @@ nwg_template!(
     ]
 );
 
+#[cfg(windows)]
 fn main() {
     let app: Ui<UiId>;
 
@@ -132,3 +135,8 @@ fn main() {
 
     dispatch_events();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
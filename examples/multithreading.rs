@@ -1,26 +1,32 @@
 /**
     A simple example demonstrating multithreading. Two simple ui and allow the user to pause the thread.
+    The test window also runs a job on a worker thread that updates a progress bar through a `UiSender`.
 */
 
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use nwg::{Event, Ui, fatal_message, dispatch_events};
-use std::thread;
-use std::time::Duration;
+#[cfg(windows)] use nwg::{Event, Ui, fatal_message, dispatch_events};
+#[cfg(windows)] use std::thread;
+#[cfg(windows)] use std::time::Duration;
 
+#[cfg(windows)]
 #[derive(Debug, Clone, Hash)]
 pub enum MultiThreadingId {
     // Controls
     SleepWindow,
     TestWindow,
     SleepButton,
+    WorkButton,
+    WorkProgress,
 
     // Events
-    Sleep
+    Sleep,
+    Work
 }
 
-use MultiThreadingId::*;
+#[cfg(windows)] use MultiThreadingId::*;
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_sleep_window<MultiThreadingId>,
     controls: [
@@ -39,16 +45,38 @@ nwg_template!(
     values: []
 );
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_test_window<MultiThreadingId>,
     controls: [
-        (TestWindow, nwg_window!( title="Test"; size=(200, 200); position=(420, 200) ))
+        (TestWindow, nwg_window!( title="Test"; size=(200, 200); position=(420, 200) )),
+        (WorkButton, nwg_button!( parent=TestWindow; text="WORK"; size=(180, 50); position=(10, 10) )),
+        (WorkProgress, nwg_progressbar!( parent=TestWindow; size=(180, 30); position=(10, 70) ))
+    ];
+    events: [
+        (WorkButton, Work, Event::Click, |ui,_,_,_| {
+            nwg_get!(ui; (WorkProgress, nwg::ProgressBar)).set_value(0);
+
+            // The worker thread cannot touch the controls, it sends the updates to the ui thread instead
+            let sender = ui.sender();
+            thread::spawn(move || {
+                for _ in 0..10 {
+                    thread::sleep(Duration::from_millis(300));
+                    let sent = sender.execute(|ui| {
+                        nwg_get!(ui; (WorkProgress, nwg::ProgressBar)).advance(10);
+                    });
+
+                    // The ui was closed
+                    if sent.is_err() { break; }
+                }
+            });
+        })
     ];
-    events: [];
     resources: [];
     values: []
 );
 
+#[cfg(windows)]
 fn main() {
     // Create the main window on the current thread
     let app: Ui<MultiThreadingId>;
@@ -80,3 +108,8 @@ fn main() {
     dispatch_events();
     t.join().unwrap();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
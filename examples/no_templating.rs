@@ -5,8 +5,9 @@
 
 extern crate native_windows_gui as nwg;
 
-use nwg::{Ui, Error, Event, simple_message, fatal_message, dispatch_events};
+#[cfg(windows)] use nwg::{Ui, Error, Event, simple_message, fatal_message, dispatch_events};
 
+#[cfg(windows)]
 #[derive(Debug, Clone, Hash)]
 pub enum AppId {
     // Controls
@@ -23,8 +24,9 @@ pub enum AppId {
     TextFont
 }
 
-use AppId::*; // Shortcut
+#[cfg(windows)] use AppId::*; // Shortcut
 
+#[cfg(windows)]
 pub fn setup_ui(ui: &Ui<AppId>) -> Result<(), Error> {
 
     // nwg_font!(family="Arial"; size=27)
@@ -46,7 +48,8 @@ pub fn setup_ui(ui: &Ui<AppId>) -> Result<(), Error> {
         title: "No template",
         position: (100, 100), size: (280, 105),
         resizable: false, visible: true, disabled: false,
-        exit_on_close: true
+        exit_on_close: true, accept_files: false,
+        icon: None
     };
 
     // nwg_label!( parent="MainWindow"; [...] font=Some("TextFont") )
@@ -97,6 +100,7 @@ pub fn setup_ui(ui: &Ui<AppId>) -> Result<(), Error> {
     ui.commit()
 }
 
+#[cfg(windows)]
 fn main() {
     let app: Ui<AppId>;
 
@@ -111,3 +115,8 @@ fn main() {
 
     dispatch_events();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
@@ -2,11 +2,12 @@
     Example that show every control implemented in NWG
 */
 
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use nwg::{Ui, Event, EventArgs, dispatch_events, exit as nwg_exit};
-use nwg::constants::{FONT_WEIGHT_BLACK, FONT_DECO_ITALIC, CheckState, FileDialogAction, HTextAlign, PickerDate};
+#[cfg(windows)] use nwg::{Ui, Event, EventArgs, dispatch_events, exit as nwg_exit};
+#[cfg(windows)] use nwg::constants::{FONT_WEIGHT_BLACK, FONT_DECO_ITALIC, CheckState, FileDialogAction, HTextAlign, PickerDate};
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_ui<&'static str>,
     controls: [
@@ -108,6 +109,7 @@ nwg_template!(
     values: []
 );
 
+#[cfg(windows)]
 pub fn main() {
     let app: Ui<&'static str> = Ui::new().expect("Failed to initialize the Ui");
     
@@ -118,4 +120,9 @@ pub fn main() {
 
     // Dispatch the events until the user quits
     dispatch_events();
-}
\ No newline at end of file
+}
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
@@ -2,11 +2,12 @@
     Simple example on how to use the nwg template system.
 */
 
-#[macro_use] extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
 
-use nwg::{Event, Ui, simple_message, fatal_message, dispatch_events};
+#[cfg(windows)] use nwg::{Event, Ui, simple_message, fatal_message, dispatch_events};
 
 /// Custom enums are the preferred way to define ui ids. It's clearer and more extensible than any other types (such as &'str).
+#[cfg(windows)]
 #[derive(Debug, Clone, Hash)]
 pub enum AppId {
     // Controls
@@ -23,8 +24,9 @@ pub enum AppId {
     TextFont
 }
 
-use AppId::*; // Shortcut
+#[cfg(windows)] use AppId::*; // Shortcut
 
+#[cfg(windows)]
 nwg_template!(
     head: setup_ui<AppId>,
     controls: [
@@ -59,6 +61,7 @@ nwg_template!(
     values: []
 );
 
+#[cfg(windows)]
 fn main() {
     let app: Ui<AppId>;
 
@@ -73,3 +76,8 @@ fn main() {
 
     dispatch_events();
 }
+
+#[cfg(not(windows))]
+fn main() {
+    println!("This example only runs on Windows");
+}
@@ -0,0 +1,353 @@
+/*!
+    Observable user values and the bindings that keep them in sync with the controls properties
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::hash::{Hash, Hasher};
+use std::any::{Any, TypeId};
+use std::rc::{Rc, Weak};
+use std::fmt::Display;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ui::{Ui, UiRef};
+use controls::{Control, ControlT, ControlType, AnyHandle, TextInput, TextBox, CheckBox, ListBox, ProgressBar, NumericInput};
+use defs::CheckState;
+use error::Error;
+use events::{Event, EventArgs};
+
+/// Source of the binding handles. Ids start at 1.
+static BINDINGS_ID: AtomicUsize = AtomicUsize::new(0);
+
+/**
+    A user value that updates the controls bound to it when it changes.
+    Pack it with `Ui::pack_observable` and access it with `ui.get::<Observable<T>>` or `ui.get_mut::<Observable<T>>`.
+*/
+pub struct Observable<T: 'static> {
+    value: T,
+    observers: Vec<Weak<Fn(&T)>>
+}
+
+impl<T: 'static> Observable<T> {
+
+    /// Create a new observable value
+    pub fn new(value: T) -> Observable<T> {
+        Observable{ value: value, observers: Vec::new() }
+    }
+
+    /// Return a reference to the value
+    pub fn get(&self) -> &T { &self.value }
+
+    /// Set the value and update the bound controls
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.notify();
+    }
+
+    /// Modify the value in place and update the bound controls
+    pub fn update<F: FnOnce(&mut T)>(&mut self, f: F) {
+        f(&mut self.value);
+        self.notify();
+    }
+
+    /// Register an observer. The observer is removed once its last strong reference is dropped.
+    fn observe(&mut self, observer: &Rc<Fn(&T)>) {
+        self.observers.push(Rc::downgrade(observer));
+    }
+
+    fn notify(&mut self) {
+        self.observers.retain(|o| o.upgrade().is_some());
+        for o in self.observers.iter() {
+            if let Some(o) = o.upgrade() { o(&self.value); }
+        }
+    }
+
+}
+
+/**
+    A control property that can be bound to an observable value.
+*/
+pub trait Property: Clone {
+    /// The type of the control that owns the property
+    type Control: 'static;
+
+    /// The type of the property value
+    type Value: PartialEq+'static;
+
+    /// Read the property from the control
+    fn get(&self, control: &Self::Control) -> Self::Value;
+
+    /// Write the property in the control
+    fn set(&self, control: &Self::Control, value: &Self::Value);
+
+    /// The control events raised when the user changes the property. Empty if the user cannot change it.
+    fn events(&self) -> Vec<Event>;
+}
+
+/// The text of a `TextInput`
+#[derive(Clone, Copy, Debug)]
+pub struct TextInputText;
+
+impl Property for TextInputText {
+    type Control = TextInput;
+    type Value = String;
+
+    fn get(&self, control: &TextInput) -> String { control.get_text() }
+    fn set(&self, control: &TextInput, value: &String) { control.set_text(value); }
+    fn events(&self) -> Vec<Event> { vec![Event::ValueChanged] }
+}
+
+/// The text of a `TextBox`
+#[derive(Clone, Copy, Debug)]
+pub struct TextBoxText;
+
+impl Property for TextBoxText {
+    type Control = TextBox;
+    type Value = String;
+
+    fn get(&self, control: &TextBox) -> String { control.get_text() }
+    fn set(&self, control: &TextBox, value: &String) { control.set_text(value); }
+    fn events(&self) -> Vec<Event> { vec![Event::ValueChanged] }
+}
+
+/// The checkstate of a `CheckBox`. `true` if the checkbox is checked
+#[derive(Clone, Copy, Debug)]
+pub struct CheckBoxChecked;
+
+impl Property for CheckBoxChecked {
+    type Control = CheckBox;
+    type Value = bool;
+
+    fn get(&self, control: &CheckBox) -> bool { control.get_checkstate() == CheckState::Checked }
+
+    fn set(&self, control: &CheckBox, value: &bool) {
+        control.set_checkstate(if *value { CheckState::Checked } else { CheckState::Unchecked });
+    }
+
+    fn events(&self) -> Vec<Event> { vec![Event::Click] }
+}
+
+/// The selected index of a single choice `ListBox`. `None` if nothing is selected
+#[derive(Clone, Copy, Debug)]
+pub struct ListBoxSelection<D: Clone+Display+'static> {
+    p: PhantomData<D>
+}
+
+impl<D: Clone+Display+'static> ListBoxSelection<D> {
+    /// Create the property. `D` must be the item type of the listbox
+    pub fn new() -> ListBoxSelection<D> { ListBoxSelection{ p: PhantomData } }
+}
+
+impl<D: Clone+Display+'static> Property for ListBoxSelection<D> {
+    type Control = ListBox<D>;
+    type Value = Option<usize>;
+
+    fn get(&self, control: &ListBox<D>) -> Option<usize> { control.get_selected_index() }
+    fn set(&self, control: &ListBox<D>, value: &Option<usize>) { control.set_selected_index(value.unwrap_or(usize::max_value())); }
+    fn events(&self) -> Vec<Event> { vec![Event::SelectionChanged] }
+}
+
+/// The value of a `ProgressBar`. The user cannot change it, so the binding only goes from the value to the control
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressBarValue;
+
+impl Property for ProgressBarValue {
+    type Control = ProgressBar;
+    type Value = u32;
+
+    fn get(&self, control: &ProgressBar) -> u32 { control.get_value() }
+    fn set(&self, control: &ProgressBar, value: &u32) { control.set_value(*value); }
+    fn events(&self) -> Vec<Event> { Vec::new() }
+}
+
+/// The value of a `NumericInput`
+#[derive(Clone, Copy, Debug)]
+pub struct NumericInputValue;
+
+impl Property for NumericInputValue {
+    type Control = NumericInput;
+    type Value = i64;
+
+    fn get(&self, control: &NumericInput) -> i64 { control.get_value() }
+    fn set(&self, control: &NumericInput, value: &i64) { control.set_value(*value); }
+    fn events(&self) -> Vec<Event> { vec![Event::ValueChanged] }
+}
+
+/**
+    A template that binds an `Observable<T>` to a property of a control.
+    When the observable is changed, the control is updated. When the user changes the control, the observable is updated.
+    When the binding is created, the control is updated from the value. Both the value and the control must be packed before the binding.
+
+    `to_control` and `from_control` convert the value to and from the property value. `from_control` can return `None` to
+    reject a property value that cannot be converted (ex: a number typed in a TextInput). The `validator` is called on the
+    value in both directions: a value that fails the validation is not copied. Use `BindingT::new` if the value and the property
+    have the same type.
+
+    Unpacking the binding stops the synchronization. The value and the control are left as they are.
+
+    Events:  
+    Event::Destroyed  
+
+    Members:  
+    • `value`: The id of the `Observable<T>`  
+    • `control`: The id of the control. Its type must be the control type of the property  
+    • `property`: The property of the control. Ex: `TextInputText`, `CheckBoxChecked`, `ListBoxSelection::<String>::new()`  
+    • `to_control`: Convert the value into the property value  
+    • `from_control`: Convert the property value into the value. Return `None` to ignore the property value  
+    • `validator`: An optional function that accepts or rejects a value  
+*/
+#[derive(Clone)]
+pub struct BindingT<T: 'static, P: Property, ID: Hash+Clone> {
+    pub value: ID,
+    pub control: ID,
+    pub property: P,
+    pub to_control: fn(&T) -> P::Value,
+    pub from_control: fn(&P::Value) -> Option<T>,
+    pub validator: Option<fn(&T) -> bool>
+}
+
+impl<T: Clone+'static, P: Property<Value=T>, ID: Hash+Clone> BindingT<T, P, ID> {
+
+    /// Create a binding template without conversion
+    pub fn new(value: ID, control: ID, property: P) -> BindingT<T, P, ID> {
+        BindingT{ value: value, control: control, property: property, to_control: copy_value::<T>, from_control: accept_value::<T>, validator: None }
+    }
+
+}
+
+impl<T: 'static, P: Property+'static, ID: Hash+Clone> ControlT<ID> for BindingT<T, P, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Binding>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        if let Err(e) = ui.get::<Observable<T>>(&self.value) { return Err(e); }
+        if let Err(e) = ui.get::<P::Control>(&self.control) { return Err(e); }
+
+        let id = BINDINGS_ID.fetch_add(1, Ordering::SeqCst) + 1;
+        let cb_id = callback_id(id);
+        let ui_ref = UiRef::new(ui);
+
+        // The control callbacks cannot be removed while they are running, so they are disabled when `alive` is dropped
+        let alive = Rc::new(());
+
+        // Value -> Control
+        let observer: Rc<Fn(&T)> = {
+            let (ui_ref, control, property, to_control, validator) = (ui_ref.clone(), self.control.clone(), self.property.clone(), self.to_control, self.validator);
+            Rc::new(move |value: &T| {
+                if !validator.map(|v| v(value)).unwrap_or(true) { return; }
+
+                let new_value = to_control(value);
+                unsafe{ ui_ref.with(|ui| {
+                    if let Ok(c) = ui.get::<P::Control>(&control) {
+                        if property.get(&c) != new_value { property.set(&c, &new_value); }
+                    }
+                }); }
+            })
+        };
+
+        match ui.get_mut::<Observable<T>>(&self.value) {
+            Ok(mut v) => {
+                v.observe(&observer);
+                observer(v.get());
+            },
+            Err(e) => { return Err(e); }
+        }
+
+        // Control -> Value
+        let events = self.property.events();
+        for event in events.iter() {
+            let (value, property, from_control, validator, token) = (self.value.clone(), self.property.clone(), self.from_control, self.validator, Rc::downgrade(&alive));
+            let cb = move |ui: &Ui<ID>, id: &ID, _: &Event, _: &EventArgs| {
+                if token.upgrade().is_none() { return; }
+
+                let new_value = match ui.get::<P::Control>(id) {
+                    Ok(c) => from_control(&property.get(&c)),
+                    Err(_) => None
+                };
+
+                if let Some(new_value) = new_value {
+                    if !validator.map(|v| v(&new_value)).unwrap_or(true) { return; }
+
+                    // The value is already borrowed if the control was changed by one of its observers
+                    if let Ok(mut v) = ui.get_mut::<Observable<T>>(&value) { v.set(new_value); }
+                }
+            };
+
+            if let Some(e) = unsafe{ ui_ref.bind(&self.control, cb_id, event.clone(), cb) } {
+                return Err(e);
+            }
+        }
+
+        let control = self.control.clone();
+        let release = move || {
+            for event in events.iter() {
+                unsafe{ ui_ref.unbind(&control, cb_id, event.clone()); }
+            }
+        };
+
+        Ok( Box::new(Binding{ id: id, observer: Some(Box::new(observer)), alive: Some(alive), release: Box::new(release) }) as Box<Control> )
+    }
+}
+
+/**
+    A binding between an observable value and a control property
+*/
+pub struct Binding {
+    id: usize,
+    observer: Option<Box<Any>>,
+    alive: Option<Rc<()>>,
+    release: Box<Fn()>
+}
+
+impl Control for Binding {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<Binding>(), self.id)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Binding
+    }
+
+    fn free(&mut self) {
+        // The callbacks are removed right away. If the control was unpacked first, the errors are ignored.
+        (self.release)();
+        self.observer.take();
+        self.alive.take();
+    }
+
+}
+
+/*
+    Private binding functions
+*/
+
+fn copy_value<T: Clone>(value: &T) -> T { value.clone() }
+
+fn accept_value<T: Clone>(value: &T) -> Option<T> { Some(value.clone()) }
+
+/// Return the id of the control callbacks of a binding. Bindings cannot use a user id because they do not know their own.
+fn callback_id(id: usize) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    let mut s = DefaultHasher::new();
+    "nwg::Binding".hash(&mut s);
+    id.hash(&mut s);
+    s.finish()
+}
@@ -0,0 +1,451 @@
+/*!
+    Access to the system clipboard: unicode text, images and custom formats.
+
+    The functions that write to the clipboard need a window-like control that becomes the owner of the clipboard data.
+    Writing always replaces the whole content of the clipboard.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::hash::Hash;
+use std::ptr;
+use std::slice;
+
+use winapi::{UINT, HWND, HANDLE, SIZE_T};
+
+use ui::Ui;
+use error::{Error, SystemError};
+use low::other_helper::{read_u16, read_u32, push_u16, push_u32};
+
+/// The number of times the clipboard is opened before giving up. Another application might be using it.
+const OPEN_ATTEMPTS: u32 = 5;
+
+/**
+    A clipboard format. `Custom` formats are created with `register_format`.
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ClipboardFormat {
+    /// Unicode text
+    Text,
+
+    /// A device independent bitmap
+    Image,
+
+    /// A format registered by name
+    Custom(u32)
+}
+
+impl ClipboardFormat {
+
+    fn code(&self) -> UINT {
+        use winapi::{CF_UNICODETEXT, CF_DIB};
+        match self {
+            &ClipboardFormat::Text => CF_UNICODETEXT,
+            &ClipboardFormat::Image => CF_DIB,
+            &ClipboardFormat::Custom(c) => c as UINT
+        }
+    }
+
+}
+
+/**
+    An image read from or written to the clipboard. The pixels use the same layout as `Canvas::create_bitmap_from_rgba`.
+
+    Members:  
+    • `width`, `height`: The size of the image in pixels  
+    • `pixels`: The pixels stored row by row, from the top left corner, with 4 bytes per pixel in the order red, green, blue, alpha.  
+       The alpha is not premultiplied.
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct ClipboardImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>
+}
+
+/**
+    Register a custom clipboard format. Every application that registers the same name gets the same format.
+
+    Errors:  
+    • `Error::System` if the system could not register the format  
+*/
+pub fn register_format(name: &str) -> Result<ClipboardFormat, Error> {
+    use user32::RegisterClipboardFormatW;
+    use low::other_helper::to_utf16;
+
+    let name = to_utf16(name);
+    match unsafe{ RegisterClipboardFormatW(name.as_ptr()) } {
+        0 => Err(Error::System(SystemError::ClipboardAccess)),
+        code => Ok(ClipboardFormat::Custom(code as u32))
+    }
+}
+
+/// Return `true` if the clipboard holds data in the selected format
+pub fn has_format(format: ClipboardFormat) -> bool {
+    use user32::IsClipboardFormatAvailable;
+    unsafe{ IsClipboardFormatAvailable(format.code()) != 0 }
+}
+
+/**
+    Read the text in the clipboard. Return `Ok(None)` if the clipboard does not hold text.
+
+    Errors:  
+    • `Error::System` if the clipboard could not be opened  
+*/
+pub fn get_text() -> Result<Option<String>, Error> {
+    use low::other_helper::from_utf16;
+
+    read_format(ClipboardFormat::Text, |bytes| {
+        let text: &[u16] = unsafe{ slice::from_raw_parts(bytes.as_ptr() as *const u16, bytes.len() / 2) };
+        Some(from_utf16(text))
+    })
+}
+
+/**
+    Replace the content of the clipboard with `text`.
+
+    Arguments:  
+    • `ui`: The Ui that owns the control `owner`  
+    • `owner`: A window-like control that owns the clipboard data  
+    • `text`: The text to copy  
+
+    Errors:  
+    • `Error::BadParent` if `owner` is not a window-like control  
+    • `Error::System` if the clipboard could not be opened or written  
+*/
+pub fn set_text<ID: Hash+Clone>(ui: &Ui<ID>, owner: &ID, text: &str) -> Result<(), Error> {
+    use low::other_helper::to_utf16;
+
+    let text = to_utf16(text);
+    let bytes: &[u8] = unsafe{ slice::from_raw_parts(text.as_ptr() as *const u8, text.len() * 2) };
+    write_format(ui, owner, ClipboardFormat::Text, bytes)
+}
+
+/**
+    Read the image in the clipboard. Return `Ok(None)` if the clipboard does not hold an image.
+    Images that are not stored with 24 or 32 bits per pixel are not supported.
+
+    Errors:  
+    • `Error::System` if the clipboard could not be opened  
+    • `Error::BadResource` if the image format is not supported  
+*/
+pub fn get_image() -> Result<Option<ClipboardImage>, Error> {
+    let mut supported = true;
+    let image = read_format(ClipboardFormat::Image, |bytes| {
+        let image = dib_to_rgba(bytes);
+        supported = image.is_some();
+        image
+    });
+
+    match image {
+        Ok(None) if !supported => Err(Error::BadResource("The clipboard image format is not supported.".to_string())),
+        r => r
+    }
+}
+
+/**
+    Replace the content of the clipboard with an image.
+
+    Errors:  
+    • `Error::BadParent` if `owner` is not a window-like control  
+    • `Error::BadResource` if the image is empty or if the size of `image.pixels` is not `width * height * 4`  
+    • `Error::System` if the clipboard could not be opened or written  
+*/
+pub fn set_image<ID: Hash+Clone>(ui: &Ui<ID>, owner: &ID, image: &ClipboardImage) -> Result<(), Error> {
+    let (w, h) = (image.width as usize, image.height as usize);
+    if w == 0 || h == 0 || image.pixels.len() != w * h * 4 {
+        let msg = format!("Image of {}x{} pixels requires {} bytes, got {}.", w, h, w * h * 4, image.pixels.len());
+        return Err(Error::BadResource(msg));
+    }
+
+    write_format(ui, owner, ClipboardFormat::Image, &rgba_to_dib(image))
+}
+
+/**
+    Read the raw data stored in the clipboard in the selected format. Return `Ok(None)` if the clipboard does not
+    hold the format. The system can add padding at the end of the data.
+
+    Errors:  
+    • `Error::System` if the clipboard could not be opened  
+*/
+pub fn get_data(format: ClipboardFormat) -> Result<Option<Vec<u8>>, Error> {
+    read_format(format, |bytes| Some(bytes.to_vec()))
+}
+
+/**
+    Replace the content of the clipboard with raw data in the selected format.
+
+    Errors:  
+    • `Error::BadParent` if `owner` is not a window-like control  
+    • `Error::System` if the clipboard could not be opened or written  
+*/
+pub fn set_data<ID: Hash+Clone>(ui: &Ui<ID>, owner: &ID, format: ClipboardFormat, data: &[u8]) -> Result<(), Error> {
+    write_format(ui, owner, format, data)
+}
+
+/**
+    Remove the content of the clipboard.
+
+    Errors:  
+    • `Error::BadParent` if `owner` is not a window-like control  
+    • `Error::System` if the clipboard could not be opened  
+*/
+pub fn clear<ID: Hash+Clone>(ui: &Ui<ID>, owner: &ID) -> Result<(), Error> {
+    use user32::EmptyClipboard;
+
+    let owner = try!(clipboard_owner(ui, owner));
+    let _lock = try!(ClipboardLock::open(owner));
+    match unsafe{ EmptyClipboard() } {
+        0 => Err(Error::System(SystemError::ClipboardAccess)),
+        _ => Ok(())
+    }
+}
+
+/**
+    Start or stop raising `Event::ClipboardChanged` on a window-like control when the content of the clipboard changes.
+    The notifications stop when the control is destroyed.
+
+    Errors:  
+    • `Error::BadParent` if `window` is not a window-like control  
+    • `Error::System` if the system could not register the window  
+*/
+pub fn listen<ID: Hash+Clone>(ui: &Ui<ID>, window: &ID, listen: bool) -> Result<(), Error> {
+    use user32::AddClipboardFormatListener;
+    use low::defs::RemoveClipboardFormatListener;
+
+    let handle = try!(clipboard_owner(ui, window));
+    let ok = unsafe {
+        if listen { AddClipboardFormatListener(handle) } else { RemoveClipboardFormatListener(handle) }
+    };
+
+    match ok {
+        0 => Err(Error::System(SystemError::ClipboardAccess)),
+        _ => Ok(())
+    }
+}
+
+
+/*
+    Private clipboard functions
+*/
+
+/**
+    Keep the clipboard open. The clipboard is closed when the lock is dropped.
+*/
+struct ClipboardLock;
+
+impl ClipboardLock {
+
+    fn open(owner: HWND) -> Result<ClipboardLock, Error> {
+        use user32::OpenClipboard;
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        for _ in 0..OPEN_ATTEMPTS {
+            if unsafe{ OpenClipboard(owner) } != 0 {
+                return Ok(ClipboardLock);
+            }
+            sleep(Duration::from_millis(10));
+        }
+
+        Err(Error::System(SystemError::ClipboardAccess))
+    }
+
+}
+
+impl Drop for ClipboardLock {
+    fn drop(&mut self) {
+        use user32::CloseClipboard;
+        unsafe{ CloseClipboard(); }
+    }
+}
+
+fn clipboard_owner<ID: Hash+Clone>(ui: &Ui<ID>, id: &ID) -> Result<HWND, Error> {
+    use low::window_helper::handle_of_window;
+    handle_of_window(ui, id, "The clipboard owner must be a window-like control.")
+}
+
+/**
+    Lock the clipboard data in the selected format and pass it to `f`. Return `Ok(None)` if the format is not available.
+*/
+fn read_format<T, F: FnOnce(&[u8]) -> Option<T>>(format: ClipboardFormat, f: F) -> Result<Option<T>, Error> {
+    use user32::GetClipboardData;
+    use kernel32::{GlobalLock, GlobalUnlock, GlobalSize};
+
+    if !has_format(format) { return Ok(None); }
+
+    let _lock = try!(ClipboardLock::open(ptr::null_mut()));
+    unsafe {
+        let handle = GetClipboardData(format.code());
+        if handle.is_null() { return Ok(None); }
+
+        let data = GlobalLock(handle);
+        if data.is_null() { return Err(Error::System(SystemError::ClipboardAccess)); }
+
+        let bytes = slice::from_raw_parts(data as *const u8, GlobalSize(handle) as usize);
+        let value = f(bytes);
+        GlobalUnlock(handle);
+
+        Ok(value)
+    }
+}
+
+/**
+    Empty the clipboard and store a copy of `data` in the selected format. The system owns the memory once it is set.
+*/
+fn write_format<ID: Hash+Clone>(ui: &Ui<ID>, owner: &ID, format: ClipboardFormat, data: &[u8]) -> Result<(), Error> {
+    use user32::{EmptyClipboard, SetClipboardData};
+    use kernel32::{GlobalAlloc, GlobalLock, GlobalUnlock, GlobalFree};
+    use low::defs::GMEM_MOVEABLE;
+
+    let owner = try!(clipboard_owner(ui, owner));
+    let _lock = try!(ClipboardLock::open(owner));
+
+    unsafe {
+        if EmptyClipboard() == 0 { return Err(Error::System(SystemError::ClipboardAccess)); }
+
+        let handle: HANDLE = GlobalAlloc(GMEM_MOVEABLE, data.len() as SIZE_T);
+        if handle.is_null() { return Err(Error::System(SystemError::ClipboardAccess)); }
+
+        let dest = GlobalLock(handle) as *mut u8;
+        if dest.is_null() {
+            GlobalFree(handle);
+            return Err(Error::System(SystemError::ClipboardAccess));
+        }
+        ptr::copy_nonoverlapping(data.as_ptr(), dest, data.len());
+        GlobalUnlock(handle);
+
+        if SetClipboardData(format.code(), handle).is_null() {
+            GlobalFree(handle);
+            return Err(Error::System(SystemError::ClipboardAccess));
+        }
+    }
+
+    Ok(())
+}
+
+/// The size of a BITMAPINFOHEADER
+const DIB_HEADER_SIZE: usize = 40;
+
+/**
+    Decode a packed device independent bitmap (a BITMAPINFOHEADER or a larger header followed by the pixels).
+    Return `None` if the bitmap is not stored with 24 or 32 bits per pixel or if the data is truncated.
+*/
+fn dib_to_rgba(dib: &[u8]) -> Option<ClipboardImage> {
+    use winapi::{BI_RGB, BI_BITFIELDS};
+
+    if dib.len() < DIB_HEADER_SIZE { return None; }
+
+    let header_size = read_u32(dib, 0) as usize;
+    let width = read_u32(dib, 4) as i32;
+    let height = read_u32(dib, 8) as i32;
+    let bit_count = read_u16(dib, 14);
+    let compression = read_u32(dib, 16);
+    let colors_used = read_u32(dib, 32) as usize;
+
+    if header_size < DIB_HEADER_SIZE || dib.len() < header_size || width <= 0 || height == 0 { return None; }
+
+    // The channel masks are stored after a BITMAPINFOHEADER, and in the larger headers.
+    // The header values come from another application: the offsets are checked for overflows.
+    let mut offset = match colors_used.checked_mul(4).and_then(|c| c.checked_add(header_size)) {
+        Some(o) => o,
+        None => { return None; }
+    };
+    let masks = match (compression, bit_count) {
+        (BI_RGB, 24) | (BI_RGB, 32) => [0x00FF0000, 0x0000FF00, 0x000000FF, if bit_count == 32 { 0xFF000000 } else { 0 }],
+        (BI_BITFIELDS, 32) => {
+            if header_size == DIB_HEADER_SIZE {
+                offset = match offset.checked_add(12) { Some(o) => o, None => { return None; } };
+            }
+            if dib.len() < DIB_HEADER_SIZE + 12 { return None; }
+            let alpha = if header_size >= DIB_HEADER_SIZE + 16 { read_u32(dib, 52) } else { 0 };
+            [read_u32(dib, 40), read_u32(dib, 44), read_u32(dib, 48), alpha]
+        },
+        _ => { return None; }
+    };
+
+    // Only masks of 8 bits are supported
+    let mut shifts = [0u32; 4];
+    for (mask, shift) in masks.iter().zip(shifts.iter_mut()) {
+        if *mask == 0 { continue; }
+        *shift = mask.trailing_zeros();
+        if mask >> *shift != 0xFF { return None; }
+    }
+
+    let (w, h) = (width as usize, (height as i64).abs() as usize);
+    let bytes_per_pixel = (bit_count / 8) as usize;
+    let stride = match w.checked_mul(bytes_per_pixel).and_then(|s| s.checked_add(3)) {
+        Some(s) => s & !3,
+        None => { return None; }
+    };
+    match stride.checked_mul(h).and_then(|len| len.checked_add(offset)) {
+        Some(end) if end <= dib.len() => {},
+        _ => { return None; }
+    }
+
+    let mut pixels = Vec::with_capacity(w * h * 4);
+    let mut has_alpha = false;
+    for y in 0..h {
+        // A positive height means that the rows are stored from the bottom
+        let row = if height > 0 { h - 1 - y } else { y };
+        let start = offset + row * stride;
+        for x in 0..w {
+            let at = start + x * bytes_per_pixel;
+            let px = if bytes_per_pixel == 4 { read_u32(dib, at) } else { read_u16(dib, at) | ((dib[at+2] as u32) << 16) };
+            for i in 0..4 {
+                pixels.push(((px & masks[i]) >> shifts[i]) as u8);
+            }
+            has_alpha |= pixels[pixels.len() - 1] != 0;
+        }
+    }
+
+    // Most applications leave the alpha of their images at 0. Such images are opaque.
+    if !has_alpha {
+        for px in pixels.chunks_mut(4) { px[3] = 0xFF; }
+    }
+
+    Some(ClipboardImage{ width: w as u32, height: h as u32, pixels: pixels })
+}
+
+/**
+    Encode an image in a packed device independent bitmap of 32 bits per pixel, with the rows stored from the bottom.
+*/
+fn rgba_to_dib(image: &ClipboardImage) -> Vec<u8> {
+    use winapi::BI_RGB;
+
+    let (w, h) = (image.width as usize, image.height as usize);
+    let mut dib = Vec::with_capacity(DIB_HEADER_SIZE + w * h * 4);
+
+    push_u32(&mut dib, DIB_HEADER_SIZE as u32);
+    push_u32(&mut dib, image.width);
+    push_u32(&mut dib, image.height);
+    push_u16(&mut dib, 1);  // Planes
+    push_u16(&mut dib, 32); // Bits per pixel
+    push_u32(&mut dib, BI_RGB);
+    push_u32(&mut dib, (w * h * 4) as u32);
+    push_u32(&mut dib, 0);  // Horizontal resolution
+    push_u32(&mut dib, 0);  // Vertical resolution
+    push_u32(&mut dib, 0);  // Colors used
+    push_u32(&mut dib, 0);  // Important colors
+
+    for row in image.pixels.chunks(w * 4).rev() {
+        for px in row.chunks(4) {
+            dib.extend_from_slice(&[px[2], px[1], px[0], px[3]]);
+        }
+    }
+
+    dib
+}
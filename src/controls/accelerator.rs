@@ -0,0 +1,285 @@
+/*!
+    A keyboard shortcut bound to a window
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::hash::Hash;
+use std::any::TypeId;
+use std::fmt;
+
+use winapi::{HWND, HMENU, UINT, WORD, BYTE};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use defs::keys;
+use error::Error;
+use events::Event;
+
+/// Names accepted for the keys of a shortcut. When a key has many names, the first one is used to display it.
+const KEY_NAMES: &'static [(&'static str, u32)] = &[
+    ("Backspace", keys::BACK), ("Back", keys::BACK),
+    ("Tab", keys::TAB),
+    ("Enter", keys::RETURN), ("Return", keys::RETURN),
+    ("Pause", keys::PAUSE),
+    ("Esc", keys::ESCAPE), ("Escape", keys::ESCAPE),
+    ("Space", keys::SPACE),
+    ("PageUp", keys::PRIOR), ("PgUp", keys::PRIOR),
+    ("PageDown", keys::NEXT), ("PgDn", keys::NEXT),
+    ("End", keys::END),
+    ("Home", keys::HOME),
+    ("Left", keys::LEFT),
+    ("Up", keys::UP),
+    ("Right", keys::RIGHT),
+    ("Down", keys::DOWN),
+    ("PrintScreen", keys::SNAPSHOT),
+    ("Ins", keys::INSERT), ("Insert", keys::INSERT),
+    ("Del", keys::DELETE), ("Delete", keys::DELETE),
+    ("Apps", keys::APPS),
+    ("Num0", keys::NUMPAD0), ("Num1", keys::NUMPAD1), ("Num2", keys::NUMPAD2), ("Num3", keys::NUMPAD3), ("Num4", keys::NUMPAD4),
+    ("Num5", keys::NUMPAD5), ("Num6", keys::NUMPAD6), ("Num7", keys::NUMPAD7), ("Num8", keys::NUMPAD8), ("Num9", keys::NUMPAD9),
+    ("Multiply", keys::MULTIPLY),
+    ("Add", keys::ADD), ("Plus", keys::ADD),
+    ("Subtract", keys::SUBTRACT), ("Minus", keys::SUBTRACT),
+    ("Decimal", keys::DECIMAL),
+    ("Divide", keys::DIVIDE),
+];
+
+/**
+    Return the virtual key code of a key name. Letters and digits are named after their character and
+    functions keys go from `F1` to `F24`. The other names are listed in `KEY_NAMES`. Names are case insensitive.
+*/
+fn key_code(name: &str) -> Option<u32> {
+    let upper = name.to_uppercase();
+    let mut chars = upper.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if (c >= 'A' && c <= 'Z') || (c >= '0' && c <= '9') => { return Some(c as u32); }
+        (Some('F'), Some(_)) => match upper[1..].parse::<u32>() {
+            Ok(n) if n >= 1 && n <= 24 => { return Some(keys::F1 + n - 1); },
+            _ => {}
+        },
+        _ => {}
+    }
+
+    KEY_NAMES.iter().find(|&&(n, _)| n.to_uppercase() == upper).map(|&(_, code)| code)
+}
+
+/**
+    Return the display name of a virtual key code. This is the reverse of `key_code`.
+*/
+fn key_name(code: u32) -> String {
+    if (code >= keys::_A && code <= keys::_Z) || (code >= keys::_0 && code <= keys::_9) {
+        return ((code as u8) as char).to_string();
+    } else if code >= keys::F1 && code <= keys::F24 {
+        return format!("F{}", code - keys::F1 + 1);
+    }
+
+    match KEY_NAMES.iter().find(|&&(_, c)| c == code) {
+        Some(&(n, _)) => n.to_string(),
+        None => format!("0x{:02X}", code)
+    }
+}
+
+/**
+    A key combination parsed from a string like "Ctrl+Shift+S".
+
+    The text is a list of modifiers (`Ctrl` or `Control`, `Shift`, `Alt`) followed by a single key, separated by `+`.
+    The key is a letter, a digit, a function key (`F1` to `F24`) or a named key (ex: `Enter`, `Esc`, `Del`, `PageUp`, `Left`, `Num0`, `Add`).
+    Names are case insensitive.
+
+    Formatting a shortcut returns its canonical text. Ex: "shift+ctrl+s" is displayed as "Ctrl+Shift+S".
+*/
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: u32
+}
+
+impl Shortcut {
+
+    /**
+        Parse a shortcut. Return a `Error::BadResource` if a modifier is unknown or repeated, or if the key is missing or unknown.
+    */
+    pub fn parse(text: &str) -> Result<Shortcut, Error> {
+        let mut shortcut = Shortcut{ ctrl: false, shift: false, alt: false, key: 0 };
+        let parts: Vec<&str> = text.split('+').map(|p| p.trim()).collect();
+        let (key, modifiers) = parts.split_last().unwrap();
+
+        for &m in modifiers {
+            let flag = match &m.to_lowercase()[..] {
+                "ctrl" | "control" => &mut shortcut.ctrl,
+                "shift" => &mut shortcut.shift,
+                "alt" => &mut shortcut.alt,
+                _ => { return Err(Error::BadResource(format!("Invalid shortcut {:?}: unknown modifier {:?}", text, m))); }
+            };
+
+            if *flag { return Err(Error::BadResource(format!("Invalid shortcut {:?}: modifier {:?} is repeated", text, m))); }
+            *flag = true;
+        }
+
+        if key.len() == 0 { return Err(Error::BadResource(format!("Invalid shortcut {:?}: missing key", text))); }
+
+        shortcut.key = match key_code(key) {
+            Some(code) => code,
+            None => { return Err(Error::BadResource(format!("Invalid shortcut {:?}: unknown key {:?}", text, key))); }
+        };
+
+        Ok(shortcut)
+    }
+
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl { try!(write!(f, "Ctrl+")); }
+        if self.shift { try!(write!(f, "Shift+")); }
+        if self.alt { try!(write!(f, "Alt+")); }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+/**
+    A template that creates a keyboard accelerator. When the shortcut is pressed while the parent window
+    (or one of its children) has the keyboard focus, the accelerator raises `Event::Triggered`.
+
+    If `item` is set, the shortcut text is displayed beside the menu item and using the shortcut also raises
+    `Event::Triggered` on the menu item, as if it was clicked. Nothing is raised on the menu item if it is disabled.
+
+    Events:  
+    Event::Destroyed, Event::Triggered  
+
+    Members:  
+    • `shortcut`: The key combination. See `Shortcut` for the accepted format  
+    • `parent`: The window that receives the shortcut. Must be a window-like control  
+    • `item`: An optional menu item triggered by the shortcut  
+*/
+#[derive(Clone)]
+pub struct AcceleratorT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub shortcut: S,
+    pub parent: ID,
+    pub item: Option<ID>
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for AcceleratorT<S, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Accelerator>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::Triggered]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+
+        let text: String = self.shortcut.clone().into();
+        let shortcut = match Shortcut::parse(&text) {
+            Ok(s) => s,
+            Err(e) => { return Err(e); }
+        };
+
+        let window = match handle_of_window(ui, &self.parent, "The parent of an accelerator must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        let item = match self.item.as_ref().map(|id| ui.handle_of(id)) {
+            Some(Ok(AnyHandle::HMENU_ITEM(menu, uid))) => Some((menu, uid)),
+            Some(Ok(h)) => { return Err(Error::BadResource(format!("The item of an accelerator must be a MenuItem. Got {:?}", h))); },
+            Some(Err(e)) => { return Err(e); },
+            None => None
+        };
+
+        Ok( Box::new(unsafe{ build_accelerator(window, shortcut, item) }) as Box<Control> )
+    }
+}
+
+/**
+    A keyboard accelerator
+*/
+pub struct Accelerator {
+    window: HWND,
+    command: WORD,
+    shortcut: Shortcut,
+    item: Option<(HMENU, UINT)>
+}
+
+impl Accelerator {
+
+    /// Return the key combination of the accelerator
+    pub fn get_shortcut(&self) -> Shortcut { self.shortcut }
+
+    /// Return the handle of the menu item triggered by the accelerator, if any
+    pub fn get_item(&self) -> Option<AnyHandle> {
+        self.item.map(|(menu, uid)| AnyHandle::HMENU_ITEM(menu, uid))
+    }
+
+}
+
+impl Control for Accelerator {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<Accelerator>(), self.command as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Accelerator
+    }
+
+    fn free(&mut self) {
+        use low::accel_helper::remove_accelerator;
+        use low::menu_helper::{get_menuitem_text, set_menuitem_text};
+
+        unsafe{
+            remove_accelerator(self.window, self.command);
+
+            // Remove the shortcut text from the menu item
+            if let Some((menu, uid)) = self.item {
+                let text = get_menuitem_text(menu, uid);
+                set_menuitem_text(menu, uid, text.split('\t').next().unwrap());
+            }
+        }
+    }
+
+}
+
+/*
+    Private unsafe accelerator methods
+*/
+
+#[inline(always)]
+unsafe fn build_accelerator(window: HWND, shortcut: Shortcut, item: Option<(HMENU, UINT)>) -> Accelerator {
+    use low::accel_helper::add_accelerator;
+    use low::menu_helper::{get_menuitem_text, set_menuitem_text};
+    use low::defs::{FVIRTKEY, FCONTROL, FSHIFT, FALT};
+
+    let mut virt: BYTE = FVIRTKEY;
+    if shortcut.ctrl { virt |= FCONTROL; }
+    if shortcut.shift { virt |= FSHIFT; }
+    if shortcut.alt { virt |= FALT; }
+
+    let command = add_accelerator(window, virt, shortcut.key as WORD);
+
+    // Display the shortcut beside the menu item text. The system right-aligns the text after a tab.
+    if let Some((menu, uid)) = item {
+        let text = get_menuitem_text(menu, uid);
+        let text = format!("{}\t{}", text.split('\t').next().unwrap(), shortcut);
+        set_menuitem_text(menu, uid, &text);
+    }
+
+    Accelerator{ window: window, command: command, shortcut: shortcut, item: item }
+}
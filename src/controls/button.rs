@@ -26,8 +26,8 @@ use winapi::{HWND, HFONT};
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
-use std::ffi::OsStr;
+use events::{Event, EventArgs, EventDecoders};
+#[cfg(windows)] use std::ffi::OsStr;
 
 /**
     A template that creates a standard button
@@ -62,9 +62,13 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for ButtonT<S, ID> {
         vec![Event::Destroyed, Event::Click, Event::DoubleClick, Event::Focus, Event::Moved, Event::Resized, Event::Raw]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_button_command), notify: None }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
-        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_TEXT, BS_BITMAP, LR_DEFAULTCOLOR, LR_DEFAULTSIZE, LR_LOADFROMFILE, IMAGE_BITMAP};
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_BITMAP};
 
         let flags: DWORD = WS_CHILD | BS_NOTIFY | BS_BITMAP |
         if self.visible    { WS_VISIBLE }   else { 0 } |
@@ -98,10 +102,12 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for ButtonT<S, ID> {
 
         match unsafe{ build_window(params) } {
             Ok(h) => {
+                #[cfg(windows)]
                 unsafe {
                     use std::os::windows::ffi::OsStrExt;
                     use user32::{LoadImageW, SendMessageW};
                     use kernel32::{GetLastError,GetModuleHandleW};
+                    use winapi::{LR_DEFAULTCOLOR, LR_DEFAULTSIZE, IMAGE_BITMAP};
                     let handle_img = LoadImageW(
                         GetModuleHandleW(std::ptr::null()),
                         OsStr::new(&self.text.clone().into()).encode_wide().chain(Some(0)).collect::<Vec<_>>().as_ptr(),
@@ -150,8 +156,22 @@ impl Control for Button {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
-}
\ No newline at end of file
+}
+
+/**
+    Decode the `WM_COMMAND` notifications of the button-like controls (buttons, checkboxes and radio buttons)
+*/
+#[allow(unused_variables)]
+pub unsafe fn decode_button_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::defs::{BN_CLICKED, BN_DBLCLK, BN_SETFOCUS, BN_KILLFOCUS};
+    match ncode {
+        BN_CLICKED => Some((Event::Click, EventArgs::None)),
+        BN_DBLCLK => Some((Event::DoubleClick, EventArgs::None)),
+        BN_SETFOCUS | BN_KILLFOCUS => Some((Event::Focus, EventArgs::Focus(ncode==BN_SETFOCUS))),
+        _ => None
+    }
+}
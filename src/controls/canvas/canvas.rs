@@ -24,13 +24,17 @@ use std::ptr;
 use std::ops::{Deref, DerefMut};
 use std::marker::PhantomData;
 use std::collections::HashMap;
+use std::mem;
 
 use winapi::{HWND, ID2D1Factory, ID2D1HwndRenderTarget, ID2D1SolidColorBrush, ID2D1StrokeStyle, 
-  S_OK, D2D1_MATRIX_3X2_F};
+  ID2D1LinearGradientBrush, ID2D1RadialGradientBrush, ID2D1GradientStopCollection, ID2D1PathGeometry, ID2D1Bitmap,
+  IDWriteFactory, IDWriteTextFormat, S_OK, D2D1_MATRIX_3X2_F};
 
 use controls::{Control, ControlType, AnyHandle};
+use low::defs::{IWICImagingFactory, IWICBitmapDecoder};
 use error::{Error, SystemError};
-use super::{CanvasRenderer, RendererProtected, build_render_target, CANVAS_CLASS_NAME};
+use super::{CanvasRenderer, RendererProtected, Path, PathSegment, TextLayout, TextLayoutProtected, TextMetrics,
+  build_render_target, CANVAS_CLASS_NAME};
 use defs;
 
 
@@ -40,7 +44,29 @@ use defs;
 #[derive(Clone)]
 pub enum CanvasResources {
     SolidBrush(*mut ID2D1SolidColorBrush),
-    StrokeStyle(*mut ID2D1StrokeStyle)
+    LinearGradientBrush(*mut ID2D1LinearGradientBrush),
+    RadialGradientBrush(*mut ID2D1RadialGradientBrush),
+    StrokeStyle(*mut ID2D1StrokeStyle),
+    Path(*mut ID2D1PathGeometry),
+    TextFormat(*mut IDWriteTextFormat),
+    Bitmap(*mut ID2D1Bitmap)
+}
+
+impl CanvasResources {
+
+    /// Return the name of the resource type. Used in the error messages
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            &CanvasResources::SolidBrush(_) => "Brush",
+            &CanvasResources::LinearGradientBrush(_) => "LinearGradientBrush",
+            &CanvasResources::RadialGradientBrush(_) => "RadialGradientBrush",
+            &CanvasResources::StrokeStyle(_) => "Pen",
+            &CanvasResources::Path(_) => "Path",
+            &CanvasResources::TextFormat(_) => "TextFormat",
+            &CanvasResources::Bitmap(_) => "Bitmap"
+        }
+    }
+
 }
 
 /**
@@ -50,6 +76,7 @@ pub struct Canvas<ID: Clone+Hash> {
     handle: HWND,
     factory: *mut ID2D1Factory,
     render_target: *mut ID2D1HwndRenderTarget,
+    write_factory: *mut IDWriteFactory,
     must_recreate_target: bool,
     resources: HashMap<u64, CanvasResources>,
     p: PhantomData<ID>
@@ -69,7 +96,7 @@ impl<ID: Clone+Hash> Canvas<ID> {
 
         Errors:  
         • `Error::System` if the canvas could not create the brush.  
-        • `Error::KeyExists` if the a resource with the specified name already exists  
+        • `Error::KeyExists` if a resource with the specified name already exists  
     */
     pub fn create_solid_brush(&mut self, name: &ID, brush: &defs::SolidBrush) -> Result<(), Error> {
         use winapi::{D2D1_COLOR_F, D2D1_BRUSH_PROPERTIES};
@@ -94,12 +121,98 @@ impl<ID: Clone+Hash> Canvas<ID> {
         }
     }
 
+    /**
+        Create a linear gradient brush into the canvas and add it under the selected `name`.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the brush.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_linear_gradient_brush(&mut self, name: &ID, brush: &defs::LinearGradientBrush) -> Result<(), Error> {
+        use winapi::{D2D1_POINT_2F, D2D1_BRUSH_PROPERTIES, D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES};
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let stops = match self.create_gradient_stops(&brush.stops, brush.extend_mode) {
+            Ok(s) => s,
+            Err(e) => { return Err(e); }
+        };
+
+        let identity = D2D1_MATRIX_3X2_F {matrix: [[1.0, 0.0],[0.0, 1.0],[0.0, 0.0]]};
+        let property = D2D1_BRUSH_PROPERTIES { opacity: 1.0, transform: identity};
+        let gradient_property = D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
+            startPoint: D2D1_POINT_2F{ x: brush.start.0, y: brush.start.1 },
+            endPoint: D2D1_POINT_2F{ x: brush.end.0, y: brush.end.1 }
+        };
+
+        let mut brush: *mut ID2D1LinearGradientBrush = ptr::null_mut();
+        let result = unsafe{ 
+            let result = self.CreateLinearGradientBrush(&gradient_property, &property, stops, &mut brush);
+            (&mut *stops).Release();
+            result
+        };
+
+        if result == S_OK {
+            self.resources.insert(id, CanvasResources::LinearGradientBrush(brush));
+            Ok(())
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to create linear gradient brush".to_string())))
+        }
+    }
+
+    /**
+        Create a radial gradient brush into the canvas and add it under the selected `name`.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the brush.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_radial_gradient_brush(&mut self, name: &ID, brush: &defs::RadialGradientBrush) -> Result<(), Error> {
+        use winapi::{D2D1_POINT_2F, D2D1_BRUSH_PROPERTIES, D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES};
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let stops = match self.create_gradient_stops(&brush.stops, brush.extend_mode) {
+            Ok(s) => s,
+            Err(e) => { return Err(e); }
+        };
+
+        let identity = D2D1_MATRIX_3X2_F {matrix: [[1.0, 0.0],[0.0, 1.0],[0.0, 0.0]]};
+        let property = D2D1_BRUSH_PROPERTIES { opacity: 1.0, transform: identity};
+        let gradient_property = D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES {
+            center: D2D1_POINT_2F{ x: brush.center.0, y: brush.center.1 },
+            gradientOriginOffset: D2D1_POINT_2F{ x: brush.origin_offset.0, y: brush.origin_offset.1 },
+            radiusX: brush.radius.0,
+            radiusY: brush.radius.1
+        };
+
+        let mut brush: *mut ID2D1RadialGradientBrush = ptr::null_mut();
+        let result = unsafe{ 
+            let result = self.CreateRadialGradientBrush(&gradient_property, &property, stops, &mut brush);
+            (&mut *stops).Release();
+            result
+        };
+
+        if result == S_OK {
+            self.resources.insert(id, CanvasResources::RadialGradientBrush(brush));
+            Ok(())
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to create radial gradient brush".to_string())))
+        }
+    }
+
     /**
         Create a pen into the canvas and add it under the selected `name`.
 
         Errors:  
         • `Error::System` if the canvas could not create the brush.  
-        • `Error::KeyExists` if the a resource with the specified name already exists  
+        • `Error::KeyExists` if a resource with the specified name already exists  
     */
     pub fn create_pen(&mut self, name: &ID, pen: &defs::Pen) -> Result<(), Error> {
         use winapi::{D2D1_STROKE_STYLE_PROPERTIES, D2D1_CAP_STYLE, D2D1_LINE_JOIN, D2D1_DASH_STYLE};
@@ -137,6 +250,316 @@ impl<ID: Clone+Hash> Canvas<ID> {
 
     }
 
+    /**
+        Create a path geometry into the canvas and add it under the selected `name`.
+        Drawing a saved path is faster than drawing a `Path` directly, because the geometry is only built once.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the geometry.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_path(&mut self, name: &ID, path: &Path) -> Result<(), Error> {
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        match self.build_geometry(path) {
+            Ok(geometry) => {
+                self.resources.insert(id, CanvasResources::Path(geometry));
+                Ok(())
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+        Create a text format into the canvas and add it under the selected `name`.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the text format.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_text_format(&mut self, name: &ID, format: &defs::TextFormat) -> Result<(), Error> {
+        use winapi::{DWRITE_FONT_WEIGHT, DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_STYLE_ITALIC, DWRITE_FONT_STRETCH_NORMAL,
+          DWRITE_TEXT_ALIGNMENT_LEADING, DWRITE_TEXT_ALIGNMENT_CENTER, DWRITE_TEXT_ALIGNMENT_TRAILING,
+          DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_PARAGRAPH_ALIGNMENT_CENTER, DWRITE_PARAGRAPH_ALIGNMENT_FAR,
+          DWRITE_WORD_WRAPPING, DWRITE_TRIMMING, DWRITE_TRIMMING_GRANULARITY, IDWriteInlineObject};
+        use low::other_helper::to_utf16;
+        use defs::{HTextAlign, VTextAlign, TextTrimming};
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let factory = match self.write_factory() {
+            Ok(f) => unsafe{ &mut *f },
+            Err(e) => { return Err(e); }
+        };
+
+        let family = to_utf16(&format.family);
+        let locale = to_utf16("");
+        let style = if format.italic { DWRITE_FONT_STYLE_ITALIC } else { DWRITE_FONT_STYLE_NORMAL };
+        let mut text_format: *mut IDWriteTextFormat = ptr::null_mut();
+        let result = unsafe{ factory.CreateTextFormat(family.as_ptr(), ptr::null_mut(), DWRITE_FONT_WEIGHT(format.weight as u32), style,
+          DWRITE_FONT_STRETCH_NORMAL, format.size, locale.as_ptr(), &mut text_format) };
+
+        if result != S_OK {
+            return Err(Error::System(SystemError::ComError("Failed to create text format".to_string())));
+        }
+
+        let align = match format.align {
+            HTextAlign::Left => DWRITE_TEXT_ALIGNMENT_LEADING,
+            HTextAlign::Center => DWRITE_TEXT_ALIGNMENT_CENTER,
+            HTextAlign::Right => DWRITE_TEXT_ALIGNMENT_TRAILING
+        };
+
+        let vertical_align = match format.vertical_align {
+            VTextAlign::Top => DWRITE_PARAGRAPH_ALIGNMENT_NEAR,
+            VTextAlign::Center => DWRITE_PARAGRAPH_ALIGNMENT_CENTER,
+            VTextAlign::Bottom => DWRITE_PARAGRAPH_ALIGNMENT_FAR
+        };
+
+        unsafe {
+            let text_format = &mut *text_format;
+            text_format.SetTextAlignment(align);
+            text_format.SetParagraphAlignment(vertical_align);
+            text_format.SetWordWrapping(DWRITE_WORD_WRAPPING(format.wrapping as u32));
+
+            // The trimmed text ends with an ellipsis
+            let trimming = DWRITE_TRIMMING{ granularity: DWRITE_TRIMMING_GRANULARITY(format.trimming as u32), delimiter: 0, delimiterCount: 0 };
+            let mut sign: *mut IDWriteInlineObject = ptr::null_mut();
+            if format.trimming != TextTrimming::None {
+                factory.CreateEllipsisTrimmingSign(text_format, &mut sign);
+            }
+
+            text_format.SetTrimming(&trimming, sign);
+            if !sign.is_null() { (&mut *sign).Release(); }
+        }
+
+        self.resources.insert(id, CanvasResources::TextFormat(text_format));
+        Ok(())
+    }
+
+    /**
+        Lay out `text` using the text format identified by `format`. The text is laid out in a box of size `max_size`
+        (`width`, `height`): the text format alignment, wrapping and trimming are computed from this box.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the layout.  
+        • `Error::KeyNotFound` if the text format is not in the canvas  
+        • `Error::BadResource` if `format` do not identify a text format  
+    */
+    pub fn create_text_layout(&mut self, format: &ID, text: &str, max_size: (f32, f32)) -> Result<TextLayout, Error> {
+        use winapi::IDWriteTextLayout;
+
+        let format = match self.get_resource(format) {
+            Ok(CanvasResources::TextFormat(f)) => f,
+            Ok(r) => { return Err(Error::BadResource(format!("Resource of type text format required, got {}.", r.type_name()))); },
+            Err(e) => { return Err(e); }
+        };
+
+        let factory = match self.write_factory() {
+            Ok(f) => unsafe{ &mut *f },
+            Err(e) => { return Err(e); }
+        };
+
+        let text_utf16: Vec<u16> = text.encode_utf16().collect();
+        let mut layout: *mut IDWriteTextLayout = ptr::null_mut();
+        let result = unsafe{ factory.CreateTextLayout(text_utf16.as_ptr(), text_utf16.len() as u32, format, max_size.0, max_size.1, &mut layout) };
+
+        if result == S_OK {
+            Ok(TextLayout::create(layout, text))
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to create text layout".to_string())))
+        }
+    }
+
+    /**
+        Return the size `text` would have if it was written with the text format identified by `format` in a box of size `max_size`.
+        Use `create_text_layout` to also hit test the text.
+
+        Errors:  
+        • `Error::System` if the canvas could not lay out the text.  
+        • `Error::KeyNotFound` if the text format is not in the canvas  
+        • `Error::BadResource` if `format` do not identify a text format  
+    */
+    pub fn measure_text(&mut self, format: &ID, text: &str, max_size: (f32, f32)) -> Result<TextMetrics, Error> {
+        self.create_text_layout(format, text, max_size).map(|layout| layout.metrics())
+    }
+
+    /**
+        Load an image file (PNG, BMP, JPEG, GIF, TIFF or ICO) into the canvas and add it under the selected `name`.
+        If the image has many frames, only the first one is loaded.
+
+        Errors:  
+        • `Error::System` if the file could not be opened or decoded.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_bitmap_from_file(&mut self, name: &ID, path: &str) -> Result<(), Error> {
+        use winapi::{GENERIC_READ, WICDecodeMetadataCacheOnLoad};
+        use low::defs::IWICBitmapDecoder;
+        use low::other_helper::to_utf16;
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let path = to_utf16(path);
+        let bitmap = unsafe{ 
+            self.decode_bitmap(|factory| {
+                let mut decoder: *mut IWICBitmapDecoder = ptr::null_mut();
+                factory.CreateDecoderFromFilename(path.as_ptr(), ptr::null(), GENERIC_READ, WICDecodeMetadataCacheOnLoad, &mut decoder);
+                decoder
+            }) 
+        };
+
+        bitmap.map(|b| { self.resources.insert(id, CanvasResources::Bitmap(b)); })
+    }
+
+    /**
+        Decode an image (PNG, BMP, JPEG, GIF, TIFF or ICO) from memory into the canvas and add it under the selected `name`.
+        If the image has many frames, only the first one is loaded.
+
+        Errors:  
+        • `Error::System` if the image could not be decoded.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+    */
+    pub fn create_bitmap_from_memory(&mut self, name: &ID, data: &[u8]) -> Result<(), Error> {
+        use winapi::{DWORD, IStream, WICDecodeMetadataCacheOnLoad};
+        use low::defs::{IWICBitmapDecoder, IWICStream};
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let bitmap = unsafe{ 
+            self.decode_bitmap(|factory| {
+                let mut decoder: *mut IWICBitmapDecoder = ptr::null_mut();
+                let mut stream: *mut IWICStream = ptr::null_mut();
+                if factory.CreateStream(&mut stream) != S_OK {
+                    return decoder;
+                }
+
+                // WIC does not write in the buffer. The stream is released once the bitmap is decoded.
+                let stream = &mut *stream;
+                if stream.InitializeFromMemory(data.as_ptr() as *mut u8, data.len() as DWORD) == S_OK {
+                    factory.CreateDecoderFromStream(stream as *mut IWICStream as *mut IStream, ptr::null(), WICDecodeMetadataCacheOnLoad, &mut decoder);
+                }
+                
+                stream.Release();
+                decoder
+            }) 
+        };
+
+        bitmap.map(|b| { self.resources.insert(id, CanvasResources::Bitmap(b)); })
+    }
+
+    /**
+        Create a bitmap from raw pixels and add it under the selected `name`. The pixels are stored row by row,
+        from the top left corner, with 4 bytes per pixel in the order red, green, blue, alpha. The alpha is not premultiplied.
+
+        Errors:  
+        • `Error::System` if the canvas could not create the bitmap.  
+        • `Error::KeyExists` if a resource with the specified name already exists  
+        • `Error::BadResource` if the size of `pixels` is not `width * height * 4`  
+    */
+    pub fn create_bitmap_from_rgba(&mut self, name: &ID, width: u32, height: u32, pixels: &[u8]) -> Result<(), Error> {
+        use winapi::{D2D1_BITMAP_PROPERTIES, D2D1_PIXEL_FORMAT, D2D_SIZE_U, DXGI_FORMAT_B8G8R8A8_UNORM, D2D1_ALPHA_MODE_PREMULTIPLIED};
+
+        let id = Canvas::hash_id(name);
+        if self.resources.contains_key(&id) {
+            return Err(Error::KeyExists);
+        }
+
+        let expected = (width as usize) * (height as usize) * 4;
+        if pixels.len() != expected {
+            let msg = format!("Expected {} bytes of pixels for a {}x{} bitmap, got {}.", expected, width, height, pixels.len());
+            return Err(Error::BadResource(msg));
+        }
+
+        // D2D only draws bitmaps with a premultiplied alpha and the HWND render targets only accept BGRA pixels
+        let data = rgba_to_pbgra(pixels);
+
+        let size = D2D_SIZE_U{ width: width, height: height };
+        let properties = D2D1_BITMAP_PROPERTIES {
+            pixelFormat: D2D1_PIXEL_FORMAT{ format: DXGI_FORMAT_B8G8R8A8_UNORM, alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED },
+            dpiX: 0.0, dpiY: 0.0
+        };
+
+        let mut bitmap: *mut ID2D1Bitmap = ptr::null_mut();
+        let result = unsafe{ self.CreateBitmap(size, data.as_ptr() as *const _, width * 4, &properties, &mut bitmap) };
+
+        if result == S_OK {
+            self.resources.insert(id, CanvasResources::Bitmap(bitmap));
+            Ok(())
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to create bitmap".to_string())))
+        }
+    }
+
+    /**
+        Return the size in pixels of the bitmap identified by `name`.
+
+        Errors:  
+        • `Error::KeyNotFound` if the bitmap is not in the canvas  
+        • `Error::BadResource` if `name` do not identify a bitmap  
+    */
+    pub fn get_bitmap_size(&mut self, name: &ID) -> Result<(u32, u32), Error> {
+        use winapi::D2D_SIZE_U;
+
+        match self.get_resource(name) {
+            Ok(CanvasResources::Bitmap(b)) => unsafe{ 
+                let mut size = D2D_SIZE_U{ width: 0, height: 0 };
+                (&mut *b).GetPixelSize(&mut size);
+                Ok((size.width, size.height))
+            },
+            Ok(r) => Err(Error::BadResource(format!("Resource of type bitmap required, got {}.", r.type_name()))),
+            Err(e) => Err(e)
+        }
+    }
+
+    /**
+        Return the gradient stops and the extend mode of the gradient brush identified by `name`.
+
+        Errors:  
+        • `Error::KeyNotFound` if the brush is not in the canvas  
+        • `Error::BadResource` if `name` do not identify a linear or a radial gradient brush  
+    */
+    pub fn get_gradient_stops(&mut self, name: &ID) -> Result<(Vec<defs::GradientStop>, defs::ExtendMode), Error> {
+        use winapi::{D2D1_GRADIENT_STOP, D2D1_COLOR_F, D2D1_EXTEND_MODE_WRAP, D2D1_EXTEND_MODE_MIRROR};
+        use defs::{GradientStop, ExtendMode};
+
+        let mut collection: *mut ID2D1GradientStopCollection = ptr::null_mut();
+        match self.get_resource(name) {
+            Ok(CanvasResources::LinearGradientBrush(b)) => unsafe{ (&mut *b).GetGradientStopCollection(&mut collection) },
+            Ok(CanvasResources::RadialGradientBrush(b)) => unsafe{ (&mut *b).GetGradientStopCollection(&mut collection) },
+            Ok(r) => { return Err(Error::BadResource(format!("Resource of type gradient brush required, got {}.", r.type_name()))); },
+            Err(e) => { return Err(e); }
+        }
+
+        unsafe {
+            let collection = &mut *collection;
+            let count = collection.GetGradientStopCount();
+            let empty = D2D1_GRADIENT_STOP{ position: 0.0, color: D2D1_COLOR_F{r: 0.0, g: 0.0, b: 0.0, a: 0.0} };
+            let mut stops = vec![empty; count as usize];
+            collection.GetGradientStops(stops.as_mut_ptr(), count);
+
+            let extend_mode = match collection.GetExtendMode() {
+                D2D1_EXTEND_MODE_WRAP => ExtendMode::Wrap,
+                D2D1_EXTEND_MODE_MIRROR => ExtendMode::Mirror,
+                _ => ExtendMode::Clamp
+            };
+            collection.Release();
+
+            let stops = stops.iter().map(|s| GradientStop{ position: s.position, color: (s.color.r, s.color.g, s.color.b, s.color.a) }).collect();
+            Ok((stops, extend_mode))
+        }
+    }
+
     /**
         Redraw the canvas
     */
@@ -179,6 +602,93 @@ impl<ID: Clone+Hash> Canvas<ID> {
         unsafe { self.SetDpi(dpix, fpiy); }
     }
 
+    /// Create the gradient stop collection of a gradient brush. The caller must release the collection.
+    fn create_gradient_stops(&mut self, stops: &[defs::GradientStop], extend_mode: defs::ExtendMode) -> Result<*mut ID2D1GradientStopCollection, Error> {
+        use winapi::{D2D1_COLOR_F, D2D1_GRADIENT_STOP, D2D1_GAMMA_2_2, D2D1_EXTEND_MODE};
+
+        let stops: Vec<D2D1_GRADIENT_STOP> = stops.iter().map(|s| {
+            let c = &s.color;
+            D2D1_GRADIENT_STOP{ position: s.position, color: D2D1_COLOR_F{r: c.0, g: c.1, b: c.2, a: c.3} }
+        }).collect();
+
+        let mut collection: *mut ID2D1GradientStopCollection = ptr::null_mut();
+        let result = unsafe{ self.CreateGradientStopCollection(stops.as_ptr(), stops.len() as u32, D2D1_GAMMA_2_2, D2D1_EXTEND_MODE(extend_mode as u32), &mut collection) };
+
+        if result == S_OK {
+            Ok(collection)
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to create gradient stops".to_string())))
+        }
+    }
+
+    /**
+        Decode the first frame of the image opened by `open` and copy it into a D2D bitmap.
+        `open` returns a null decoder if the image could not be opened.
+    */
+    unsafe fn decode_bitmap<F>(&mut self, open: F) -> Result<*mut ID2D1Bitmap, Error> 
+      where F: FnOnce(&mut IWICImagingFactory) -> *mut IWICBitmapDecoder
+    {
+        use ole32::CoCreateInstance;
+        use winapi::{CLSCTX_INPROC_SERVER, IWICBitmapSource, IWICFormatConverter, WICBitmapDitherTypeNone, WICBitmapPaletteTypeCustom};
+        use low::clsid::{CLSID_WICImagingFactory, IID_IWICImagingFactory, GUID_WICPixelFormat32bppPBGRA};
+
+        let mut factory: *mut IWICImagingFactory = ptr::null_mut();
+        let r = CoCreateInstance(&CLSID_WICImagingFactory(), ptr::null_mut(), CLSCTX_INPROC_SERVER, &IID_IWICImagingFactory(), mem::transmute(&mut factory));
+        if r != S_OK {
+            return Err(Error::System(SystemError::ComInstanceCreation("WICImagingFactory".to_string())));
+        }
+
+        let factory = &mut *factory;
+        let decoder = open(factory);
+        if decoder.is_null() {
+            factory.Release();
+            return Err(Error::System(SystemError::ComError("Failed to open image".to_string())));
+        }
+
+        let decoder = &mut *decoder;
+        let mut frame: *mut IWICBitmapSource = ptr::null_mut();
+        let mut converter: *mut IWICFormatConverter = ptr::null_mut();
+        let mut bitmap: *mut ID2D1Bitmap = ptr::null_mut();
+
+        // The frame is converted to the pixel format of the render target
+        let mut result = decoder.GetFrame(0, &mut frame);
+        if result == S_OK { result = factory.CreateFormatConverter(&mut converter); }
+        if result == S_OK { 
+            result = (&mut *converter).Initialize(frame, &GUID_WICPixelFormat32bppPBGRA(), WICBitmapDitherTypeNone, ptr::null_mut(), 0.0, WICBitmapPaletteTypeCustom);
+        }
+        if result == S_OK { result = self.CreateBitmapFromWicBitmap(converter as *mut IWICBitmapSource, ptr::null(), &mut bitmap); }
+
+        if !converter.is_null() { (&mut *converter).Release(); }
+        if !frame.is_null() { (&mut *frame).Release(); }
+        decoder.Release();
+        factory.Release();
+
+        if result == S_OK {
+            Ok(bitmap)
+        } else {
+            Err(Error::System(SystemError::ComError("Failed to decode image".to_string())))
+        }
+    }
+
+    /// Return the DirectWrite factory of the canvas. The factory is created the first time it is needed.
+    fn write_factory(&mut self) -> Result<*mut IDWriteFactory, Error> {
+        use winapi::{IUnknown, DWRITE_FACTORY_TYPE_SHARED};
+        use low::defs::DWriteCreateFactory;
+        use low::clsid::IID_IDWriteFactory;
+
+        if self.write_factory.is_null() {
+            let mut factory: *mut IUnknown = ptr::null_mut();
+            let result = unsafe{ DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED, &IID_IDWriteFactory(), &mut factory) };
+            if result != S_OK {
+                return Err(Error::System(SystemError::ComError("Could not create DirectWrite factory".to_string())));
+            }
+
+            self.write_factory = factory as *mut IDWriteFactory;
+        }
+
+        Ok(self.write_factory)
+    }
+
     /// Hash an ID before inserting it in the canvas resources
     #[inline(always)]
     fn hash_id(id: &ID) -> u64 {
@@ -221,10 +731,19 @@ impl<ID: Clone+Hash> Control for Canvas<ID> {
             for (_, v) in self.resources.drain() {
                 match v {
                     CanvasResources::SolidBrush(r) => { (&mut *r).Release(); },
+                    CanvasResources::LinearGradientBrush(b) => { (&mut *b).Release(); },
+                    CanvasResources::RadialGradientBrush(b) => { (&mut *b).Release(); },
                     CanvasResources::StrokeStyle(s) => { (&mut *s).Release(); },
+                    CanvasResources::Path(p) => { (&mut *p).Release(); },
+                    CanvasResources::TextFormat(f) => { (&mut *f).Release(); },
+                    CanvasResources::Bitmap(b) => { (&mut *b).Release(); },
                 }
             }
 
+            if !self.write_factory.is_null() {
+                (&mut *self.write_factory).Release();
+            }
+
             render_target.Release();
             factory.Release();
             DestroyWindow(self.handle);
@@ -265,6 +784,7 @@ pub trait CanvasProtected<ID: Clone+Hash>  {
     fn create(h: HWND, f: *mut ID2D1Factory, r: *mut ID2D1HwndRenderTarget) -> Canvas<ID>;
     fn rebuild(&mut self) -> Result<(), SystemError>;
     fn get_resource(&mut self, id: &ID) -> Result<CanvasResources, Error>;
+    fn build_geometry(&mut self, path: &Path) -> Result<*mut ID2D1PathGeometry, Error>;
 }
 
 impl<ID: Clone+Hash> CanvasProtected<ID> for Canvas<ID> {
@@ -282,6 +802,7 @@ impl<ID: Clone+Hash> CanvasProtected<ID> for Canvas<ID> {
             handle: h,
             factory: f,
             render_target: r,
+            write_factory: ptr::null_mut(),
             must_recreate_target: false,
             resources: HashMap::with_capacity(10),
             p: PhantomData
@@ -310,4 +831,91 @@ impl<ID: Clone+Hash> CanvasProtected<ID> for Canvas<ID> {
         }
     }
 
+    /// Build a D2D path geometry from a path. The caller must release the geometry.
+    fn build_geometry(&mut self, path: &Path) -> Result<*mut ID2D1PathGeometry, Error> {
+        let mut geometry: *mut ID2D1PathGeometry = ptr::null_mut();
+        let result = unsafe{ (&mut *self.factory).CreatePathGeometry(&mut geometry) };
+        if result != S_OK {
+            return Err(Error::System(SystemError::ComError("Failed to create path geometry".to_string())));
+        }
+
+        let result = unsafe{ write_geometry(&mut *geometry, path) };
+        if result == S_OK {
+            Ok(geometry)
+        } else {
+            unsafe{ (&mut *geometry).Release(); }
+            Err(Error::System(SystemError::ComError("Failed to build path geometry".to_string())))
+        }
+    }
+
+}
+
+/**
+    Convert pixels in the order red, green, blue, alpha to the pixel layout used by the canvas bitmaps:
+    blue, green, red, alpha with the colors multiplied by the alpha. Trailing bytes that do not make a whole pixel are ignored.
+*/
+pub fn rgba_to_pbgra(pixels: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(pixels.len());
+    for p in pixels.chunks(4).filter(|p| p.len() == 4) {
+        let a = p[3] as u32;
+        let mul = |c: u8| ((c as u32 * a + 127) / 255) as u8;
+        data.extend_from_slice(&[mul(p[2]), mul(p[1]), mul(p[0]), p[3]]);
+    }
+    data
+}
+
+/**
+    Write the figures of a path in an empty path geometry
+*/
+unsafe fn write_geometry(geometry: &mut ID2D1PathGeometry, path: &Path) -> ::winapi::HRESULT {
+    use winapi::{ID2D1GeometrySink, D2D1_POINT_2F, D2D1_SIZE_F, D2D1_BEZIER_SEGMENT, D2D1_QUADRATIC_BEZIER_SEGMENT,
+      D2D1_ARC_SEGMENT, D2D1_FILL_MODE, D2D1_FIGURE_BEGIN_FILLED, D2D1_FIGURE_END_OPEN, D2D1_FIGURE_END_CLOSED,
+      D2D1_SWEEP_DIRECTION, D2D1_ARC_SIZE};
+
+    let point = |p: (f32, f32)| D2D1_POINT_2F{ x: p.0, y: p.1 };
+
+    let mut sink: *mut ID2D1GeometrySink = ptr::null_mut();
+    let result = geometry.Open(&mut sink);
+    if result != S_OK {
+        return result;
+    }
+
+    let sink = &mut *sink;
+    sink.SetFillMode(D2D1_FILL_MODE(path.fill_mode as u32));
+
+    for figure in path.figures.iter() {
+        sink.BeginFigure(point(figure.start), D2D1_FIGURE_BEGIN_FILLED);
+
+        for segment in figure.segments.iter() {
+            match segment {
+                &PathSegment::Line{end} => {
+                    sink.AddLine(point(end));
+                },
+                &PathSegment::QuadraticBezier{control, end} => {
+                    let bezier = D2D1_QUADRATIC_BEZIER_SEGMENT{ point1: point(control), point2: point(end) };
+                    sink.AddQuadraticBezier(&bezier);
+                },
+                &PathSegment::CubicBezier{control1, control2, end} => {
+                    let bezier = D2D1_BEZIER_SEGMENT{ point1: point(control1), point2: point(control2), point3: point(end) };
+                    sink.AddBezier(&bezier);
+                },
+                &PathSegment::Arc{end, radius, rotation, sweep, size} => {
+                    let arc = D2D1_ARC_SEGMENT{
+                        point: point(end),
+                        size: D2D1_SIZE_F{ width: radius.0, height: radius.1 },
+                        rotationAngle: rotation,
+                        sweepDirection: D2D1_SWEEP_DIRECTION(sweep as u32),
+                        arcSize: D2D1_ARC_SIZE(size as u32)
+                    };
+                    sink.AddArc(&arc);
+                }
+            }
+        }
+
+        sink.EndFigure(if figure.closed { D2D1_FIGURE_END_CLOSED } else { D2D1_FIGURE_END_OPEN });
+    }
+
+    let result = sink.Close();
+    sink.Release();
+    result
 }
\ No newline at end of file
@@ -43,6 +43,7 @@ use super::{Canvas, CanvasProtected};
     • `size` : Starting size of the canvas after it is created  
     • `visible` : If the user can see the canvas or not  
     • `disabled` : If the canvas is enabled or not. A disabled canvas do not process events  
+    • `accept_files` : If the user can drop files from the shell on the canvas. Dropping files raises `Event::FilesDropped`  
 */
 pub struct CanvasT<ID: Hash+Clone> {
     pub parent: ID,
@@ -50,6 +51,7 @@ pub struct CanvasT<ID: Hash+Clone> {
     pub size: (u32, u32),
     pub visible: bool,
     pub disabled: bool,
+    pub accept_files: bool,
 }
 
 impl<ID: Hash+Clone+'static> ControlT<ID> for CanvasT<ID> {
@@ -57,7 +59,8 @@ impl<ID: Hash+Clone+'static> ControlT<ID> for CanvasT<ID> {
 
     fn events(&self) -> Vec<Event> {
         vec![Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::MouseDown, Event::MouseUp,
-             Event::Moved, Event::Resized, Event::Paint, Event::Raw]
+             Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel,
+             Event::Moved, Event::Resized, Event::Paint, Event::FilesDropped, Event::ContextMenu, Event::Raw]
     }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
@@ -194,7 +197,7 @@ unsafe fn build_renderer(hwnd: HWND) -> Result<RenderOut, SystemError> {
 #[inline(always)]
 unsafe fn build_window<ID: Hash+Clone>(ui: &Ui<ID>, t: &CanvasT<ID>) -> Result<(HWND, RenderOut), Error> {
     use low::window_helper::{WindowParams, build_window, handle_of_window};
-    use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD};
+    use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_EX_ACCEPTFILES};
     use user32::DestroyWindow;   
 
     let flags: DWORD = WS_CHILD | 
@@ -213,7 +216,7 @@ unsafe fn build_window<ID: Hash+Clone>(ui: &Ui<ID>, t: &CanvasT<ID>) -> Result<(
         position: t.position.clone(),
         size: t.size.clone(),
         flags: flags,
-        ex_flags: None,
+        ex_flags: if t.accept_files { Some(WS_EX_ACCEPTFILES) } else { None },
         parent: parent
     };
 
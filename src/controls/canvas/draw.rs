@@ -0,0 +1,58 @@
+/*!
+    The drawing interface shared by the canvas renderer and the recording renderer.
+    It does not depend on the system, so the paint code can be tested on any platform.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::hash::Hash;
+
+use winapi::FLOAT;
+
+use error::Error;
+use defs::{Rectangle, Ellipse, BitmapInterpolation};
+use super::{Path, TextLayout};
+
+/**
+    The drawing commands of a canvas renderer. Write the paint code against this trait to run it with a
+    `CanvasRenderer` in the application and with a `RecordingRenderer` in the tests.
+
+    The methods are documented in `CanvasRenderer`.
+*/
+pub trait Renderer<ID: Clone+Hash> {
+    fn clear(&mut self, r:f32, g:f32, b: f32, a: f32);
+    fn get_render_size(&mut self) -> (f32, f32);
+    fn set_transform(&mut self, m: &[[FLOAT; 2]; 3]);
+    fn get_transform(&mut self) -> [[FLOAT; 2]; 3];
+    fn fill_rectangle(&mut self, brush: &ID, r: &Rectangle) -> Result<(), Error>;
+    fn fill_rounded_rectangle(&mut self, brush: &ID, r: &Rectangle, radius: (f32, f32)) -> Result<(), Error>;
+    fn fill_ellipse(&mut self, brush: &ID, e: &Ellipse) -> Result<(), Error>;
+    fn draw_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32) -> Result<(), Error>;
+    fn draw_rounded_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32, radius: (f32, f32)) -> Result<(), Error>;
+    fn draw_ellipse(&mut self, brush: &ID, pen: Option<&ID>, e: &Ellipse, width: f32) -> Result<(), Error>;
+    fn draw_line(&mut self, brush: &ID, pen: Option<&ID>, p0: (f32, f32), p1: (f32, f32), width: f32) -> Result<(), Error>;
+    fn draw_polyline(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error>;
+    fn draw_polygon(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error>;
+    fn fill_polygon(&mut self, brush: &ID, points: &[(f32, f32)]) -> Result<(), Error>;
+    fn draw_path(&mut self, brush: &ID, pen: Option<&ID>, path: &Path, width: f32) -> Result<(), Error>;
+    fn fill_path(&mut self, brush: &ID, path: &Path) -> Result<(), Error>;
+    fn draw_saved_path(&mut self, brush: &ID, pen: Option<&ID>, path: &ID, width: f32) -> Result<(), Error>;
+    fn fill_saved_path(&mut self, brush: &ID, path: &ID) -> Result<(), Error>;
+    fn draw_text(&mut self, brush: &ID, format: &ID, text: &str, r: &Rectangle) -> Result<(), Error>;
+    fn draw_text_layout(&mut self, brush: &ID, layout: &TextLayout, origin: (f32, f32)) -> Result<(), Error>;
+    fn draw_bitmap(&mut self, bitmap: &ID, dest: &Rectangle, source: Option<&Rectangle>, opacity: f32, interpolation: BitmapInterpolation) -> Result<(), Error>;
+}
@@ -1,7 +1,16 @@
-mod canvas;
-mod renderer;
-mod canvas_t;
+mod path;
+mod text;
+mod draw;
+mod recorder;
+#[cfg(windows)] mod canvas;
+#[cfg(windows)] mod renderer;
+#[cfg(windows)] mod canvas_t;
 
-pub use self::canvas::{Canvas, CanvasProtected, CanvasResources};
-pub use self::renderer::{CanvasRenderer, RendererProtected};
-pub use self::canvas_t::{CanvasT, build_render_target, CANVAS_CLASS_NAME};
+pub use self::path::{Path, PathBuilder, PathSegment, Figure};
+pub use self::text::{TextLayout, TextMetrics, HitTestMetrics};
+pub use self::draw::Renderer;
+pub use self::recorder::{RecordingRenderer, DrawCommand};
+#[cfg(windows)] pub use self::canvas::{Canvas, CanvasProtected, CanvasResources, rgba_to_pbgra};
+#[cfg(windows)] pub use self::renderer::{CanvasRenderer, RendererProtected};
+#[cfg(windows)] pub use self::canvas_t::{CanvasT, build_render_target, CANVAS_CLASS_NAME};
+#[cfg(windows)] pub use self::text::TextLayoutProtected;
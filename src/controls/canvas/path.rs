@@ -0,0 +1,228 @@
+/*!
+    Paths made of lines, bezier curves and arcs that can be drawn or filled in a canvas.
+    Paths are plain data: they are converted to D2D geometries when they are added to a canvas or when they are drawn.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use defs::{FillMode, SweepDirection, ArcSize};
+
+/**
+    A segment of a path figure. Every segment starts where the previous one ended.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    /// A straight line to `end`
+    Line{ end: (f32, f32) },
+
+    /// A quadratic bezier curve to `end`
+    QuadraticBezier{ control: (f32, f32), end: (f32, f32) },
+
+    /// A cubic bezier curve to `end`
+    CubicBezier{ control1: (f32, f32), control2: (f32, f32), end: (f32, f32) },
+
+    /// An elliptical arc to `end`. `rotation` is the rotation of the ellipse in degrees
+    Arc{ end: (f32, f32), radius: (f32, f32), rotation: f32, sweep: SweepDirection, size: ArcSize }
+}
+
+impl PathSegment {
+
+    /// Return the point where the segment ends
+    pub fn end(&self) -> (f32, f32) {
+        match self {
+            &PathSegment::Line{end} => end,
+            &PathSegment::QuadraticBezier{end, ..} => end,
+            &PathSegment::CubicBezier{end, ..} => end,
+            &PathSegment::Arc{end, ..} => end
+        }
+    }
+
+}
+
+/**
+    A figure of a path: a start point followed by connected segments.
+
+    Members:  
+    • `start`: The point where the figure starts  
+    • `segments`: The segments of the figure  
+    • `closed`: If a line joins the last point of the figure to its start point  
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Figure {
+    pub start: (f32, f32),
+    pub segments: Vec<PathSegment>,
+    pub closed: bool
+}
+
+impl Figure {
+
+    /// Return the point where the figure ends. This is the start point if the figure is closed.
+    pub fn end(&self) -> (f32, f32) {
+        if self.closed {
+            self.start
+        } else {
+            self.segments.last().map(|s| s.end()).unwrap_or(self.start)
+        }
+    }
+
+}
+
+/**
+    A shape made of lines, curves and arcs. Create it with a `PathBuilder`, `Path::polyline` or `Path::polygon`.
+    Draw it directly with `CanvasRenderer::draw_path` or save it in a canvas with `Canvas::create_path`.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path {
+    pub figures: Vec<Figure>,
+    pub fill_mode: FillMode
+}
+
+impl Path {
+
+    /// Create a path of lines that join the points. Return an empty path if `points` is empty.
+    pub fn polyline(points: &[(f32, f32)]) -> Path {
+        Path::from_points(points, false)
+    }
+
+    /// Create a closed path of lines that join the points. Return an empty path if `points` is empty.
+    pub fn polygon(points: &[(f32, f32)]) -> Path {
+        Path::from_points(points, true)
+    }
+
+    /// Return the number of segments in all the figures of the path
+    pub fn len(&self) -> usize {
+        self.figures.iter().map(|f| f.segments.len()).sum()
+    }
+
+    /// Return `true` if the path has no figures
+    pub fn is_empty(&self) -> bool {
+        self.figures.is_empty()
+    }
+
+    fn from_points(points: &[(f32, f32)], closed: bool) -> Path {
+        let figures = match points.split_first() {
+            Some((start, others)) => vec![Figure{
+                start: *start,
+                segments: others.iter().map(|&p| PathSegment::Line{end: p}).collect(),
+                closed: closed
+            }],
+            None => Vec::new()
+        };
+
+        Path{ figures: figures, fill_mode: FillMode::Alternate }
+    }
+
+}
+
+/**
+    Build a path one segment at a time. The builder starts a figure at the point passed to `PathBuilder::new`.
+
+    Usage:  
+    `PathBuilder::new((0.0, 0.0)).line_to((10.0, 0.0)).quadratic_to((15.0, 5.0), (10.0, 10.0)).close().build()`  
+*/
+#[derive(Clone, Debug)]
+pub struct PathBuilder {
+    figures: Vec<Figure>,
+    current: Figure,
+    fill_mode: FillMode
+}
+
+impl PathBuilder {
+
+    /// Create a builder and start the first figure at `start`
+    pub fn new(start: (f32, f32)) -> PathBuilder {
+        PathBuilder{
+            figures: Vec::new(),
+            current: Figure{ start: start, segments: Vec::new(), closed: false },
+            fill_mode: FillMode::Alternate
+        }
+    }
+
+    /// Set how the inside of the path is computed. The default is `FillMode::Alternate`
+    pub fn fill_mode(mut self, mode: FillMode) -> PathBuilder {
+        self.fill_mode = mode;
+        self
+    }
+
+    /**
+        End the current figure and start a new one at `point`. The current figure is kept open,
+        unless `close` was called. A figure without segments is discarded.
+    */
+    pub fn move_to(mut self, point: (f32, f32)) -> PathBuilder {
+        let next = Figure{ start: point, segments: Vec::new(), closed: false };
+        let figure = ::std::mem::replace(&mut self.current, next);
+        if !figure.segments.is_empty() {
+            self.figures.push(figure);
+        }
+        self
+    }
+
+    /// Add a straight line from the current point to `end`
+    pub fn line_to(self, end: (f32, f32)) -> PathBuilder {
+        self.push(PathSegment::Line{ end: end })
+    }
+
+    /// Add a quadratic bezier curve from the current point to `end`
+    pub fn quadratic_to(self, control: (f32, f32), end: (f32, f32)) -> PathBuilder {
+        self.push(PathSegment::QuadraticBezier{ control: control, end: end })
+    }
+
+    /// Add a cubic bezier curve from the current point to `end`
+    pub fn cubic_to(self, control1: (f32, f32), control2: (f32, f32), end: (f32, f32)) -> PathBuilder {
+        self.push(PathSegment::CubicBezier{ control1: control1, control2: control2, end: end })
+    }
+
+    /**
+        Add an elliptical arc from the current point to `end`.
+
+        Arguments:  
+        • `end`: The end point of the arc  
+        • `radius`: The radius of the ellipse (`x`, `y`)  
+        • `rotation`: The rotation of the ellipse in degrees  
+        • `sweep`: The direction of the arc  
+        • `size`: Whether the arc is the small or the large arc of the ellipse that joins the two points  
+    */
+    pub fn arc_to(self, end: (f32, f32), radius: (f32, f32), rotation: f32, sweep: SweepDirection, size: ArcSize) -> PathBuilder {
+        self.push(PathSegment::Arc{ end: end, radius: radius, rotation: rotation, sweep: sweep, size: size })
+    }
+
+    /**
+        Close the current figure with a line to its start point. The next segment starts a new figure
+        from the start point of the closed figure.
+    */
+    pub fn close(mut self) -> PathBuilder {
+        self.current.closed = true;
+        let start = self.current.start;
+        self.move_to(start)
+    }
+
+    /// Return the path
+    pub fn build(self) -> Path {
+        let PathBuilder{ mut figures, current, fill_mode } = self;
+        if !current.segments.is_empty() {
+            figures.push(current);
+        }
+
+        Path{ figures: figures, fill_mode: fill_mode }
+    }
+
+    fn push(mut self, segment: PathSegment) -> PathBuilder {
+        self.current.segments.push(segment);
+        self
+    }
+
+}
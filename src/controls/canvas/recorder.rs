@@ -0,0 +1,640 @@
+/*!
+    A renderer that records the draw commands instead of drawing them. It does not need a
+    canvas nor Direct2D, so the paint code can be tested and its output compared to a stored snapshot.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::hash::Hash;
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+
+use winapi::FLOAT;
+
+use error::Error;
+use defs::{Rectangle, Ellipse, BitmapInterpolation, FillMode, SweepDirection, ArcSize};
+use super::{Renderer, Path, PathSegment, Figure, TextLayout};
+
+/// The identity matrix. This is the transform of a new renderer
+const IDENTITY: [[FLOAT; 2]; 3] = [[1.0, 0.0], [0.0, 1.0], [0.0, 0.0]];
+
+/**
+    A draw command recorded by a `RecordingRenderer`. There is one variant for each drawing method of the `Renderer` trait
+    and the variants hold the method arguments. The resources (brushes, pens, paths, text formats, bitmaps) are recorded by id.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum DrawCommand<ID: Clone+Hash> {
+    Clear{ color: (f32, f32, f32, f32) },
+    SetTransform{ matrix: [[FLOAT; 2]; 3] },
+    FillRectangle{ brush: ID, rect: Rectangle },
+    FillRoundedRectangle{ brush: ID, rect: Rectangle, radius: (f32, f32) },
+    FillEllipse{ brush: ID, ellipse: Ellipse },
+    DrawRectangle{ brush: ID, pen: Option<ID>, rect: Rectangle, width: f32 },
+    DrawRoundedRectangle{ brush: ID, pen: Option<ID>, rect: Rectangle, width: f32, radius: (f32, f32) },
+    DrawEllipse{ brush: ID, pen: Option<ID>, ellipse: Ellipse, width: f32 },
+    DrawLine{ brush: ID, pen: Option<ID>, start: (f32, f32), end: (f32, f32), width: f32 },
+    DrawPolyline{ brush: ID, pen: Option<ID>, points: Vec<(f32, f32)>, width: f32 },
+    DrawPolygon{ brush: ID, pen: Option<ID>, points: Vec<(f32, f32)>, width: f32 },
+    FillPolygon{ brush: ID, points: Vec<(f32, f32)> },
+    DrawPath{ brush: ID, pen: Option<ID>, path: Path, width: f32 },
+    FillPath{ brush: ID, path: Path },
+    DrawSavedPath{ brush: ID, pen: Option<ID>, path: ID, width: f32 },
+    FillSavedPath{ brush: ID, path: ID },
+    DrawText{ brush: ID, format: ID, text: String, rect: Rectangle },
+    DrawTextLayout{ brush: ID, text: String, max_size: (f32, f32), origin: (f32, f32) },
+    DrawBitmap{ bitmap: ID, dest: Rectangle, source: Option<Rectangle>, opacity: f32, interpolation: BitmapInterpolation }
+}
+
+/**
+    A renderer that saves the draw commands in a list, in the order they were called.
+    The commands always succeed: the resource ids are recorded without being checked.
+    Text layouts are recorded by their text and their size.
+
+    Usage:  
+    `let mut r = RecordingRenderer::new((100.0, 100.0)); paint(&mut r); assert_eq!(r.to_text(), SNAPSHOT);`  
+    where `paint` is a function generic over `Renderer<ID>`.
+*/
+pub struct RecordingRenderer<ID: Clone+Hash> {
+    size: (f32, f32),
+    transform: [[FLOAT; 2]; 3],
+    commands: Vec<DrawCommand<ID>>
+}
+
+impl<ID: Clone+Hash> RecordingRenderer<ID> {
+
+    /// Create an empty recorder. `size` is the value returned by `get_render_size`.
+    pub fn new(size: (f32, f32)) -> RecordingRenderer<ID> {
+        RecordingRenderer{ size: size, transform: IDENTITY, commands: Vec::new() }
+    }
+
+    /// Return the recorded commands
+    pub fn commands(&self) -> &[DrawCommand<ID>] {
+        &self.commands
+    }
+
+    /// Return the recorded commands and clear the recorder. The transform is reset to the identity matrix.
+    pub fn take_commands(&mut self) -> Vec<DrawCommand<ID>> {
+        self.transform = IDENTITY;
+        ::std::mem::replace(&mut self.commands, Vec::new())
+    }
+
+    fn push(&mut self, cmd: DrawCommand<ID>) -> Result<(), Error> {
+        self.commands.push(cmd);
+        Ok(())
+    }
+
+}
+
+impl<ID: Clone+Hash+Display> RecordingRenderer<ID> {
+
+    /**
+        Return the recorded commands as text, one command per line.
+        The text is stable between runs and can be saved as a snapshot and compared line by line.
+        Read it back with `RecordingRenderer::parse_text`.
+
+        A line is the name of the renderer method followed by its arguments, separated by spaces:  
+        • ids are written with `Display` and a missing pen is written `-`  
+        • numbers are written with `Display`, points and sizes as `x,y`, rectangles as `left,top,right,bottom`,
+          ellipses as `x,y,radius_x,radius_y` and matrices as `m11,m12,m21,m22,dx,dy`  
+        • texts are quoted. `"`, `\`, new lines and tabs are escaped with `\`  
+        • lists of points are written as their length followed by the points  
+        • paths are written as their fill mode and their number of figures followed by the figures. A figure is
+          its start point, `open` or `closed`, and its number of segments followed by the segments  
+    */
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for cmd in self.commands.iter() {
+            writeln!(text, "{}", cmd).unwrap();
+        }
+        text
+    }
+
+}
+
+impl<ID: Clone+Hash+FromStr> RecordingRenderer<ID> {
+
+    /**
+        Read the commands written by `RecordingRenderer::to_text`. Empty lines are ignored.
+
+        Errors:  
+        • `Error::BadResource` if a line is not a valid command. The message starts with the line number.  
+    */
+    pub fn parse_text(text: &str) -> Result<Vec<DrawCommand<ID>>, Error> {
+        let mut commands = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() { continue; }
+            match parse_command(line) {
+                Ok(cmd) => commands.push(cmd),
+                Err(msg) => { return Err(Error::BadResource(format!("line {}: {}", index + 1, msg))); }
+            }
+        }
+
+        Ok(commands)
+    }
+
+}
+
+impl<ID: Clone+Hash> Renderer<ID> for RecordingRenderer<ID> {
+
+    fn clear(&mut self, r:f32, g:f32, b: f32, a: f32) {
+        self.commands.push(DrawCommand::Clear{ color: (r, g, b, a) });
+    }
+
+    fn get_render_size(&mut self) -> (f32, f32) {
+        self.size
+    }
+
+    fn set_transform(&mut self, m: &[[FLOAT; 2]; 3]) {
+        self.transform = *m;
+        self.commands.push(DrawCommand::SetTransform{ matrix: *m });
+    }
+
+    fn get_transform(&mut self) -> [[FLOAT; 2]; 3] {
+        self.transform
+    }
+
+    fn fill_rectangle(&mut self, brush: &ID, r: &Rectangle) -> Result<(), Error> {
+        self.push(DrawCommand::FillRectangle{ brush: brush.clone(), rect: r.clone() })
+    }
+
+    fn fill_rounded_rectangle(&mut self, brush: &ID, r: &Rectangle, radius: (f32, f32)) -> Result<(), Error> {
+        self.push(DrawCommand::FillRoundedRectangle{ brush: brush.clone(), rect: r.clone(), radius: radius })
+    }
+
+    fn fill_ellipse(&mut self, brush: &ID, e: &Ellipse) -> Result<(), Error> {
+        self.push(DrawCommand::FillEllipse{ brush: brush.clone(), ellipse: e.clone() })
+    }
+
+    fn draw_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawRectangle{ brush: brush.clone(), pen: pen.cloned(), rect: r.clone(), width: width })
+    }
+
+    fn draw_rounded_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32, radius: (f32, f32)) -> Result<(), Error> {
+        self.push(DrawCommand::DrawRoundedRectangle{ brush: brush.clone(), pen: pen.cloned(), rect: r.clone(), width: width, radius: radius })
+    }
+
+    fn draw_ellipse(&mut self, brush: &ID, pen: Option<&ID>, e: &Ellipse, width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawEllipse{ brush: brush.clone(), pen: pen.cloned(), ellipse: e.clone(), width: width })
+    }
+
+    fn draw_line(&mut self, brush: &ID, pen: Option<&ID>, p0: (f32, f32), p1: (f32, f32), width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawLine{ brush: brush.clone(), pen: pen.cloned(), start: p0, end: p1, width: width })
+    }
+
+    fn draw_polyline(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawPolyline{ brush: brush.clone(), pen: pen.cloned(), points: points.to_vec(), width: width })
+    }
+
+    fn draw_polygon(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawPolygon{ brush: brush.clone(), pen: pen.cloned(), points: points.to_vec(), width: width })
+    }
+
+    fn fill_polygon(&mut self, brush: &ID, points: &[(f32, f32)]) -> Result<(), Error> {
+        self.push(DrawCommand::FillPolygon{ brush: brush.clone(), points: points.to_vec() })
+    }
+
+    fn draw_path(&mut self, brush: &ID, pen: Option<&ID>, path: &Path, width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawPath{ brush: brush.clone(), pen: pen.cloned(), path: path.clone(), width: width })
+    }
+
+    fn fill_path(&mut self, brush: &ID, path: &Path) -> Result<(), Error> {
+        self.push(DrawCommand::FillPath{ brush: brush.clone(), path: path.clone() })
+    }
+
+    fn draw_saved_path(&mut self, brush: &ID, pen: Option<&ID>, path: &ID, width: f32) -> Result<(), Error> {
+        self.push(DrawCommand::DrawSavedPath{ brush: brush.clone(), pen: pen.cloned(), path: path.clone(), width: width })
+    }
+
+    fn fill_saved_path(&mut self, brush: &ID, path: &ID) -> Result<(), Error> {
+        self.push(DrawCommand::FillSavedPath{ brush: brush.clone(), path: path.clone() })
+    }
+
+    fn draw_text(&mut self, brush: &ID, format: &ID, text: &str, r: &Rectangle) -> Result<(), Error> {
+        self.push(DrawCommand::DrawText{ brush: brush.clone(), format: format.clone(), text: text.to_string(), rect: r.clone() })
+    }
+
+    fn draw_text_layout(&mut self, brush: &ID, layout: &TextLayout, origin: (f32, f32)) -> Result<(), Error> {
+        self.push(DrawCommand::DrawTextLayout{ brush: brush.clone(), text: layout.get_text().to_string(), max_size: layout.get_max_size(), origin: origin })
+    }
+
+    fn draw_bitmap(&mut self, bitmap: &ID, dest: &Rectangle, source: Option<&Rectangle>, opacity: f32, interpolation: BitmapInterpolation) -> Result<(), Error> {
+        self.push(DrawCommand::DrawBitmap{ bitmap: bitmap.clone(), dest: dest.clone(), source: source.cloned(), opacity: opacity, interpolation: interpolation })
+    }
+
+}
+
+impl<ID: Clone+Hash+Display> Display for DrawCommand<ID> {
+
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            &DrawCommand::Clear{color: (r, g, b, a)} => write!(f, "clear {},{},{},{}", r, g, b, a),
+            &DrawCommand::SetTransform{ref matrix} => {
+                let m = matrix;
+                write!(f, "set_transform {},{},{},{},{},{}", m[0][0], m[0][1], m[1][0], m[1][1], m[2][0], m[2][1])
+            },
+            &DrawCommand::FillRectangle{ref brush, ref rect} => write!(f, "fill_rectangle {} {}", brush, TextRect(rect)),
+            &DrawCommand::FillRoundedRectangle{ref brush, ref rect, radius} => 
+                write!(f, "fill_rounded_rectangle {} {} {}", brush, TextRect(rect), TextPoint(radius)),
+            &DrawCommand::FillEllipse{ref brush, ref ellipse} => write!(f, "fill_ellipse {} {}", brush, TextEllipse(ellipse)),
+            &DrawCommand::DrawRectangle{ref brush, ref pen, ref rect, width} => 
+                write!(f, "draw_rectangle {} {} {} {}", brush, TextPen(pen), TextRect(rect), width),
+            &DrawCommand::DrawRoundedRectangle{ref brush, ref pen, ref rect, width, radius} => 
+                write!(f, "draw_rounded_rectangle {} {} {} {} {}", brush, TextPen(pen), TextRect(rect), width, TextPoint(radius)),
+            &DrawCommand::DrawEllipse{ref brush, ref pen, ref ellipse, width} => 
+                write!(f, "draw_ellipse {} {} {} {}", brush, TextPen(pen), TextEllipse(ellipse), width),
+            &DrawCommand::DrawLine{ref brush, ref pen, start, end, width} => 
+                write!(f, "draw_line {} {} {} {} {}", brush, TextPen(pen), TextPoint(start), TextPoint(end), width),
+            &DrawCommand::DrawPolyline{ref brush, ref pen, ref points, width} => 
+                write!(f, "draw_polyline {} {} {} {}", brush, TextPen(pen), TextPoints(points), width),
+            &DrawCommand::DrawPolygon{ref brush, ref pen, ref points, width} => 
+                write!(f, "draw_polygon {} {} {} {}", brush, TextPen(pen), TextPoints(points), width),
+            &DrawCommand::FillPolygon{ref brush, ref points} => write!(f, "fill_polygon {} {}", brush, TextPoints(points)),
+            &DrawCommand::DrawPath{ref brush, ref pen, ref path, width} => 
+                write!(f, "draw_path {} {} {} {}", brush, TextPen(pen), TextPath(path), width),
+            &DrawCommand::FillPath{ref brush, ref path} => write!(f, "fill_path {} {}", brush, TextPath(path)),
+            &DrawCommand::DrawSavedPath{ref brush, ref pen, ref path, width} => 
+                write!(f, "draw_saved_path {} {} {} {}", brush, TextPen(pen), path, width),
+            &DrawCommand::FillSavedPath{ref brush, ref path} => write!(f, "fill_saved_path {} {}", brush, path),
+            &DrawCommand::DrawText{ref brush, ref format, ref text, ref rect} => 
+                write!(f, "draw_text {} {} {} {}", brush, format, TextString(text), TextRect(rect)),
+            &DrawCommand::DrawTextLayout{ref brush, ref text, max_size, origin} => 
+                write!(f, "draw_text_layout {} {} {} {}", brush, TextString(text), TextPoint(max_size), TextPoint(origin)),
+            &DrawCommand::DrawBitmap{ref bitmap, ref dest, ref source, opacity, interpolation} => {
+                let interpolation = match interpolation {
+                    BitmapInterpolation::NearestNeighbor => "nearest_neighbor",
+                    BitmapInterpolation::Linear => "linear"
+                };
+
+                try!(write!(f, "draw_bitmap {} {} ", bitmap, TextRect(dest)));
+                match source {
+                    &Some(ref s) => try!(write!(f, "{}", TextRect(s))),
+                    &None => try!(write!(f, "-"))
+                }
+                write!(f, " {} {}", opacity, interpolation)
+            }
+        }
+    }
+
+}
+
+/*
+    Values of the draw commands written in the text format of `RecordingRenderer::to_text`
+*/
+
+struct TextPoint((f32, f32));
+struct TextRect<'a>(&'a Rectangle);
+struct TextEllipse<'a>(&'a Ellipse);
+struct TextPen<'a, ID: 'a>(&'a Option<ID>);
+struct TextPoints<'a>(&'a [(f32, f32)]);
+struct TextString<'a>(&'a str);
+struct TextPath<'a>(&'a Path);
+
+impl Display for TextPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", (self.0).0, (self.0).1)
+    }
+}
+
+impl<'a> Display for TextRect<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.0.left, self.0.top, self.0.right, self.0.bottom)
+    }
+}
+
+impl<'a> Display for TextEllipse<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let e = self.0;
+        write!(f, "{},{},{},{}", e.center.0, e.center.1, e.radius.0, e.radius.1)
+    }
+}
+
+impl<'a, ID: Display> Display for TextPen<'a, ID> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            &Some(ref pen) => write!(f, "{}", pen),
+            &None => write!(f, "-")
+        }
+    }
+}
+
+impl<'a> Display for TextPoints<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(write!(f, "{}", self.0.len()));
+        for &p in self.0.iter() {
+            try!(write!(f, " {}", TextPoint(p)));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Display for TextString<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        try!(f.write_char('"'));
+        for c in self.0.chars() {
+            try!(match c {
+                '"' => f.write_str("\\\""),
+                '\\' => f.write_str("\\\\"),
+                '\n' => f.write_str("\\n"),
+                '\r' => f.write_str("\\r"),
+                '\t' => f.write_str("\\t"),
+                c => f.write_char(c)
+            });
+        }
+        f.write_char('"')
+    }
+}
+
+impl<'a> Display for TextPath<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = self.0;
+        let fill_mode = match path.fill_mode {
+            FillMode::Alternate => "alternate",
+            FillMode::Winding => "winding"
+        };
+
+        try!(write!(f, "{} {}", fill_mode, path.figures.len()));
+        for figure in path.figures.iter() {
+            let closed = if figure.closed { "closed" } else { "open" };
+            try!(write!(f, " {} {} {}", TextPoint(figure.start), closed, figure.segments.len()));
+
+            for segment in figure.segments.iter() {
+                try!(match segment {
+                    &PathSegment::Line{end} => write!(f, " line {}", TextPoint(end)),
+                    &PathSegment::QuadraticBezier{control, end} => write!(f, " quadratic {} {}", TextPoint(control), TextPoint(end)),
+                    &PathSegment::CubicBezier{control1, control2, end} => 
+                        write!(f, " cubic {} {} {}", TextPoint(control1), TextPoint(control2), TextPoint(end)),
+                    &PathSegment::Arc{end, radius, rotation, sweep, size} => {
+                        let sweep = match sweep {
+                            SweepDirection::Clockwise => "clockwise",
+                            SweepDirection::CounterClockwise => "counter_clockwise"
+                        };
+                        let size = match size {
+                            ArcSize::Small => "small",
+                            ArcSize::Large => "large"
+                        };
+                        write!(f, " arc {} {} {} {} {}", TextPoint(end), TextPoint(radius), rotation, sweep, size)
+                    }
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/**
+    Read the values of a line written by `RecordingRenderer::to_text`.
+    The errors are returned as a message, `RecordingRenderer::parse_text` adds the line number.
+*/
+struct TextReader {
+    tokens: ::std::vec::IntoIter<String>
+}
+
+impl TextReader {
+
+    /// Split a line in values. The quoted texts are unescaped.
+    fn new(line: &str) -> Result<TextReader, String> {
+        let mut tokens = Vec::new();
+        let mut chars = line.chars().peekable();
+
+        loop {
+            while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                chars.next();
+            }
+
+            let mut token = String::new();
+            match chars.peek().cloned() {
+                None => break,
+                Some('"') => {
+                    chars.next();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some('n') => token.push('\n'),
+                                Some('r') => token.push('\r'),
+                                Some('t') => token.push('\t'),
+                                Some(c @ '"') | Some(c @ '\\') => token.push(c),
+                                Some(c) => { return Err(format!("Unknown escape sequence \\{}", c)); },
+                                None => { return Err("Unterminated text".to_string()); }
+                            },
+                            Some(c) => token.push(c),
+                            None => { return Err("Unterminated text".to_string()); }
+                        }
+                    }
+                },
+                Some(_) => {
+                    while let Some(c) = chars.peek().cloned() {
+                        if c.is_whitespace() { break; }
+                        token.push(c);
+                        chars.next();
+                    }
+                }
+            }
+
+            tokens.push(token);
+        }
+
+        Ok(TextReader{ tokens: tokens.into_iter() })
+    }
+
+    fn token(&mut self) -> Result<String, String> {
+        match self.tokens.next() {
+            Some(t) => Ok(t),
+            None => Err("Missing value".to_string())
+        }
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        match self.tokens.next() {
+            Some(t) => Err(format!("Unexpected value \"{}\"", t)),
+            None => Ok(())
+        }
+    }
+
+    fn float(&mut self) -> Result<f32, String> {
+        let token = try!(self.token());
+        token.parse::<f32>().map_err(|_| format!("\"{}\" is not a number", token))
+    }
+
+    fn count(&mut self) -> Result<usize, String> {
+        let token = try!(self.token());
+        token.parse::<usize>().map_err(|_| format!("\"{}\" is not a length", token))
+    }
+
+    /// Read `count` numbers separated by commas
+    fn floats(&mut self, count: usize) -> Result<Vec<f32>, String> {
+        let token = try!(self.token());
+        TextReader::parse_floats(&token, count)
+    }
+
+    fn parse_floats(token: &str, count: usize) -> Result<Vec<f32>, String> {
+        let values: Vec<f32> = match token.split(',').map(|v| v.parse::<f32>()).collect() {
+            Ok(v) => v,
+            Err(_) => { return Err(format!("\"{}\" is not a list of numbers", token)); }
+        };
+
+        if values.len() == count {
+            Ok(values)
+        } else {
+            Err(format!("Expected {} numbers, got \"{}\"", count, token))
+        }
+    }
+
+    fn point(&mut self) -> Result<(f32, f32), String> {
+        self.floats(2).map(|v| (v[0], v[1]))
+    }
+
+    fn rect(&mut self) -> Result<Rectangle, String> {
+        self.floats(4).map(|v| Rectangle{ left: v[0], top: v[1], right: v[2], bottom: v[3] })
+    }
+
+    /// Read a rectangle or `-`
+    fn optional_rect(&mut self) -> Result<Option<Rectangle>, String> {
+        let token = try!(self.token());
+        if token == "-" {
+            Ok(None)
+        } else {
+            TextReader::parse_floats(&token, 4).map(|v| Some(Rectangle{ left: v[0], top: v[1], right: v[2], bottom: v[3] }))
+        }
+    }
+
+    fn ellipse(&mut self) -> Result<Ellipse, String> {
+        self.floats(4).map(|v| Ellipse{ center: (v[0], v[1]), radius: (v[2], v[3]) })
+    }
+
+    fn points(&mut self) -> Result<Vec<(f32, f32)>, String> {
+        let count = try!(self.count());
+        (0..count).map(|_| self.point()).collect()
+    }
+
+    fn id<ID: FromStr>(&mut self) -> Result<ID, String> {
+        let token = try!(self.token());
+        token.parse::<ID>().map_err(|_| format!("\"{}\" is not a valid id", token))
+    }
+
+    fn pen<ID: FromStr>(&mut self) -> Result<Option<ID>, String> {
+        let token = try!(self.token());
+        if token == "-" {
+            Ok(None)
+        } else {
+            token.parse::<ID>().map(Some).map_err(|_| format!("\"{}\" is not a valid id", token))
+        }
+    }
+
+    /// Read one of the `names`. Return its index.
+    fn keyword(&mut self, names: &[&str]) -> Result<usize, String> {
+        let token = try!(self.token());
+        match names.iter().position(|&n| n == token) {
+            Some(i) => Ok(i),
+            None => Err(format!("Expected one of {:?}, got \"{}\"", names, token))
+        }
+    }
+
+    fn path(&mut self) -> Result<Path, String> {
+        let fill_mode = match try!(self.keyword(&["alternate", "winding"])) {
+            0 => FillMode::Alternate,
+            _ => FillMode::Winding
+        };
+
+        let mut figures = Vec::new();
+        for _ in 0..try!(self.count()) {
+            let start = try!(self.point());
+            let closed = try!(self.keyword(&["open", "closed"])) == 1;
+            let mut segments = Vec::new();
+
+            for _ in 0..try!(self.count()) {
+                let segment = match try!(self.keyword(&["line", "quadratic", "cubic", "arc"])) {
+                    0 => PathSegment::Line{ end: try!(self.point()) },
+                    1 => PathSegment::QuadraticBezier{ control: try!(self.point()), end: try!(self.point()) },
+                    2 => PathSegment::CubicBezier{ control1: try!(self.point()), control2: try!(self.point()), end: try!(self.point()) },
+                    _ => PathSegment::Arc{
+                        end: try!(self.point()),
+                        radius: try!(self.point()),
+                        rotation: try!(self.float()),
+                        sweep: match try!(self.keyword(&["clockwise", "counter_clockwise"])) {
+                            0 => SweepDirection::Clockwise,
+                            _ => SweepDirection::CounterClockwise
+                        },
+                        size: match try!(self.keyword(&["small", "large"])) {
+                            0 => ArcSize::Small,
+                            _ => ArcSize::Large
+                        }
+                    }
+                };
+                segments.push(segment);
+            }
+
+            figures.push(Figure{ start: start, segments: segments, closed: closed });
+        }
+
+        Ok(Path{ figures: figures, fill_mode: fill_mode })
+    }
+
+}
+
+/// Parse a line written by `DrawCommand::fmt`
+fn parse_command<ID: Clone+Hash+FromStr>(line: &str) -> Result<DrawCommand<ID>, String> {
+    let mut r = try!(TextReader::new(line));
+    let name = try!(r.token());
+
+    let cmd = match name.as_ref() {
+        "clear" => {
+            let c = try!(r.floats(4));
+            DrawCommand::Clear{ color: (c[0], c[1], c[2], c[3]) }
+        },
+        "set_transform" => {
+            let m = try!(r.floats(6));
+            DrawCommand::SetTransform{ matrix: [[m[0], m[1]], [m[2], m[3]], [m[4], m[5]]] }
+        },
+        "fill_rectangle" => DrawCommand::FillRectangle{ brush: try!(r.id()), rect: try!(r.rect()) },
+        "fill_rounded_rectangle" => DrawCommand::FillRoundedRectangle{ brush: try!(r.id()), rect: try!(r.rect()), radius: try!(r.point()) },
+        "fill_ellipse" => DrawCommand::FillEllipse{ brush: try!(r.id()), ellipse: try!(r.ellipse()) },
+        "draw_rectangle" => DrawCommand::DrawRectangle{ brush: try!(r.id()), pen: try!(r.pen()), rect: try!(r.rect()), width: try!(r.float()) },
+        "draw_rounded_rectangle" => DrawCommand::DrawRoundedRectangle{ brush: try!(r.id()), pen: try!(r.pen()), rect: try!(r.rect()),
+            width: try!(r.float()), radius: try!(r.point()) },
+        "draw_ellipse" => DrawCommand::DrawEllipse{ brush: try!(r.id()), pen: try!(r.pen()), ellipse: try!(r.ellipse()), width: try!(r.float()) },
+        "draw_line" => DrawCommand::DrawLine{ brush: try!(r.id()), pen: try!(r.pen()), start: try!(r.point()), end: try!(r.point()),
+            width: try!(r.float()) },
+        "draw_polyline" => DrawCommand::DrawPolyline{ brush: try!(r.id()), pen: try!(r.pen()), points: try!(r.points()), width: try!(r.float()) },
+        "draw_polygon" => DrawCommand::DrawPolygon{ brush: try!(r.id()), pen: try!(r.pen()), points: try!(r.points()), width: try!(r.float()) },
+        "fill_polygon" => DrawCommand::FillPolygon{ brush: try!(r.id()), points: try!(r.points()) },
+        "draw_path" => DrawCommand::DrawPath{ brush: try!(r.id()), pen: try!(r.pen()), path: try!(r.path()), width: try!(r.float()) },
+        "fill_path" => DrawCommand::FillPath{ brush: try!(r.id()), path: try!(r.path()) },
+        "draw_saved_path" => DrawCommand::DrawSavedPath{ brush: try!(r.id()), pen: try!(r.pen()), path: try!(r.id()), width: try!(r.float()) },
+        "fill_saved_path" => DrawCommand::FillSavedPath{ brush: try!(r.id()), path: try!(r.id()) },
+        "draw_text" => DrawCommand::DrawText{ brush: try!(r.id()), format: try!(r.id()), text: try!(r.token()), rect: try!(r.rect()) },
+        "draw_text_layout" => DrawCommand::DrawTextLayout{ brush: try!(r.id()), text: try!(r.token()), max_size: try!(r.point()),
+            origin: try!(r.point()) },
+        "draw_bitmap" => {
+            let bitmap = try!(r.id());
+            let dest = try!(r.rect());
+            let source = try!(r.optional_rect());
+            let opacity = try!(r.float());
+            let interpolation = match try!(r.keyword(&["nearest_neighbor", "linear"])) {
+                0 => BitmapInterpolation::NearestNeighbor,
+                _ => BitmapInterpolation::Linear
+            };
+
+            DrawCommand::DrawBitmap{ bitmap: bitmap, dest: dest, source: source, opacity: opacity, interpolation: interpolation }
+        },
+        _ => { return Err(format!("Unknown command \"{}\"", name)); }
+    };
+
+    try!(r.end());
+    Ok(cmd)
+}
@@ -25,11 +25,11 @@ use std::mem;
 use std::ops::{Deref, DerefMut};
 
 use winapi::{FLOAT, D2D1_RECT_F, D2D1_ROUNDED_RECT, D2D1_ELLIPSE, D2D1_POINT_2F, 
-  D2D1_MATRIX_3X2_F, ID2D1Brush, ID2D1StrokeStyle};
+  D2D1_MATRIX_3X2_F, ID2D1Brush, ID2D1StrokeStyle, ID2D1Geometry, ID2D1PathGeometry, ID2D1Bitmap, IDWriteTextFormat};
 
 use error::Error;
-use defs::{Rectangle, Ellipse};
-use super::{Canvas, CanvasProtected, CanvasResources};
+use defs::{Rectangle, Ellipse, BitmapInterpolation};
+use super::{Canvas, CanvasProtected, CanvasResources, Renderer, Path, TextLayout, TextLayoutProtected};
 
 /**
     Object that offers a light wrapper over the D2D1 api.
@@ -210,22 +210,273 @@ impl<'a, ID: Clone+Hash> CanvasRenderer<'a, ID> {
         Ok(())
     }
 
+    /**
+        Draw a line between the points `p0` and `p1` using the brush identified by `brush` and the pen identifed
+        by `pen`.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `pen`: Id of the pen saved in the canvas  
+        • `p0`: The start point of the line  
+        • `p1`: The end point of the line  
+        • `width`: Width of the line to draw  
+    */
+    pub fn draw_line(&mut self, brush: &ID, pen: Option<&ID>, p0: (f32, f32), p1: (f32, f32), width: f32) -> Result<(), Error> {
+        let p0 = D2D1_POINT_2F{ x: p0.0, y: p0.1 };
+        let p1 = D2D1_POINT_2F{ x: p1.0, y: p1.1 };
+        let (brush, pen) = match self.draw_setup(brush, pen) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ self.DrawLine(p0, p1, mem::transmute(brush), width, mem::transmute(pen)); }
+
+        Ok(())
+    }
+
+    /**
+        Draw lines that join `points` using the brush identified by `brush` and the pen identifed by `pen`.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `pen`: Id of the pen saved in the canvas  
+        • `points`: The points to join  
+        • `width`: Width of the lines to draw  
+    */
+    pub fn draw_polyline(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        self.draw_path(brush, pen, &Path::polyline(points), width)
+    }
+
+    /**
+        Draw the outline of a polygon using the brush identified by `brush` and the pen identifed by `pen`.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `pen`: Id of the pen saved in the canvas  
+        • `points`: The corners of the polygon  
+        • `width`: Width of the outline to draw  
+    */
+    pub fn draw_polygon(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        self.draw_path(brush, pen, &Path::polygon(points), width)
+    }
+
+    /**
+        Fill a polygon using the brush identified by `brush`  
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `points`: The corners of the polygon  
+    */
+    pub fn fill_polygon(&mut self, brush: &ID, points: &[(f32, f32)]) -> Result<(), Error> {
+        self.fill_path(brush, &Path::polygon(points))
+    }
+
+    /**
+        Draw the outline of `path` using the brush identified by `brush` and the pen identifed by `pen`.
+        The path geometry is built and released on every call. Use `Canvas::create_path` and `draw_saved_path`
+        to draw the same path many times.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `pen`: Id of the pen saved in the canvas  
+        • `path`: The path to draw  
+        • `width`: Width of the outline to draw  
+    */
+    pub fn draw_path(&mut self, brush: &ID, pen: Option<&ID>, path: &Path, width: f32) -> Result<(), Error> {
+        let (brush, pen) = match self.draw_setup(brush, pen) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        let geometry = match self.build_geometry(path) {
+            Ok(g) => g,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ 
+            self.DrawGeometry(geometry as *mut ID2D1Geometry, mem::transmute(brush), width, mem::transmute(pen));
+            (&mut *geometry).Release();
+        }
+
+        Ok(())
+    }
+
+    /**
+        Fill `path` using the brush identified by `brush`. The path geometry is built and released on every call.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `path`: The path to fill  
+    */
+    pub fn fill_path(&mut self, brush: &ID, path: &Path) -> Result<(), Error> {
+        let brush = match self.fill_setup(brush) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        let geometry = match self.build_geometry(path) {
+            Ok(g) => g,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ 
+            self.FillGeometry(geometry as *mut ID2D1Geometry, mem::transmute(brush), ptr::null_mut());
+            (&mut *geometry).Release();
+        }
+
+        Ok(())
+    }
+
+    /**
+        Draw the outline of the path identified by `path` using the brush identified by `brush` and the pen identifed
+        by `pen`.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `pen`: Id of the pen saved in the canvas  
+        • `path`: Id of the path saved in the canvas  
+        • `width`: Width of the outline to draw  
+    */
+    pub fn draw_saved_path(&mut self, brush: &ID, pen: Option<&ID>, path: &ID, width: f32) -> Result<(), Error> {
+        let (brush, pen) = match self.draw_setup(brush, pen) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        let geometry = match self.path_setup(path) {
+            Ok(g) => g,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ self.DrawGeometry(geometry as *mut ID2D1Geometry, mem::transmute(brush), width, mem::transmute(pen)); }
+
+        Ok(())
+    }
+
+    /**
+        Fill the path identified by `path` using the brush identified by `brush`  
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `path`: Id of the path saved in the canvas  
+    */
+    pub fn fill_saved_path(&mut self, brush: &ID, path: &ID) -> Result<(), Error> {
+        let brush = match self.fill_setup(brush) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        let geometry = match self.path_setup(path) {
+            Ok(g) => g,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ self.FillGeometry(geometry as *mut ID2D1Geometry, mem::transmute(brush), ptr::null_mut()); }
+
+        Ok(())
+    }
+
+    /**
+        Write `text` in the rectangle `r` using the brush identified by `brush` and the text format identified by `format`.
+        The text is laid out every time it is drawn. Use `Canvas::create_text_layout` and `draw_text_layout` to draw
+        the same text many times.
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `format`: Id of the text format saved in the canvas  
+        • `text`: The text to write  
+        • `r`: The layout box of the text  
+    */
+    pub fn draw_text(&mut self, brush: &ID, format: &ID, text: &str, r: &Rectangle) -> Result<(), Error> {
+        use winapi::{D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL};
+
+        let rect = D2D1_RECT_F{left: r.left, top: r.top, bottom: r.bottom, right: r.right};
+        let brush = match self.fill_setup(brush) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        let format = match self.text_format_setup(format) {
+            Ok(f) => f,
+            Err(e) => { return Err(e); }
+        };
+
+        let text: Vec<u16> = text.encode_utf16().collect();
+        unsafe{ 
+            self.DrawText(text.as_ptr(), text.len() as u32, format, &rect, mem::transmute(brush),
+              D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL);
+        }
+
+        Ok(())
+    }
+
+    /**
+        Draw a text layout with its top left corner at `origin` using the brush identified by `brush`
+
+        Arguments:  
+        • `brush`: Id of the brush saved in the canvas  
+        • `layout`: The text layout to draw  
+        • `origin`: The position of the top left corner of the layout box  
+    */
+    pub fn draw_text_layout(&mut self, brush: &ID, layout: &TextLayout, origin: (f32, f32)) -> Result<(), Error> {
+        use winapi::D2D1_DRAW_TEXT_OPTIONS_NONE;
+
+        let origin = D2D1_POINT_2F{ x: origin.0, y: origin.1 };
+        let brush = match self.fill_setup(brush) {
+            Ok(d) => d,
+            Err(e) => { return Err(e); }
+        };
+
+        unsafe{ self.DrawTextLayout(origin, layout.layout(), mem::transmute(brush), D2D1_DRAW_TEXT_OPTIONS_NONE); }
+
+        Ok(())
+    }
+
+    /**
+        Draw the bitmap identified by `bitmap`, scaled to fit the rectangle `dest`.
+
+        Arguments:  
+        • `bitmap`: Id of the bitmap saved in the canvas  
+        • `dest`: The rectangle where the bitmap is drawn  
+        • `source`: The part of the bitmap to draw, in pixels. If `None`, the whole bitmap is drawn  
+        • `opacity`: The opacity of the bitmap. 0.0 - 1.0  
+        • `interpolation`: How the bitmap is scaled  
+    */
+    pub fn draw_bitmap(&mut self, bitmap: &ID, dest: &Rectangle, source: Option<&Rectangle>, opacity: f32, interpolation: BitmapInterpolation) -> Result<(), Error> {
+        use winapi::D2D1_BITMAP_INTERPOLATION_MODE;
+
+        let dest = D2D1_RECT_F{left: dest.left, top: dest.top, bottom: dest.bottom, right: dest.right};
+        let source = source.map(|r| D2D1_RECT_F{left: r.left, top: r.top, bottom: r.bottom, right: r.right});
+        let bitmap = match self.bitmap_setup(bitmap) {
+            Ok(b) => b,
+            Err(e) => { return Err(e); }
+        };
+
+        let source_ptr = match source.as_ref() {
+            Some(r) => r as *const D2D1_RECT_F,
+            None => ptr::null()
+        };
+
+        unsafe{ self.DrawBitmap(bitmap, &dest, opacity, D2D1_BITMAP_INTERPOLATION_MODE(interpolation as u32), source_ptr); }
+
+        Ok(())
+    }
+
     fn fill_setup(&mut self, brush: &ID) -> Result<*mut ID2D1Brush, Error> {
         match self.get_resource(brush) {
             Ok(brush) => match brush {
                 CanvasResources::SolidBrush(b) => unsafe{ Ok(mem::transmute(b)) },
-                CanvasResources::StrokeStyle(_) => Err(Error::BadResource("Resource of type brush required, got Brush.".to_string()))
+                CanvasResources::LinearGradientBrush(b) => unsafe{ Ok(mem::transmute(b)) },
+                CanvasResources::RadialGradientBrush(b) => unsafe{ Ok(mem::transmute(b)) },
+                r => Err(Error::BadResource(format!("Resource of type brush required, got {}.", r.type_name())))
             },
             Err(e) => Err(e)
         }
     }
 
     fn draw_setup(&mut self, brush: &ID, pen: Option<&ID>) -> Result<(*mut ID2D1Brush, *mut ID2D1StrokeStyle), Error> {
-        let brush = match self.get_resource(brush) {
-            Ok(brush) => match brush {
-                CanvasResources::SolidBrush(b) => b,
-                CanvasResources::StrokeStyle(_) => { return Err(Error::BadResource("Resource of type brush required, got Pen.".to_string())); }
-            },
+        let brush = match self.fill_setup(brush) {
+            Ok(b) => b,
             Err(e) => { return Err(e); }
         };
 
@@ -233,14 +484,44 @@ impl<'a, ID: Clone+Hash> CanvasRenderer<'a, ID> {
             Some(pen) => match self.get_resource(pen) {
                 Ok(pen) => match pen {
                     CanvasResources::StrokeStyle(s) => { s },
-                    CanvasResources::SolidBrush(_) => { return Err(Error::BadResource("Resource of type pen required, got Brush.".to_string())); },
+                    r => { return Err(Error::BadResource(format!("Resource of type pen required, got {}.", r.type_name()))); }
                 },
                 Err(e) => { return Err(e); }
             },
             None => ptr::null_mut()
         };
 
-        unsafe{ Ok(( mem::transmute(brush), pen)) }
+        Ok((brush, pen))
+    }
+
+    fn path_setup(&mut self, path: &ID) -> Result<*mut ID2D1PathGeometry, Error> {
+        match self.get_resource(path) {
+            Ok(path) => match path {
+                CanvasResources::Path(p) => Ok(p),
+                r => Err(Error::BadResource(format!("Resource of type path required, got {}.", r.type_name())))
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    fn bitmap_setup(&mut self, bitmap: &ID) -> Result<*mut ID2D1Bitmap, Error> {
+        match self.get_resource(bitmap) {
+            Ok(bitmap) => match bitmap {
+                CanvasResources::Bitmap(b) => Ok(b),
+                r => Err(Error::BadResource(format!("Resource of type bitmap required, got {}.", r.type_name())))
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    fn text_format_setup(&mut self, format: &ID) -> Result<*mut IDWriteTextFormat, Error> {
+        match self.get_resource(format) {
+            Ok(format) => match format {
+                CanvasResources::TextFormat(f) => Ok(f),
+                r => Err(Error::BadResource(format!("Resource of type text format required, got {}.", r.type_name())))
+            },
+            Err(e) => Err(e)
+        }
     }
 
 }
@@ -264,6 +545,81 @@ impl<'a, ID: Clone+Hash> Drop for CanvasRenderer<'a, ID> {
     }
 }
 
+impl<'a, ID: Clone+Hash> Renderer<ID> for CanvasRenderer<'a, ID> {
+    fn clear(&mut self, r:f32, g:f32, b: f32, a: f32) { CanvasRenderer::clear(self, r, g, b, a) }
+    fn get_render_size(&mut self) -> (f32, f32) { CanvasRenderer::get_render_size(self) }
+    fn set_transform(&mut self, m: &[[FLOAT; 2]; 3]) { CanvasRenderer::set_transform(self, m) }
+    fn get_transform(&mut self) -> [[FLOAT; 2]; 3] { CanvasRenderer::get_transform(self) }
+
+    fn fill_rectangle(&mut self, brush: &ID, r: &Rectangle) -> Result<(), Error> {
+        CanvasRenderer::fill_rectangle(self, brush, r)
+    }
+
+    fn fill_rounded_rectangle(&mut self, brush: &ID, r: &Rectangle, radius: (f32, f32)) -> Result<(), Error> {
+        CanvasRenderer::fill_rounded_rectangle(self, brush, r, radius)
+    }
+
+    fn fill_ellipse(&mut self, brush: &ID, e: &Ellipse) -> Result<(), Error> {
+        CanvasRenderer::fill_ellipse(self, brush, e)
+    }
+
+    fn draw_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_rectangle(self, brush, pen, r, width)
+    }
+
+    fn draw_rounded_rectangle(&mut self, brush: &ID, pen: Option<&ID>, r: &Rectangle, width: f32, radius: (f32, f32)) -> Result<(), Error> {
+        CanvasRenderer::draw_rounded_rectangle(self, brush, pen, r, width, radius)
+    }
+
+    fn draw_ellipse(&mut self, brush: &ID, pen: Option<&ID>, e: &Ellipse, width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_ellipse(self, brush, pen, e, width)
+    }
+
+    fn draw_line(&mut self, brush: &ID, pen: Option<&ID>, p0: (f32, f32), p1: (f32, f32), width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_line(self, brush, pen, p0, p1, width)
+    }
+
+    fn draw_polyline(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_polyline(self, brush, pen, points, width)
+    }
+
+    fn draw_polygon(&mut self, brush: &ID, pen: Option<&ID>, points: &[(f32, f32)], width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_polygon(self, brush, pen, points, width)
+    }
+
+    fn fill_polygon(&mut self, brush: &ID, points: &[(f32, f32)]) -> Result<(), Error> {
+        CanvasRenderer::fill_polygon(self, brush, points)
+    }
+
+    fn draw_path(&mut self, brush: &ID, pen: Option<&ID>, path: &Path, width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_path(self, brush, pen, path, width)
+    }
+
+    fn fill_path(&mut self, brush: &ID, path: &Path) -> Result<(), Error> {
+        CanvasRenderer::fill_path(self, brush, path)
+    }
+
+    fn draw_saved_path(&mut self, brush: &ID, pen: Option<&ID>, path: &ID, width: f32) -> Result<(), Error> {
+        CanvasRenderer::draw_saved_path(self, brush, pen, path, width)
+    }
+
+    fn fill_saved_path(&mut self, brush: &ID, path: &ID) -> Result<(), Error> {
+        CanvasRenderer::fill_saved_path(self, brush, path)
+    }
+
+    fn draw_text(&mut self, brush: &ID, format: &ID, text: &str, r: &Rectangle) -> Result<(), Error> {
+        CanvasRenderer::draw_text(self, brush, format, text, r)
+    }
+
+    fn draw_text_layout(&mut self, brush: &ID, layout: &TextLayout, origin: (f32, f32)) -> Result<(), Error> {
+        CanvasRenderer::draw_text_layout(self, brush, layout, origin)
+    }
+
+    fn draw_bitmap(&mut self, bitmap: &ID, dest: &Rectangle, source: Option<&Rectangle>, opacity: f32, interpolation: BitmapInterpolation) -> Result<(), Error> {
+        CanvasRenderer::draw_bitmap(self, bitmap, dest, source, opacity, interpolation)
+    }
+}
+
 /**
     Protected renderer method (only available in the canvas control module)
 */
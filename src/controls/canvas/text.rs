@@ -0,0 +1,221 @@
+/*!
+    Text layouts created by a canvas from a text format, and the metrics returned when the text is measured
+    or hit tested. Outside of Windows, a text layout only holds its text and its size.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+#[cfg(windows)] use std::mem;
+#[cfg(not(windows))] use std::cell::Cell;
+
+#[cfg(windows)] use winapi::{IDWriteTextLayout, DWRITE_TEXT_METRICS, DWRITE_HIT_TEST_METRICS, BOOL};
+
+/**
+    The size of a text once it is laid out.
+
+    Members:  
+    • `left`, `top`: The position of the text in its layout box  
+    • `width`: The width of the text, without the trailing whitespaces  
+    • `height`: The height of the text  
+    • `layout_size`: The size of the layout box (`width`, `height`)  
+    • `line_count`: The number of lines of the text  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextMetrics {
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    pub layout_size: (f32, f32),
+    pub line_count: u32
+}
+
+/**
+    The part of a text found by a hit test.
+
+    Members:  
+    • `text_position`: The index of the first utf-16 character of the hit text  
+    • `length`: The number of utf-16 characters of the hit text  
+    • `left`, `top`, `width`, `height`: The box of the hit text in the layout box  
+    • `inside`: If the tested point is inside the text. Always `true` when testing a text position  
+    • `trailing`: If the tested point is on the trailing side of the hit character  
+    • `trimmed`: If the hit text is cut by the text trimming  
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HitTestMetrics {
+    pub text_position: u32,
+    pub length: u32,
+    pub left: f32,
+    pub top: f32,
+    pub width: f32,
+    pub height: f32,
+    pub inside: bool,
+    pub trailing: bool,
+    pub trimmed: bool
+}
+
+/**
+    A text that was formatted and laid out by a canvas. Create it with `Canvas::create_text_layout`
+    and draw it with `CanvasRenderer::draw_text_layout`.
+    The layout can be kept between paints, it does not depend on the canvas render target.
+
+    Outside of Windows, there is no canvas: create the layout with `TextLayout::new` to draw it with a `RecordingRenderer`.
+*/
+pub struct TextLayout {
+    #[cfg(windows)]
+    layout: *mut IDWriteTextLayout,
+    #[cfg(not(windows))]
+    max_size: Cell<(f32, f32)>,
+    text: String
+}
+
+impl TextLayout {
+
+    /// Return the text of the layout
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+}
+
+#[cfg(not(windows))]
+impl TextLayout {
+
+    /// Create a text layout with a layout width and height
+    pub fn new(text: &str, max_size: (f32, f32)) -> TextLayout {
+        TextLayout{ max_size: Cell::new(max_size), text: text.to_string() }
+    }
+
+    /// Return the layout width and height
+    pub fn get_max_size(&self) -> (f32, f32) {
+        self.max_size.get()
+    }
+
+    /// Set the layout width and height
+    pub fn set_max_size(&self, w: f32, h: f32) {
+        self.max_size.set((w, h));
+    }
+
+}
+
+#[cfg(windows)]
+impl TextLayout {
+
+    /// Return the size of the laid out text
+    pub fn metrics(&self) -> TextMetrics {
+        unsafe {
+            let mut m: DWRITE_TEXT_METRICS = mem::zeroed();
+            (&mut *self.layout).GetMetrics(&mut m);
+            TextMetrics {
+                left: m.left,
+                top: m.top,
+                width: m.width,
+                height: m.height,
+                layout_size: (m.layoutWidth, m.layoutHeight),
+                line_count: m.lineCount
+            }
+        }
+    }
+
+    /**
+        Return the text under the point (`x`, `y`). The point is relative to the layout box.
+        If the point is outside the text, the nearest character is returned.
+    */
+    pub fn hit_test_point(&self, x: f32, y: f32) -> HitTestMetrics {
+        unsafe {
+            let (mut trailing, mut inside): (BOOL, BOOL) = (0, 0);
+            let mut m: DWRITE_HIT_TEST_METRICS = mem::zeroed();
+            (&mut *self.layout).HitTestPoint(x, y, &mut trailing, &mut inside, &mut m);
+            hit_test_metrics(&m, inside != 0, trailing != 0)
+        }
+    }
+
+    /**
+        Return the position of the caret before the utf-16 character at `position` (or after if `trailing` is `true`),
+        and the metrics of this character.
+    */
+    pub fn hit_test_position(&self, position: u32, trailing: bool) -> ((f32, f32), HitTestMetrics) {
+        unsafe {
+            let (mut x, mut y) = (0.0, 0.0);
+            let mut m: DWRITE_HIT_TEST_METRICS = mem::zeroed();
+            (&mut *self.layout).HitTestTextPosition(position, trailing as BOOL, &mut x, &mut y, &mut m);
+            ((x, y), hit_test_metrics(&m, true, trailing))
+        }
+    }
+
+    /// Return the layout width and height
+    pub fn get_max_size(&self) -> (f32, f32) {
+        unsafe {
+            let layout = &mut *self.layout;
+            (layout.GetMaxWidth(), layout.GetMaxHeight())
+        }
+    }
+
+    /// Set the layout width and height. The text is laid out again.
+    pub fn set_max_size(&self, w: f32, h: f32) {
+        unsafe {
+            let layout = &mut *self.layout;
+            layout.SetMaxWidth(w);
+            layout.SetMaxHeight(h);
+        }
+    }
+
+}
+
+#[cfg(windows)]
+impl Drop for TextLayout {
+    fn drop(&mut self) {
+        unsafe{ (&mut *self.layout).Release(); }
+    }
+}
+
+/**
+    Protected text layout methods (only available in the canvas control module)
+*/
+#[cfg(windows)]
+pub trait TextLayoutProtected {
+    fn create(layout: *mut IDWriteTextLayout, text: &str) -> TextLayout;
+    fn layout(&self) -> *mut IDWriteTextLayout;
+}
+
+#[cfg(windows)]
+impl TextLayoutProtected for TextLayout {
+
+    fn create(layout: *mut IDWriteTextLayout, text: &str) -> TextLayout {
+        TextLayout{ layout: layout, text: text.to_string() }
+    }
+
+    fn layout(&self) -> *mut IDWriteTextLayout {
+        self.layout
+    }
+
+}
+
+#[cfg(windows)]
+fn hit_test_metrics(m: &DWRITE_HIT_TEST_METRICS, inside: bool, trailing: bool) -> HitTestMetrics {
+    HitTestMetrics {
+        text_position: m.textPosition,
+        length: m.length,
+        left: m.left,
+        top: m.top,
+        width: m.width,
+        height: m.height,
+        inside: inside,
+        trailing: trailing,
+        trimmed: m.isTrimmed != 0
+    }
+}
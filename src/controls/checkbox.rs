@@ -22,12 +22,13 @@ use std::hash::Hash;
 use std::any::TypeId;
 
 use winapi::{HWND, HFONT};
-use user32::SendMessageW;
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventDecoders};
+use controls::button::decode_button_command;
 use defs::CheckState;
 
 /**
@@ -67,6 +68,10 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for CheckBoxT<S, ID> {
         vec![Event::Destroyed, Event::Click, Event::DoubleClick, Event::Focus, Event::Moved, Event::Resized, Event::Raw]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_button_command), notify: None }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, BS_NOTIFY, BS_AUTO3STATE, BS_AUTOCHECKBOX, BS_TEXT};
@@ -129,7 +134,7 @@ impl CheckBox {
     */
     pub fn get_checkstate(&self) -> CheckState {
         use low::defs::{BM_GETCHECK, BST_CHECKED, BST_UNCHECKED};
-        match unsafe{ SendMessageW(self.handle, BM_GETCHECK, 0 , 0) as u32 } {
+        match unsafe{ send_message(self.handle, BM_GETCHECK, 0 , 0) as u32 } {
             BST_CHECKED => CheckState::Checked,
             BST_UNCHECKED => CheckState::Unchecked,
             _ => CheckState::Indeterminate
@@ -166,8 +171,8 @@ impl Control for CheckBox {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
@@ -184,5 +189,5 @@ unsafe fn set_checkstate(handle: HWND, check: &CheckState) {
         &CheckState::Indeterminate => BST_INDETERMINATE,
         &CheckState::Unchecked => BST_UNCHECKED
     };
-    SendMessageW(handle, BM_SETCHECK, check_state as WPARAM, 0);
+    send_message(handle, BM_SETCHECK, check_state as WPARAM, 0);
 }
\ No newline at end of file
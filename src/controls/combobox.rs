@@ -22,13 +22,13 @@ use std::any::TypeId;
 use std::fmt::Display;
 use std::mem;
 
-use user32::SendMessageW;
+use low::window_helper::send_message;
 use winapi::{HWND, HFONT, WPARAM};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
 use low::other_helper::{to_utf16, from_utf16};
 
 /**
@@ -65,6 +65,10 @@ impl<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<String>> ControlT<I
         vec![Event::Destroyed, Event::SelectionChanged, Event::DoubleClick, Event::Focus, Event::Moved, Event::Resized, Event::Raw]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_combobox_command), notify: None }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
         use low::defs::{CBS_DROPDOWNLIST, CBS_HASSTRINGS, CB_ADDSTRING};
@@ -116,7 +120,7 @@ impl<D: Clone+Display+'static, ID: Hash+Clone, S: Clone+Into<String>> ControlT<I
                     let collection: Vec<D> = self.collection.iter().map(
                         |s|{  
                             let text = to_utf16(format!("{}", s).as_str());
-                            SendMessageW(h, CB_ADDSTRING, 0, mem::transmute(text.as_ptr()));
+                            send_message(h, CB_ADDSTRING, 0, mem::transmute(text.as_ptr()));
                             s.clone() 
                         } 
                     ).collect();
@@ -154,11 +158,11 @@ impl<D: Clone+Display> ComboBox<D> {
     pub fn sync(&self) {
         use low::defs::{CB_RESETCONTENT, CB_ADDSTRING};
 
-        unsafe{ SendMessageW(self.handle, CB_RESETCONTENT, 0, 0); }
+        unsafe{ send_message(self.handle, CB_RESETCONTENT, 0, 0); }
 
         for i in self.collection.iter() {
             let text = to_utf16(format!("{}", i).as_str());
-            unsafe{ SendMessageW(self.handle, CB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
+            unsafe{ send_message(self.handle, CB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
         }
     }
 
@@ -167,7 +171,7 @@ impl<D: Clone+Display> ComboBox<D> {
         use low::defs::CB_ADDSTRING;
 
         let text = to_utf16(format!("{}", item).as_str());
-        unsafe{ SendMessageW(self.handle, CB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
+        unsafe{ send_message(self.handle, CB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
 
         self.collection.push(item);
     }
@@ -176,7 +180,7 @@ impl<D: Clone+Display> ComboBox<D> {
     /// `Panics` if index is out of bounds.
     pub fn remove(&mut self, index: usize) -> D {
         use low::defs::CB_DELETESTRING;
-        unsafe{ SendMessageW(self.handle, CB_DELETESTRING, index as WPARAM, 0); }
+        unsafe{ send_message(self.handle, CB_DELETESTRING, index as WPARAM, 0); }
         self.collection.remove(index)
     }
 
@@ -186,7 +190,7 @@ impl<D: Clone+Display> ComboBox<D> {
         use low::defs::CB_INSERTSTRING;
 
         let text = to_utf16(format!("{}", item).as_str());
-        unsafe{ SendMessageW(self.handle, CB_INSERTSTRING, index as WPARAM, mem::transmute(text.as_ptr())); }
+        unsafe{ send_message(self.handle, CB_INSERTSTRING, index as WPARAM, mem::transmute(text.as_ptr())); }
 
         self.collection.insert(index, item);
     }
@@ -199,7 +203,7 @@ impl<D: Clone+Display> ComboBox<D> {
 
         let text = to_utf16(text);
         let msg = if full_match { CB_FINDSTRINGEXACT } else { CB_FINDSTRING };
-        let index = unsafe{ SendMessageW(self.handle, msg, -1isize as WPARAM, mem::transmute(text.as_ptr()) ) };
+        let index = unsafe{ send_message(self.handle, msg, -1isize as WPARAM, mem::transmute(text.as_ptr()) ) };
 
         if index == -1 {
             None
@@ -213,7 +217,7 @@ impl<D: Clone+Display> ComboBox<D> {
     pub fn get_selected_index(&self) -> Option<usize> {
         use low::defs::CB_GETCURSEL;
 
-        let index = unsafe{ SendMessageW(self.handle, CB_GETCURSEL, 0, 0) };
+        let index = unsafe{ send_message(self.handle, CB_GETCURSEL, 0, 0) };
         if index == -1 { None } 
         else { Some(index as usize) }
     }
@@ -227,21 +231,21 @@ impl<D: Clone+Display> ComboBox<D> {
     /// If `index` is `usize::max_value`, remove the selected index from the combobox
     pub fn set_selected_index(&self, index: usize) {
         use low::defs::CB_SETCURSEL;
-        unsafe{ SendMessageW(self.handle, CB_SETCURSEL, index as WPARAM, 0); }
+        unsafe{ send_message(self.handle, CB_SETCURSEL, index as WPARAM, 0); }
     }
 
     /// Return the item text at the provided index. Returns None if the index is not valid.
     pub fn get_string(&self, index: usize) -> Option<String> {
         use low::defs::{CB_GETLBTEXT, CB_GETLBTEXTLEN};
 
-        let length = unsafe{ SendMessageW(self.handle, CB_GETLBTEXTLEN, index as WPARAM, 0) };
+        let length = unsafe{ send_message(self.handle, CB_GETLBTEXTLEN, index as WPARAM, 0) };
         if length == -1 { return None; }
 
         let length = (length+1) as usize;
         let mut buffer: Vec<u16> = Vec::with_capacity(length);
         unsafe {
             buffer.set_len(length);
-            let err = SendMessageW(self.handle, CB_GETLBTEXT, index as WPARAM, mem::transmute( buffer.as_mut_ptr() ));
+            let err = send_message(self.handle, CB_GETLBTEXT, index as WPARAM, mem::transmute( buffer.as_mut_ptr() ));
             if err == -1 { return None; }
         }
 
@@ -264,7 +268,7 @@ impl<D: Clone+Display> ComboBox<D> {
         let mut buffer: [u16; 256] = [0; 256];
         let mut buffer_size = 0;
 
-        let placeholder_found = unsafe{ SendMessageW(self.handle, CB_GETCUEBANNER, mem::transmute(buffer.as_mut_ptr()), mem::transmute(&mut buffer_size)) };
+        let placeholder_found = unsafe{ send_message(self.handle, CB_GETCUEBANNER, mem::transmute(buffer.as_mut_ptr()), mem::transmute(&mut buffer_size)) };
 
         if placeholder_found == 1 {
             Some(from_utf16(&buffer))
@@ -277,19 +281,19 @@ impl<D: Clone+Display> ComboBox<D> {
     /// Return true if the combobox dropdown is visible
     pub fn get_dropped_state(&self) -> bool {
         use low::defs::CB_GETDROPPEDSTATE;
-        unsafe{ SendMessageW(self.handle, CB_GETDROPPEDSTATE, 0, 0) != 0 }
+        unsafe{ send_message(self.handle, CB_GETDROPPEDSTATE, 0, 0) != 0 }
     }
 
     /// Show or hide the control dropdown
     pub fn set_dropped_state(&self, dropped: bool) {
         use low::defs::CB_SHOWDROPDOWN;
-        unsafe{ SendMessageW(self.handle, CB_SHOWDROPDOWN, dropped as WPARAM, 0); }
+        unsafe{ send_message(self.handle, CB_SHOWDROPDOWN, dropped as WPARAM, 0); }
     }
 
     /// Remove every item in the inner collection and in the combobox
     pub fn clear(&mut self) {
         use low::defs::CB_RESETCONTENT;
-        unsafe{ SendMessageW(self.handle, CB_RESETCONTENT, 0, 0) };
+        unsafe{ send_message(self.handle, CB_RESETCONTENT, 0, 0) };
         self.collection.clear();
     }
 
@@ -314,8 +318,8 @@ impl<D: Clone+Display> Control for ComboBox<D> {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 }
 
@@ -325,5 +329,20 @@ impl<D: Clone+Display> Control for ComboBox<D> {
 fn set_placeholder<S: Into<String>>(handle: HWND, placeholder: S) {
     use low::defs::CB_SETCUEBANNER;
     let text = to_utf16(placeholder.into().as_str());
-    unsafe{ SendMessageW(handle, CB_SETCUEBANNER, 0, mem::transmute(text.as_ptr()) ); }
-}
\ No newline at end of file
+    unsafe{ send_message(handle, CB_SETCUEBANNER, 0, mem::transmute(text.as_ptr()) ); }
+}
+
+unsafe fn decode_combobox_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::defs::{CBN_SELCHANGE, CBN_DBLCLK, CBN_SETFOCUS, CBN_KILLFOCUS, CB_GETCURSEL};
+
+    match ncode {
+        CBN_SELCHANGE => {
+            let index = send_message(handle, CB_GETCURSEL, 0, 0);
+            let index = if index < 0 { None } else { Some(index as usize) };
+            Some((Event::SelectionChanged, EventArgs::Selection(index)))
+        },
+        CBN_DBLCLK => Some((Event::DoubleClick, EventArgs::None)),
+        CBN_SETFOCUS | CBN_KILLFOCUS => Some((Event::Focus, EventArgs::Focus(ncode==CBN_SETFOCUS))),
+        _ => None
+    }
+}
@@ -21,13 +21,13 @@ use std::hash::Hash;
 use std::any::TypeId;
 use std::mem;
 
-use winapi::{HWND, HFONT, DATETIMEPICKERINFO, SYSTEMTIME};
-use user32::SendMessageW;
+use winapi::{HWND, HFONT, NMHDR, DATETIMEPICKERINFO, SYSTEMTIME};
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
 use defs::{HTextAlign, CheckState, PickerDate};
 use low::other_helper::to_utf16;
 
@@ -85,6 +85,10 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for DatePickerT<S, ID>
         vec![Event::Destroyed, Event::Moved, Event::Resized, Event::Raw, Event::DateChanged]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: None, notify: Some(decode_datepicker_notify) }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
         use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, DTS_SHOWNONE, DTS_RIGHTALIGN};
@@ -156,7 +160,7 @@ impl DatePicker {
         use winapi::{DTM_SETFORMATW, LPARAM};
         unsafe{
             let format = to_utf16(format.clone().into().as_str());
-            SendMessageW(self.handle, DTM_SETFORMATW, 0, format.as_ptr() as LPARAM);
+            send_message(self.handle, DTM_SETFORMATW, 0, format.as_ptr() as LPARAM);
         }
     }
 
@@ -180,18 +184,7 @@ impl DatePicker {
         Note: use `get_date_string` to get the text value of the control.
     */
     pub fn get_value(&self) -> Option<PickerDate> {
-        use winapi::{DTM_GETSYSTEMTIME, GDT_VALID};
-        let mut syst: SYSTEMTIME = unsafe{ mem::uninitialized() };
-
-        let r = unsafe{ SendMessageW(self.handle, DTM_GETSYSTEMTIME, 0, mem::transmute(&mut syst)) };
-        match r {
-            GDT_VALID => Some(PickerDate{
-                year: syst.wYear,
-                month: syst.wMonth,
-                day: syst.wDay
-            }),
-            _ => None
-        }
+        unsafe{ get_value(self.handle) }
     }
 
     /**
@@ -209,10 +202,10 @@ impl DatePicker {
                         wDay: date.day, 
                         wDayOfWeek:0, wHour:0, wMinute:0, wSecond:0, wMilliseconds: 0 
                     };
-                    SendMessageW(self.handle, DTM_SETSYSTEMTIME, GDT_VALID as WPARAM, mem::transmute(&syst));
+                    send_message(self.handle, DTM_SETSYSTEMTIME, GDT_VALID as WPARAM, mem::transmute(&syst));
                 },
                 &None => { 
-                    SendMessageW(self.handle, DTM_SETSYSTEMTIME, GDT_NONE as WPARAM, 0); 
+                    send_message(self.handle, DTM_SETSYSTEMTIME, GDT_NONE as WPARAM, 0); 
                 }
             };
         }
@@ -258,7 +251,7 @@ impl DatePicker {
     /// Close the calendar popup if it is open  
     pub fn close_calendar(&self) {
         use winapi::{DTM_CLOSEMONTHCAL};
-        unsafe{ SendMessageW(self.handle, DTM_CLOSEMONTHCAL, 0, 0); }
+        unsafe{ send_message(self.handle, DTM_CLOSEMONTHCAL, 0, 0); }
     }
 
     pub fn get_value_string(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.handle) } }
@@ -283,8 +276,8 @@ impl Control for DatePicker {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
@@ -296,7 +289,33 @@ unsafe fn get_dtp_info(handle: HWND) -> DATETIMEPICKERINFO {
     use winapi::{DTM_GETDATETIMEPICKERINFO, DWORD};
     let mut dtp_info: DATETIMEPICKERINFO = mem::uninitialized();
     dtp_info.cbSize = mem::size_of::<DATETIMEPICKERINFO>() as DWORD;
-    SendMessageW(handle, DTM_GETDATETIMEPICKERINFO, 0, mem::transmute(&mut dtp_info));
+    send_message(handle, DTM_GETDATETIMEPICKERINFO, 0, mem::transmute(&mut dtp_info));
 
     dtp_info
-}
\ No newline at end of file
+}
+
+unsafe fn get_value(handle: HWND) -> Option<PickerDate> {
+    use winapi::{DTM_GETSYSTEMTIME, GDT_VALID};
+    let mut syst: SYSTEMTIME = mem::uninitialized();
+
+    let r = send_message(handle, DTM_GETSYSTEMTIME, 0, mem::transmute(&mut syst));
+    match r {
+        GDT_VALID => Some(PickerDate{
+            year: syst.wYear,
+            month: syst.wMonth,
+            day: syst.wDay
+        }),
+        _ => None
+    }
+}
+
+unsafe fn decode_datepicker_notify(handle: HWND, nmhdr: *const NMHDR) -> Option<(Event, EventArgs)> {
+    use winapi::DTN_CLOSEUP;
+
+    match (&*nmhdr).code {
+        DTN_CLOSEUP => {  // DTN_DATETIMECHANGE is sent twice so instead we catch DTN_CLOSEUP ¯\_(ツ)_/¯
+            Some((Event::DateChanged, EventArgs::Date(get_value(handle))))
+        },
+        _ => None
+    }
+}
@@ -0,0 +1,288 @@
+/*!
+    A modal dialog window that returns a value to the code that runs it
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::any::{Any, TypeId};
+use std::hash::Hash;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::ptr;
+
+use winapi::HWND;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+use events::{Event, EventArgs};
+
+/// System class identifier
+const DIALOG_CLASS_NAME: &'static str = "NWG_BUILTIN_DIALOG";
+
+/**
+    A template that creates a dialog window. The dialog is hidden until it is shown with `ui.run_modal`.
+
+    While the dialog runs, its owner is disabled. Pressing Enter in the dialog raises `Event::Accepted`: the callback
+    reads the dialog controls and closes the dialog with `Dialog::accept`. Pressing Esc or the close button cancels the dialog.
+
+    Events:  
+    Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::Closed, Event::MouseDown, Event::MouseUp, Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel, Event::Moved, Event::Resized, Event::Accepted, Event::FilesDropped, Event::ClipboardChanged, Event::ContextMenu, Event::Raw  
+
+    Members:  
+      • `title` : The title of the dialog (in the title bar)  
+      • `position` : Position of the dialog  
+      • `size` : Size of the dialog  
+      • `resizable` : If the user can resize the dialog or not  
+      • `owner` : The window disabled while the dialog runs. Must be a window-like control. If `None`, no window is disabled  
+      • `accept_files` : If the user can drop files from the shell on the dialog. Dropping files raises `Event::FilesDropped`  
+*/
+#[derive(Clone)]
+pub struct DialogT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub title: S,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub resizable: bool,
+    pub owner: Option<ID>,
+    pub accept_files: bool
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for DialogT<S, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Dialog>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::Closed, Event::MouseDown, Event::MouseUp,
+             Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel,
+             Event::Moved, Event::Resized, Event::Accepted, Event::FilesDropped, Event::ClipboardChanged, Event::ContextMenu, Event::Raw]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+
+        let owner = match self.owner.as_ref() {
+            Some(id) => match handle_of_window(ui, id, "The owner of a dialog must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => ptr::null_mut()
+        };
+
+        unsafe{
+            if let Err(e) = build_sysclass() { return Err(e); }
+            match build_dialog(&self, owner) {
+                Ok(h) => { Ok( Box::new(Dialog{handle: h, owner: owner, state: Rc::new(RefCell::new(ModalState{ running: false, result: None }))}) as Box<Control> ) },
+                Err(e) => Err(e)
+            }
+        } // unsafe
+    }
+}
+
+/**
+    The state of a running dialog. Shared with `run_modal` so that the dialog does not have to be borrowed
+    while the events are dispatched.
+*/
+struct ModalState {
+    running: bool,
+    result: Option<Box<Any>>
+}
+
+/**
+    A dialog window
+*/
+pub struct Dialog {
+    handle: HWND,
+    owner: HWND,
+    state: Rc<RefCell<ModalState>>
+}
+
+impl Dialog {
+
+    /**
+        Close the dialog and make `ui.run_modal` return `Ok(Some(value))`. The type of `value` must match the
+        type passed to `run_modal`. Do nothing if the dialog is not running.
+    */
+    pub fn accept<T: 'static>(&self, value: T) {
+        let mut state = self.state.borrow_mut();
+        if state.running {
+            state.result = Some(Box::new(value) as Box<Any>);
+            state.running = false;
+        }
+    }
+
+    /**
+        Close the dialog and make `ui.run_modal` return `Ok(None)`. Do nothing if the dialog is not running.
+    */
+    pub fn cancel(&self) {
+        self.state.borrow_mut().running = false;
+    }
+
+    /// Return `true` if the dialog is currently shown by `ui.run_modal`
+    pub fn running(&self) -> bool {
+        self.state.borrow().running
+    }
+
+    pub fn get_title(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.handle) } }
+    pub fn set_title<'a>(&self, text: &'a str) { unsafe{ ::low::window_helper::set_window_text(self.handle, text); } }
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, true); } }
+}
+
+impl Control for Dialog {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Dialog
+    }
+
+    fn free(&mut self) {
+        use low::window_helper::destroy_window;
+        self.cancel();
+        unsafe{ destroy_window(self.handle) };
+    }
+
+}
+
+/**
+    Show a dialog and dispatch the events until the dialog is closed. See `Ui::run_modal`.
+*/
+pub fn run_modal<ID: Hash+Clone, T: 'static>(ui: &Ui<ID>, id: &ID) -> Result<Option<T>, Error> {
+    use low::events::dispatch_modal_events;
+    use low::window_helper::{set_window_enabled, set_window_visibility};
+    use defs::keys;
+    use user32::SetActiveWindow;
+
+    let (handle, owner, state) = match ui.get::<Dialog>(id) {
+        Ok(d) => (d.handle, d.owner, d.state.clone()),
+        Err(e) => { return Err(e); }
+    };
+
+    {
+        let mut state = state.borrow_mut();
+        if state.running { return Err(Error::ControlInUse); }
+        state.running = true;
+        state.result = None;
+    }
+
+    unsafe{
+        if !owner.is_null() { set_window_enabled(owner, false); }
+        set_window_visibility(handle, true);
+        SetActiveWindow(handle);
+
+        dispatch_modal_events(handle, || state.borrow().running, |key| {
+            if key == keys::RETURN {
+                ui.trigger(id, Event::Accepted, EventArgs::None);
+            } else {
+                state.borrow_mut().running = false;
+            }
+        });
+
+        // The owner must be enabled before the dialog is hidden, otherwise the system activates another application
+        if !owner.is_null() {
+            set_window_enabled(owner, true);
+            SetActiveWindow(owner);
+        }
+        set_window_visibility(handle, false);
+    }
+
+    let result = {
+        let mut state = state.borrow_mut();
+        state.running = false;
+        state.result.take()
+    };
+
+    match result {
+        Some(value) => match value.downcast::<T>() {
+            Ok(v) => Ok(Some(*v)),
+            Err(_) => Err(Error::BadType)
+        },
+        None => Ok(None)
+    }
+}
+
+
+/*
+    Private unsafe control methods
+*/
+
+use winapi::{UINT, WPARAM, LPARAM, LRESULT};
+
+#[allow(unused_variables)]
+unsafe extern "system" fn dialog_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    use winapi::{WM_CREATE, WM_CLOSE};
+    use user32::{DefWindowProcW, ShowWindow};
+
+    let handled = match msg {
+        WM_CREATE => true,
+        WM_CLOSE => {
+            // Hiding the dialog ends the modal loop
+            ShowWindow(hwnd, 0);
+            true
+        }
+        _ => false
+    };
+
+    if handled {
+        0
+    } else {
+        DefWindowProcW(hwnd, msg, w, l)
+    }
+}
+
+#[inline(always)]
+unsafe fn build_sysclass() -> Result<(), Error> {
+    use low::window_helper::{SysclassParams, build_sysclass};
+    let params = SysclassParams {
+        class_name: DIALOG_CLASS_NAME,
+        sysproc: Some(dialog_sysproc),
+        background: None, style: None
+    };
+
+    if let Err(e) = build_sysclass(params) {
+        Err(Error::System(e))
+    } else {
+        Ok(())
+    }
+}
+
+#[inline(always)]
+unsafe fn build_dialog<S: Clone+Into<String>, ID: Hash+Clone>(t: &DialogT<S, ID>, owner: HWND) -> Result<HWND, Error> {
+    use low::window_helper::{WindowParams, build_window};
+    use winapi::{DWORD, WS_POPUP, WS_CAPTION, WS_SYSMENU, WS_THICKFRAME, WS_CLIPCHILDREN, WS_EX_DLGMODALFRAME, WS_EX_ACCEPTFILES};
+
+    let flags: DWORD = WS_POPUP | WS_CAPTION | WS_SYSMENU | WS_CLIPCHILDREN |
+    if t.resizable { WS_THICKFRAME } else { 0 };
+
+    // A window created with a parent and the WS_POPUP style is owned by its parent: it stays above it
+    let params = WindowParams {
+        title: t.title.clone().into(),
+        class_name: DIALOG_CLASS_NAME,
+        position: t.position.clone(),
+        size: t.size.clone(),
+        flags: flags,
+        ex_flags: Some(WS_EX_DLGMODALFRAME | if t.accept_files { WS_EX_ACCEPTFILES } else { 0 }),
+        parent: owner
+    };
+
+    match build_window(params) {
+        Ok(h) => Ok(h),
+        Err(e) => Err(Error::System(e))
+    }
+}
@@ -139,8 +139,8 @@ impl Control for GroupBox {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
\ No newline at end of file
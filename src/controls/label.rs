@@ -26,7 +26,7 @@ use winapi::{HWND, HFONT};
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
 use defs::HTextAlign;
 
 /**
@@ -64,6 +64,10 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for LabelT<S, ID> {
         vec![Event::Destroyed, Event::Click, Event::DoubleClick, Event::Moved, Event::Resized, Event::Raw]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_label_command), notify: None }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
         use low::defs::{SS_NOTIFY, SS_NOPREFIX, SS_LEFT, SS_RIGHT, SS_CENTER};
@@ -141,8 +145,18 @@ impl Control for Label {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
-}
\ No newline at end of file
+}
+
+#[allow(unused_variables)]
+unsafe fn decode_label_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::defs::{STN_CLICKED, STN_DBLCLK};
+    match ncode {
+        STN_CLICKED => Some((Event::Click, EventArgs::None)),
+        STN_DBLCLK => Some((Event::DoubleClick, EventArgs::None)),
+        _ => None
+    }
+}
@@ -23,13 +23,13 @@ use std::any::TypeId;
 use std::fmt::Display;
 use std::mem;
 
-use user32::SendMessageW;
+use low::window_helper::send_message;
 use winapi::{HWND, HFONT, WPARAM};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
 use low::other_helper::{to_utf16, from_utf16};
 
 /**
@@ -69,6 +69,10 @@ impl<D: Clone+Display+'static, ID: Hash+Clone> ControlT<ID> for ListBoxT<D, ID>
         vec![Event::Destroyed, Event::SelectionChanged, Event::DoubleClick, Event::Focus, Event::Moved, Event::Resized, Event::Raw]
     }
 
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_listbox_command), notify: None }
+    }
+
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{WindowParams, build_window, set_window_font, handle_of_window, handle_of_font};
         use low::defs::{LB_ADDSTRING, LBS_HASSTRINGS, LBS_MULTIPLESEL, LBS_NOSEL, LBS_NOTIFY};
@@ -116,7 +120,7 @@ impl<D: Clone+Display+'static, ID: Hash+Clone> ControlT<ID> for ListBoxT<D, ID>
                     let collection: Vec<D> = self.collection.iter().map(
                         |s|{  
                             let text = to_utf16(format!("{}", s).as_str());
-                            SendMessageW(h, LB_ADDSTRING, 0, mem::transmute(text.as_ptr()));
+                            send_message(h, LB_ADDSTRING, 0, mem::transmute(text.as_ptr()));
                             s.clone() 
                         } 
                     ).collect();
@@ -153,11 +157,11 @@ impl<D: Clone+Display> ListBox<D> {
     pub fn sync(&self) {
         use low::defs::{LB_RESETCONTENT, LB_ADDSTRING};
 
-        unsafe{ SendMessageW(self.handle, LB_RESETCONTENT, 0, 0); }
+        unsafe{ send_message(self.handle, LB_RESETCONTENT, 0, 0); }
 
         for i in self.collection.iter() {
             let text = to_utf16(format!("{}", i).as_str());
-            unsafe{ SendMessageW(self.handle, LB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
+            unsafe{ send_message(self.handle, LB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
         }
     }
 
@@ -166,7 +170,7 @@ impl<D: Clone+Display> ListBox<D> {
         use low::defs::LB_ADDSTRING;
 
         let text = to_utf16(format!("{}", item).as_str());
-        unsafe{ SendMessageW(self.handle, LB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
+        unsafe{ send_message(self.handle, LB_ADDSTRING, 0, mem::transmute(text.as_ptr())); }
 
         self.collection.push(item);
     }
@@ -175,7 +179,7 @@ impl<D: Clone+Display> ListBox<D> {
     /// `Panics` if index is out of bounds.
     pub fn remove(&mut self, index: usize) -> D {
         use low::defs::LB_DELETESTRING;
-        unsafe{ SendMessageW(self.handle, LB_DELETESTRING, index as WPARAM, 0); }
+        unsafe{ send_message(self.handle, LB_DELETESTRING, index as WPARAM, 0); }
         self.collection.remove(index)
     }
 
@@ -185,7 +189,7 @@ impl<D: Clone+Display> ListBox<D> {
         use low::defs::LB_INSERTSTRING;
 
         let text = to_utf16(format!("{}", item).as_str());
-        unsafe{ SendMessageW(self.handle, LB_INSERTSTRING, index as WPARAM, mem::transmute(text.as_ptr())); }
+        unsafe{ send_message(self.handle, LB_INSERTSTRING, index as WPARAM, mem::transmute(text.as_ptr())); }
 
         self.collection.insert(index, item);
     }
@@ -196,7 +200,7 @@ impl<D: Clone+Display> ListBox<D> {
     pub fn get_selected_index(&self) -> Option<usize> {
         use low::defs::LB_GETCURSEL;
 
-        let index = unsafe{ SendMessageW(self.handle, LB_GETCURSEL, 0, 0) };
+        let index = unsafe{ send_message(self.handle, LB_GETCURSEL, 0, 0) };
         if index == -1 { None } 
         else { Some(index as usize) }
     }
@@ -206,7 +210,7 @@ impl<D: Clone+Display> ListBox<D> {
     pub fn get_selected_indexes(&self) -> Vec<usize> {
         use low::defs::{LB_GETSELCOUNT, LB_GETSELITEMS};
 
-        let selected_count = unsafe{ SendMessageW(self.handle, LB_GETSELCOUNT, 0, 0) };
+        let selected_count = unsafe{ send_message(self.handle, LB_GETSELCOUNT, 0, 0) };
         if selected_count == 0 || selected_count == -1 {
             return Vec::new();
         } 
@@ -214,7 +218,7 @@ impl<D: Clone+Display> ListBox<D> {
         unsafe{ 
             let mut buffer: Vec<u32> = Vec::with_capacity(selected_count as usize);
             buffer.set_len(selected_count as usize);
-            SendMessageW(self.handle, LB_GETSELITEMS, selected_count as WPARAM, mem::transmute(buffer.as_mut_ptr()) );
+            send_message(self.handle, LB_GETSELITEMS, selected_count as WPARAM, mem::transmute(buffer.as_mut_ptr()) );
             buffer.into_iter().map(|i| i as usize).collect()
         }
     }
@@ -222,7 +226,7 @@ impl<D: Clone+Display> ListBox<D> {
     /// Return true if `index` is currently selected in the listbox
     pub fn index_selected(&self, index: usize) -> bool {
        use low::defs::LB_GETSEL;
-       unsafe{ SendMessageW(self.handle, LB_GETSEL, index as WPARAM, 0) > 0 }
+       unsafe{ send_message(self.handle, LB_GETSEL, index as WPARAM, 0) > 0 }
     }
 
     /// Set the selected index in a single choice listbox.  
@@ -230,7 +234,7 @@ impl<D: Clone+Display> ListBox<D> {
     /// If `index` is `usize::max_value`, remove the selected index from the listbox
     pub fn set_selected_index(&self, index: usize) {
         use low::defs::LB_SETCURSEL;
-        unsafe{ SendMessageW(self.handle, LB_SETCURSEL, index as WPARAM, 0); }
+        unsafe{ send_message(self.handle, LB_SETCURSEL, index as WPARAM, 0); }
     }
 
     /// Set the selected state of the item located at index. Only work for multi-select listbox
@@ -241,7 +245,7 @@ impl<D: Clone+Display> ListBox<D> {
         use winapi::LPARAM;
 
         let selected: WPARAM = (selected == true) as WPARAM;
-        unsafe { SendMessageW(self.handle, LB_SETSEL, selected, index as LPARAM); }
+        unsafe { send_message(self.handle, LB_SETSEL, selected, index as LPARAM); }
     }
 
     /// Select or unselect a range of index in the list box. The range is inclusive. Only work if the listbox can have multiple items selected.  
@@ -256,13 +260,13 @@ impl<D: Clone+Display> ListBox<D> {
             (index_max as WPARAM, index_min as LPARAM)
         };
 
-        unsafe{ SendMessageW(self.handle, LB_SELITEMRANGEEX, min, max); }
+        unsafe{ send_message(self.handle, LB_SELITEMRANGEEX, min, max); }
     }
 
     /// Return the number of selected items.
     pub fn len_selected(&self) -> usize {
         use low::defs::LB_GETSELCOUNT;
-        let index = unsafe{ SendMessageW(self.handle, LB_GETSELCOUNT, 0, 0) };
+        let index = unsafe{ send_message(self.handle, LB_GETSELCOUNT, 0, 0) };
         if index == -1 { 
             1
         } else {
@@ -273,7 +277,7 @@ impl<D: Clone+Display> ListBox<D> {
     /// Remove every item in the inner collection and in the listbox
     pub fn clear(&mut self) {
         use low::defs::LB_RESETCONTENT;
-        unsafe{ SendMessageW(self.handle, LB_RESETCONTENT, 0, 0) };
+        unsafe{ send_message(self.handle, LB_RESETCONTENT, 0, 0) };
         self.collection.clear();
     }
 
@@ -285,7 +289,7 @@ impl<D: Clone+Display> ListBox<D> {
 
         let text = to_utf16(text);
         let msg = if full_match { LB_FINDSTRINGEXACT } else { LB_FINDSTRING };
-        let index = unsafe{ SendMessageW(self.handle, msg, -1isize as WPARAM, mem::transmute(text.as_ptr()) ) };
+        let index = unsafe{ send_message(self.handle, msg, -1isize as WPARAM, mem::transmute(text.as_ptr()) ) };
 
         if index == -1 {
             None
@@ -298,14 +302,14 @@ impl<D: Clone+Display> ListBox<D> {
     pub fn get_string(&self, index: usize) -> Option<String> {
         use low::defs::{LB_GETTEXT, LB_GETTEXTLEN};
 
-        let length = unsafe{ SendMessageW(self.handle, LB_GETTEXTLEN, index as WPARAM, 0) };
+        let length = unsafe{ send_message(self.handle, LB_GETTEXTLEN, index as WPARAM, 0) };
         if length == -1 { return None; }
 
         let length = (length+1) as usize;
         let mut buffer: Vec<u16> = Vec::with_capacity(length);
         unsafe {
             buffer.set_len(length);
-            let err = SendMessageW(self.handle, LB_GETTEXT, index as WPARAM, mem::transmute( buffer.as_mut_ptr() ));
+            let err = send_message(self.handle, LB_GETTEXT, index as WPARAM, mem::transmute( buffer.as_mut_ptr() ));
             if err == -1 { return None; }
         }
 
@@ -383,8 +387,24 @@ impl<D: Clone+Display> Control for ListBox<D> {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
-}
\ No newline at end of file
+}
+
+unsafe fn decode_listbox_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::defs::{LBN_SELCHANGE, LBN_DBLCLK, LBN_SETFOCUS, LBN_KILLFOCUS, LB_GETCURSEL};
+
+    match ncode {
+        LBN_SELCHANGE => {
+            // With multi selection listbox, this is the index of the item that has the focus
+            let index = send_message(handle, LB_GETCURSEL, 0, 0);
+            let index = if index < 0 { None } else { Some(index as usize) };
+            Some((Event::SelectionChanged, EventArgs::Selection(index)))
+        },
+        LBN_DBLCLK => Some((Event::DoubleClick, EventArgs::None)),
+        LBN_SETFOCUS | LBN_KILLFOCUS => Some((Event::Focus, EventArgs::Focus(ncode==LBN_SETFOCUS))),
+        _ => None
+    }
+}
@@ -0,0 +1,635 @@
+/*!
+    A list view control that displays a collection of items in a table (report view) or as icons
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::ptr;
+use std::mem;
+
+use low::window_helper::send_message;
+use winapi::{HWND, HFONT, WPARAM, LPARAM, NMHDR, UINT, DWORD};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+use events::{Event, EventArgs, EventDecoders};
+use defs::ListViewStyle;
+use low::other_helper::to_utf16;
+
+/**
+    A column of a list view. Defines the header of the column and how the items of the collection are displayed in it.
+
+    In the `Icon`, `SmallIcon` and `List` styles, only the first column is displayed.
+*/
+pub trait ListViewColumn<D> {
+
+    /// The text displayed in the column header
+    fn header(&self) -> String;
+
+    /// The text displayed in the cell of `item`
+    fn cell(&self, item: &D) -> String;
+
+    /// The start width of the column
+    fn width(&self) -> u32 { 100 }
+
+    /// Compare two items when the list view is sorted on this column. By default, compare the cells text.
+    fn compare(&self, a: &D, b: &D) -> Ordering {
+        self.cell(a).cmp(&self.cell(b))
+    }
+}
+
+/**
+    A list view column that formats its cells with a function
+
+    Members:  
+    • `header`: The text displayed in the column header  
+    • `width`: The start width of the column  
+    • `cell`: The function that returns the text of a cell  
+*/
+pub struct TextColumn<D> {
+    pub header: String,
+    pub width: u32,
+    pub cell: fn(&D) -> String
+}
+
+impl<D> ListViewColumn<D> for TextColumn<D> {
+    fn header(&self) -> String { self.header.clone() }
+    fn cell(&self, item: &D) -> String { (self.cell)(item) }
+    fn width(&self) -> u32 { self.width }
+}
+
+/**
+    Template that creates a list view control
+
+    Available events:  
+    Event::Destroyed, Event::SelectionChanged, Event::DoubleClick, Event::ColumnClick, Event::ValueChanged, Event::Focus, Event::Moved, Event::Resized, Event::Raw  
+
+    `Event::ValueChanged` is raised when an item is checked or unchecked. The arguments are `EventArgs::Checked(index, checked)`.
+
+    Members:  
+    • `collection`: Item collection of the list view  
+    • `columns`: The columns of the list view  
+    • `style`: How the items are displayed  
+    • `position`: The start position of the list view  
+    • `size`: The start size of the list view  
+    • `visible`: If the list view should be visible to the user  
+    • `disabled`: If the user can or can't use the list view  
+    • `multi_select`: If true, allow the user to select more than one item  
+    • `checkboxes`: If true, display a check box next to each item. Not available in virtual mode.  
+    • `sortable`: If true, clicking on a column header sorts the collection on that column  
+    • `virtual_mode`: If true, the items are not copied in the control. The text of the visible cells is requested when needed.
+                      Use this for very large collections.  
+    • `parent`: The list view parent  
+    • `font`: The list view font. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct ListViewT<D: Clone+'static, ID: Hash+Clone> {
+    pub collection: Vec<D>,
+    pub columns: Vec<Rc<ListViewColumn<D>>>,
+    pub style: ListViewStyle,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub multi_select: bool,
+    pub checkboxes: bool,
+    pub sortable: bool,
+    pub virtual_mode: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<D: Clone+'static, ID: Hash+Clone+'static> ControlT<ID> for ListViewT<D, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<ListView<D>>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::SelectionChanged, Event::DoubleClick, Event::ColumnClick, Event::ValueChanged, Event::Focus,
+             Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: None, notify: Some(decode_listview_notify::<D>) }
+    }
+
+    fn packed(&self, ui: &Ui<ID>, id: &ID) {
+        if self.sortable {
+            ui.bind(id, id, Event::ColumnClick, sort_on_column_click::<D, ID>);
+        }
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font, set_window_long, handle_of_window, handle_of_font};
+        use winapi::{WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_BORDER, WS_TABSTOP, LVS_SHOWSELALWAYS, LVS_SINGLESEL, LVS_OWNERDATA,
+          LVM_SETEXTENDEDLISTVIEWSTYLE, LVS_EX_FULLROWSELECT, LVS_EX_CHECKBOXES, LVS_EX_DOUBLEBUFFER, GWL_USERDATA};
+
+        let flags: DWORD = WS_CHILD | WS_BORDER | WS_TABSTOP | LVS_SHOWSELALWAYS | style_flags(self.style) |
+        if self.visible       { WS_VISIBLE }    else { 0 } |
+        if self.disabled      { WS_DISABLED }   else { 0 } |
+        if !self.multi_select { LVS_SINGLESEL } else { 0 } |
+        if self.virtual_mode  { LVS_OWNERDATA } else { 0 };
+
+        let ex_style: DWORD = LVS_EX_FULLROWSELECT | LVS_EX_DOUBLEBUFFER |
+        if self.checkboxes && !self.virtual_mode { LVS_EX_CHECKBOXES } else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a list view must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a list view must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "SysListView32",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => {
+                let data = Box::new(ListViewData {
+                    collection: self.collection.clone(),
+                    columns: self.columns.clone(),
+                    sort: None,
+                    virtual_mode: self.virtual_mode
+                });
+
+                unsafe{
+                    set_window_font(h, font_handle, true);
+                    send_message(h, LVM_SETEXTENDEDLISTVIEWSTYLE, ex_style as WPARAM, ex_style as LPARAM);
+
+                    // The notifications decoder reads the collection through the window user data. It must never modify it.
+                    set_window_long(h, GWL_USERDATA, &*data as *const ListViewData<D> as usize);
+
+                    for (i, column) in data.columns.iter().enumerate() {
+                        insert_column(h, i, &**column);
+                    }
+
+                    sync_items(h, &data);
+                }
+
+                Ok( Box::new(ListView{handle: h, data: data}) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    A list view control
+*/
+pub struct ListView<D: Clone+'static> {
+    handle: HWND,
+    data: Box<ListViewData<D>>
+}
+
+impl<D: Clone+'static> ListView<D> {
+
+    /// Return the number of items in the inner collection
+    pub fn len(&self) -> usize { self.data.collection.len() }
+
+    /// Return the inner collection of the list view
+    pub fn collection(&self) -> &Vec<D> { &self.data.collection }
+
+    /// Return the inner collection of the list view, mutable.
+    /// If the inner collection is changed, `listview.sync` must be called to show the changes in the list view
+    pub fn collection_mut(&mut self) -> &mut Vec<D> { &mut self.data.collection }
+
+    /// Return the columns of the list view
+    pub fn columns(&self) -> &Vec<Rc<ListViewColumn<D>>> { &self.data.columns }
+
+    /// Reload the content of the list view. The check boxes and the selection are reset.
+    pub fn sync(&self) {
+        unsafe{ sync_items(self.handle, &self.data); }
+    }
+
+    /// Add an item at the end of the list view. Updates both the inner collection and the ui.
+    pub fn push(&mut self, item: D) {
+        let index = self.data.collection.len();
+        self.insert(index, item);
+    }
+
+    /// Insert an item at the selected position in the list view and the inner collection.
+    /// `Panics` if index is out of bounds.
+    pub fn insert(&mut self, index: usize, item: D) {
+        self.data.collection.insert(index, item);
+        unsafe{
+            if self.data.virtual_mode {
+                sync_items(self.handle, &self.data);
+            } else {
+                insert_item(self.handle, index, &self.data.collection[index], &self.data.columns);
+            }
+        }
+    }
+
+    /// Remove an item from the inner collection and the list view. Return the removed item.
+    /// `Panics` if index is out of bounds.
+    pub fn remove(&mut self, index: usize) -> D {
+        use winapi::LVM_DELETEITEM;
+
+        let item = self.data.collection.remove(index);
+        unsafe{
+            if self.data.virtual_mode {
+                sync_items(self.handle, &self.data);
+            } else {
+                send_message(self.handle, LVM_DELETEITEM, index as WPARAM, 0);
+            }
+        }
+
+        item
+    }
+
+    /// Remove every item in the inner collection and in the list view
+    pub fn clear(&mut self) {
+        self.data.collection.clear();
+        unsafe{ sync_items(self.handle, &self.data); }
+    }
+
+    /// Return the index of the first selected item.
+    /// Return None if there is no selected item
+    pub fn get_selected_index(&self) -> Option<usize> {
+        unsafe{ first_selected(self.handle) }
+    }
+
+    /// Return a vector filled with the selected indexes of the list view.
+    pub fn get_selected_indexes(&self) -> Vec<usize> {
+        use winapi::{LVM_GETNEXTITEM, LVNI_SELECTED};
+
+        let mut indexes = Vec::new();
+        let mut index = -1isize;
+        loop {
+            index = unsafe{ send_message(self.handle, LVM_GETNEXTITEM, index as WPARAM, LVNI_SELECTED) } as isize;
+            if index < 0 || indexes.len() >= self.len() { break; }
+            indexes.push(index as usize);
+        }
+
+        indexes
+    }
+
+    /// Return the number of selected items.
+    pub fn len_selected(&self) -> usize {
+        use winapi::LVM_GETSELECTEDCOUNT;
+        unsafe{ send_message(self.handle, LVM_GETSELECTEDCOUNT, 0, 0) as usize }
+    }
+
+    /// Return true if `index` is currently selected in the list view
+    pub fn index_selected(&self, index: usize) -> bool {
+        use winapi::{LVM_GETITEMSTATE, LVIS_SELECTED};
+        unsafe{ (send_message(self.handle, LVM_GETITEMSTATE, index as WPARAM, LVIS_SELECTED as LPARAM) as UINT) & LVIS_SELECTED != 0 }
+    }
+
+    /// Set the selected state of the item located at index.
+    /// If index is `usize::max_value`, the change is applied to every item.
+    pub fn set_index_selected(&self, index: usize, selected: bool) {
+        use winapi::LVIS_SELECTED;
+        let state = if selected { LVIS_SELECTED } else { 0 };
+        unsafe{ set_item_state(self.handle, index, state, LVIS_SELECTED); }
+    }
+
+    /// Return true if the item at `index` is checked. Always false if the list view has no check boxes.
+    pub fn get_checked(&self, index: usize) -> bool {
+        unsafe{ item_checked(self.handle, index) }
+    }
+
+    /// Check or uncheck the item at `index`. Do nothing if the list view has no check boxes.
+    /// If index is `usize::max_value`, the change is applied to every item.
+    pub fn set_checked(&self, index: usize, checked: bool) {
+        use winapi::{LVIS_STATEIMAGEMASK, INDEXTOSTATEIMAGEMASK};
+        let state = INDEXTOSTATEIMAGEMASK(if checked { 2 } else { 1 });
+        unsafe{ set_item_state(self.handle, index, state, LVIS_STATEIMAGEMASK); }
+    }
+
+    /// Return the indexes of the checked items
+    pub fn get_checked_indexes(&self) -> Vec<usize> {
+        (0..self.len()).filter(|&i| self.get_checked(i)).collect()
+    }
+
+    /**
+        Sort the inner collection using the `compare` method of the column at index `column` and reload the list view.
+        The check boxes are kept with their item. The selection is reset.
+
+        `Panics` if column is out of bounds.
+    */
+    pub fn sort(&mut self, column: usize, ascending: bool) {
+        unsafe{ sort_items(self.handle, &mut self.data, column, ascending); }
+    }
+
+    /// Return the column and the direction (`true` if ascending) used by the last sort. Return `None` if the list view was never sorted.
+    pub fn get_sort(&self) -> Option<(usize, bool)> {
+        self.data.sort
+    }
+
+    /// Return the way the list view displays its items
+    pub fn get_style(&self) -> ListViewStyle {
+        use low::window_helper::get_window_long;
+        use winapi::{GWL_STYLE, LVS_TYPEMASK, LVS_ICON, LVS_SMALLICON, LVS_LIST};
+
+        match (get_window_long(self.handle, GWL_STYLE) as DWORD) & LVS_TYPEMASK {
+            LVS_ICON => ListViewStyle::Icon,
+            LVS_SMALLICON => ListViewStyle::SmallIcon,
+            LVS_LIST => ListViewStyle::List,
+            _ => ListViewStyle::Report
+        }
+    }
+
+    /// Change the way the list view displays its items
+    pub fn set_style(&self, style: ListViewStyle) {
+        use low::window_helper::{set_window_long, get_window_long};
+        use winapi::{GWL_STYLE, LVS_TYPEMASK};
+
+        let old_style = get_window_long(self.handle, GWL_STYLE) as usize;
+        set_window_long(self.handle, GWL_STYLE, (old_style & !(LVS_TYPEMASK as usize)) | (style_flags(style) as usize));
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+}
+
+impl<D: Clone+'static> Control for ListView<D> {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::ListView
+    }
+
+    fn free(&mut self) {
+        use low::window_helper::{destroy_window, set_window_long};
+        use winapi::GWL_USERDATA;
+
+        set_window_long(self.handle, GWL_USERDATA, 0);
+        unsafe{ destroy_window(self.handle) };
+    }
+
+}
+
+// Private functions
+
+/**
+    The data of a list view. Shared with the notifications decoder through the window user data.
+*/
+struct ListViewData<D: Clone+'static> {
+    collection: Vec<D>,
+    columns: Vec<Rc<ListViewColumn<D>>>,
+    sort: Option<(usize, bool)>,
+    virtual_mode: bool
+}
+
+#[inline(always)]
+fn style_flags(style: ListViewStyle) -> DWORD {
+    use winapi::{LVS_REPORT, LVS_ICON, LVS_SMALLICON, LVS_LIST};
+    match style {
+        ListViewStyle::Report => LVS_REPORT,
+        ListViewStyle::Icon => LVS_ICON,
+        ListViewStyle::SmallIcon => LVS_SMALLICON,
+        ListViewStyle::List => LVS_LIST
+    }
+}
+
+unsafe fn insert_column<D>(handle: HWND, index: usize, column: &ListViewColumn<D>) {
+    use winapi::{LVCOLUMNW, LVM_INSERTCOLUMNW, LVCF_TEXT, LVCF_WIDTH, LVCF_FMT, LVCFMT_LEFT};
+
+    let mut text = to_utf16(&column.header());
+    let mut col: LVCOLUMNW = mem::zeroed();
+    col.mask = LVCF_TEXT | LVCF_WIDTH | LVCF_FMT;
+    col.fmt = LVCFMT_LEFT;
+    col.cx = column.width() as i32;
+    col.pszText = text.as_mut_ptr();
+
+    send_message(handle, LVM_INSERTCOLUMNW, index as WPARAM, mem::transmute(&col));
+}
+
+unsafe fn insert_item<D>(handle: HWND, index: usize, item: &D, columns: &[Rc<ListViewColumn<D>>]) {
+    use winapi::{LVITEMW, LVM_INSERTITEMW, LVM_SETITEMTEXTW, LVIF_TEXT};
+
+    let mut lvitem: LVITEMW = mem::zeroed();
+    lvitem.mask = LVIF_TEXT;
+    lvitem.iItem = index as i32;
+
+    for (i, column) in columns.iter().enumerate() {
+        let mut text = to_utf16(&column.cell(item));
+        lvitem.iSubItem = i as i32;
+        lvitem.pszText = text.as_mut_ptr();
+
+        if i == 0 {
+            send_message(handle, LVM_INSERTITEMW, 0, mem::transmute(&lvitem));
+        } else {
+            send_message(handle, LVM_SETITEMTEXTW, index as WPARAM, mem::transmute(&lvitem));
+        }
+    }
+
+    // A list view without columns still needs its items
+    if columns.len() == 0 {
+        let mut text = to_utf16("");
+        lvitem.pszText = text.as_mut_ptr();
+        send_message(handle, LVM_INSERTITEMW, 0, mem::transmute(&lvitem));
+    }
+}
+
+/**
+    Reload the items of the list view. In virtual mode, only the item count is updated.
+*/
+unsafe fn sync_items<D: Clone+'static>(handle: HWND, data: &ListViewData<D>) {
+    use winapi::{LVM_DELETEALLITEMS, LVM_SETITEMCOUNT, LVM_REDRAWITEMS};
+
+    if data.virtual_mode {
+        let len = data.collection.len();
+        send_message(handle, LVM_SETITEMCOUNT, len as WPARAM, 0);
+        if len > 0 {
+            send_message(handle, LVM_REDRAWITEMS, 0, (len - 1) as LPARAM);
+        }
+    } else {
+        send_message(handle, LVM_DELETEALLITEMS, 0, 0);
+        for (i, item) in data.collection.iter().enumerate() {
+            insert_item(handle, i, item, &data.columns);
+        }
+    }
+}
+
+/**
+    Sort a sortable list view on the column that was clicked. Clicking twice on the same column reverses the order.
+*/
+#[allow(unused_variables)]
+fn sort_on_column_click<D: Clone+'static, ID: Hash+Clone+'static>(ui: &Ui<ID>, id: &ID, event: &Event, args: &EventArgs) {
+    let column = match args {
+        &EventArgs::Column(c) => c,
+        _ => { return; }
+    };
+
+    if let Ok(mut list) = ui.get_mut::<ListView<D>>(id) {
+        if column < list.columns().len() {
+            let ascending = match list.get_sort() {
+                Some((c, asc)) if c == column => !asc,
+                _ => true
+            };
+            list.sort(column, ascending);
+        }
+    }
+}
+
+unsafe fn sort_items<D: Clone+'static>(handle: HWND, data: &mut ListViewData<D>, column: usize, ascending: bool) {
+    let checked: Vec<bool> = if data.virtual_mode {
+        Vec::new()
+    } else {
+        (0..data.collection.len()).map(|i| item_checked(handle, i)).collect()
+    };
+
+    let mut order: Vec<usize> = (0..data.collection.len()).collect();
+    {
+        let col = &data.columns[column];
+        let collection = &data.collection;
+        order.sort_by(|&a, &b| {
+            let o = col.compare(&collection[a], &collection[b]);
+            if ascending { o } else { o.reverse() }
+        });
+    }
+
+    data.collection = order.iter().map(|&i| data.collection[i].clone()).collect();
+    data.sort = Some((column, ascending));
+    sync_items(handle, data);
+
+    if !checked.is_empty() {
+        use winapi::{LVIS_STATEIMAGEMASK, INDEXTOSTATEIMAGEMASK};
+        for (new_index, &old_index) in order.iter().enumerate() {
+            if checked[old_index] {
+                set_item_state(handle, new_index, INDEXTOSTATEIMAGEMASK(2), LVIS_STATEIMAGEMASK);
+            }
+        }
+    }
+}
+
+unsafe fn set_item_state(handle: HWND, index: usize, state: UINT, mask: UINT) {
+    use winapi::{LVITEMW, LVM_SETITEMSTATE};
+
+    let mut lvitem: LVITEMW = mem::zeroed();
+    lvitem.state = state;
+    lvitem.stateMask = mask;
+
+    // Index -1 applies the state to every item
+    let index = if index == usize::max_value() { -1isize as WPARAM } else { index as WPARAM };
+    send_message(handle, LVM_SETITEMSTATE, index, mem::transmute(&lvitem));
+}
+
+#[inline(always)]
+unsafe fn item_checked(handle: HWND, index: usize) -> bool {
+    use winapi::{LVM_GETITEMSTATE, LVIS_STATEIMAGEMASK};
+    let state = send_message(handle, LVM_GETITEMSTATE, index as WPARAM, LVIS_STATEIMAGEMASK as LPARAM) as UINT;
+    (state & LVIS_STATEIMAGEMASK) >> 12 == 2
+}
+
+#[inline(always)]
+unsafe fn first_selected(handle: HWND) -> Option<usize> {
+    use winapi::{LVM_GETNEXTITEM, LVNI_SELECTED};
+    let index = send_message(handle, LVM_GETNEXTITEM, -1isize as WPARAM, LVNI_SELECTED);
+    if index < 0 { None } else { Some(index as usize) }
+}
+
+/**
+    Copy `text` into a buffer provided by the system. The text is truncated if the buffer is too small.
+*/
+unsafe fn copy_text(text: &str, buffer: *mut u16, buffer_len: i32) {
+    if buffer.is_null() || buffer_len <= 0 { return; }
+
+    let text = to_utf16(text); // Null terminated
+    let len = ::std::cmp::min(text.len(), buffer_len as usize);
+    ptr::copy_nonoverlapping(text.as_ptr(), buffer, len);
+    *buffer.offset((len - 1) as isize) = 0;
+}
+
+unsafe fn decode_listview_notify<D: Clone+'static>(handle: HWND, nmhdr: *const NMHDR) -> Option<(Event, EventArgs)> {
+    use low::window_helper::get_window_long;
+    use winapi::{NMLISTVIEW, NMITEMACTIVATE, NMLVDISPINFOW, GWL_USERDATA, LVN_GETDISPINFOW, LVN_COLUMNCLICK, LVN_ITEMCHANGED,
+      LVN_ODSTATECHANGED, NM_DBLCLK, NM_SETFOCUS, NM_KILLFOCUS, LVIF_TEXT, LVIF_STATE, LVIS_SELECTED, LVIS_STATEIMAGEMASK};
+
+    let data: *const ListViewData<D> = get_window_long(handle, GWL_USERDATA) as *const ListViewData<D>;
+    if data.is_null() { return None; }
+    let data = &*data;
+
+    match (&*nmhdr).code {
+        LVN_GETDISPINFOW => {
+            // Only sent in virtual mode
+            let info = &mut *(nmhdr as *mut NMLVDISPINFOW);
+            let item = &mut info.item;
+            let index = item.iItem as usize;
+            let column = item.iSubItem as usize;
+            if item.mask & LVIF_TEXT != 0 && index < data.collection.len() && column < data.columns.len() {
+                let text = data.columns[column].cell(&data.collection[index]);
+                copy_text(&text, item.pszText, item.cchTextMax);
+            }
+            None
+        },
+        LVN_COLUMNCLICK => {
+            // Sortable list views are sorted by the callback bound in `ListViewT::packed`
+            let nmlv = &*(nmhdr as *const NMLISTVIEW);
+            Some((Event::ColumnClick, EventArgs::Column(nmlv.iSubItem as usize)))
+        },
+        LVN_ITEMCHANGED => {
+            let nmlv = &*(nmhdr as *const NMLISTVIEW);
+            if nmlv.uChanged & LVIF_STATE == 0 { return None; }
+
+            let changed = nmlv.uNewState ^ nmlv.uOldState;
+            let old_image = (nmlv.uOldState & LVIS_STATEIMAGEMASK) >> 12;
+            if changed & LVIS_STATEIMAGEMASK != 0 && old_image != 0 && nmlv.iItem >= 0 {
+                // The state image goes from 0 to 1 when the item is inserted. This is not a user check.
+                let checked = (nmlv.uNewState & LVIS_STATEIMAGEMASK) >> 12 == 2;
+                Some((Event::ValueChanged, EventArgs::Checked(nmlv.iItem as usize, checked)))
+            } else if changed & LVIS_SELECTED != 0 {
+                Some((Event::SelectionChanged, EventArgs::Selection(first_selected(handle))))
+            } else {
+                None
+            }
+        },
+        LVN_ODSTATECHANGED => {
+            // A range of items changed in a virtual list view
+            Some((Event::SelectionChanged, EventArgs::Selection(first_selected(handle))))
+        },
+        NM_DBLCLK => {
+            let nmia = &*(nmhdr as *const NMITEMACTIVATE);
+            let index = if nmia.iItem < 0 { None } else { Some(nmia.iItem as usize) };
+            Some((Event::DoubleClick, EventArgs::Selection(index)))
+        },
+        NM_SETFOCUS | NM_KILLFOCUS => {
+            Some((Event::Focus, EventArgs::Focus((&*nmhdr).code == NM_SETFOCUS)))
+        },
+        _ => None
+    }
+}
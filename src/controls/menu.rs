@@ -22,10 +22,11 @@ use std::hash::Hash;
 use std::any::TypeId;
 use std::mem;
 
-use winapi::{HMENU, UINT, BOOL};
+use winapi::{HMENU, HWND, UINT, BOOL};
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
+use resources::{Resource, Image};
 use error::Error;
 use events::Event;
 
@@ -107,18 +108,123 @@ impl Control for Menu {
 }
 
 
+/**
+    A template to create a popup menu. A popup menu is not attached to a window: it is shown at a position with
+    `PopupMenu::popup`, usually from an `Event::ContextMenu` callback. Items are added with `MenuItemT`, `SeparatorT`
+    and `MenuT` (for submenus) using the popup menu as parent.
+
+    Events:  
+    Event::Destroyed  
+
+    Members:  
+      • `owner` : ID of the window-like control that receives the menu commands. If `None`, the commands are sent to the Ui.
+                  The popup menu is a child of its owner: it is removed from the Ui with it.  
+*/
+#[derive(Clone)]
+pub struct PopupMenuT<ID: Hash+Clone> {
+    pub owner: Option<ID>
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for PopupMenuT<ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<PopupMenu>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::handle_of_window;
+        use low::menu_helper::add_popup_menu;
+
+        let owner = match self.owner.as_ref() {
+            Some(id) => match handle_of_window(ui, id, "The owner of a popup menu must be a window-like control.") {
+                Ok(h) => h,
+                Err(e) => { return Err(e); }
+            },
+            None => unsafe{ ui.message_handle() }
+        };
+
+        let handle = unsafe{ build_popup_menu() };
+        add_popup_menu(owner, handle);
+
+        Ok( Box::new(PopupMenu{handle: handle, owner: owner}) as Box<Control> )
+    }
+}
+
+/**
+    A popup menu control
+*/
+pub struct PopupMenu {
+    handle: HMENU,
+    owner: HWND    // The popup menu is freed with its owner, so the handle stays valid
+}
+
+impl PopupMenu {
+
+    /**
+        Show the menu at the (`x`, `y`) screen position and return when the menu is closed. The position sent with
+        `Event::ContextMenu` can be used as is. The menu items raise `Event::Triggered` when they are clicked.
+    */
+    pub fn popup(&self, x: i32, y: i32) {
+        unsafe{ ::low::menu_helper::popup_menu(self.handle, self.owner, x, y); }
+    }
+
+    /// Show the menu at the cursor position. See `popup`.
+    pub fn popup_at_cursor(&self) {
+        use user32::GetCursorPos;
+        use winapi::POINT;
+
+        let mut pt = POINT{ x: 0, y: 0 };
+        unsafe{ GetCursorPos(&mut pt); }
+        self.popup(pt.x as i32, pt.y as i32);
+    }
+}
+
+impl Control for PopupMenu {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HMENU(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Menu
+    }
+
+    fn free(&mut self) {
+        use user32::DestroyMenu;
+        use low::menu_helper::remove_popup_menu;
+
+        remove_popup_menu(self.owner, self.handle);
+        unsafe{ DestroyMenu(self.handle) };
+    }
+
+}
+
+
 /**
     A template to create menuitems
 
+    Events:  
+    Event::Destroyed, Event::Triggered  
+
     Members:  
       • `text` : The menu text  
       • `parent` : ID of the parent control to add the menu to  
+      • `disabled` : If the menuitem cannot be clicked  
+      • `checked` : If a check mark (or a bullet for radio items) is shown beside the menuitem  
+      • `radio` : If the menuitem is part of a radio group. Radio items next to each other in a menu form a group  
+      • `default` : If the menuitem is the default item of its menu. The default item is shown in bold  
+      • `image` : ID of an `Image` resource of type `Bitmap` shown beside the menuitem. The image is not owned by the menuitem  
 */
 #[derive(Clone)]
 pub struct MenuItemT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub text: S,
     pub parent: ID,
-    pub disabled: bool
+    pub disabled: bool,
+    pub checked: bool,
+    pub radio: bool,
+    pub default: bool,
+    pub image: Option<ID>
 }
 
 impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MenuItemT<S, ID> {
@@ -128,11 +234,30 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MenuItemT<S, ID> {
         vec![Event::Destroyed, Event::Triggered]
     }
 
-   #[allow(unused_variables)]
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::menu_helper::{set_menuitem_radio, set_menuitem_bitmap};
+
+        // The image is checked first, so that a bad image does not leave an item in the menu
+        let image = match self.image.as_ref().map(|id| ui.handle_of(id)) {
+            Some(Ok(AnyHandle::HBITMAP(h))) => Some(h),
+            Some(Ok(h)) => { return Err(Error::BadResource(format!("The image of a menuitem must be an Image of type Bitmap. Got {:?}", h))); },
+            Some(Err(e)) => { return Err(e); },
+            None => None
+        };
+
         let handle_result = unsafe { build_menu_item(ui, self) };
         match handle_result {
-            Ok((parent, uid)) => { Ok( Box::new(MenuItem{parent: parent, unique_id: uid}) as Box<Control> ) },
+            Ok((parent, uid)) => {
+                let item = MenuItem{parent: parent, unique_id: uid, radio: self.radio};
+                unsafe{
+                    if self.radio { set_menuitem_radio(parent, uid, true); }
+                    if let Some(h) = image { set_menuitem_bitmap(parent, uid, h); }
+                }
+                if self.checked { item.set_checked(true); }
+                if self.default { item.set_default(true); }
+
+                Ok( Box::new(item) as Box<Control> )
+            },
             Err(e) => Err(e)
         }
     }
@@ -143,7 +268,8 @@ impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for MenuItemT<S, ID> {
 */ 
 pub struct MenuItem {
     parent: HMENU,
-    unique_id: UINT
+    unique_id: UINT,
+    radio: bool
 }
 
 impl MenuItem {
@@ -158,6 +284,95 @@ impl MenuItem {
         unsafe{ ::low::menu_helper::enable_menuitem(self.parent, None, Some(self.unique_id), enabled); }
     }
 
+    /// Return the text of the menuitem, without the shortcut of the accelerator bound to it
+    pub fn get_text(&self) -> String {
+        let text = unsafe{ ::low::menu_helper::get_menuitem_text(self.parent, self.unique_id) };
+        text.split('\t').next().unwrap().to_string()
+    }
+
+    /// Return the shortcut of the accelerator bound to the menuitem, as displayed beside the item text
+    pub fn get_shortcut(&self) -> Option<String> {
+        let text = unsafe{ ::low::menu_helper::get_menuitem_text(self.parent, self.unique_id) };
+        text.find('\t').map(|i| text[i+1..].to_string())
+    }
+
+    /// Set the text of the menuitem. The shortcut of the accelerator bound to the item is kept.
+    pub fn set_text<'a>(&self, text: &'a str) {
+        use low::menu_helper::{get_menuitem_text, set_menuitem_text};
+
+        unsafe{
+            let current = get_menuitem_text(self.parent, self.unique_id);
+            let text = match current.find('\t') {
+                Some(i) => format!("{}{}", text, &current[i..]),
+                None => text.to_string()
+            };
+            set_menuitem_text(self.parent, self.unique_id, &text);
+        }
+    }
+
+    /// Return true if the menuitem is checked or false otherwise
+    pub fn get_checked(&self) -> bool {
+        use low::defs::MFS_CHECKED;
+        unsafe{ ::low::menu_helper::get_menuitem_state(self.parent, self.unique_id) & MFS_CHECKED == MFS_CHECKED }
+    }
+
+    /**
+        Check or uncheck the menuitem. Checking a radio item unchecks the other items of its group.
+        Clicking a menuitem does not change its check state, this must be done in the `Event::Triggered` callback.
+    */
+    pub fn set_checked(&self, checked: bool) {
+        use low::menu_helper::{check_menuitem_radio, set_menuitem_state};
+        use low::defs::MFS_CHECKED;
+
+        unsafe{
+            if checked && self.radio {
+                check_menuitem_radio(self.parent, self.unique_id);
+            } else {
+                set_menuitem_state(self.parent, self.unique_id, MFS_CHECKED, checked);
+            }
+        }
+    }
+
+    /// Return true if the menuitem is part of a radio group
+    pub fn is_radio(&self) -> bool { self.radio }
+
+    /// Return true if the menuitem is the default item of its menu
+    pub fn get_default(&self) -> bool {
+        use low::defs::MFS_DEFAULT;
+        unsafe{ ::low::menu_helper::get_menuitem_state(self.parent, self.unique_id) & MFS_DEFAULT == MFS_DEFAULT }
+    }
+
+    /// Make the menuitem the default item of its menu. A menu has at most one default item, shown in bold.
+    pub fn set_default(&self, default: bool) {
+        use low::defs::{SetMenuDefaultItem, GetMenuDefaultItem, GMDI_USEDISABLED};
+
+        unsafe{
+            if default {
+                SetMenuDefaultItem(self.parent, self.unique_id, 0);
+            } else if GetMenuDefaultItem(self.parent, 0, GMDI_USEDISABLED) == self.unique_id {
+                // Passing -1 removes the default item of the menu
+                SetMenuDefaultItem(self.parent, !0, 0);
+            }
+        }
+    }
+
+    /**
+        Set the image shown beside the menuitem. If `image` is `None`, the image is removed.
+        The image is not owned by the menuitem. Return a `Error::BadResource` if the image is not a bitmap.
+    */
+    pub fn set_image(&self, image: Option<&Image>) -> Result<(), Error> {
+        use low::menu_helper::set_menuitem_bitmap;
+
+        let image = match image.map(|i| i.handle()) {
+            Some(AnyHandle::HBITMAP(h)) => h,
+            Some(h) => { return Err(Error::BadResource(format!("The image of a menuitem must be an Image of type Bitmap. Got {:?}", h))); },
+            None => ::std::ptr::null_mut()
+        };
+
+        unsafe{ set_menuitem_bitmap(self.parent, self.unique_id, image); }
+        Ok(())
+    }
+
 }
 
 
@@ -281,10 +496,21 @@ unsafe fn build_menu<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &Men
         },
         AnyHandle::HMENU_ITEM(_, _) => Err(Error::BadParent("Window or menu parent required, got MenuItem".to_string())),
         AnyHandle::HFONT(_) =>  Err(Error::BadParent("Window or menu parent required, got Font".to_string())),
+        AnyHandle::HBITMAP(_) | AnyHandle::HICON(_) | AnyHandle::HCURSOR(_) =>  Err(Error::BadParent("Window or menu parent required, got Image".to_string())),
         AnyHandle::Custom(_, _) =>  Err(Error::BadParent("Window or menu parent required, got custom control".to_string())),
    }
 }
 
+#[inline(always)]
+unsafe fn build_popup_menu() -> HMENU {
+    use user32::CreatePopupMenu;
+    use low::menu_helper::use_menu_command;
+
+    let h = CreatePopupMenu();
+    use_menu_command(h);
+    h
+}
+
 #[inline(always)]
 unsafe fn build_menu_item<S: Clone+Into<String>, ID: Clone+Hash>(ui: &Ui<ID>, t: &MenuItemT<S, ID>) -> Result<(HMENU, UINT), Error> {
     use user32::{AppendMenuW, CreateMenu, GetMenu, SetMenu, DrawMenuBar};
@@ -19,7 +19,8 @@
 */
 
 pub mod window;
-pub mod menu;
+#[cfg(windows)] pub mod dialog;
+#[cfg(windows)] pub mod menu;
 pub mod button;
 pub mod textinput;
 pub mod textbox;
@@ -31,17 +32,24 @@ pub mod combobox;
 pub mod groupbox;
 pub mod progress_bar;
 pub mod datepicker;
-pub mod file_dialog;
-pub mod timer;
+pub mod numinput;
+pub mod listview;
+pub mod treeview;
+pub mod tabs;
+#[cfg(windows)] pub mod accelerator;
+#[cfg(windows)] pub mod file_dialog;
+#[cfg(windows)] pub mod timer;
+#[cfg(windows)] pub mod tray;
 pub mod canvas;
 
 use std::any::TypeId;
 use std::hash::Hash;
 
-use winapi::{HWND, HMENU, UINT, HFONT};
+use winapi::{HWND, HMENU, UINT, HFONT, HBITMAP, HICON, HCURSOR};
 
 pub use controls::window::{WindowT, Window};
-pub use controls::menu::{MenuT, Menu, MenuItemT, MenuItem, SeparatorT, Separator};
+#[cfg(windows)] pub use controls::dialog::{DialogT, Dialog};
+#[cfg(windows)] pub use controls::menu::{MenuT, Menu, PopupMenuT, PopupMenu, MenuItemT, MenuItem, SeparatorT, Separator};
 pub use controls::button::{ButtonT, Button};
 pub use controls::textinput::{TextInputT, TextInput};
 pub use controls::textbox::{TextBoxT, TextBox};
@@ -52,12 +60,19 @@ pub use controls::listbox::{ListBoxT, ListBox};
 pub use controls::combobox::{ComboBoxT, ComboBox};
 pub use controls::groupbox::{GroupBoxT, GroupBox};
 pub use controls::progress_bar::{ProgressBarT, ProgressBar};
-pub use controls::file_dialog::{FileDialogT, FileDialog};
-pub use controls::timer::{TimerT, Timer};
-pub use controls::canvas::{CanvasT, Canvas, CanvasRenderer};
+#[cfg(windows)] pub use controls::file_dialog::{FileDialogT, FileDialog};
+#[cfg(windows)] pub use controls::timer::{TimerT, Timer};
+#[cfg(windows)] pub use controls::tray::{TrayIconT, TrayIcon};
+#[cfg(windows)] pub use controls::canvas::{CanvasT, Canvas, rgba_to_pbgra, CanvasRenderer};
+pub use controls::canvas::{Path, PathBuilder, PathSegment, Figure, TextLayout, TextMetrics, HitTestMetrics, Renderer, RecordingRenderer, DrawCommand};
 pub use controls::datepicker::{DatePickerT, DatePicker};
+pub use controls::numinput::{NumericInputT, NumericInput};
+pub use controls::listview::{ListViewT, ListView, ListViewColumn, TextColumn};
+pub use controls::treeview::{TreeViewT, TreeView, TreeNode, TreeViewLoader};
+pub use controls::tabs::{TabsContainerT, TabsContainer, TabT, Tab};
+#[cfg(windows)] pub use controls::accelerator::{AcceleratorT, Accelerator, Shortcut};
 use ui::Ui;
-use events::Event;
+use events::{Event, EventDecoders};
 use error::Error;
 
 /**
@@ -70,17 +85,21 @@ pub enum AnyHandle {
     HMENU(HMENU),
     HMENU_ITEM(HMENU, UINT),
     HFONT(HFONT),
+    HBITMAP(HBITMAP),
+    HICON(HICON),
+    HCURSOR(HCURSOR),
     Custom(TypeId, usize)
 }
 
 /**
     An enum that list type names for the common controls.
 
-    This is used internally to differentiate the common control notification codes.
+    The notification codes are decoded by the `EventDecoders` returned by `ControlT::decoders`.
 */
 #[derive(Clone, Debug)]
 pub enum ControlType {
     Window,
+    Dialog,
     Button,
     TextInput,
     TextBox,
@@ -95,9 +114,16 @@ pub enum ControlType {
     ComboBox,
     GroupBox,
     NumericInput,
+    ListView,
+    TreeView,
+    TabsContainer,
+    Tab,
+    Accelerator,
+    Binding,
     DatePicker,
     FileDialog,
     Canvas,
+    TrayIcon,
     Undefined  // Control is not a common control
 }
 
@@ -120,6 +146,19 @@ pub trait ControlT<ID: Clone+Hash> {
         Should return the events supported by the control.
     */
     fn events(&self) -> Vec<Event> { Vec::new() }
+
+    /**
+        Should return the functions that translate the system notifications (`WM_COMMAND` and `WM_NOTIFY`) sent by the control
+        into NWG events. The decoders are registered under `resource_type_id` when the first control of this type is packed.
+    */
+    fn decoders(&self) -> EventDecoders { EventDecoders::default() }
+
+    /**
+        Called once the control was added to the ui under `id`. Controls that react to their own events
+        bind their callbacks here, using `id` as callback ID. Does nothing by default.
+    */
+    #[allow(unused_variables)]
+    fn packed(&self, ui: &Ui<ID>, id: &ID) { }
 }
 
 /**
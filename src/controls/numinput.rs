@@ -19,38 +19,57 @@
 */
 use std::hash::Hash;
 use std::any::TypeId;
+use std::cell::Cell;
+use std::cmp;
+use std::mem;
 use std::ptr;
 
 use winapi::{HWND, HFONT, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR};
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
+
+/// Maximum number of decimals of a numeric input in floating point mode
+pub const NUMERICINPUT_MAX_DECIMALS: u32 = 18;
 
 /**
-    An NumericInput control is a pair of arrow buttons next to a edit control.
+    A template that creates a numeric input. A numeric input is a pair of arrow buttons next to a edit control.
     The numeric input edit only accepts numbers.
 
+    The value of the control is always an `i64`. If `decimals` is greater than 0, the control is in floating point mode:
+    the value is a fixed point number with `decimals` digits after the dot. For example, with `decimals: 2`, the value
+    `1234` is displayed as `12.34`. `range` and `step` use the same unit as `value`.
+
+    Events:  
+    Event::Destroyed, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw  
+
     Members:  
     • `value`: The default value of the control  
+    • `range`: The range of values accepted by the control (min, max)  
+    • `step`: The value added or removed when the user clicks on the arrows  
+    • `decimals`: The number of decimals displayed. If 0, the control only accepts integers. Maximum 18.  
     • `position`: The start position of the numeric input  
     • `size`: The start size of the numeric input  
     • `visible`: If the numeric input should be visible to the user  
     • `disabled`: If the user can or can't execute any actions on the numeric input  
-    • `readonly`: If the user can or can't edit the numeric input value 
-    • `range`: The range of values accepted by the control
+    • `readonly`: If the user can or can't edit the numeric input value  
     • `parent`: The numeric input parent  
     • `font`: The numeric input font. If None, use the system default  
 */
+#[derive(Clone)]
 pub struct NumericInputT<ID: Hash+Clone> {
     pub value: i64,
+    pub range: (i64, i64),
+    pub step: i64,
+    pub decimals: u32,
     pub position: (i32, i32),
     pub size: (u32, u32),
     pub visible: bool,
     pub disabled: bool,
     pub readonly: bool,
-    pub range: (i64, i64),
     pub parent: ID,
     pub font: Option<ID>,
 }
@@ -59,14 +78,16 @@ impl<ID: Hash+Clone> ControlT<ID> for NumericInputT<ID> {
     fn resource_type_id(&self) -> TypeId { TypeId::of::<NumericInput>() }
 
     fn events(&self) -> Vec<Event> {
-        vec![Event::Destroyed, Event::Moved, Event::Resized]
+        vec![Event::Destroyed, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_numeric_command), notify: None }
     }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
         use low::window_helper::{set_window_font, handle_of_window, handle_of_font};
 
-        return Err(Error::Unimplemented);
-
         // A NumericInput is composed of three controls: a custom window, a textinput and a "up down".
 
         // Get the parent handle
@@ -77,41 +98,148 @@ impl<ID: Hash+Clone> ControlT<ID> for NumericInputT<ID> {
 
         // Get the font handle (if any)
         let font_handle: Option<HFONT> = match self.font.as_ref() {
-            Some(font_id) => 
-                match handle_of_font(ui, &font_id, "The font of a button must be a font resource.") {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a numeric input must be a font resource.") {
                     Ok(h) => Some(h),
                     Err(e) => { return Err(e); }
                 },
             None => None
         };
 
+        let range = (cmp::min(self.range.0, self.range.1), cmp::max(self.range.0, self.range.1));
+        let data = Box::new(NumericData {
+            value: Cell::new(clamp(self.value, range)),
+            range: Cell::new(range),
+            step: Cell::new(self.step),
+            decimals: cmp::min(self.decimals, NUMERICINPUT_MAX_DECIMALS),
+            edit: Cell::new(ptr::null_mut()),
+            updown: Cell::new(ptr::null_mut()),
+        });
+
         let base_handle = match unsafe{ build_base(self, parent) } {
             Ok(h) => h,
             Err(e) => { return Err(e); }
         };
 
-        let edit_handle =  match unsafe{ build_edit(self, base_handle) } {
+        let edit_handle = match unsafe{ build_edit(self, &data, base_handle) } {
             Ok(h) => h,
-            Err(e) => { return Err(e); }
+            Err(e) => { unsafe{ free_base(base_handle) }; return Err(e); }
         };
 
-        unsafe{ 
-            hook(edit_handle);
-            set_window_font(edit_handle, font_handle, true); 
+        let updown_handle = match unsafe{ build_updown(edit_handle, base_handle) } {
+            Ok(h) => h,
+            Err(e) => { unsafe{ free_base(base_handle) }; return Err(e); }
+        };
+
+        data.edit.set(edit_handle);
+        data.updown.set(updown_handle);
+
+        unsafe{
+            layout_children(base_handle, &data);
+            hook(base_handle, edit_handle, &data);
+            set_window_font(edit_handle, font_handle, true);
         }
 
         Ok(Box::new(
             NumericInput {
                 handle: base_handle,
-                edit_handle: edit_handle,
+                data: data
             }
         ))
     }
 }
 
+/**
+    A numeric input control
+*/
 pub struct NumericInput {
     handle: HWND,
-    edit_handle: HWND,
+    data: Box<NumericData>
+}
+
+impl NumericInput {
+
+    /// Return the value of the control. In floating point mode, this is the value multiplied by `10^decimals`.
+    pub fn get_value(&self) -> i64 {
+        self.data.value.get()
+    }
+
+    /// Set the value of the control. The value is clamped to the control range. Raises `ValueChanged` if the value changes.
+    pub fn set_value(&self, value: i64) {
+        unsafe{ set_value(&self.data, value); }
+    }
+
+    /// Return the value of the control as a floating point number
+    pub fn get_float(&self) -> f64 {
+        (self.data.value.get() as f64) / 10f64.powi(self.data.decimals as i32)
+    }
+
+    /// Set the value of the control from a floating point number. The value is rounded to the number of decimals of the control.
+    pub fn set_float(&self, value: f64) {
+        let value = (value * 10f64.powi(self.data.decimals as i32)).round();
+        let value = if value >= (i64::max_value() as f64) {
+            i64::max_value()
+        } else if value <= (i64::min_value() as f64) {
+            i64::min_value()
+        } else {
+            value as i64
+        };
+
+        unsafe{ set_value(&self.data, value); }
+    }
+
+    /// Return the range of values accepted by the control (min, max)
+    pub fn get_range(&self) -> (i64, i64) {
+        self.data.range.get()
+    }
+
+    /// Set the range of values accepted by the control. The current value is clamped to the new range.
+    pub fn set_range(&self, min: i64, max: i64) {
+        self.data.range.set((cmp::min(min, max), cmp::max(min, max)));
+        unsafe{ set_value(&self.data, self.data.value.get()); }
+    }
+
+    /// Return the value added or removed when the user clicks on the arrows
+    pub fn get_step(&self) -> i64 {
+        self.data.step.get()
+    }
+
+    /// Set the value added or removed when the user clicks on the arrows
+    pub fn set_step(&self, step: i64) {
+        self.data.step.set(step);
+    }
+
+    /// Return the number of decimals of the control. If 0, the control only accepts integers.
+    pub fn get_decimals(&self) -> u32 {
+        self.data.decimals
+    }
+
+    /// Set or unset the readonly status on the control
+    pub fn set_readonly(&self, readonly: bool) {
+        use low::defs::EM_SETREADONLY;
+        unsafe{ send_message(self.data.edit.get(), EM_SETREADONLY, readonly as WPARAM, 0); }
+    }
+
+    /// Return `true` if the user cannot edit the value of the control or `false` if the user can
+    pub fn get_readonly(&self) -> bool {
+        use low::window_helper::get_window_long;
+        use low::defs::ES_READONLY;
+        use winapi::GWL_STYLE;
+
+        let style = get_window_long(self.data.edit.get(), GWL_STYLE) as u32;
+
+        (style & ES_READONLY) == ES_READONLY
+    }
+
+    pub fn get_text(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.data.edit.get()) } }
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); layout_children(self.handle, &self.data); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
 }
 
 impl Control for NumericInput {
@@ -120,23 +248,14 @@ impl Control for NumericInput {
         AnyHandle::HWND(self.handle)
     }
 
-    fn control_type(&self) -> ControlType { 
-        ControlType::NumericInput 
+    fn control_type(&self) -> ControlType {
+        ControlType::NumericInput
     }
 
     fn free(&mut self) {
-        use user32::{DestroyWindow, UnregisterClassW};
-        use kernel32::GetModuleHandleW;
-        use low::other_helper::to_utf16;
-        
         unsafe{
-            unhook(self.edit_handle);
-
-            DestroyWindow(self.handle); 
-
-            let cls = to_utf16(NUMERICINPUT_CLASS_NAME);
-            let hmod = GetModuleHandleW(ptr::null_mut());
-            UnregisterClassW(cls.as_ptr(), hmod);
+            unhook(self.handle, self.data.edit.get());
+            free_base(self.handle);
         }
     }
 
@@ -147,16 +266,129 @@ impl Control for NumericInput {
 
 const NUMERICINPUT_CLASS_NAME: &'static str = "NWG_BUILTIN_NUMERICINPUT";
 const CUSTOM_EVENTS_DISPATCH_ID: UINT_PTR = 5674;
+const UPDOWN_WIDTH: u32 = 16;
+
+/// Notification code sent to the parent of a numeric input when its value changes
+const NIN_VALUECHANGED: u32 = 1;
+
+/**
+    The state of a numeric input. Shared between the control and its subclasses.
+*/
+struct NumericData {
+    value: Cell<i64>,
+    range: Cell<(i64, i64)>,
+    step: Cell<i64>,
+    decimals: u32,
+    edit: Cell<HWND>,
+    updown: Cell<HWND>
+}
+
+#[inline(always)]
+fn clamp(value: i64, range: (i64, i64)) -> i64 {
+    cmp::max(range.0, cmp::min(range.1, value))
+}
+
+/**
+    Format a value with `decimals` digits after the dot
+*/
+fn format_value(value: i64, decimals: u32) -> String {
+    if decimals == 0 {
+        return format!("{}", value);
+    }
+
+    let sign = if value < 0 { "-" } else { "" };
+    let abs = value.wrapping_abs() as u64; // i64::MIN wraps to itself which is the right value once cast to u64
+    let p = 10u64.pow(decimals);
+
+    format!("{}{}.{:0width$}", sign, abs / p, abs % p, width = decimals as usize)
+}
+
+/**
+    Parse a value with at most `decimals` digits after the dot. Return `None` if the text is not a valid number.
+*/
+fn parse_value(text: &str, decimals: u32) -> Option<i64> {
+    let text = text.trim();
+    let (negative, text) = if text.starts_with('-') { (true, &text[1..]) } else { (false, text) };
+
+    let (int_part, frac_part) = match text.find('.') {
+        Some(i) => (&text[..i], &text[i+1..]),
+        None => (text, "")
+    };
+
+    if (int_part.is_empty() && frac_part.is_empty()) || frac_part.len() > decimals as usize {
+        return None;
+    }
+
+    if !int_part.chars().chain(frac_part.chars()).all(|c| c.is_digit(10)) {
+        return None;
+    }
+
+    // Pad the decimals with zeroes and parse the whole thing as a single integer
+    let mut digits = String::with_capacity(int_part.len() + decimals as usize + 1);
+    if negative { digits.push('-'); }
+    digits.push_str(int_part);
+    digits.push_str(frac_part);
+    for _ in frac_part.len()..(decimals as usize) { digits.push('0'); }
+
+    digits.parse::<i64>().ok()
+}
+
+/**
+    Clamp `value`, save it and update the edit text. Raise `ValueChanged` if the value is not the same as the old one.
+
+    The value is saved before the edit text is updated, so the `EN_UPDATE` sent by the edit is ignored.
+*/
+unsafe fn set_value(data: &NumericData, value: i64) {
+    use low::window_helper::{set_window_text, get_window_parent};
+
+    let old_value = data.value.get();
+    let value = clamp(value, data.range.get());
+    data.value.set(value);
+    set_window_text(data.edit.get(), &format_value(value, data.decimals));
+
+    if value != old_value {
+        notify_parent(get_window_parent(data.edit.get()), NIN_VALUECHANGED);
+    }
+}
+
+/**
+    Send a notification to the parent of the numeric input the same way a system control would.
+*/
+unsafe fn notify_parent(hwnd: HWND, code: u32) {
+    use winapi::{WM_COMMAND, MAKELONG};
+    use low::window_helper::get_window_parent;
+
+    let w = MAKELONG(0, code as u16) as WPARAM;
+    send_message(get_window_parent(hwnd), WM_COMMAND, w, hwnd as LPARAM);
+}
+
+/**
+    Decode the notifications sent by the numeric input to its parent
+*/
+unsafe fn decode_numeric_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::backend;
+
+    match ncode {
+        NIN_VALUECHANGED => match backend::current().get_subclass(handle, Some(numeric_hook), CUSTOM_EVENTS_DISPATCH_ID) {
+            Some(data) => {
+                let data: &NumericData = mem::transmute(data);
+                Some((Event::ValueChanged, EventArgs::Value(data.value.get())))
+            },
+            None => None
+        },
+        _ => None
+    }
+}
 
 #[allow(unused_variables)]
 unsafe extern "system" fn numeric_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
     use winapi::{WM_CREATE, WM_CLOSE};
-    use user32::{DefWindowProcW, ShowWindow};
+    use low::window_helper::{set_window_visibility, def_window_proc};
 
     let handled = match msg {
         WM_CREATE => true,
         WM_CLOSE => {
-            ShowWindow(hwnd, 0);
+            set_window_visibility(hwnd, false);
             true
         }
         _ => false
@@ -165,14 +397,95 @@ unsafe extern "system" fn numeric_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: L
     if handled {
         0
     } else {
-        DefWindowProcW(hwnd, msg, w, l)
+        def_window_proc(hwnd, msg, w, l)
     }
 }
 
+/**
+    Handle the notifications of the edit and the up-down controls
+*/
+#[allow(unused_variables)]
+unsafe extern "system" fn numeric_hook(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use winapi::{WM_COMMAND, WM_NOTIFY, WM_SIZE, NMUPDOWN, UDN_DELTAPOS, HIWORD, DWORD};
+    use low::defs::{EN_UPDATE, EN_KILLFOCUS};
+    use low::window_helper::{get_window_text, def_subclass_proc};
+
+    let data: &NumericData = mem::transmute(data);
+
+    match msg {
+        WM_COMMAND if l as HWND == data.edit.get() => {
+            match HIWORD(w as DWORD) as u32 {
+                EN_UPDATE => {
+                    // Invalid or out of range values are fixed when the edit loses the focus
+                    if let Some(v) = parse_value(&get_window_text(data.edit.get()), data.decimals) {
+                        let v = clamp(v, data.range.get());
+                        if v != data.value.get() {
+                            data.value.set(v);
+                            notify_parent(hwnd, NIN_VALUECHANGED);
+                        }
+                    }
+                },
+                EN_KILLFOCUS => {
+                    set_value(data, data.value.get());
+                },
+                _ => {}
+            }
+            0
+        },
+        WM_NOTIFY => {
+            let nmud: &NMUPDOWN = mem::transmute(l);
+            if nmud.hdr.hwndFrom == data.updown.get() && nmud.hdr.code == UDN_DELTAPOS {
+                // Up is a positive delta because the up-down range is not inverted
+                let delta = data.step.get().saturating_mul(nmud.iDelta as i64);
+                set_value(data, data.value.get().saturating_add(delta));
+                1 // Prevent the up-down control from changing its own position
+            } else {
+                def_subclass_proc(hwnd, msg, w, l)
+            }
+        },
+        WM_SIZE => {
+            layout_children(hwnd, data);
+            def_subclass_proc(hwnd, msg, w, l)
+        },
+        _ => def_subclass_proc(hwnd, msg, w, l)
+    }
+}
+
+/**
+    Filter the characters typed in the edit. Only accept digits, the minus sign and, in floating point mode, the dot.
+*/
 #[allow(unused_variables)]
 unsafe extern "system" fn edit_hook(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
-    use comctl32::DefSubclassProc;
-    DefSubclassProc(hwnd, msg, w, l)
+    use winapi::WM_CHAR;
+    use low::window_helper::def_subclass_proc;
+
+    let data: &NumericData = mem::transmute(data);
+
+    if msg == WM_CHAR {
+        let accepted = match ::std::char::from_u32(w as u32) {
+            Some(c) => c.is_digit(10) || c == '-' || (c == '.' && data.decimals > 0) || (c as u32) < 0x20,
+            None => false
+        };
+
+        if !accepted { return 0; }
+    }
+
+    def_subclass_proc(hwnd, msg, w, l)
+}
+
+/**
+    Place the edit and the up-down control in the client area of the base window
+*/
+unsafe fn layout_children(base: HWND, data: &NumericData) {
+    use low::window_helper::{get_window_size, set_window_position, set_window_size};
+
+    let (w, h) = get_window_size(base);
+    let edit_w = w.saturating_sub(UPDOWN_WIDTH);
+
+    set_window_position(data.edit.get(), 0, 0);
+    set_window_size(data.edit.get(), edit_w, h, false);
+    set_window_position(data.updown.get(), edit_w as i32, 0);
+    set_window_size(data.updown.get(), cmp::min(w, UPDOWN_WIDTH), h, false);
 }
 
 #[inline(always)]
@@ -180,13 +493,13 @@ unsafe fn build_base<ID: Hash+Clone>(t: &NumericInputT<ID>, parent: HWND) -> Res
     use low::window_helper::{WindowParams, SysclassParams, build_sysclass, build_window};
     use winapi::{CS_HREDRAW, CS_VREDRAW, WS_VISIBLE, WS_CHILD, WS_BORDER, WS_DISABLED, WS_CLIPCHILDREN};
 
-    let params = SysclassParams { 
+    let params = SysclassParams {
         class_name: NUMERICINPUT_CLASS_NAME,
         sysproc: Some(numeric_sysproc),
         background: None,
         style: Some(CS_HREDRAW | CS_VREDRAW)
     };
-    
+
     if let Err(e) = build_sysclass(params) {
         return Err(Error::System(e));
     }
@@ -206,31 +519,25 @@ unsafe fn build_base<ID: Hash+Clone>(t: &NumericInputT<ID>, parent: HWND) -> Res
     };
 
     match build_window(params) {
-        Ok(h) => {
-            //set_window_long(h, GWL_USERDATA, t.exit_on_close as usize);
-            Ok(h)
-        },
+        Ok(h) => Ok(h),
         Err(e) => Err(Error::System(e))
     }
 }
 
 #[inline(always)]
-unsafe fn build_edit<ID: Hash+Clone>(t: &NumericInputT<ID>, parent: HWND) -> Result<HWND, Error> {
+unsafe fn build_edit<ID: Hash+Clone>(t: &NumericInputT<ID>, data: &NumericData, parent: HWND) -> Result<HWND, Error> {
     use low::window_helper::{WindowParams, build_window};
     use low::defs::{ES_AUTOHSCROLL, ES_READONLY};
     use winapi::{DWORD, WS_VISIBLE, WS_CHILD};
 
-    let flags: DWORD = WS_CHILD | ES_AUTOHSCROLL | WS_VISIBLE | 
+    let flags: DWORD = WS_CHILD | ES_AUTOHSCROLL | WS_VISIBLE |
         if t.readonly { ES_READONLY } else { 0 };
 
-    let mut size = t.size.clone();
-    size.0 -= 15;
-
     let params = WindowParams {
-        title: format!("{}", t.value),
+        title: format_value(data.value.get(), data.decimals),
         class_name: "EDIT",
-        position: (0,0),
-        size: size,
+        position: (0, 0),
+        size: t.size.clone(),
         flags: flags,
         ex_flags: Some(0),
         parent: parent
@@ -242,20 +549,58 @@ unsafe fn build_edit<ID: Hash+Clone>(t: &NumericInputT<ID>, parent: HWND) -> Res
     }
 }
 
-#[allow(unused_variables, dead_code)]
 #[inline(always)]
-unsafe fn build_updown<ID: Hash+Clone>(t: &NumericInputT<ID>, parent: HWND) -> Result<HWND, Error> {
-    Err(Error::Unimplemented)
+unsafe fn build_updown(edit: HWND, parent: HWND) -> Result<HWND, Error> {
+    use low::window_helper::{WindowParams, build_window};
+    use winapi::{DWORD, WS_VISIBLE, WS_CHILD, UDS_ARROWKEYS, UDS_HOTTRACK, UDM_SETBUDDY, UDM_SETRANGE32};
+
+    let flags: DWORD = WS_CHILD | WS_VISIBLE | UDS_ARROWKEYS | UDS_HOTTRACK;
+
+    let params = WindowParams {
+        title: "",
+        class_name: "msctls_updown32",
+        position: (0, 0),
+        size: (UPDOWN_WIDTH, 0),
+        flags: flags,
+        ex_flags: Some(0),
+        parent: parent
+    };
+
+    match build_window(params) {
+        Ok(h) => {
+            // The value is not stored in the up-down control. The range only makes sure that the up arrow sends a positive delta.
+            send_message(h, UDM_SETBUDDY, edit as WPARAM, 0);
+            send_message(h, UDM_SETRANGE32, 0, 100);
+            Ok(h)
+        },
+        Err(e) => Err(Error::System(e))
+    }
+}
+
+#[inline(always)]
+unsafe fn free_base(base: HWND) {
+    use low::window_helper::destroy_window;
+    use low::backend;
+
+    destroy_window(base);
+    backend::current().unregister_sysclass(NUMERICINPUT_CLASS_NAME);
 }
 
 #[inline(always)]
-unsafe fn hook(edit: HWND) {
-    use comctl32::SetWindowSubclass;
-    SetWindowSubclass(edit, Some(edit_hook), CUSTOM_EVENTS_DISPATCH_ID, 0);
+unsafe fn hook(base: HWND, edit: HWND, data: &NumericData) {
+    use low::backend;
+
+    let backend = backend::current();
+    let data = data as *const NumericData as DWORD_PTR;
+    backend.set_subclass(base, Some(numeric_hook), CUSTOM_EVENTS_DISPATCH_ID, data);
+    backend.set_subclass(edit, Some(edit_hook), CUSTOM_EVENTS_DISPATCH_ID, data);
 }
 
 #[inline(always)]
-unsafe fn unhook(edit: HWND) {
-    use comctl32::RemoveWindowSubclass;
-    RemoveWindowSubclass(edit, Some(edit_hook), CUSTOM_EVENTS_DISPATCH_ID);
-}
\ No newline at end of file
+unsafe fn unhook(base: HWND, edit: HWND) {
+    use low::backend;
+
+    let backend = backend::current();
+    backend.remove_subclass(base, Some(numeric_hook), CUSTOM_EVENTS_DISPATCH_ID);
+    backend.remove_subclass(edit, Some(edit_hook), CUSTOM_EVENTS_DISPATCH_ID);
+}
@@ -23,7 +23,7 @@ use std::any::TypeId;
 use std::mem;
 
 use winapi::HWND;
-use user32::SendMessageW;
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
@@ -128,7 +128,7 @@ impl ProgressBar {
     pub fn get_range(&self) -> (u32, u32) {
         use winapi::{PBM_GETRANGE, PBRANGE};
         let mut range = PBRANGE{iLow: 0, iHigh: 0};
-        unsafe{ SendMessageW(self.handle, PBM_GETRANGE, 0, mem::transmute(&mut range) ); }
+        unsafe{ send_message(self.handle, PBM_GETRANGE, 0, mem::transmute(&mut range) ); }
 
         (range.iLow as u32, range.iHigh as u32)
     }
@@ -149,7 +149,7 @@ impl ProgressBar {
     /// Return the current step of the progress bar
     pub fn get_step(&self) -> u32 {
         use winapi::PBM_GETSTEP;
-        unsafe{ SendMessageW(self.handle, PBM_GETSTEP, 0, 0) as u32 }
+        unsafe{ send_message(self.handle, PBM_GETSTEP, 0, 0) as u32 }
     }
 
     /**
@@ -163,7 +163,7 @@ impl ProgressBar {
     /// Return the current value of the progress bar
     pub fn get_value(&self) -> u32 {
         use winapi::PBM_GETPOS;
-        unsafe{ SendMessageW(self.handle, PBM_GETPOS, 0, 0) as u32 }
+        unsafe{ send_message(self.handle, PBM_GETPOS, 0, 0) as u32 }
     }
 
     /// Set the progress bar value
@@ -174,7 +174,7 @@ impl ProgressBar {
     /// Get the progress bar state
     pub fn get_state(&self) -> ProgressBarState {
         use winapi::{PBM_GETSTATE, PBST_ERROR, PBST_PAUSED};
-        match unsafe{ SendMessageW(self.handle, PBM_GETSTATE, 0, 0) as i32 } {
+        match unsafe{ send_message(self.handle, PBM_GETSTATE, 0, 0) as i32 } {
             PBST_ERROR => ProgressBarState::Error,
             PBST_PAUSED => ProgressBarState::Paused,
             _ => ProgressBarState::Normal
@@ -192,7 +192,7 @@ impl ProgressBar {
     */
     pub fn step(&self) {
         use winapi::PBM_STEPIT;
-        unsafe{ SendMessageW(self.handle, PBM_STEPIT, 0, 0); }
+        unsafe{ send_message(self.handle, PBM_STEPIT, 0, 0); }
     }
 
     /**
@@ -200,7 +200,7 @@ impl ProgressBar {
     */
     pub fn advance(&self, amount: u32) {
         use winapi::{PBM_DELTAPOS, WPARAM};
-        unsafe{ SendMessageW(self.handle, PBM_DELTAPOS, amount as WPARAM, 0); }
+        unsafe{ send_message(self.handle, PBM_DELTAPOS, amount as WPARAM, 0); }
     }
 
     pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
@@ -224,8 +224,8 @@ impl Control for ProgressBar {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
@@ -235,19 +235,19 @@ impl Control for ProgressBar {
 #[inline(always)]
 unsafe fn set_range(handle: HWND, min: u32, max: u32) {
     use winapi::{PBM_SETRANGE32, WPARAM, LPARAM};
-    SendMessageW(handle, PBM_SETRANGE32, min as WPARAM, max as LPARAM);
+    send_message(handle, PBM_SETRANGE32, min as WPARAM, max as LPARAM);
 }
 
 #[inline(always)]
 unsafe fn set_step(handle: HWND, step: u32) {
     use winapi::{PBM_SETSTEP, WPARAM};
-    SendMessageW(handle, PBM_SETSTEP, step as WPARAM, 0);
+    send_message(handle, PBM_SETSTEP, step as WPARAM, 0);
 }
 
 #[inline(always)]
 unsafe fn set_value(handle: HWND, val: u32) {
     use winapi::{PBM_SETPOS, WPARAM};
-    SendMessageW(handle, PBM_SETPOS, val as WPARAM, 0);
+    send_message(handle, PBM_SETPOS, val as WPARAM, 0);
 }
 
 #[inline(always)]
@@ -259,5 +259,5 @@ unsafe fn set_state(handle: HWND, state: &ProgressBarState) {
         &ProgressBarState::Error => PBST_ERROR,
     };
 
-    SendMessageW(handle, PBM_SETSTATE, state as WPARAM, 0);
+    send_message(handle, PBM_SETSTATE, state as WPARAM, 0);
 }
\ No newline at end of file
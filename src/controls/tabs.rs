@@ -0,0 +1,460 @@
+/*!
+    A tabs container and the tab pages it displays
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::mem;
+
+use winapi::{HWND, HFONT, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, NMHDR, RECT};
+use low::window_helper::send_message;
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+use events::{Event, EventArgs, EventDecoders};
+use low::other_helper::to_utf16;
+
+const TAB_CLASS_NAME: &'static str = "NWG_TAB";
+
+/// A magic number to identify the subclass that resizes the tab pages
+const TABS_LAYOUT_ID: UINT_PTR = 5675;
+
+/**
+    A template that creates a tabs container. The pages of the container are created with `TabT`.
+
+    Only the page of the selected tab is visible. When the user selects another tab, the container raises
+    `Event::TabChanged` with `EventArgs::Selection(Some(index))`.
+
+    Available events:  
+    Event::Destroyed, Event::TabChanged, Event::Moved, Event::Resized, Event::Raw  
+
+    Members:  
+    • `position`: The start position of the tabs container  
+    • `size`: The start size of the tabs container  
+    • `visible`: If the tabs container should be visible to the user  
+    • `disabled`: If the user can or can't select the tabs  
+    • `parent`: The tabs container parent  
+    • `font`: The font of the tabs. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct TabsContainerT<ID: Hash+Clone> {
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<ID: Hash+Clone> ControlT<ID> for TabsContainerT<ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<TabsContainer>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::TabChanged, Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: None, notify: Some(decode_tabs_notify) }
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, set_window_font, set_window_long, handle_of_window, handle_of_font};
+        use low::backend;
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_CLIPCHILDREN, WS_CLIPSIBLINGS, WS_TABSTOP, GWL_USERDATA};
+
+        let flags: DWORD = WS_CHILD | WS_CLIPCHILDREN | WS_CLIPSIBLINGS | WS_TABSTOP |
+        if self.visible    { WS_VISIBLE }   else { 0 } |
+        if self.disabled   { WS_DISABLED }  else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a tabs container must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a tabs container must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "SysTabControl32",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => {
+                let data = Rc::new(TabsData{ handle: h, pages: RefCell::new(Vec::new()), selected: Cell::new(0) });
+                unsafe{
+                    set_window_font(h, font_handle, true);
+
+                    // The notifications decoder and the layout hook read the pages through the container data
+                    let data_ptr = &*data as *const TabsData;
+                    set_window_long(h, GWL_USERDATA, data_ptr as usize);
+                    backend::current().set_subclass(h, Some(tabs_hook), TABS_LAYOUT_ID, data_ptr as DWORD_PTR);
+                }
+
+                Ok( Box::new(TabsContainer{handle: h, data: data}) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    A tabs container
+*/
+pub struct TabsContainer {
+    handle: HWND,
+    data: Rc<TabsData>
+}
+
+impl TabsContainer {
+
+    /// Return the number of tabs in the container
+    pub fn len(&self) -> usize { self.data.pages.borrow().len() }
+
+    /// Return the index of the selected tab. Return None if the container has no tabs.
+    pub fn get_selected_index(&self) -> Option<usize> {
+        if self.len() > 0 { Some(self.data.selected.get()) } else { None }
+    }
+
+    /// Select the tab at `index` and show its page. Do not raise `Event::TabChanged`.
+    /// `Panics` if index is out of bounds.
+    pub fn set_selected_index(&self, index: usize) {
+        use winapi::TCM_SETCURSEL;
+
+        assert!(index < self.len(), "Tab index out of bounds");
+        unsafe{
+            send_message(self.handle, TCM_SETCURSEL, index as WPARAM, 0);
+            show_page(&self.data, index);
+        }
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); layout_pages(&self.data); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+}
+
+impl Control for TabsContainer {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::TabsContainer
+    }
+
+    fn free(&mut self) {
+        use low::window_helper::{destroy_window, set_window_long};
+        use low::backend;
+        use winapi::GWL_USERDATA;
+
+        unsafe{
+            set_window_long(self.handle, GWL_USERDATA, 0);
+            backend::current().remove_subclass(self.handle, Some(tabs_hook), TABS_LAYOUT_ID);
+            destroy_window(self.handle);
+        }
+    }
+
+}
+
+/**
+    A template that creates a tab page. The parent of a tab must be a `TabsContainer`.
+
+    A tab page can be used as the parent of other controls. The tab is added at the end of the container tabs and the
+    page fills the display area of the container.
+
+    Available events:  
+    Event::Destroyed, Event::Moved, Event::Resized, Event::Raw  
+
+    Members:  
+    • `text`: The text of the tab  
+    • `parent`: The tabs container  
+*/
+#[derive(Clone)]
+pub struct TabT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub text: S,
+    pub parent: ID,
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TabT<S, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Tab>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, SysclassParams, build_sysclass, build_window};
+        use winapi::{DWORD, TCITEMW, TCIF_TEXT, TCM_INSERTITEMW, WS_CHILD, WS_CLIPCHILDREN, WS_CLIPSIBLINGS, WS_VISIBLE};
+
+        let data = match ui.get::<TabsContainer>(&self.parent) {
+            Ok(container) => container.data.clone(),
+            Err(Error::BadType) => { return Err(Error::BadParent("The parent of a tab must be a tabs container.".to_string())); },
+            Err(e) => { return Err(e); }
+        };
+
+        let params = SysclassParams {
+            class_name: TAB_CLASS_NAME,
+            sysproc: Some(tab_sysproc),
+            background: None,
+            style: None
+        };
+
+        if let Err(e) = unsafe{ build_sysclass(params) } {
+            return Err(Error::System(e));
+        }
+
+        // Only the page of the selected tab is visible. The first page is selected by default.
+        let index = data.pages.borrow().len();
+        let visible = index == data.selected.get();
+        let text: String = self.text.clone().into();
+
+        let flags: DWORD = WS_CHILD | WS_CLIPCHILDREN | WS_CLIPSIBLINGS |
+        if visible { WS_VISIBLE } else { 0 };
+
+        let params = WindowParams {
+            title: text.clone(),
+            class_name: TAB_CLASS_NAME,
+            position: (0, 0),
+            size: (0, 0),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: data.handle
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => unsafe {
+                let mut text = to_utf16(&text);
+                let mut item: TCITEMW = mem::zeroed();
+                item.mask = TCIF_TEXT;
+                item.pszText = text.as_mut_ptr();
+                send_message(data.handle, TCM_INSERTITEMW, index as WPARAM, &item as *const TCITEMW as LPARAM);
+
+                data.pages.borrow_mut().push(h);
+                layout_pages(&data);
+
+                Ok( Box::new(Tab{handle: h, data: data}) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    A tab page
+*/
+pub struct Tab {
+    handle: HWND,
+    data: Rc<TabsData>
+}
+
+impl Tab {
+
+    /// Return the index of the tab in its container
+    pub fn get_index(&self) -> usize {
+        self.data.pages.borrow().iter().position(|&h| h == self.handle).unwrap()
+    }
+
+    /// Return true if the tab is the selected tab of its container
+    pub fn is_selected(&self) -> bool { self.get_index() == self.data.selected.get() }
+
+    /// Select the tab and show its page. Do not raise `Event::TabChanged`.
+    pub fn select(&self) {
+        use winapi::TCM_SETCURSEL;
+
+        let index = self.get_index();
+        unsafe{
+            send_message(self.data.handle, TCM_SETCURSEL, index as WPARAM, 0);
+            show_page(&self.data, index);
+        }
+    }
+
+    /// Return the text of the tab
+    pub fn get_text(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.handle) } }
+
+    /// Set the text of the tab
+    pub fn set_text<'a>(&self, text: &'a str) {
+        use low::window_helper::set_window_text;
+        use winapi::{TCITEMW, TCIF_TEXT, TCM_SETITEMW};
+
+        unsafe{
+            set_window_text(self.handle, text);
+
+            let mut text = to_utf16(text);
+            let mut item: TCITEMW = mem::zeroed();
+            item.mask = TCIF_TEXT;
+            item.pszText = text.as_mut_ptr();
+            send_message(self.data.handle, TCM_SETITEMW, self.get_index() as WPARAM, &item as *const TCITEMW as LPARAM);
+        }
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+}
+
+impl Control for Tab {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::Tab
+    }
+
+    fn free(&mut self) {
+        use low::window_helper::destroy_window;
+        use low::backend;
+        use winapi::{TCM_DELETEITEM, TCM_SETCURSEL};
+
+        unsafe{
+            let index = self.get_index();
+            let len = {
+                let mut pages = self.data.pages.borrow_mut();
+                pages.remove(index);
+                pages.len()
+            };
+
+            send_message(self.data.handle, TCM_DELETEITEM, index as WPARAM, 0);
+
+            // Keep a tab selected and its page visible if the selected tab was removed. The system leaves the container without selection.
+            let selected = self.data.selected.get();
+            if len == 0 {
+                self.data.selected.set(0);
+            } else if selected >= index {
+                let selected = if selected == index { ::std::cmp::min(index, len-1) } else { selected - 1 };
+                send_message(self.data.handle, TCM_SETCURSEL, selected as WPARAM, 0);
+                show_page(&self.data, selected);
+            }
+
+            destroy_window(self.handle);
+            backend::current().unregister_sysclass(TAB_CLASS_NAME);
+        }
+    }
+
+}
+
+// Private functions
+
+/**
+    The pages of a tabs container, in the order of the tabs. Shared by the container and its tabs.
+*/
+struct TabsData {
+    handle: HWND,
+    pages: RefCell<Vec<HWND>>,
+    selected: Cell<usize>
+}
+
+/**
+    Show the page at `index` and hide the others
+*/
+unsafe fn show_page(data: &TabsData, index: usize) {
+    use low::window_helper::set_window_visibility;
+
+    data.selected.set(index);
+    for (i, &page) in data.pages.borrow().iter().enumerate() {
+        set_window_visibility(page, i == index);
+    }
+}
+
+/**
+    Resize the pages to fill the display area of the tabs container
+*/
+unsafe fn layout_pages(data: &TabsData) {
+    use low::window_helper::{get_window_size, set_window_position, set_window_size};
+    use winapi::TCM_ADJUSTRECT;
+
+    let (w, h) = get_window_size(data.handle);
+    let mut r = RECT{ left: 0, top: 0, right: w as i32, bottom: h as i32 };
+    send_message(data.handle, TCM_ADJUSTRECT, 0, &mut r as *mut RECT as LPARAM);
+
+    let (w, h) = ((r.right - r.left).max(0) as u32, (r.bottom - r.top).max(0) as u32);
+    for &page in data.pages.borrow().iter() {
+        set_window_position(page, r.left, r.top);
+        set_window_size(page, w, h, false);
+    }
+}
+
+unsafe fn decode_tabs_notify(handle: HWND, nmhdr: *const NMHDR) -> Option<(Event, EventArgs)> {
+    use low::window_helper::get_window_long;
+    use winapi::{GWL_USERDATA, TCN_SELCHANGE, TCM_GETCURSEL};
+
+    let data: *const TabsData = get_window_long(handle, GWL_USERDATA) as *const TabsData;
+    if data.is_null() { return None; }
+
+    match (&*nmhdr).code {
+        TCN_SELCHANGE => {
+            let index = send_message(handle, TCM_GETCURSEL, 0, 0);
+            if index < 0 { return None; }
+            show_page(&*data, index as usize);
+            Some((Event::TabChanged, EventArgs::Selection(Some(index as usize))))
+        },
+        _ => None
+    }
+}
+
+#[allow(unused_variables)]
+unsafe extern "system" fn tab_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    use winapi::WM_CREATE;
+    use low::window_helper::def_window_proc;
+
+    match msg {
+        WM_CREATE => 0,
+        _ => def_window_proc(hwnd, msg, w, l)
+    }
+}
+
+/**
+    Resize the pages when the tabs container is resized
+*/
+#[allow(unused_variables)]
+unsafe extern "system" fn tabs_hook(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
+    use winapi::WM_SIZE;
+    use low::window_helper::def_subclass_proc;
+
+    if msg == WM_SIZE {
+        layout_pages(&*(data as *const TabsData));
+    }
+
+    def_subclass_proc(hwnd, msg, w, l)
+}
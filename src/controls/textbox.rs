@@ -27,18 +27,19 @@ use std::hash::Hash;
 use std::any::TypeId;
 
 use winapi::{HWND, HFONT, WPARAM};
-use user32::SendMessageW;
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use error::Error;
-use events::Event;
+use events::{Event, EventDecoders};
+use controls::textinput::decode_edit_command;
 
 /**
     A template that creates a multi line textinput control
 
     Events:  
-    Event: Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw  
+    Event: Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp, Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw  
 
     Members:  
     • `text`: The text of the textbox  
@@ -71,7 +72,12 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TextBoxT<S1, ID> {
 
     fn events(&self) -> Vec<Event> {
         vec![Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp,
-             Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw]
+             Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel,
+             Event::ValueChanged, Event::LimitReached, Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_edit_command), notify: None }
     }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
@@ -116,7 +122,7 @@ impl<S1: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for TextBoxT<S1, ID> {
             Ok(h) => {
                 unsafe{ 
                     set_window_font(h, font_handle, true); 
-                    SendMessageW(h, EM_LIMITTEXT, self.limit as WPARAM, 0);
+                    send_message(h, EM_LIMITTEXT, self.limit as WPARAM, 0);
                 };
 
                 Ok( Box::new(TextBox{handle: h}) )
@@ -163,13 +169,13 @@ impl TextBox {
     /// Set the maximum number of characters that the control can hold
     pub fn set_limit(&self, limit: u32) {
         use low::defs::EM_LIMITTEXT;
-        unsafe{ SendMessageW(self.handle, EM_LIMITTEXT, limit as WPARAM, 0); }
+        unsafe{ send_message(self.handle, EM_LIMITTEXT, limit as WPARAM, 0); }
     }
 
     /// Return the maximum number of characters that the control can hold
     pub fn get_limit(&self) -> u32 {
         use low::defs::EM_GETLIMITTEXT;
-        unsafe{ SendMessageW(self.handle, EM_GETLIMITTEXT, 0, 0) as u32 }
+        unsafe{ send_message(self.handle, EM_GETLIMITTEXT, 0, 0) as u32 }
     }
 
     pub fn get_text(&self) -> String { unsafe{ ::low::window_helper::get_window_text(self.handle) } }
@@ -195,8 +201,8 @@ impl Control for TextBox {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
\ No newline at end of file
@@ -24,19 +24,19 @@ use std::any::TypeId;
 use std::mem;
 
 use winapi::{HWND, HFONT, WPARAM};
-use user32::SendMessageW;
+use low::window_helper::send_message;
 
 use ui::Ui;
 use controls::{Control, ControlT, ControlType, AnyHandle};
 use low::other_helper::to_utf16;
 use error::Error;
-use events::Event;
+use events::{Event, EventArgs, EventDecoders};
 
 /**
     A template that creates a single line textinput control
 
     Events:  
-    Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw  
+    Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp, Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel, Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw  
 
     Members:  
     • `text`: The text of the textinput  
@@ -71,7 +71,12 @@ impl<S1: Clone+Into<String>, S2: Clone+Into<String>, ID: Hash+Clone> ControlT<ID
 
     fn events(&self) -> Vec<Event> {
         vec![Event::Destroyed, Event::Focus, Event::Char, Event::KeyDown, Event::KeyUp, Event::MouseDown, Event::MouseUp,
-             Event::ValueChanged, Event::Moved, Event::Resized, Event::Raw]
+             Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel,
+             Event::ValueChanged, Event::LimitReached, Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: Some(decode_edit_command), notify: None }
     }
 
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
@@ -115,7 +120,7 @@ impl<S1: Clone+Into<String>, S2: Clone+Into<String>, ID: Hash+Clone> ControlT<ID
             Ok(h) => {
                 unsafe{ 
                     set_window_font(h, font_handle, true); 
-                    SendMessageW(h, EM_LIMITTEXT, self.limit as WPARAM, 0);
+                    send_message(h, EM_LIMITTEXT, self.limit as WPARAM, 0);
                     
                     if let Some(placeholder) = self.placeholder.as_ref() {
                         set_placeholder(h, placeholder.clone());
@@ -191,13 +196,13 @@ impl TextInput {
     /// Set the maximum number of characters that the control can hold
     pub fn set_limit(&self, limit: u32) {
         use low::defs::EM_LIMITTEXT;
-        unsafe{ SendMessageW(self.handle, EM_LIMITTEXT, limit as WPARAM, 0); }
+        unsafe{ send_message(self.handle, EM_LIMITTEXT, limit as WPARAM, 0); }
     }
 
     /// Return the maximum number of characters that the control can hold
     pub fn get_limit(&self) -> u32 {
         use low::defs::EM_GETLIMITTEXT;
-        unsafe{ SendMessageW(self.handle, EM_GETLIMITTEXT, 0, 0) as u32 }
+        unsafe{ send_message(self.handle, EM_GETLIMITTEXT, 0, 0) as u32 }
     }
 
     /// Set a new placeholder for the TextInput. To remove the current placeholder, send `""`  
@@ -214,7 +219,7 @@ impl TextInput {
         let mut buffer: [u16; 256] = [0; 256];
         let mut buffer_size = 256;
 
-        let placeholder_found = unsafe{ SendMessageW(self.handle, EM_GETCUEBANNER, mem::transmute(buffer.as_mut_ptr()), mem::transmute(&mut buffer_size)) };
+        let placeholder_found = unsafe{ send_message(self.handle, EM_GETCUEBANNER, mem::transmute(buffer.as_mut_ptr()), mem::transmute(&mut buffer_size)) };
 
         if placeholder_found == 1 {
             Some(from_utf16(&buffer))
@@ -247,8 +252,8 @@ impl Control for TextInput {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
@@ -257,5 +262,20 @@ impl Control for TextInput {
 fn set_placeholder<S: Into<String>>(handle: HWND, placeholder: S) {
     use winapi::EM_SETCUEBANNER;
     let text = to_utf16(placeholder.into().as_str());
-    unsafe{ SendMessageW(handle, EM_SETCUEBANNER, 0, mem::transmute(text.as_ptr()) ); }
-}
\ No newline at end of file
+    unsafe{ send_message(handle, EM_SETCUEBANNER, 0, mem::transmute(text.as_ptr()) ); }
+}
+
+/**
+    Decode the `WM_COMMAND` notifications of the edit controls (text inputs and text boxes)
+*/
+pub unsafe fn decode_edit_command(handle: HWND, ncode: u32) -> Option<(Event, EventArgs)> {
+    use low::defs::{EN_SETFOCUS, EN_KILLFOCUS, EN_UPDATE, EN_MAXTEXT};
+    use low::window_helper::get_window_text;
+
+    match ncode {
+        EN_UPDATE => Some((Event::ValueChanged, EventArgs::Text(get_window_text(handle)))),
+        EN_MAXTEXT => Some((Event::LimitReached, EventArgs::None)),
+        EN_SETFOCUS | EN_KILLFOCUS => Some((Event::Focus, EventArgs::Focus(ncode==EN_SETFOCUS))),
+        _ => None
+    }
+}
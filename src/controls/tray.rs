@@ -0,0 +1,291 @@
+/*!
+    An icon in the notification area of the taskbar
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::hash::Hash;
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::mem;
+
+use winapi::{HWND, HICON, UINT, DWORD, NOTIFYICONDATAW};
+
+use ui::{Ui, UiInner};
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use defs::BalloonIcon;
+use error::{Error, SystemError};
+use events::Event;
+
+/// Source of the tray icon ids. Ids start at 1.
+static TRAY_ICONS_ID: AtomicUsize = AtomicUsize::new(0);
+
+/**
+    A template that creates an icon in the notification area (the system tray).
+
+    The icon does not need a window: the system sends its notifications to the Ui. A left click raises `Event::Click`
+    and a right click raises `Event::ContextMenu` with the cursor position in screen coordinates. If a menu is attached to
+    the icon, it is shown after the `ContextMenu` callbacks are executed and its items raise `Event::Triggered` as usual.
+    The menu is found from its id when it is shown: if it was removed from the Ui, nothing is shown.
+
+    Events:  
+    Event::Destroyed, Event::Click, Event::DoubleClick, Event::ContextMenu  
+
+    Members:  
+    • `tooltip`: The text shown when the mouse is over the icon. Truncated to 127 characters  
+    • `icon`: The id of an `Image` resource of type `Icon`. If `None`, the default application icon is used. The image is not owned by the tray icon  
+    • `menu`: The id of a `Menu` or a `PopupMenu` shown when the user right clicks on the icon  
+    • `visible`: If the icon is shown in the notification area  
+*/
+#[derive(Clone)]
+pub struct TrayIconT<S: Clone+Into<String>, ID: Hash+Clone> {
+    pub tooltip: S,
+    pub icon: Option<ID>,
+    pub menu: Option<ID>,
+    pub visible: bool
+}
+
+impl<S: Clone+Into<String>, ID: Hash+Clone+'static> ControlT<ID> for TrayIconT<S, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<TrayIcon>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::Click, Event::DoubleClick, Event::ContextMenu]
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        let menu = match self.menu.as_ref().map(|id| (id, ui.handle_of(id))) {
+            Some((id, Ok(AnyHandle::HMENU(_)))) => Some(UiInner::<ID>::hash_id(id)),
+            Some((_, Ok(h))) => { return Err(Error::BadResource(format!("The menu of a tray icon must be a Menu or a PopupMenu. Got {:?}", h))); },
+            Some((_, Err(e))) => { return Err(e); },
+            None => None
+        };
+
+        let icon = match self.icon.as_ref().map(|id| ui.handle_of(id)) {
+            Some(Ok(AnyHandle::HICON(h))) => h,
+            Some(Ok(h)) => { return Err(Error::BadResource(format!("The icon of a tray icon must be an Image of type Icon. Got {:?}", h))); },
+            Some(Err(e)) => { return Err(e); },
+            None => unsafe{ default_icon() }
+        };
+
+        let tray = TrayIcon {
+            watcher: unsafe{ ui.message_handle() },
+            id: (TRAY_ICONS_ID.fetch_add(1, Ordering::SeqCst) + 1) as UINT,
+            icon: icon,
+            menu: menu,
+            tooltip: RefCell::new(self.tooltip.clone().into()),
+            visible: Cell::new(self.visible)
+        };
+
+        match unsafe{ add_tray_icon(&tray) } {
+            Ok(()) => Ok( Box::new(tray) as Box<Control> ),
+            Err(e) => Err(e)
+        }
+    }
+}
+
+/**
+    A tray icon
+*/
+pub struct TrayIcon {
+    watcher: HWND,        // The watcher of a tray icon is always its Ui. The system sends the icon notifications to this window.
+    id: UINT,             // A unique id to identify the icon
+    icon: HICON,          // The icon is owned by an Image resource or by the system
+    menu: Option<u64>,    // The inner id of the menu. The handle is resolved when the menu is shown, because the menu might be removed first.
+    tooltip: RefCell<String>,
+    visible: Cell<bool>
+}
+
+impl TrayIcon {
+
+    /// Return the tooltip of the icon
+    pub fn get_tooltip(&self) -> String { self.tooltip.borrow().clone() }
+
+    /// Set the tooltip of the icon. The text is truncated to 127 characters.
+    pub fn set_tooltip<'a>(&self, text: &'a str) {
+        use winapi::{NIF_TIP, NIF_SHOWTIP};
+
+        *self.tooltip.borrow_mut() = text.to_string();
+
+        unsafe{
+            let mut data = self.notify_data(NIF_TIP | NIF_SHOWTIP);
+            copy_text(&mut data.szTip, text);
+            notify(::winapi::NIM_MODIFY, &mut data);
+        }
+    }
+
+    /// Return `true` if the icon is shown in the notification area
+    pub fn get_visibility(&self) -> bool { self.visible.get() }
+
+    /// Show or hide the icon
+    pub fn set_visibility(&self, visible: bool) {
+        use winapi::NIF_STATE;
+        use low::defs::NIS_HIDDEN;
+
+        self.visible.set(visible);
+
+        unsafe{
+            let mut data = self.notify_data(NIF_STATE);
+            data.dwState = if visible { 0 } else { NIS_HIDDEN };
+            data.dwStateMask = NIS_HIDDEN;
+            notify(::winapi::NIM_MODIFY, &mut data);
+        }
+    }
+
+    /// Return `true` if a menu is attached to the icon. The menu might have been removed from the Ui since.
+    pub fn has_menu(&self) -> bool {
+        self.menu.is_some()
+    }
+
+    /**
+        Set the id of the `Menu` or the `PopupMenu` shown when the user right clicks on the icon.
+        The menu is not owned by the icon. It is found from its id when it is shown: if the id does not identify a menu, nothing is shown.
+    */
+    pub fn set_menu<ID: Hash+Clone+'static>(&mut self, menu: &ID) {
+        self.menu = Some(UiInner::<ID>::hash_id(menu));
+    }
+
+    /// Detach the menu of the icon. No menu is shown when the user right clicks on the icon.
+    pub fn remove_menu(&mut self) {
+        self.menu = None;
+    }
+
+    /**
+        Show a notification balloon next to the icon. The system hides the balloon after a few seconds or
+        when the user clicks on it. Showing a new balloon replaces the current one.
+
+        Arguments:  
+        • `title`: The title of the balloon. Truncated to 63 characters  
+        • `text`: The text of the balloon. Truncated to 255 characters  
+        • `icon`: The icon shown beside the title  
+    */
+    pub fn show_balloon<'a>(&self, title: &'a str, text: &'a str, icon: BalloonIcon) {
+        use winapi::NIF_INFO;
+        use low::defs::{NIIF_NONE, NIIF_INFO, NIIF_WARNING, NIIF_ERROR, NIIF_USER};
+
+        unsafe{
+            let mut data = self.notify_data(NIF_INFO);
+            copy_text(&mut data.szInfoTitle, title);
+            copy_text(&mut data.szInfo, text);
+            data.dwInfoFlags = match icon {
+                BalloonIcon::None => NIIF_NONE,
+                BalloonIcon::Info => NIIF_INFO,
+                BalloonIcon::Warning => NIIF_WARNING,
+                BalloonIcon::Error => NIIF_ERROR,
+                BalloonIcon::Tray => NIIF_USER
+            };
+            data.hBalloonIcon = self.icon;
+            notify(::winapi::NIM_MODIFY, &mut data);
+        }
+    }
+
+    /// Return a notification data struct that identifies the icon
+    fn notify_data(&self, flags: UINT) -> NOTIFYICONDATAW {
+        unsafe {
+            let mut data: NOTIFYICONDATAW = mem::zeroed();
+            data.cbSize = mem::size_of::<NOTIFYICONDATAW>() as DWORD;
+            data.hWnd = self.watcher;
+            data.uID = self.id;
+            data.uFlags = flags;
+            data
+        }
+    }
+
+}
+
+impl Control for TrayIcon {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::Custom(TypeId::of::<TrayIcon>(), self.id as usize)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::TrayIcon
+    }
+
+    fn free(&mut self) {
+        unsafe{
+            let mut data = self.notify_data(0);
+            notify(::winapi::NIM_DELETE, &mut data);
+        }
+    }
+
+}
+
+
+/**
+    Return the inner id of the menu attached to a tray icon. Used by the events dispatcher when the icon is right clicked.
+*/
+pub fn tray_menu_id(tray: &TrayIcon) -> Option<u64> {
+    tray.menu
+}
+
+/**
+    Return the icon shown by a tray icon. Used to check if an image resource is still in use before it is freed.
+*/
+pub fn tray_icon_handle(tray: &TrayIcon) -> HICON {
+    tray.icon
+}
+
+/*
+    Private unsafe control methods
+*/
+
+#[inline(always)]
+unsafe fn notify(action: DWORD, data: &mut NOTIFYICONDATAW) -> bool {
+    use low::defs::Shell_NotifyIconW;
+    Shell_NotifyIconW(action, data) != 0
+}
+
+/**
+    Copy a text in a fixed size buffer of the notification data. The text is truncated to fit the buffer with its null terminator.
+*/
+fn copy_text(buffer: &mut [u16], text: &str) {
+    let text: Vec<u16> = text.encode_utf16().take(buffer.len() - 1).collect();
+    buffer[..text.len()].copy_from_slice(&text);
+    buffer[text.len()] = 0;
+}
+
+unsafe fn add_tray_icon(tray: &TrayIcon) -> Result<(), Error> {
+    use winapi::{NIM_ADD, NIM_SETVERSION, NIF_MESSAGE, NIF_ICON, NIF_TIP, NIF_SHOWTIP, NIF_STATE, NOTIFYICON_VERSION_4};
+    use low::defs::{NWG_TRAY, NIS_HIDDEN};
+
+    let mut data = tray.notify_data(NIF_MESSAGE | NIF_ICON | NIF_TIP | NIF_SHOWTIP | NIF_STATE);
+    data.uCallbackMessage = NWG_TRAY;
+    data.hIcon = tray.icon;
+    data.dwState = if tray.visible.get() { 0 } else { NIS_HIDDEN };
+    data.dwStateMask = NIS_HIDDEN;
+    copy_text(&mut data.szTip, &tray.tooltip.borrow());
+
+    if !notify(NIM_ADD, &mut data) {
+        return Err(Error::System(SystemError::TrayIconCreation));
+    }
+
+    // Version 4 sends the cursor position with the notifications and sends `WM_CONTEXTMENU` on right click
+    *data.uVersion_mut() = NOTIFYICON_VERSION_4;
+    notify(NIM_SETVERSION, &mut data);
+
+    Ok(())
+}
+
+#[inline(always)]
+unsafe fn default_icon() -> HICON {
+    use user32::LoadIconW;
+    use winapi::IDI_APPLICATION;
+    use std::ptr;
+
+    LoadIconW(ptr::null_mut(), IDI_APPLICATION)
+}
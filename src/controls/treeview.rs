@@ -0,0 +1,841 @@
+/*!
+    A tree view control that displays a hierarchy of nodes
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::hash::{Hash, Hasher};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::any::TypeId;
+use std::fmt::Display;
+use std::rc::Rc;
+use std::ptr;
+use std::mem;
+
+use low::window_helper::send_message;
+use winapi::{HWND, HFONT, WPARAM, LPARAM, NMHDR, UINT, HTREEITEM};
+
+use ui::Ui;
+use controls::{Control, ControlT, ControlType, AnyHandle};
+use error::Error;
+use events::{Event, EventArgs, EventDecoders};
+use low::other_helper::to_utf16;
+
+/**
+    A node of a tree view.
+
+    Members:  
+    • `id`: The ID of the node. Must be unique within the tree view.  
+    • `data`: The data of the node. The text of the node is the `Display` representation of the data.  
+    • `lazy`: If true, the node is displayed with an expand button and its children are fetched from the tree view loader the first time it is expanded.  
+*/
+#[derive(Clone)]
+pub struct TreeNode<D, ID> {
+    pub id: ID,
+    pub data: D,
+    pub lazy: bool
+}
+
+/**
+    Fetch the children of the lazy nodes of a tree view. Called the first time a lazy node is expanded.
+*/
+pub trait TreeViewLoader<D, ID> {
+
+    /// Return the children of the node `id`
+    fn children(&self, id: &ID, data: &D) -> Vec<TreeNode<D, ID>>;
+}
+
+/**
+    Template that creates a tree view control
+
+    Nodes are addressed by ID, in the same way the Ui addresses its controls. Events identify their node by its path,
+    the child indexes from the root (see `TreeView::node_at`).
+
+    Available events:  
+    Event::Destroyed, Event::SelectionChanged, Event::Expanding, Event::Expanded, Event::Collapsed, Event::ValueChanged, Event::Focus, Event::Moved, Event::Resized, Event::Raw  
+
+    Events arguments:  
+    • `SelectionChanged`, `Expanding`, `Expanded`, `Collapsed`: `EventArgs::Node(path)`. The path is empty if no node is selected.  
+    • `Expanding` is raised before a node is expanded by the user. The children of a lazy node are loaded at this moment.  
+    • `ValueChanged`: `EventArgs::NodeChecked(path, checked)` when a check box is toggled  
+
+    Members:  
+    • `nodes`: The nodes created with the tree view, as `(parent, node)`. The nodes are inserted in order.  
+    • `position`: The start position of the tree view  
+    • `size`: The start size of the tree view  
+    • `visible`: If the tree view should be visible to the user  
+    • `disabled`: If the user can or can't use the tree view  
+    • `checkboxes`: If true, display a check box next to each node  
+    • `loader`: Fetch the children of the lazy nodes. If None, lazy nodes have no children.  
+    • `parent`: The tree view parent  
+    • `font`: The tree view font. If None, use the system default  
+*/
+#[derive(Clone)]
+pub struct TreeViewT<D: Clone+Display+'static, ID: Hash+Clone+'static> {
+    pub nodes: Vec<(Option<ID>, TreeNode<D, ID>)>,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub visible: bool,
+    pub disabled: bool,
+    pub checkboxes: bool,
+    pub loader: Option<Rc<TreeViewLoader<D, ID>>>,
+    pub parent: ID,
+    pub font: Option<ID>,
+}
+
+impl<D: Clone+Display+'static, ID: Hash+Clone+'static> ControlT<ID> for TreeViewT<D, ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<TreeView<D, ID>>() }
+
+    fn events(&self) -> Vec<Event> {
+        vec![Event::Destroyed, Event::SelectionChanged, Event::Expanding, Event::Expanded, Event::Collapsed, Event::ValueChanged, Event::Focus,
+             Event::Moved, Event::Resized, Event::Raw]
+    }
+
+    fn decoders(&self) -> EventDecoders {
+        EventDecoders{ command: None, notify: Some(decode_treeview_notify::<D, ID>) }
+    }
+
+    fn packed(&self, ui: &Ui<ID>, id: &ID) {
+        ui.bind(id, id, Event::Expanding, load_on_expanding::<D, ID>);
+    }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        use low::window_helper::{WindowParams, build_window, destroy_window, set_window_font, set_window_long, get_window_long, handle_of_window, handle_of_font};
+        use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_CHILD, WS_BORDER, WS_TABSTOP, TVS_HASBUTTONS, TVS_HASLINES, TVS_LINESATROOT,
+          TVS_SHOWSELALWAYS, TVS_CHECKBOXES, GWL_STYLE, GWL_USERDATA};
+
+        let flags: DWORD = WS_CHILD | WS_BORDER | WS_TABSTOP | TVS_HASBUTTONS | TVS_HASLINES | TVS_LINESATROOT | TVS_SHOWSELALWAYS |
+        if self.visible  { WS_VISIBLE }   else { 0 } |
+        if self.disabled { WS_DISABLED }  else { 0 };
+
+        // Get the parent handle
+        let parent = match handle_of_window(ui, &self.parent, "The parent of a tree view must be a window-like control.") {
+            Ok(h) => h,
+            Err(e) => { return Err(e); }
+        };
+
+        // Get the font handle (if any)
+        let font_handle: Option<HFONT> = match self.font.as_ref() {
+            Some(font_id) =>
+                match handle_of_font(ui, &font_id, "The font of a tree view must be a font resource.") {
+                    Ok(h) => Some(h),
+                    Err(e) => { return Err(e); }
+                },
+            None => None
+        };
+
+        let params = WindowParams {
+            title: "",
+            class_name: "SysTreeView32",
+            position: self.position.clone(),
+            size: self.size.clone(),
+            flags: flags,
+            ex_flags: Some(0),
+            parent: parent
+        };
+
+        match unsafe{ build_window(params) } {
+            Ok(h) => {
+                let mut data = Box::new(TreeViewData {
+                    nodes: HashMap::with_capacity(self.nodes.len()),
+                    roots: Vec::new(),
+                    handles: HashMap::with_capacity(self.nodes.len()),
+                    loader: self.loader.clone()
+                });
+
+                unsafe{
+                    set_window_font(h, font_handle, true);
+
+                    // The check boxes style must be set after the control creation, or the state images are not created
+                    if self.checkboxes {
+                        let style = get_window_long(h, GWL_STYLE) as usize;
+                        set_window_long(h, GWL_STYLE, style | (TVS_CHECKBOXES as usize));
+                    }
+
+                    for &(ref parent, ref node) in self.nodes.iter() {
+                        let index = match children_of(&data, parent.as_ref()) {
+                            Some(c) => c.len(),
+                            None => { destroy_window(h); return Err(Error::BadParent("The parent of a tree node must be a node of the tree view.".to_string())); }
+                        };
+
+                        if let Err(e) = insert_node(h, &mut data, parent.as_ref(), index, node.clone()) {
+                            destroy_window(h);
+                            return Err(e);
+                        }
+                    }
+
+                    // The notifications decoder reads the nodes through the window user data. It must never modify them.
+                    set_window_long(h, GWL_USERDATA, &*data as *const TreeViewData<D, ID> as usize);
+                }
+
+                Ok( Box::new(TreeView{handle: h, data: data}) )
+            },
+            Err(e) => Err(Error::System(e))
+        }
+    }
+}
+
+/**
+    A tree view control
+*/
+pub struct TreeView<D: Clone+Display+'static, ID: Hash+Clone+'static> {
+    handle: HWND,
+    data: Box<TreeViewData<D, ID>>
+}
+
+impl<D: Clone+Display+'static, ID: Hash+Clone+'static> TreeView<D, ID> {
+
+    /// Return the number of nodes in the tree view
+    pub fn len(&self) -> usize { self.data.nodes.len() }
+
+    /// Return true if the tree view contains the node `id`
+    pub fn contains(&self, id: &ID) -> bool { self.data.nodes.contains_key(&node_key(id)) }
+
+    /// Return the data of the node `id`
+    pub fn get(&self, id: &ID) -> Option<&D> {
+        self.data.nodes.get(&node_key(id)).map(|n| &n.data)
+    }
+
+    /// Replace the data of the node `id` and update the node text
+    pub fn set(&mut self, id: &ID, data: D) -> Result<(), Error> {
+        use winapi::{TVITEMW, TVM_SETITEMW, TVIF_TEXT, TVIF_HANDLE};
+
+        let node = match self.data.nodes.get_mut(&node_key(id)) {
+            Some(n) => n,
+            None => { return Err(Error::KeyNotFound); }
+        };
+
+        let mut text = to_utf16(&data.to_string());
+        node.data = data;
+
+        unsafe{
+            let mut item: TVITEMW = mem::zeroed();
+            item.mask = TVIF_TEXT | TVIF_HANDLE;
+            item.hItem = node.handle;
+            item.pszText = text.as_mut_ptr();
+            send_message(self.handle, TVM_SETITEMW, 0, &item as *const TVITEMW as LPARAM);
+        }
+
+        Ok(())
+    }
+
+    /// Return the ID of the root nodes
+    pub fn roots(&self) -> Vec<ID> {
+        self.data.roots.iter().map(|k| self.data.nodes[k].id.clone()).collect()
+    }
+
+    /// Return the ID of the children of the node `id`. Lazy nodes have no children until they are expanded.
+    pub fn children(&self, id: &ID) -> Option<Vec<ID>> {
+        self.data.nodes.get(&node_key(id)).map(|n| n.children.iter().map(|k| self.data.nodes[k].id.clone()).collect())
+    }
+
+    /// Return the ID of the parent of the node `id`. Return `None` if the node is a root or if it does not exist.
+    pub fn parent(&self, id: &ID) -> Option<ID> {
+        self.data.nodes.get(&node_key(id))
+          .and_then(|n| n.parent)
+          .map(|p| self.data.nodes[&p].id.clone())
+    }
+
+    /// Return the path of the node `id`, as the child indexes from the root
+    pub fn path_of(&self, id: &ID) -> Option<Vec<usize>> {
+        let key = node_key(id);
+        if self.data.nodes.contains_key(&key) {
+            Some(node_path(&self.data, key))
+        } else {
+            None
+        }
+    }
+
+    /// Return the ID of the node at `path`. Used with the paths sent by the tree view events.
+    pub fn node_at(&self, path: &[usize]) -> Option<ID> {
+        node_at_path(&self.data, path).map(|k| self.data.nodes[&k].id.clone())
+    }
+
+    /**
+        Add a node at the end of the children of `parent`. If `parent` is None, the node is added to the roots.
+
+        Return `Error::KeyExists` if the tree view already has a node with the same ID, or `Error::KeyNotFound` if
+        `parent` is not in the tree view.
+    */
+    pub fn push(&mut self, parent: Option<&ID>, node: TreeNode<D, ID>) -> Result<(), Error> {
+        let index = match children_of(&self.data, parent) {
+            Some(c) => c.len(),
+            None => { return Err(Error::KeyNotFound); }
+        };
+
+        unsafe{ insert_node(self.handle, &mut self.data, parent, index, node) }
+    }
+
+    /**
+        Insert a node in the children of `parent` at the selected position. If `parent` is None, the node is inserted in the roots.
+
+        Return `Error::KeyExists` if the tree view already has a node with the same ID, or `Error::KeyNotFound` if
+        `parent` is not in the tree view. `Panics` if index is out of bounds.
+    */
+    pub fn insert(&mut self, parent: Option<&ID>, index: usize, node: TreeNode<D, ID>) -> Result<(), Error> {
+        unsafe{ insert_node(self.handle, &mut self.data, parent, index, node) }
+    }
+
+    /// Remove the node `id` and its children from the tree view. Return the data of the removed node.
+    pub fn remove(&mut self, id: &ID) -> Result<D, Error> {
+        use winapi::TVM_DELETEITEM;
+
+        let key = node_key(id);
+        if !self.data.nodes.contains_key(&key) {
+            return Err(Error::KeyNotFound);
+        }
+
+        let (item, parent) = {
+            let node = &self.data.nodes[&key];
+            (node.handle, node.parent)
+        };
+
+        detach_node(&mut self.data, key);
+
+        let mut data = None;
+        for k in subtree(&self.data, key) {
+            let node = self.data.nodes.remove(&k).unwrap();
+            self.data.handles.remove(&(node.handle as usize));
+            if k == key { data = Some(node.data); }
+        }
+
+        unsafe{
+            send_message(self.handle, TVM_DELETEITEM, 0, item as LPARAM);
+            update_children_flag(self.handle, &self.data, parent);
+        }
+
+        Ok(data.unwrap())
+    }
+
+    /**
+        Move the node `id` and its children in the children of `parent` at the selected position. If `parent` is None,
+        the node is moved to the roots. `index` is the position of the node after it was removed from its current parent.
+
+        Return `Error::KeyNotFound` if one of the node is not in the tree view, or `Error::BadParent` if `parent` is
+        the node itself or one of its children. `Panics` if index is out of bounds.
+    */
+    pub fn move_node(&mut self, id: &ID, parent: Option<&ID>, index: usize) -> Result<(), Error> {
+        use winapi::TVM_DELETEITEM;
+
+        let key = node_key(id);
+        let parent_key = parent.map(|p| node_key(p));
+        if !self.data.nodes.contains_key(&key) {
+            return Err(Error::KeyNotFound);
+        }
+
+        if let Some(p) = parent_key {
+            if !self.data.nodes.contains_key(&p) {
+                return Err(Error::KeyNotFound);
+            } else if is_descendant(&self.data, p, key) {
+                return Err(Error::BadParent("A tree node cannot be moved into itself or its children.".to_string()));
+            }
+        }
+
+        let keys = subtree(&self.data, key);
+        let (item, old_parent) = {
+            let node = &self.data.nodes[&key];
+            (node.handle, node.parent)
+        };
+
+        unsafe{
+            // The tree view control cannot move items. The items are deleted and inserted back with their check state.
+            let checked: Vec<u64> = keys.iter().cloned().filter(|k| item_checked(self.handle, self.data.nodes[k].handle)).collect();
+
+            detach_node(&mut self.data, key);
+            for k in keys.iter() {
+                let node = self.data.nodes.get_mut(k).unwrap();
+                self.data.handles.remove(&(node.handle as usize));
+                node.handle = ptr::null_mut();
+            }
+
+            send_message(self.handle, TVM_DELETEITEM, 0, item as LPARAM);
+
+            children_of_key_mut(&mut self.data, parent_key).insert(index, key);
+            self.data.nodes.get_mut(&key).unwrap().parent = parent_key;
+            insert_subtree(self.handle, &mut self.data, key);
+
+            for k in checked.iter() {
+                set_item_checked(self.handle, self.data.nodes[k].handle, true);
+            }
+
+            update_children_flag(self.handle, &self.data, old_parent);
+            update_children_flag(self.handle, &self.data, parent_key);
+        }
+
+        Ok(())
+    }
+
+    /// Remove every node in the tree view
+    pub fn clear(&mut self) {
+        use winapi::{TVM_DELETEITEM, TVI_ROOT};
+
+        self.data.nodes.clear();
+        self.data.roots.clear();
+        self.data.handles.clear();
+        unsafe{ send_message(self.handle, TVM_DELETEITEM, 0, TVI_ROOT as LPARAM); }
+    }
+
+    /// Expand the node `id`. If the node is lazy, its children are fetched from the loader.
+    pub fn expand(&mut self, id: &ID) -> Result<(), Error> {
+        use winapi::{TVM_EXPAND, TVE_EXPAND};
+
+        let key = node_key(id);
+        if !self.data.nodes.contains_key(&key) {
+            return Err(Error::KeyNotFound);
+        }
+
+        unsafe{
+            // TVM_EXPAND do not send the TVN_ITEMEXPANDING notification
+            load_children(self.handle, &mut self.data, key);
+            send_message(self.handle, TVM_EXPAND, TVE_EXPAND, self.data.nodes[&key].handle as LPARAM);
+        }
+
+        Ok(())
+    }
+
+    /// Collapse the node `id`
+    pub fn collapse(&self, id: &ID) -> Result<(), Error> {
+        use winapi::{TVM_EXPAND, TVE_COLLAPSE};
+
+        match self.data.nodes.get(&node_key(id)) {
+            Some(n) => { unsafe{ send_message(self.handle, TVM_EXPAND, TVE_COLLAPSE, n.handle as LPARAM); } Ok(()) },
+            None => Err(Error::KeyNotFound)
+        }
+    }
+
+    /// Return true if the node `id` is expanded
+    pub fn is_expanded(&self, id: &ID) -> bool {
+        use winapi::{TVM_GETITEMSTATE, TVIS_EXPANDED};
+
+        match self.data.nodes.get(&node_key(id)) {
+            Some(n) => unsafe{ (send_message(self.handle, TVM_GETITEMSTATE, n.handle as WPARAM, TVIS_EXPANDED as LPARAM) as UINT) & TVIS_EXPANDED != 0 },
+            None => false
+        }
+    }
+
+    /// Return the ID of the selected node, if any
+    pub fn get_selected(&self) -> Option<ID> {
+        use winapi::{TVM_GETNEXTITEM, TVGN_CARET};
+
+        let item = unsafe{ send_message(self.handle, TVM_GETNEXTITEM, TVGN_CARET, 0) as usize };
+        self.data.handles.get(&item).map(|k| self.data.nodes[k].id.clone())
+    }
+
+    /// Select the node `id`. If `id` is None, clear the selection.
+    pub fn set_selected(&self, id: Option<&ID>) -> Result<(), Error> {
+        use winapi::{TVM_SELECTITEM, TVGN_CARET};
+
+        let item = match id {
+            Some(id) => match self.data.nodes.get(&node_key(id)) {
+                Some(n) => n.handle,
+                None => { return Err(Error::KeyNotFound); }
+            },
+            None => ptr::null_mut()
+        };
+
+        unsafe{ send_message(self.handle, TVM_SELECTITEM, TVGN_CARET, item as LPARAM); }
+        Ok(())
+    }
+
+    /// Return true if the node `id` is checked. Always false if the tree view has no check boxes.
+    pub fn get_checked(&self, id: &ID) -> bool {
+        match self.data.nodes.get(&node_key(id)) {
+            Some(n) => unsafe{ item_checked(self.handle, n.handle) },
+            None => false
+        }
+    }
+
+    /// Check or uncheck the node `id`. Do nothing if the tree view has no check boxes.
+    pub fn set_checked(&self, id: &ID, checked: bool) -> Result<(), Error> {
+        match self.data.nodes.get(&node_key(id)) {
+            Some(n) => { unsafe{ set_item_checked(self.handle, n.handle, checked); } Ok(()) },
+            None => Err(Error::KeyNotFound)
+        }
+    }
+
+    pub fn get_visibility(&self) -> bool { unsafe{ ::low::window_helper::get_window_visibility(self.handle) } }
+    pub fn set_visibility(&self, visible: bool) { unsafe{ ::low::window_helper::set_window_visibility(self.handle, visible); }}
+    pub fn get_position(&self) -> (i32, i32) { unsafe{ ::low::window_helper::get_window_position(self.handle) } }
+    pub fn set_position(&self, x: i32, y: i32) { unsafe{ ::low::window_helper::set_window_position(self.handle, x, y); }}
+    pub fn get_size(&self) -> (u32, u32) { unsafe{ ::low::window_helper::get_window_size(self.handle) } }
+    pub fn set_size(&self, w: u32, h: u32) { unsafe{ ::low::window_helper::set_window_size(self.handle, w, h, false); } }
+    pub fn get_enabled(&self) -> bool { unsafe{ ::low::window_helper::get_window_enabled(self.handle) } }
+    pub fn set_enabled(&self, e:bool) { unsafe{ ::low::window_helper::set_window_enabled(self.handle, e); } }
+}
+
+impl<D: Clone+Display+'static, ID: Hash+Clone+'static> Control for TreeView<D, ID> {
+
+    fn handle(&self) -> AnyHandle {
+        AnyHandle::HWND(self.handle)
+    }
+
+    fn control_type(&self) -> ControlType {
+        ControlType::TreeView
+    }
+
+    fn free(&mut self) {
+        use low::window_helper::{destroy_window, set_window_long};
+        use winapi::GWL_USERDATA;
+
+        set_window_long(self.handle, GWL_USERDATA, 0);
+        unsafe{ destroy_window(self.handle) };
+    }
+
+}
+
+// Private functions
+
+/**
+    The nodes of a tree view. The nodes are stored by the hash of their ID. Shared with the notifications decoder
+    through the window user data. The decoder only reads them.
+*/
+struct TreeViewData<D, ID> {
+    nodes: HashMap<u64, TreeViewNode<D, ID>>,
+    roots: Vec<u64>,
+    handles: HashMap<usize, u64>,
+    loader: Option<Rc<TreeViewLoader<D, ID>>>
+}
+
+struct TreeViewNode<D, ID> {
+    id: ID,
+    data: D,
+    parent: Option<u64>,
+    children: Vec<u64>,
+    handle: HTREEITEM,
+    lazy: bool
+}
+
+#[inline(always)]
+fn node_key<ID: Hash>(id: &ID) -> u64 {
+    let mut s1 = DefaultHasher::new();
+    id.hash(&mut s1);
+    s1.finish()
+}
+
+fn children_of<'a, D, ID: Hash>(data: &'a TreeViewData<D, ID>, parent: Option<&ID>) -> Option<&'a Vec<u64>> {
+    match parent {
+        Some(p) => data.nodes.get(&node_key(p)).map(|n| &n.children),
+        None => Some(&data.roots)
+    }
+}
+
+fn children_of_key<D, ID>(data: &TreeViewData<D, ID>, parent: Option<u64>) -> &Vec<u64> {
+    match parent {
+        Some(p) => &data.nodes[&p].children,
+        None => &data.roots
+    }
+}
+
+fn children_of_key_mut<D, ID>(data: &mut TreeViewData<D, ID>, parent: Option<u64>) -> &mut Vec<u64> {
+    match parent {
+        Some(p) => &mut data.nodes.get_mut(&p).unwrap().children,
+        None => &mut data.roots
+    }
+}
+
+/**
+    Remove a node from the children of its parent. The node and its children are kept in the tree view data.
+*/
+fn detach_node<D, ID>(data: &mut TreeViewData<D, ID>, key: u64) {
+    let parent = data.nodes[&key].parent;
+    let siblings = children_of_key_mut(data, parent);
+    let index = siblings.iter().position(|&k| k == key).unwrap();
+    siblings.remove(index);
+}
+
+/**
+    Return the node and all its children, parents first
+*/
+fn subtree<D, ID>(data: &TreeViewData<D, ID>, key: u64) -> Vec<u64> {
+    let mut keys = vec![key];
+    let mut i = 0;
+    while i < keys.len() {
+        let children = data.nodes[&keys[i]].children.clone();
+        keys.extend(children);
+        i += 1;
+    }
+
+    keys
+}
+
+/**
+    Return true if `key` is `ancestor` or one of its children
+*/
+fn is_descendant<D, ID>(data: &TreeViewData<D, ID>, key: u64, ancestor: u64) -> bool {
+    let mut current = Some(key);
+    while let Some(k) = current {
+        if k == ancestor { return true; }
+        current = data.nodes[&k].parent;
+    }
+
+    false
+}
+
+fn node_path<D, ID>(data: &TreeViewData<D, ID>, key: u64) -> Vec<usize> {
+    let mut path = Vec::new();
+    let mut current = key;
+    loop {
+        let parent = data.nodes[&current].parent;
+        path.push(children_of_key(data, parent).iter().position(|&k| k == current).unwrap());
+        match parent {
+            Some(p) => { current = p; },
+            None => { break; }
+        }
+    }
+
+    path.reverse();
+    path
+}
+
+fn node_at_path<D, ID>(data: &TreeViewData<D, ID>, path: &[usize]) -> Option<u64> {
+    let mut key = None;
+    let mut children = &data.roots;
+    for &i in path {
+        match children.get(i) {
+            Some(&k) => {
+                key = Some(k);
+                children = &data.nodes[&k].children;
+            },
+            None => { return None; }
+        }
+    }
+
+    key
+}
+
+/**
+    Add a node to the tree view data and to the control
+*/
+unsafe fn insert_node<D: Clone+Display+'static, ID: Hash+Clone+'static>(handle: HWND, data: &mut TreeViewData<D, ID>, parent: Option<&ID>, index: usize, node: TreeNode<D, ID>) -> Result<(), Error> {
+    let key = node_key(&node.id);
+    let parent_key = parent.map(|p| node_key(p));
+
+    if data.nodes.contains_key(&key) {
+        return Err(Error::KeyExists);
+    } else if parent_key.map(|p| !data.nodes.contains_key(&p)).unwrap_or(false) {
+        return Err(Error::KeyNotFound);
+    }
+
+    children_of_key_mut(data, parent_key).insert(index, key);
+    data.nodes.insert(key, TreeViewNode {
+        id: node.id,
+        data: node.data,
+        parent: parent_key,
+        children: Vec::new(),
+        handle: ptr::null_mut(),
+        lazy: node.lazy
+    });
+
+    insert_item(handle, data, key);
+    update_children_flag(handle, data, parent_key);
+
+    Ok(())
+}
+
+/**
+    Insert a node in the control, after its previous sibling
+*/
+unsafe fn insert_item<D: Display, ID>(handle: HWND, data: &mut TreeViewData<D, ID>, key: u64) {
+    use winapi::{TVINSERTSTRUCTW, TVM_INSERTITEMW, TVIF_TEXT, TVIF_CHILDREN, TVI_ROOT, TVI_FIRST};
+
+    let item = {
+        let node = &data.nodes[&key];
+        let siblings = children_of_key(data, node.parent);
+        let index = siblings.iter().position(|&k| k == key).unwrap();
+
+        let mut text = to_utf16(&node.data.to_string());
+        let mut insert: TVINSERTSTRUCTW = mem::zeroed();
+        insert.hParent = match node.parent { Some(p) => data.nodes[&p].handle, None => TVI_ROOT };
+        insert.hInsertAfter = if index == 0 { TVI_FIRST } else { data.nodes[&siblings[index-1]].handle };
+
+        {
+            let item = insert.item_mut();
+            item.mask = TVIF_TEXT | TVIF_CHILDREN;
+            item.pszText = text.as_mut_ptr();
+            item.cChildren = if node.lazy || node.children.len() > 0 { 1 } else { 0 };
+        }
+
+        send_message(handle, TVM_INSERTITEMW, 0, &insert as *const TVINSERTSTRUCTW as LPARAM) as HTREEITEM
+    };
+
+    data.nodes.get_mut(&key).unwrap().handle = item;
+    if !item.is_null() {
+        data.handles.insert(item as usize, key);
+    }
+}
+
+/**
+    Insert a node and its children in the control
+*/
+unsafe fn insert_subtree<D: Display, ID>(handle: HWND, data: &mut TreeViewData<D, ID>, key: u64) {
+    for k in subtree(data, key) {
+        insert_item(handle, data, k);
+    }
+}
+
+/**
+    Show the expand button of a node if it has children or if it is lazy
+*/
+unsafe fn update_children_flag<D, ID>(handle: HWND, data: &TreeViewData<D, ID>, key: Option<u64>) {
+    use winapi::{TVITEMW, TVM_SETITEMW, TVIF_CHILDREN, TVIF_HANDLE};
+
+    let node = match key {
+        Some(k) => &data.nodes[&k],
+        None => { return; }
+    };
+
+    let mut item: TVITEMW = mem::zeroed();
+    item.mask = TVIF_CHILDREN | TVIF_HANDLE;
+    item.hItem = node.handle;
+    item.cChildren = if node.lazy || node.children.len() > 0 { 1 } else { 0 };
+    send_message(handle, TVM_SETITEMW, 0, &item as *const TVITEMW as LPARAM);
+}
+
+/**
+    Fetch the children of a lazy node from the tree view loader. Do nothing if the node is not lazy.
+*/
+unsafe fn load_children<D: Clone+Display+'static, ID: Hash+Clone+'static>(handle: HWND, data: &mut TreeViewData<D, ID>, key: u64) {
+    let children = {
+        let node = data.nodes.get_mut(&key).unwrap();
+        if !node.lazy { return; }
+        node.lazy = false;
+
+        match data.loader.as_ref() {
+            Some(loader) => loader.children(&node.id, &node.data),
+            None => Vec::new()
+        }
+    };
+
+    let parent = data.nodes[&key].id.clone();
+    for child in children {
+        let index = data.nodes[&key].children.len();
+        // A child that is already in the tree view is ignored
+        insert_node(handle, data, Some(&parent), index, child).ok();
+    }
+
+    update_children_flag(handle, data, Some(key));
+}
+
+/**
+    Load the children of a lazy node before the user expands it. Bound to `Event::Expanding` in `TreeViewT::packed`.
+*/
+#[allow(unused_variables)]
+fn load_on_expanding<D: Clone+Display+'static, ID: Hash+Clone+'static>(ui: &Ui<ID>, id: &ID, event: &Event, args: &EventArgs) {
+    let path = match args {
+        &EventArgs::Node(ref path) => path,
+        _ => { return; }
+    };
+
+    if let Ok(mut tree) = ui.get_mut::<TreeView<D, ID>>(id) {
+        let tree = &mut **tree;
+        if let Some(key) = node_at_path(&tree.data, path) {
+            unsafe{ load_children(tree.handle, &mut tree.data, key); }
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn item_checked(handle: HWND, item: HTREEITEM) -> bool {
+    use winapi::{TVM_GETITEMSTATE, TVIS_STATEIMAGEMASK};
+    let state = send_message(handle, TVM_GETITEMSTATE, item as WPARAM, TVIS_STATEIMAGEMASK as LPARAM) as UINT;
+    (state & TVIS_STATEIMAGEMASK) >> 12 == 2
+}
+
+unsafe fn set_item_checked(handle: HWND, item: HTREEITEM, checked: bool) {
+    use winapi::{TVITEMW, TVM_SETITEMW, TVIF_STATE, TVIF_HANDLE, TVIS_STATEIMAGEMASK, INDEXTOSTATEIMAGEMASK};
+
+    let mut tvitem: TVITEMW = mem::zeroed();
+    tvitem.mask = TVIF_STATE | TVIF_HANDLE;
+    tvitem.hItem = item;
+    tvitem.state = INDEXTOSTATEIMAGEMASK(if checked { 2 } else { 1 });
+    tvitem.stateMask = TVIS_STATEIMAGEMASK;
+    send_message(handle, TVM_SETITEMW, 0, &tvitem as *const TVITEMW as LPARAM);
+}
+
+/**
+    Sent with TVN_ITEMCHANGED. Not defined in winapi.
+*/
+#[repr(C)]
+#[allow(non_snake_case)]
+struct NMTVITEMCHANGE {
+    hdr: NMHDR,
+    uChanged: UINT,
+    hItem: HTREEITEM,
+    uStateNew: UINT,
+    uStateOld: UINT,
+    lParam: LPARAM
+}
+
+unsafe fn decode_treeview_notify<D: Clone+Display+'static, ID: Hash+Clone+'static>(handle: HWND, nmhdr: *const NMHDR) -> Option<(Event, EventArgs)> {
+    use low::window_helper::get_window_long;
+    use winapi::{NMTREEVIEWW, GWL_USERDATA, TVN_SELCHANGEDW, TVN_ITEMEXPANDINGW, TVN_ITEMEXPANDEDW, TVN_ITEMCHANGEDW, NM_SETFOCUS,
+      NM_KILLFOCUS, TVE_EXPAND, TVE_COLLAPSE, TVIS_STATEIMAGEMASK};
+
+    let data: *const TreeViewData<D, ID> = get_window_long(handle, GWL_USERDATA) as *const TreeViewData<D, ID>;
+    if data.is_null() { return None; }
+    let data = &*data;
+
+    let path_of = |item: HTREEITEM, data: &TreeViewData<D, ID>| -> Vec<usize> {
+        match data.handles.get(&(item as usize)) {
+            Some(&k) => node_path(data, k),
+            None => Vec::new()
+        }
+    };
+
+    match (&*nmhdr).code {
+        TVN_SELCHANGEDW => {
+            let nmtv = &*(nmhdr as *const NMTREEVIEWW);
+            Some((Event::SelectionChanged, EventArgs::Node(path_of(nmtv.itemNew.hItem, data))))
+        },
+        TVN_ITEMEXPANDINGW => {
+            // The callbacks run before the system expands the node, so the children loaded by `load_on_expanding` are shown
+            let nmtv = &*(nmhdr as *const NMTREEVIEWW);
+            if nmtv.action & (TVE_EXPAND as UINT) != 0 && data.handles.contains_key(&(nmtv.itemNew.hItem as usize)) {
+                Some((Event::Expanding, EventArgs::Node(path_of(nmtv.itemNew.hItem, data))))
+            } else {
+                None
+            }
+        },
+        TVN_ITEMEXPANDEDW => {
+            let nmtv = &*(nmhdr as *const NMTREEVIEWW);
+            let path = path_of(nmtv.itemNew.hItem, data);
+            match nmtv.action as WPARAM {
+                TVE_EXPAND => Some((Event::Expanded, EventArgs::Node(path))),
+                TVE_COLLAPSE => Some((Event::Collapsed, EventArgs::Node(path))),
+                _ => None
+            }
+        },
+        TVN_ITEMCHANGEDW => {
+            let nmtv = &*(nmhdr as *const NMTVITEMCHANGE);
+            let changed = nmtv.uStateNew ^ nmtv.uStateOld;
+            let old_image = (nmtv.uStateOld & TVIS_STATEIMAGEMASK) >> 12;
+
+            // The state image goes from 0 to 1 when the check boxes are created. This is not a user check.
+            if changed & TVIS_STATEIMAGEMASK != 0 && old_image != 0 {
+                let checked = (nmtv.uStateNew & TVIS_STATEIMAGEMASK) >> 12 == 2;
+                Some((Event::ValueChanged, EventArgs::NodeChecked(path_of(nmtv.hItem, data), checked)))
+            } else {
+                None
+            }
+        },
+        NM_SETFOCUS | NM_KILLFOCUS => {
+            Some((Event::Focus, EventArgs::Focus((&*nmhdr).code == NM_SETFOCUS)))
+        },
+        _ => None
+    }
+}
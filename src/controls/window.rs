@@ -35,7 +35,7 @@ const WINDOW_CLASS_NAME: &'static str = "NWG_BUILTIN_WINDOW";
     A template that will create a window.
 
     Events:  
-    Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::Closed, Event::MouseDown, Event::MouseUp, Event::Moved, Event::Resized, Event::Raw
+    Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::Closed, Event::MouseDown, Event::MouseUp, Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel, Event::Moved, Event::Resized, Event::FilesDropped, Event::ClipboardChanged, Event::ContextMenu, Event::Raw
 
     Members:  
       • `title` : The title of the window (in the title bar)  
@@ -45,32 +45,46 @@ const WINDOW_CLASS_NAME: &'static str = "NWG_BUILTIN_WINDOW";
       • `visible` : If the user can see the window or not  
       • `disabled` : If the window is enabled or not. A disabled window do not process events  
       • `exit_on_close` : If NWG should break the event processing loop when this window is closed  
+      • `accept_files` : If the user can drop files from the shell on the window. Dropping files raises `Event::FilesDropped`  
+      • `icon` : The id of an `Image` resource of type `Icon` shown in the title bar and in the taskbar. If `None`, the default icon is used  
 */
 #[derive(Clone)]
-pub struct WindowT<S: Clone+Into<String>> {
+pub struct WindowT<S: Clone+Into<String>, ID: Hash+Clone> {
     pub title: S,
     pub position: (i32, i32),
     pub size: (u32, u32),
     pub resizable: bool,
     pub visible: bool,
     pub disabled: bool,
-    pub exit_on_close: bool
+    pub exit_on_close: bool,
+    pub accept_files: bool,
+    pub icon: Option<ID>
 }
 
-impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for WindowT<S> {
+impl<S: Clone+Into<String>, ID: Hash+Clone> ControlT<ID> for WindowT<S, ID> {
     fn resource_type_id(&self) -> TypeId { TypeId::of::<Window>() }
 
     fn events(&self) -> Vec<Event> {
         vec![Event::Destroyed, Event::KeyDown, Event::KeyUp, Event::Char, Event::Closed, Event::MouseDown, Event::MouseUp,
-             Event::Moved, Event::Resized, Event::Raw]
+             Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel,
+             Event::Moved, Event::Resized, Event::FilesDropped, Event::ClipboardChanged, Event::ContextMenu, Event::Raw]
     }
 
-    #[allow(unused_variables)]
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Control>, Error> {
+        let icon = match self.icon.as_ref().map(|id| ui.handle_of(id)) {
+            Some(Ok(AnyHandle::HICON(h))) => Some(h),
+            Some(Ok(h)) => { return Err(Error::BadResource(format!("The icon of a window must be an Image of type Icon. Got {:?}", h))); },
+            Some(Err(e)) => { return Err(e); },
+            None => None
+        };
+
         unsafe{
             if let Err(e) = build_sysclass() { return Err(e); }
             match build_window(&self) {
-                Ok(h) => { Ok( Box::new(Window{handle: h}) as Box<Control> ) },
+                Ok(h) => {
+                    if icon.is_some() { ::low::window_helper::set_window_icon(h, icon); }
+                    Ok( Box::new(Window{handle: h}) as Box<Control> )
+                },
                 Err(e) => Err(e)
             }
         } // unsafe
@@ -91,13 +105,14 @@ impl Window {
         it only set it hidden. In order to also destroy the window, add an unpack statement on the **Closed** event.
     */
     pub fn close(&self) {
-        use user32::PostMessageW;
+        use low::window_helper::post_message;
         use winapi::WM_CLOSE;
 
-        unsafe{ PostMessageW(self.handle, WM_CLOSE, 0, 0) };
+        unsafe{ post_message(self.handle, WM_CLOSE, 0, 0) };
     }
 
     /// Activate the window and set it above the other windows
+    #[cfg(windows)]
     pub fn activate(&self) { unsafe{ 
         use user32::SetForegroundWindow;
         SetForegroundWindow(self.handle); 
@@ -126,8 +141,8 @@ impl Control for Window {
     }
 
     fn free(&mut self) {
-        use user32::DestroyWindow;
-        unsafe{ DestroyWindow(self.handle) };
+        use low::window_helper::destroy_window;
+        unsafe{ destroy_window(self.handle) };
     }
 
 }
@@ -142,17 +157,16 @@ use winapi::{UINT, WPARAM, LPARAM, LRESULT};
 #[allow(unused_variables)]
 unsafe extern "system" fn window_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
     use winapi::{WM_CREATE, WM_CLOSE, GWL_USERDATA};
-    use user32::{DefWindowProcW, PostQuitMessage, ShowWindow};
-    use low::window_helper::get_window_long;
+    use low::window_helper::{get_window_long, set_window_visibility, post_quit_message, def_window_proc};
 
     let handled = match msg {
         WM_CREATE => true,
         WM_CLOSE => {
-            ShowWindow(hwnd, 0);
+            set_window_visibility(hwnd, false);
 
             let exit_on_close = get_window_long(hwnd, GWL_USERDATA) & 0x01 == 1;
             if exit_on_close {
-                PostQuitMessage(0);
+                post_quit_message(0);
             }
             true
         }
@@ -162,7 +176,7 @@ unsafe extern "system" fn window_sysproc(hwnd: HWND, msg: UINT, w: WPARAM, l: LP
     if handled {
         0
     } else {
-        DefWindowProcW(hwnd, msg, w, l)
+        def_window_proc(hwnd, msg, w, l)
     }
 }
 
@@ -183,10 +197,10 @@ unsafe fn build_sysclass() -> Result<(), Error> {
 }
 
 #[inline(always)]
-unsafe fn build_window<S: Clone+Into<String>>(t: &WindowT<S>) -> Result<HWND, Error> {
+unsafe fn build_window<S: Clone+Into<String>, ID: Hash+Clone>(t: &WindowT<S, ID>) -> Result<HWND, Error> {
     use low::window_helper::{WindowParams, build_window, set_window_long};
     use winapi::{DWORD, WS_VISIBLE, WS_DISABLED, WS_OVERLAPPEDWINDOW, WS_CAPTION, WS_OVERLAPPED, WS_MINIMIZEBOX,
-      WS_MAXIMIZEBOX, WS_SYSMENU, GWL_USERDATA, WS_CLIPCHILDREN};
+      WS_MAXIMIZEBOX, WS_SYSMENU, GWL_USERDATA, WS_CLIPCHILDREN, WS_EX_ACCEPTFILES};
 
     let fixed_window: DWORD = WS_CLIPCHILDREN| WS_SYSMENU | WS_CAPTION | WS_OVERLAPPED | WS_MINIMIZEBOX | WS_MAXIMIZEBOX;
     let flags: DWORD = 
@@ -200,7 +214,7 @@ unsafe fn build_window<S: Clone+Into<String>>(t: &WindowT<S>) -> Result<HWND, Er
         position: t.position.clone(),
         size: t.size.clone(),
         flags: flags,
-        ex_flags: None,
+        ex_flags: if t.accept_files { Some(WS_EX_ACCEPTFILES) } else { None },
         parent: ::std::ptr::null_mut()
     };
 
@@ -211,4 +225,4 @@ unsafe fn build_window<S: Clone+Into<String>>(t: &WindowT<S>) -> Result<HWND, Er
         },
         Err(e) => Err(Error::System(e))
     }
-}
\ No newline at end of file
+}
@@ -41,6 +41,23 @@ pub enum MouseButton {
     Middle
 }
 
+/**
+    The state of the mouse buttons and of the modifier keys when a mouse event was raised
+
+    Members:  
+    • `left`, `right`, `middle`: If the mouse button is pressed  
+    • `ctrl`, `shift`, `alt`: If the modifier key is pressed  
+*/
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MouseState {
+    pub left: bool,
+    pub right: bool,
+    pub middle: bool,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool
+}
+
 /**
     Text align constant on the horizontal axis
 */
@@ -87,6 +104,29 @@ pub enum MessageIcons {
     None
 }
 
+/**
+    Enum of the icons shown in a tray icon balloon (to use with `TrayIcon::show_balloon`).
+    `BalloonIcon::Tray` shows the icon of the tray icon.
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BalloonIcon {
+    None,
+    Info,
+    Warning,
+    Error,
+    Tray
+}
+
+/**
+    Enum of the image types loaded by an image resource (to use with `ImageT`)
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ImageType {
+    Bitmap,
+    Icon,
+    Cursor
+}
+
 /**
     Return value of `message`. Define the button that the user clicked. If the user 
     cancelled the message box by clicking on X button of the window, `MessageChoice::Cancel` is returned.
@@ -164,7 +204,7 @@ pub enum ProgressBarState {
 /**
     Define a rectangle shape that can be used with canvases
 */
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Rectangle {
     pub left: f32,
     pub top: f32,
@@ -175,7 +215,7 @@ pub struct Rectangle {
 /**
     Define a ellipse shape that can be used with canvases
 */
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct Ellipse {
     pub center: (f32, f32),
     pub radius: (f32, f32),
@@ -192,6 +232,65 @@ pub struct SolidBrush {
     pub color: (f32, f32, f32, f32)
 }
 
+/**
+    A color at a position of a gradient
+
+    Members:  
+    • `position`: The position of the color in the gradient. 0.0 - 1.0  
+    • `color`: The color at this position (red, green, blue, alpha)
+*/
+#[derive(Clone, PartialEq, Debug)]
+pub struct GradientStop {
+    pub position: f32,
+    pub color: (f32, f32, f32, f32)
+}
+
+/**
+    How a gradient is painted outside of its start and end positions
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExtendMode {
+    Clamp = 0,
+    Wrap = 1,
+    Mirror = 2
+}
+
+/**
+    A brush that paints a gradient along a line. Used when painting in a canvas
+
+    Members:  
+    • `start`: The start point of the gradient line. The coordinates are the same as the shapes painted with the brush  
+    • `end`: The end point of the gradient line  
+    • `stops`: The colors of the gradient  
+    • `extend_mode`: How the gradient is painted before the start point and after the end point
+*/
+#[derive(Clone, Debug)]
+pub struct LinearGradientBrush {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    pub stops: Vec<GradientStop>,
+    pub extend_mode: ExtendMode
+}
+
+/**
+    A brush that paints a gradient from the center of an ellipse to its edge. Used when painting in a canvas
+
+    Members:  
+    • `center`: The center of the gradient ellipse. The coordinates are the same as the shapes painted with the brush  
+    • `origin_offset`: The position of the gradient origin, relative to the center  
+    • `radius`: The radius of the gradient ellipse (`x`, `y`)  
+    • `stops`: The colors of the gradient  
+    • `extend_mode`: How the gradient is painted outside of the ellipse
+*/
+#[derive(Clone, Debug)]
+pub struct RadialGradientBrush {
+    pub center: (f32, f32),
+    pub origin_offset: (f32, f32),
+    pub radius: (f32, f32),
+    pub stops: Vec<GradientStop>,
+    pub extend_mode: ExtendMode
+}
+
 /**
     Cap style used when creating a Pen
 */
@@ -250,6 +349,178 @@ pub struct Pen {
     pub dash_offset: f32,
 }
 
+/**
+    How the inside of a path is computed when it is filled
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum FillMode {
+    Alternate = 0,
+    Winding = 1
+}
+
+/**
+    Direction in which an arc is drawn from its start point to its end point
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SweepDirection {
+    CounterClockwise = 0,
+    Clockwise = 1
+}
+
+/**
+    Which of the two possible arcs between two points is drawn
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ArcSize {
+    Small = 0,
+    Large = 1
+}
+
+/**
+    How a bitmap is scaled when it is drawn in a canvas
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BitmapInterpolation {
+    NearestNeighbor = 0,
+    Linear = 1
+}
+
+/**
+    How the text of a TextFormat is broken into lines
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextWrapping {
+    Wrap = 0,
+    NoWrap = 1
+}
+
+/**
+    How the text of a TextFormat is cut when it overflows its layout box. The cut text ends with an ellipsis.
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextTrimming {
+    None = 0,
+    Character = 1,
+    Word = 2
+}
+
+/**
+    Describe how text should be written. Used when painting in a canvas
+
+    Members:  
+    • `family`: The name of the font family. Ex: `"Segoe UI"`  
+    • `size`: The size of the font in device independent pixels  
+    • `weight`: The weight of the font in the range 1 through 999. See the FONT_WEIGHT_* constants for convenience  
+    • `italic`: If the text is written in italic  
+    • `align`: The alignment of the text in its layout box on the horizontal axis  
+    • `vertical_align`: The alignment of the text in its layout box on the vertical axis  
+    • `wrapping`: How the text is broken into lines  
+    • `trimming`: How the text is cut when it overflows its layout box  
+*/
+#[derive(Clone, Debug)]
+pub struct TextFormat {
+    pub family: String,
+    pub size: f32,
+    pub weight: c_int,
+    pub italic: bool,
+    pub align: HTextAlign,
+    pub vertical_align: VTextAlign,
+    pub wrapping: TextWrapping,
+    pub trimming: TextTrimming
+}
+
+/**
+    Direction in which a box layout stack its children
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Orientation {
+    Horizontal,
+    Vertical
+}
+
+/**
+    Describe how a layout share the available space between its rows, its columns or its box children.
+
+    Members:  
+    • `weight`: The share of the remaining space the track receives. A track with a weight of 0 keeps its minimum size  
+    • `min`: The minimum size of the track  
+    • `max`: The maximum size of the track. `None` means no limit  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LayoutTrack {
+    pub weight: u32,
+    pub min: u32,
+    pub max: Option<u32>
+}
+
+/**
+    Position of a child in a grid layout
+
+    Members:  
+    • `col`: The first column of the cell  
+    • `row`: The first row of the cell  
+    • `col_span`: The number of columns the cell covers. Must be at least 1  
+    • `row_span`: The number of rows the cell covers. Must be at least 1  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct GridCell {
+    pub col: usize,
+    pub row: usize,
+    pub col_span: usize,
+    pub row_span: usize
+}
+
+/**
+    Side of the parent a docked child sticks to. `Fill` takes the space left by the other docked children.
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Dock {
+    Left,
+    Top,
+    Right,
+    Bottom,
+    Fill
+}
+
+/**
+    Define how a child of an anchor layout follows its parent when the parent is resized
+
+    Members:  
+    • `Anchor`: The child starts at `position` with `size`. Each side in `anchor` (a combination of the ANCHOR_* constants)
+      keeps its distance from the same side of the parent.  
+    • `Dock`: The child sticks to one side of the parent. `size` is the width (Left/Right) or height (Top/Bottom) of the child  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AnchorItem {
+    Anchor{ position: (i32, i32), size: (u32, u32), anchor: u32 },
+    Dock{ dock: Dock, size: u32 }
+}
+
+/**
+    How a list view displays its items
+
+    Members:  
+    • `Report`: One row per item and one column per `ListViewColumn`, with a header  
+    • `Icon`: Large icons with the text of the first column under them  
+    • `SmallIcon`: Small icons with the text of the first column next to them  
+    • `List`: Like `SmallIcon`, but the items are arranged in columns  
+*/
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ListViewStyle {
+    Report,
+    Icon,
+    SmallIcon,
+    List
+}
+
+
+// Anchor layout sides
+pub const ANCHOR_NONE: u32 = 0x00;
+pub const ANCHOR_LEFT: u32 = 0x01;
+pub const ANCHOR_TOP: u32 = 0x02;
+pub const ANCHOR_RIGHT: u32 = 0x04;
+pub const ANCHOR_BOTTOM: u32 = 0x08;
+pub const ANCHOR_ALL: u32 = 0x0F;
 
 // Special window position constants
 pub const DEFAULT_POSITION: c_int = CW_USEDEFAULT;
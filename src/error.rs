@@ -33,6 +33,8 @@ pub enum SystemError {
     FontCreation,
     ComInstanceCreation(String),
     ComError(String),
+    ClipboardAccess,
+    TrayIconCreation,
 }
 
 impl SystemError {
@@ -47,6 +49,8 @@ impl SystemError {
             &SystemError::FontCreation => format!("Failed to create a system font"),
             &SystemError::ComInstanceCreation(ref name) => format!("Failed to create a COM instance for {}", name),
             &SystemError::ComError(ref details) => format!("An error ocurred while executing a COM method, {}", details),
+            &SystemError::ClipboardAccess => format!("Failed to access the clipboard"),
+            &SystemError::TrayIconCreation => format!("Failed to add an icon to the notification area"),
         };
 
         format!("{}.\nID {:?} - {}", tr, code, code_txt)
@@ -19,9 +19,12 @@
 */
 
 use std::time::Duration;
+use std::any::Any;
+
+use winapi::{HWND, NMHDR};
 
 use ui::Ui;
-use defs::MouseButton;
+use defs::{MouseButton, MouseState, PickerDate};
 
 /**
 The function signature for the event callback
@@ -50,6 +53,10 @@ pub enum Event {
     Closed,
     MouseUp,
     MouseDown,
+    MouseMove,
+    MouseEnter,
+    MouseLeave,
+    MouseWheel,
     Moved,
     Resized,
     Paint,
@@ -66,7 +73,19 @@ pub enum Event {
     Focus,
     Tick,
     Triggered,
-    DateChanged
+    DateChanged,
+    ColumnClick,
+    Expanding,
+    Expanded,
+    Collapsed,
+    TabChanged,
+    Accepted,
+    FilesDropped,
+    ClipboardChanged,
+    ContextMenu,
+
+    // Events raised by custom controls. The name only needs to be unique within a control.
+    Custom(&'static str)
 }
 
 /**
@@ -76,10 +95,54 @@ pub enum EventArgs {
     Key(u32),
     Char(char),
     MouseClick{btn: MouseButton, pos: (i32, i32)},
+    MouseMove{pos: (i32, i32), state: MouseState},
+    MouseWheel{pos: (i32, i32), delta: i32, state: MouseState}, // `delta` is a multiple of 120 (WHEEL_DELTA), positive when the wheel is rotated forward
     Focus(bool),
     Tick(Duration),
     Position(i32, i32),
     Size(u32, u32),
     Raw(u32, usize, usize), // MSG, WPARAM, LPARAM
+    Selection(Option<usize>), // Index of the selected item
+    Text(String),             // New text of the control
+    Date(Option<PickerDate>), // New date of a date picker
+    Value(i64),               // New value of a numeric input
+    Column(usize),            // Index of a list view column
+    Checked(usize, bool),     // Index of an item and its new check state
+    Node(Vec<usize>),         // Path of a tree view node, as child indexes from the root
+    NodeChecked(Vec<usize>, bool), // Path of a tree view node and its new check state
+    FilesDropped{paths: Vec<String>, pos: (i32, i32)}, // Paths of the files dropped on a control and the drop point in the control
+    Custom(Box<Any+Send>),    // Arguments of custom events
     None
+}
+
+/**
+    Decode a `WM_COMMAND` notification sent by a control into a NWG event.
+
+    Arguments:  
+      • 1: The handle of the control that sent the notification  
+      • 2: The notification code  
+*/
+pub type CommandDecoder = unsafe fn(HWND, u32) -> Option<(Event, EventArgs)>;
+
+/**
+    Decode a `WM_NOTIFY` notification sent by a control into a NWG event.
+
+    Arguments:  
+      • 1: The handle of the control that sent the notification  
+      • 2: A pointer to the notification header. Can be cast to the control specific structure.  
+*/
+pub type NotifyDecoder = unsafe fn(HWND, *const NMHDR) -> Option<(Event, EventArgs)>;
+
+/**
+    The notification decoders of a control type. Returned by `ControlT::decoders` and registered in the Ui
+    when the first control of the type is packed.
+
+    Members:  
+      • `command`: Decode the `WM_COMMAND` notifications  
+      • `notify`: Decode the `WM_NOTIFY` notifications  
+*/
+#[derive(Clone, Copy, Default)]
+pub struct EventDecoders {
+    pub command: Option<CommandDecoder>,
+    pub notify: Option<NotifyDecoder>
 }
\ No newline at end of file
@@ -0,0 +1,444 @@
+/*!
+    Local tasks executed on the thread that owns a Ui. Tasks are spawned with `Ui::spawn_local` and are polled
+    by the event loop each time they are woken. The futures in this module wake their task once they are ready.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::any::Any;
+#[cfg(windows)] use std::hash::Hash;
+use std::pin::Pin;
+use std::future::Future;
+use std::task::{Context, Poll, Waker, Wake};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(windows)] use std::cell::RefCell;
+#[cfg(windows)] use std::collections::HashMap;
+use std::collections::VecDeque;
+#[cfg(windows)] use std::time::Duration;
+
+use winapi::WPARAM;
+#[cfg(windows)] use winapi::{HWND, UINT, UINT_PTR, DWORD};
+
+#[cfg(windows)] use ui::LocalUi;
+use low::backend::RemotePoster;
+#[cfg(windows)] use controls::FileDialog;
+use error::Error;
+
+/// A task spawned with `Ui::spawn_local`
+pub type LocalTask = Pin<Box<Future<Output=()>>>;
+
+/**
+    Wake a local task by posting a `NWG_WAKE` message to the message window of its Ui.
+    Wakers can be sent to other threads, so the Ui is identified the same way as in a `UiSender`.
+*/
+pub struct TaskWaker {
+    pub task: u64,
+    pub inner: usize,  // *mut UiInner<ID>. Only dereferenced by the thread that owns the Ui.
+    pub hwnd: usize,   // The ui message window
+    pub poster: Arc<RemotePoster>,
+    pub alive: Arc<AtomicBool>
+}
+
+impl Wake for TaskWaker {
+
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        use low::defs::{NWG_WAKE, WakeArgs};
+        use low::message_handler::{pack_message_data, free_message_data};
+
+        // The tasks of a dropped Ui were dropped with it
+        if !self.alive.load(Ordering::SeqCst) { return; }
+
+        unsafe {
+            let data = pack_message_data(Box::new(WakeArgs{ task: self.task }) as Box<Any>);
+            if !self.poster.post_message(self.hwnd, NWG_WAKE, self.inner as WPARAM, data) {
+                free_message_data(data);
+            }
+        }
+    }
+
+}
+
+struct BlockingState<T> {
+    result: Option<Result<T, Error>>,
+    waker: Option<Waker>
+}
+
+/**
+    A future that resolves to the value returned by a function executed on a worker thread. Created with `Ui::spawn_blocking`.
+
+    Resolves to `Err(Error::UserError)` if the function panicked.
+*/
+pub struct BlockingTask<T> {
+    state: Arc<Mutex<BlockingState<T>>>
+}
+
+impl<T: Send+'static> BlockingTask<T> {
+
+    /// Execute `f` on a new thread
+    pub fn spawn<F: FnOnce() -> T + Send + 'static>(f: F) -> BlockingTask<T> {
+        use std::thread;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let state = Arc::new(Mutex::new(BlockingState{ result: None, waker: None }));
+        let thread_state = state.clone();
+
+        thread::spawn(move || {
+            let result = match catch_unwind(AssertUnwindSafe(f)) {
+                Ok(v) => Ok(v),
+                Err(_) => Err(Error::UserError("The blocking task panicked".to_string()))
+            };
+
+            // The waker is called outside the lock
+            let waker = match thread_state.lock() {
+                Ok(mut state) => { state.result = Some(result); state.waker.take() },
+                Err(_) => None
+            };
+
+            if let Some(waker) = waker { waker.wake(); }
+        });
+
+        BlockingTask{ state: state }
+    }
+
+}
+
+impl<T> Future for BlockingTask<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<T, Error>> {
+        let mut state = match self.state.lock() {
+            Ok(s) => s,
+            Err(_) => { return Poll::Ready(Err(Error::UserError("The blocking task panicked".to_string()))); }
+        };
+
+        match state.result.take() {
+            Some(r) => Poll::Ready(r),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+struct ChannelState<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+    senders: usize,
+    receiver: bool
+}
+
+/**
+    Create a channel that sends values from any thread to a local task.
+    Unlike `std::sync::mpsc`, receiving a value can be awaited without blocking the event loop.
+*/
+pub fn channel<T: Send>() -> (TaskSender<T>, TaskReceiver<T>) {
+    let state = Arc::new(Mutex::new(ChannelState{ queue: VecDeque::new(), waker: None, senders: 1, receiver: true }));
+    (TaskSender{ state: state.clone() }, TaskReceiver{ state: state })
+}
+
+/**
+    The sending half of a task channel. Can be cloned and sent to other threads.
+*/
+pub struct TaskSender<T: Send> {
+    state: Arc<Mutex<ChannelState<T>>>
+}
+
+impl<T: Send> TaskSender<T> {
+
+    /// Send a value to the receiver. Return the value if the receiver was dropped.
+    pub fn send(&self, value: T) -> Result<(), T> {
+        let waker = match self.state.lock() {
+            Ok(mut state) => {
+                if !state.receiver { return Err(value); }
+                state.queue.push_back(value);
+                state.waker.take()
+            },
+            Err(_) => { return Err(value); }
+        };
+
+        if let Some(waker) = waker { waker.wake(); }
+        Ok(())
+    }
+
+}
+
+impl<T: Send> Clone for TaskSender<T> {
+    fn clone(&self) -> TaskSender<T> {
+        if let Ok(mut state) = self.state.lock() { state.senders += 1; }
+        TaskSender{ state: self.state.clone() }
+    }
+}
+
+impl<T: Send> Drop for TaskSender<T> {
+    fn drop(&mut self) {
+        // Wake the receiver when the last sender is dropped, so that it knows that the channel is closed
+        let waker = match self.state.lock() {
+            Ok(mut state) => {
+                state.senders -= 1;
+                if state.senders == 0 { state.waker.take() } else { None }
+            },
+            Err(_) => None
+        };
+
+        if let Some(waker) = waker { waker.wake(); }
+    }
+}
+
+/**
+    The receiving half of a task channel. Use `recv` to await the values in a local task.
+*/
+pub struct TaskReceiver<T: Send> {
+    state: Arc<Mutex<ChannelState<T>>>
+}
+
+impl<T: Send> TaskReceiver<T> {
+
+    /**
+        Return a future that resolves to the next value of the channel, or to `None` if every sender was dropped
+        and the channel is empty.
+    */
+    pub fn recv<'a>(&'a mut self) -> Recv<'a, T> {
+        Recv{ receiver: self }
+    }
+
+    /**
+        Return the next value of the channel. Return `Poll::Pending` and register the task waker if the channel is empty.
+    */
+    pub fn poll_recv(&mut self, cx: &mut Context) -> Poll<Option<T>> {
+        let mut state = match self.state.lock() {
+            Ok(s) => s,
+            Err(_) => { return Poll::Ready(None); }
+        };
+
+        match state.queue.pop_front() {
+            Some(v) => Poll::Ready(Some(v)),
+            None if state.senders == 0 => Poll::Ready(None),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Return the next value of the channel without waiting
+    pub fn try_recv(&mut self) -> Option<T> {
+        match self.state.lock() {
+            Ok(mut state) => state.queue.pop_front(),
+            Err(_) => None
+        }
+    }
+
+}
+
+impl<T: Send> Drop for TaskReceiver<T> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.receiver = false;
+            state.queue.clear();
+        }
+    }
+}
+
+/**
+    A future that resolves to the next value of a task channel. Created with `TaskReceiver::recv`.
+*/
+pub struct Recv<'a, T: Send+'a> {
+    receiver: &'a mut TaskReceiver<T>
+}
+
+impl<'a, T: Send+'a> Future for Recv<'a, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        self.get_mut().receiver.poll_recv(cx)
+    }
+}
+
+#[cfg(windows)]
+struct DelayState {
+    fired: bool,
+    waker: Option<Waker>
+}
+
+#[cfg(windows)]
+thread_local!(
+    static DELAYS: RefCell<HashMap<UINT_PTR, DelayState>> = RefCell::new(HashMap::new())
+);
+
+/**
+    Return a future that resolves once `duration` has elapsed. The delay uses a system timer of the current thread,
+    so it must be awaited by a local task.
+
+    Delays longer than `USER_TIMER_MAXIMUM` (about 24.8 days) are shortened to this value. If the system cannot
+    create the timer, the future resolves immediately.
+*/
+#[cfg(windows)]
+pub fn sleep(duration: Duration) -> Delay {
+    Delay{ duration: duration, timer: None, done: false }
+}
+
+/**
+    A future that resolves after a delay. Created with `sleep`.
+*/
+#[cfg(windows)]
+pub struct Delay {
+    duration: Duration,
+    timer: Option<UINT_PTR>,
+    done: bool
+}
+
+#[cfg(windows)]
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        use user32::SetTimer;
+        use low::defs::USER_TIMER_MAXIMUM;
+        use std::{ptr, cmp};
+
+        let delay = self.get_mut();
+        if delay.done { return Poll::Ready(()); }
+
+        let timer = match delay.timer {
+            Some(t) => t,
+            None => {
+                let ms = delay.duration.as_secs() * 1000 + (delay.duration.subsec_nanos() / 1_000_000) as u64;
+                if ms == 0 {
+                    delay.done = true;
+                    return Poll::Ready(());
+                }
+
+                let ms = cmp::min(ms, USER_TIMER_MAXIMUM as u64) as UINT;
+                let t = unsafe{ SetTimer(ptr::null_mut(), 0, ms, Some(delay_callback)) };
+                if t == 0 {
+                    delay.done = true;
+                    return Poll::Ready(());
+                }
+
+                DELAYS.with(|d| d.borrow_mut().insert(t, DelayState{ fired: false, waker: None }) );
+                delay.timer = Some(t);
+                t
+            }
+        };
+
+        let fired = DELAYS.with(|d| {
+            let mut delays = d.borrow_mut();
+            let state = delays.get_mut(&timer).unwrap();
+            if !state.fired { state.waker = Some(cx.waker().clone()); }
+            state.fired
+        });
+
+        if fired {
+            DELAYS.with(|d| d.borrow_mut().remove(&timer) );
+            delay.timer = None;
+            delay.done = true;
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for Delay {
+    fn drop(&mut self) {
+        use user32::KillTimer;
+        use std::ptr;
+
+        if let Some(timer) = self.timer.take() {
+            unsafe{ KillTimer(ptr::null_mut(), timer); }
+            DELAYS.with(|d| d.borrow_mut().remove(&timer) );
+        }
+    }
+}
+
+#[cfg(windows)]
+#[allow(unused_variables, non_snake_case)]
+unsafe extern "system" fn delay_callback(hwnd: HWND, uMsg: UINT, idEvent: UINT_PTR, dwTime: DWORD) {
+    use user32::KillTimer;
+    use std::ptr;
+
+    KillTimer(ptr::null_mut(), idEvent);
+
+    // The waker is called outside the borrow because waking a task might create a new delay
+    let waker = DELAYS.with(|d| {
+        match d.borrow_mut().get_mut(&idEvent) {
+            Some(state) => { state.fired = true; state.waker.take() },
+            None => None
+        }
+    });
+
+    if let Some(waker) = waker { waker.wake(); }
+}
+
+/**
+    Return a future that shows a file dialog and resolves to the result of `FileDialog::run`.
+    The dialog is shown the next time the task is polled, so that the code that started the task is not blocked.
+    The event loop keeps running while the dialog is opened.
+
+    Resolves to `Err` if the Ui was dropped or if `id` do not identify a FileDialog.
+*/
+#[cfg(windows)]
+pub fn run_file_dialog<ID: Hash+Clone+'static>(ui: &LocalUi<ID>, id: &ID) -> FileDialogRun<ID> {
+    FileDialogRun{ ui: ui.clone(), id: id.clone(), yielded: false }
+}
+
+/**
+    A future that shows a file dialog. Created with `run_file_dialog`.
+*/
+#[cfg(windows)]
+pub struct FileDialogRun<ID: Hash+Clone+'static> {
+    ui: LocalUi<ID>,
+    id: ID,
+    yielded: bool
+}
+
+#[cfg(windows)]
+// The future is never pinned structurally
+impl<ID: Hash+Clone+'static> Unpin for FileDialogRun<ID> {}
+
+#[cfg(windows)]
+impl<ID: Hash+Clone+'static> Future for FileDialogRun<ID> {
+    type Output = Result<bool, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<bool, Error>> {
+        let run = self.get_mut();
+        if !run.yielded {
+            run.yielded = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        let id = &run.id;
+        let result = run.ui.with(|ui| {
+            match ui.get::<FileDialog>(id) {
+                Ok(dialog) => Ok(dialog.run()),
+                Err(e) => Err(e)
+            }
+        });
+
+        match result {
+            Ok(r) => Poll::Ready(r),
+            Err(e) => Poll::Ready(Err(e))
+        }
+    }
+}
@@ -0,0 +1,283 @@
+/*!
+    Layouts that place the children of a window-like control and follow its size.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+pub mod solver;
+
+use std::any::TypeId;
+use std::hash::Hash;
+use std::rc::Rc;
+
+use ui::Ui;
+use controls::AnyHandle;
+use resources::{ResourceT, Resource};
+use events::Event;
+use error::Error;
+use defs::{Orientation, LayoutTrack, GridCell, AnchorItem};
+use layouts::solver::{Geometry, Margin, solve_box, solve_grid, solve_anchor};
+
+/**
+    A template that creates a box layout. The children are stacked horizontally or vertically
+    and share the parent client area according to their `LayoutTrack`.
+
+    A layout binds a callback on the `Event::Resized` of its parent using the layout ID as callback ID.
+
+    Members:  
+    • `parent`: The window-like control that holds the children  
+    • `orientation`: The direction in which the children are stacked  
+    • `margin`: Space around the children (left, top, right, bottom)  
+    • `spacing`: Space between two children  
+    • `children`: The ID of the children, in order, and how they share the space  
+*/
+#[derive(Clone)]
+pub struct BoxLayoutT<ID: Hash+Clone> {
+    pub parent: ID,
+    pub orientation: Orientation,
+    pub margin: Margin,
+    pub spacing: u32,
+    pub children: Vec<(ID, LayoutTrack)>
+}
+
+impl<ID: Hash+Clone+'static> ResourceT<ID> for BoxLayoutT<ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Layout<ID>>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Resource>, Error> {
+        let kind = LayoutKind::Box {
+            orientation: self.orientation,
+            margin: self.margin,
+            spacing: self.spacing,
+            tracks: self.children.iter().map(|&(_, t)| t).collect()
+        };
+
+        let children = self.children.iter().map(|&(ref id, _)| id.clone()).collect();
+        build_layout(ui, &self.parent, children, kind)
+    }
+
+    fn packed(&self, ui: &Ui<ID>, id: &ID) {
+        bind_layout(ui, id);
+    }
+}
+
+/**
+    A template that creates a grid layout. The columns and the rows share the parent client area
+    according to their `LayoutTrack`, then each child covers the cells defined by its `GridCell`.
+
+    A layout binds a callback on the `Event::Resized` of its parent using the layout ID as callback ID.
+
+    Members:  
+    • `parent`: The window-like control that holds the children  
+    • `margin`: Space around the grid (left, top, right, bottom)  
+    • `spacing`: Space between two columns or two rows  
+    • `columns`: The columns of the grid  
+    • `rows`: The rows of the grid  
+    • `children`: The ID of the children and the cells they cover  
+*/
+#[derive(Clone)]
+pub struct GridLayoutT<ID: Hash+Clone> {
+    pub parent: ID,
+    pub margin: Margin,
+    pub spacing: u32,
+    pub columns: Vec<LayoutTrack>,
+    pub rows: Vec<LayoutTrack>,
+    pub children: Vec<(ID, GridCell)>
+}
+
+impl<ID: Hash+Clone+'static> ResourceT<ID> for GridLayoutT<ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Layout<ID>>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Resource>, Error> {
+        for &(_, ref c) in self.children.iter() {
+            let (col_end, row_end) = (c.col + c.col_span, c.row + c.row_span);
+            if c.col_span == 0 || c.row_span == 0 || col_end > self.columns.len() || row_end > self.rows.len() {
+                let msg = format!("The grid cell {:?} is outside of a {}x{} grid.", c, self.columns.len(), self.rows.len());
+                return Err(Error::BadResource(msg));
+            }
+        }
+
+        let kind = LayoutKind::Grid {
+            margin: self.margin,
+            spacing: self.spacing,
+            columns: self.columns.clone(),
+            rows: self.rows.clone(),
+            cells: self.children.iter().map(|&(_, c)| c).collect()
+        };
+
+        let children = self.children.iter().map(|&(ref id, _)| id.clone()).collect();
+        build_layout(ui, &self.parent, children, kind)
+    }
+
+    fn packed(&self, ui: &Ui<ID>, id: &ID) {
+        bind_layout(ui, id);
+    }
+}
+
+/**
+    A template that creates an anchor layout. Anchored children keep their distance from the anchored sides
+    of the parent and docked children stick to one side of the parent. The anchored positions are relative to the
+    size of the parent when the layout is created.
+
+    A layout binds a callback on the `Event::Resized` of its parent using the layout ID as callback ID.
+
+    Members:  
+    • `parent`: The window-like control that holds the children  
+    • `children`: The ID of the children and how they are attached to the parent  
+*/
+#[derive(Clone)]
+pub struct AnchorLayoutT<ID: Hash+Clone> {
+    pub parent: ID,
+    pub children: Vec<(ID, AnchorItem)>
+}
+
+impl<ID: Hash+Clone+'static> ResourceT<ID> for AnchorLayoutT<ID> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Layout<ID>>() }
+
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Resource>, Error> {
+        let kind = LayoutKind::Anchor {
+            reference: (0, 0), // Set to the parent size in `build_layout`
+            items: self.children.iter().map(|&(_, a)| a).collect()
+        };
+
+        let children = self.children.iter().map(|&(ref id, _)| id.clone()).collect();
+        build_layout(ui, &self.parent, children, kind)
+    }
+
+    fn packed(&self, ui: &Ui<ID>, id: &ID) {
+        bind_layout(ui, id);
+    }
+}
+
+enum LayoutKind {
+    Box{ orientation: Orientation, margin: Margin, spacing: u32, tracks: Vec<LayoutTrack> },
+    Grid{ margin: Margin, spacing: u32, columns: Vec<LayoutTrack>, rows: Vec<LayoutTrack>, cells: Vec<GridCell> },
+    Anchor{ reference: (u32, u32), items: Vec<AnchorItem> }
+}
+
+struct LayoutData<ID: Hash+Clone> {
+    parent: ID,
+    children: Vec<ID>,
+    kind: LayoutKind
+}
+
+impl<ID: Hash+Clone> LayoutData<ID> {
+
+    fn solve(&self, size: (u32, u32)) -> Vec<Geometry> {
+        match &self.kind {
+            &LayoutKind::Box{orientation, margin, spacing, ref tracks} => solve_box(size, margin, spacing, orientation, tracks),
+            &LayoutKind::Grid{margin, spacing, ref columns, ref rows, ref cells} => solve_grid(size, margin, spacing, columns, rows, cells),
+            &LayoutKind::Anchor{reference, ref items} => solve_anchor(reference, size, items)
+        }
+    }
+
+    /// Return the client size of the parent, or `None` if the parent was removed from the ui
+    fn parent_size(&self, ui: &Ui<ID>) -> Option<(u32, u32)> {
+        use low::window_helper::get_window_size;
+        match ui.handle_of(&self.parent) {
+            Ok(AnyHandle::HWND(h)) => Some(unsafe{ get_window_size(h) }),
+            _ => None
+        }
+    }
+
+    /// Move and resize the children. Children that are not yet in the ui are skipped.
+    fn apply(&self, ui: &Ui<ID>) {
+        use low::window_helper::{set_window_position, set_window_size};
+
+        let size = match self.parent_size(ui) {
+            Some(s) => s,
+            None => { return; }
+        };
+
+        for (id, ((x, y), (w, h))) in self.children.iter().zip(self.solve(size)) {
+            if let Ok(AnyHandle::HWND(handle)) = ui.handle_of(id) {
+                unsafe{
+                    set_window_position(handle, x, y);
+                    set_window_size(handle, w, h, false);
+                }
+            }
+        }
+    }
+}
+
+fn build_layout<ID: Hash+Clone+'static>(ui: &Ui<ID>, parent: &ID, children: Vec<ID>, kind: LayoutKind) -> Result<Box<Resource>, Error> {
+    use low::window_helper::{handle_of_window, get_window_size};
+
+    let parent_handle = match handle_of_window(ui, parent, "The parent of a layout must be a window-like control.") {
+        Ok(h) => h,
+        Err(e) => { return Err(e); }
+    };
+
+    let kind = match kind {
+        LayoutKind::Anchor{items, ..} => LayoutKind::Anchor{ reference: unsafe{ get_window_size(parent_handle) }, items: items },
+        k => k
+    };
+
+    let data = Rc::new(LayoutData{ parent: parent.clone(), children: children, kind: kind });
+    data.apply(ui);
+
+    Ok( Box::new(Layout{ data: data }) )
+}
+
+/// Bind the callback that fits the children of the layout `id` when its parent is resized
+fn bind_layout<ID: Hash+Clone+'static>(ui: &Ui<ID>, id: &ID) {
+    let (parent, weak_data) = match ui.get::<Layout<ID>>(id) {
+        Ok(layout) => (layout.data.parent.clone(), Rc::downgrade(&layout.data)),
+        Err(_) => { return; }
+    };
+
+    // The callback does not keep the layout alive. Once the layout is unpacked, the callback unbinds itself.
+    let cb_id = id.clone();
+    ui.bind(&parent, id, Event::Resized, move |ui, parent, _, _| {
+        match weak_data.upgrade() {
+            Some(data) => data.apply(ui),
+            None => ui.unbind(parent, &cb_id, Event::Resized)
+        }
+    });
+}
+
+/**
+    A layout resource. Created from a `BoxLayoutT`, a `GridLayoutT` or an `AnchorLayoutT`.
+*/
+pub struct Layout<ID: Hash+Clone+'static> {
+    data: Rc<LayoutData<ID>>
+}
+
+impl<ID: Hash+Clone+'static> Layout<ID> {
+
+    /// Move and resize the children of the layout to fit the current size of the parent.
+    /// Useful after packing children that did not exist when the layout was created.
+    pub fn fit(&self, ui: &Ui<ID>) {
+        self.data.apply(ui);
+    }
+
+    /// Return the ID of the children of the layout
+    pub fn children(&self) -> &[ID] {
+        &self.data.children
+    }
+
+    /// Return the position and size each child would have if the parent client area was `size`
+    pub fn geometry(&self, size: (u32, u32)) -> Vec<(ID, Geometry)> {
+        self.data.children.iter().cloned().zip(self.data.solve(size)).collect()
+    }
+}
+
+impl<ID: Hash+Clone+'static> Resource for Layout<ID> {
+    fn handle(&self) -> AnyHandle {
+        let ptr = &*self.data as *const LayoutData<ID> as usize;
+        AnyHandle::Custom(TypeId::of::<Layout<ID>>(), ptr)
+    }
+}
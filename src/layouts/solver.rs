@@ -0,0 +1,237 @@
+/*!
+    Pure geometry solvers used by the layouts. These functions do not touch any window,
+    they only compute the position and the size of the children of a layout.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::cmp;
+
+use defs::{Orientation, LayoutTrack, GridCell, Dock, AnchorItem, ANCHOR_LEFT, ANCHOR_TOP, ANCHOR_RIGHT, ANCHOR_BOTTOM};
+
+/// The position and the size of a layout child
+pub type Geometry = ((i32, i32), (u32, u32));
+
+/// Margins around the content of a layout: (left, top, right, bottom)
+pub type Margin = (u32, u32, u32, u32);
+
+#[inline(always)]
+fn clamp_track(t: &LayoutTrack, v: u64) -> u64 {
+    let v = cmp::max(v, t.min as u64);
+    match t.max {
+        Some(max) => cmp::min(v, cmp::max(max, t.min) as u64),
+        None => v
+    }
+}
+
+/**
+    Share `total` between `tracks`. `spacing` is inserted between each track.
+
+    The remaining space is shared according to the tracks weight. Tracks that would break their `min` or `max`
+    constraint are frozen at that size and the remaining space is shared again between the other tracks.
+    If the available space is too small, the tracks keep their minimum size and overflow.
+*/
+pub fn distribute(total: u32, spacing: u32, tracks: &[LayoutTrack]) -> Vec<u32> {
+    let count = tracks.len();
+    if count == 0 { return Vec::new(); }
+
+    let spacing_total = (spacing as u64) * ((count - 1) as u64);
+    let available = (total as u64).saturating_sub(spacing_total);
+
+    let mut sizes: Vec<u64> = vec![0; count];
+    let mut frozen: Vec<bool> = vec![false; count];
+
+    // Tracks without weight keep their minimum size
+    for (i, t) in tracks.iter().enumerate() {
+        if t.weight == 0 {
+            sizes[i] = clamp_track(t, 0);
+            frozen[i] = true;
+        }
+    }
+
+    loop {
+        let used: u64 = (0..count).filter(|&i| frozen[i]).map(|i| sizes[i]).sum();
+        let remaining = available.saturating_sub(used);
+        let total_weight: u64 = (0..count).filter(|&i| !frozen[i]).map(|i| tracks[i].weight as u64).sum();
+        if total_weight == 0 { break; }
+
+        let shares: Vec<u64> = (0..count).map(|i| remaining * (tracks[i].weight as u64) / total_weight).collect();
+
+        // Sum of the clamping adjustments. Positive if the min constraints won, negative if the max constraints won
+        let mut adjust: i64 = 0;
+        let mut violations = false;
+        for i in (0..count).filter(|&i| !frozen[i]) {
+            let clamped = clamp_track(&tracks[i], shares[i]);
+            if clamped != shares[i] {
+                violations = true;
+                adjust += (clamped as i64) - (shares[i] as i64);
+            }
+        }
+
+        if !violations {
+            let mut leftover = remaining - (0..count).filter(|&i| !frozen[i]).map(|i| shares[i]).sum::<u64>();
+            for i in (0..count).filter(|&i| !frozen[i]) {
+                sizes[i] = shares[i];
+                if leftover > 0 && clamp_track(&tracks[i], shares[i] + 1) == shares[i] + 1 {
+                    sizes[i] += 1;
+                    leftover -= 1;
+                }
+            }
+            break;
+        }
+
+        for i in 0..count {
+            if frozen[i] { continue; }
+            let clamped = clamp_track(&tracks[i], shares[i]);
+            let freeze = (adjust > 0 && clamped > shares[i]) || (adjust < 0 && clamped < shares[i]) || (adjust == 0 && clamped != shares[i]);
+            if freeze {
+                sizes[i] = clamped;
+                frozen[i] = true;
+            }
+        }
+    }
+
+    sizes.iter().map(|&s| cmp::min(s, u32::max_value() as u64) as u32).collect()
+}
+
+#[inline(always)]
+fn content(size: (u32, u32), margin: Margin) -> (i32, i32, u32, u32) {
+    let (left, top, right, bottom) = margin;
+    let w = size.0.saturating_sub(left.saturating_add(right));
+    let h = size.1.saturating_sub(top.saturating_add(bottom));
+    (left as i32, top as i32, w, h)
+}
+
+/**
+    Compute the geometry of the children of a box layout. Children are stacked in the order of `tracks`.
+*/
+pub fn solve_box(size: (u32, u32), margin: Margin, spacing: u32, orientation: Orientation, tracks: &[LayoutTrack]) -> Vec<Geometry> {
+    let (x, y, w, h) = content(size, margin);
+
+    let main = match orientation {
+        Orientation::Horizontal => w,
+        Orientation::Vertical => h,
+    };
+
+    let mut offset = 0i32;
+    let mut geometry = Vec::with_capacity(tracks.len());
+    for s in distribute(main, spacing, tracks) {
+        geometry.push(match orientation {
+            Orientation::Horizontal => ((x + offset, y), (s, h)),
+            Orientation::Vertical => ((x, y + offset), (w, s)),
+        });
+        offset += (s + spacing) as i32;
+    }
+
+    geometry
+}
+
+#[inline(always)]
+fn span(offsets: &[i32], sizes: &[u32], spacing: u32, start: usize, span: usize) -> (i32, u32) {
+    if start >= sizes.len() { return (0, 0); }
+
+    let end = cmp::min(start + cmp::max(span, 1), sizes.len());
+    let size: u32 = sizes[start..end].iter().sum::<u32>() + spacing * ((end - start - 1) as u32);
+    (offsets[start], size)
+}
+
+/**
+    Compute the geometry of the children of a grid layout. The columns and the rows are sized with `distribute`,
+    then each child covers the cells defined in `cells`. Cells outside the grid have an empty geometry.
+*/
+pub fn solve_grid(size: (u32, u32), margin: Margin, spacing: u32, columns: &[LayoutTrack], rows: &[LayoutTrack], cells: &[GridCell]) -> Vec<Geometry> {
+    let (x, y, w, h) = content(size, margin);
+
+    let widths = distribute(w, spacing, columns);
+    let heights = distribute(h, spacing, rows);
+
+    let offsets = |start: i32, sizes: &[u32]| -> Vec<i32> {
+        let mut offset = start;
+        sizes.iter().map(|s| { let o = offset; offset += (s + spacing) as i32; o }).collect()
+    };
+
+    let cols_x = offsets(x, &widths);
+    let rows_y = offsets(y, &heights);
+
+    cells.iter().map(|c| {
+        let (cx, cw) = span(&cols_x, &widths, spacing, c.col, c.col_span);
+        let (cy, ch) = span(&rows_y, &heights, spacing, c.row, c.row_span);
+        ((cx, cy), (cw, ch))
+    }).collect()
+}
+
+#[inline(always)]
+fn anchor_axis(start: i32, length: u32, delta: i64, low: bool, high: bool) -> (i32, u32) {
+    match (low, high) {
+        (true, true) => (start, cmp::max((length as i64) + delta, 0) as u32),
+        (false, true) => (((start as i64) + delta) as i32, length),
+        (true, false) => (start, length),
+        (false, false) => (((start as i64) + delta/2) as i32, length),
+    }
+}
+
+/**
+    Compute the geometry of the children of an anchor layout. `reference` is the size of the parent
+    when the anchored positions were defined and `size` is the current size of the parent.
+
+    Docked children are placed in order, each one taking its side of the space left by the previous ones.
+*/
+pub fn solve_anchor(reference: (u32, u32), size: (u32, u32), items: &[AnchorItem]) -> Vec<Geometry> {
+    let dx = (size.0 as i64) - (reference.0 as i64);
+    let dy = (size.1 as i64) - (reference.1 as i64);
+
+    // Space left for the docked children
+    let (mut l, mut t, mut r, mut b) = (0u32, 0u32, size.0, size.1);
+
+    items.iter().map(|item| {
+        match item {
+            &AnchorItem::Anchor{position, size, anchor} => {
+                let (x, w) = anchor_axis(position.0, size.0, dx, anchor & ANCHOR_LEFT != 0, anchor & ANCHOR_RIGHT != 0);
+                let (y, h) = anchor_axis(position.1, size.1, dy, anchor & ANCHOR_TOP != 0, anchor & ANCHOR_BOTTOM != 0);
+                ((x, y), (w, h))
+            },
+            &AnchorItem::Dock{dock, size} => {
+                let (free_w, free_h) = (r - l, b - t);
+                match dock {
+                    Dock::Left => {
+                        let w = cmp::min(size, free_w);
+                        let g = ((l as i32, t as i32), (w, free_h));
+                        l += w;
+                        g
+                    },
+                    Dock::Right => {
+                        let w = cmp::min(size, free_w);
+                        r -= w;
+                        ((r as i32, t as i32), (w, free_h))
+                    },
+                    Dock::Top => {
+                        let h = cmp::min(size, free_h);
+                        let g = ((l as i32, t as i32), (free_w, h));
+                        t += h;
+                        g
+                    },
+                    Dock::Bottom => {
+                        let h = cmp::min(size, free_h);
+                        b -= h;
+                        ((l as i32, b as i32), (free_w, h))
+                    },
+                    Dock::Fill => ((l as i32, t as i32), (free_w, free_h))
+                }
+            }
+        }
+    }).collect()
+}
@@ -17,14 +17,15 @@
     You should have received a copy of the GNU General Public License
     along with this program.  If not, see <http://www.gnu.org/licenses/>.
 */
-#![cfg(windows)]
+#[cfg(windows)] extern crate winapi;
+#[cfg(windows)] extern crate user32;
+#[cfg(windows)] extern crate kernel32;
+#[cfg(windows)] extern crate comctl32;
+#[cfg(windows)] extern crate gdi32;
+#[cfg(windows)] extern crate ole32;
 
-extern crate winapi;
-extern crate user32;
-extern crate kernel32;
-extern crate comctl32;
-extern crate gdi32;
-extern crate ole32;
+// On the other platforms, the few WINAPI types used by the platform independent modules are defined by nwg
+#[cfg(not(windows))] use low::types as winapi;
 
 mod low;
 mod defs;
@@ -32,9 +33,14 @@ mod error;
 mod events;
 mod controls;
 mod resources;
+mod layouts;
+mod loader;
+mod bindings;
+mod executor;
 mod ui;
 
 pub mod templates;
+#[cfg(windows)] pub mod clipboard;
 
 pub mod custom {
     /*!
@@ -44,10 +50,37 @@ pub mod custom {
     pub use resources::{ResourceT, Resource};
     pub use low::window_helper::{build_window, build_sysclass, SysclassParams, WindowParams, set_window_long, get_window_long,
     get_window_text, set_window_text, get_window_visibility, set_window_visibility, get_window_position, set_window_position,
-    get_window_size, set_window_size, get_window_enabled, set_window_enabled};
+    get_window_size, set_window_size, get_window_enabled, set_window_enabled, destroy_window, send_message, post_message,
+    def_window_proc, def_subclass_proc};
+    pub use events::{EventDecoders, CommandDecoder, NotifyDecoder};
+    pub use controls::button::decode_button_command;
+    pub use controls::textinput::decode_edit_command;
 
 }
 
+pub mod geometry {
+    /*!
+        Geometry solvers used by the layouts. They do not require a window.
+    */
+    pub use layouts::solver::{Geometry, Margin, distribute, solve_box, solve_grid, solve_anchor};
+}
+
+pub mod backend {
+    /*!
+        System backends. Use `Ui::with_backend` with a `MockBackend` to run a Ui without creating system windows.
+    */
+    pub use low::backend::{Backend, MockBackend, MockWindow, MockMessage, RemotePoster};
+    #[cfg(windows)] pub use low::backend::{SystemBackend, SystemPoster};
+}
+
+pub mod tasks {
+    /*!
+        Futures that can be awaited by the local tasks spawned with `Ui::spawn_local`
+    */
+    pub use executor::{BlockingTask, TaskSender, TaskReceiver, Recv, channel};
+    #[cfg(windows)] pub use executor::{Delay, FileDialogRun, sleep, run_file_dialog};
+}
+
 pub mod constants {
     /*!
         Controls constants
@@ -58,10 +91,19 @@ pub mod constants {
 
 pub use error::{Error, SystemError};
 pub use events::{EventCallback, Event, EventArgs};
-pub use low::other_helper::{message, simple_message, fatal_message, error_message};
-pub use controls::{WindowT, Window, MenuT, Menu, MenuItemT, MenuItem, ButtonT, Button, ListBoxT, ListBox, CheckBoxT, CheckBox,
- RadioButtonT, RadioButton, TimerT, Timer, LabelT, Label, ComboBoxT, ComboBox, SeparatorT, Separator, TextInputT, TextInput,
- FileDialogT, FileDialog, CanvasT, Canvas, CanvasRenderer, TextBoxT, TextBox, GroupBoxT, GroupBox, ProgressBarT, ProgressBar,
- DatePickerT, DatePicker};
-pub use resources::{FontT, Font};
-pub use ui::{Ui, dispatch_events, exit};
\ No newline at end of file
+#[cfg(windows)] pub use low::other_helper::{message, simple_message, fatal_message, error_message};
+pub use controls::{WindowT, Window, ButtonT, Button, ListBoxT, ListBox, CheckBoxT, CheckBox, RadioButtonT, RadioButton,
+ LabelT, Label, ComboBoxT, ComboBox, TextInputT, TextInput, TextBoxT, TextBox, GroupBoxT, GroupBox, ProgressBarT, ProgressBar,
+ DatePickerT, DatePicker, NumericInputT, NumericInput, ListViewT, ListView, ListViewColumn, TextColumn,
+ TreeViewT, TreeView, TreeNode, TreeViewLoader, TabsContainerT, TabsContainer, TabT, Tab, Path, PathBuilder, PathSegment, Figure,
+ TextLayout, TextMetrics, HitTestMetrics, Renderer, RecordingRenderer, DrawCommand};
+#[cfg(windows)]
+pub use controls::{DialogT, Dialog, MenuT, Menu, PopupMenuT, PopupMenu, MenuItemT, MenuItem, SeparatorT, Separator, TimerT, Timer,
+ FileDialogT, FileDialog, CanvasT, Canvas, rgba_to_pbgra, CanvasRenderer, AcceleratorT, Accelerator, Shortcut, TrayIconT, TrayIcon};
+#[cfg(windows)] pub use resources::{FontT, Font, ImageT, Image, ImageSource, set_control_icon, set_control_cursor};
+pub use layouts::{BoxLayoutT, GridLayoutT, AnchorLayoutT, Layout};
+pub use loader::UiDescription;
+pub use bindings::{Observable, Property, BindingT, Binding, TextInputText, TextBoxText, CheckBoxChecked, ListBoxSelection,
+ ProgressBarValue, NumericInputValue};
+pub use ui::{Ui, UiSender, LocalUi, exit};
+#[cfg(windows)] pub use ui::dispatch_events;
@@ -0,0 +1,297 @@
+/*!
+    A small JSON parser that remembers where every value was written. The positions are used
+    by the ui descriptions to report errors.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/**
+    A position in a document. Lines and columns start at 1.
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/**
+    A JSON value. Objects keep their keys in the order they were written.
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Node>),
+    Object(Vec<(Node, Node)>)
+}
+
+/**
+    A JSON value and the position of its first character
+*/
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub value: Value,
+    pub position: Position
+}
+
+impl Node {
+
+    /// Return the string value of the node, if the node is a string
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            &Value::String(ref s) => Some(s.as_str()),
+            _ => None
+        }
+    }
+
+}
+
+/// A parsing error and where it happened
+pub type ParseError = (Position, String);
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+    column: usize
+}
+
+impl<'a> Parser<'a> {
+
+    fn position(&self) -> Position {
+        Position{ line: self.line, column: self.column }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().cloned()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        match c {
+            Some('\n') => { self.line += 1; self.column = 1; },
+            Some(_) => { self.column += 1; },
+            None => {}
+        }
+        c
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() { break; }
+            self.bump();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        let pos = self.position();
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err((pos, format!("expected '{}', found '{}'", expected, c))),
+            None => Err((pos, format!("expected '{}', found the end of the document", expected)))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node, ParseError> {
+        self.skip_whitespace();
+
+        let pos = self.position();
+        let value = match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string().map(|s| Value::String(s)),
+            Some(c) if c == '-' || c.is_digit(10) => self.parse_number(),
+            Some(c) if c.is_alphabetic() => self.parse_literal(),
+            Some(c) => Err((pos, format!("unexpected character '{}'", c))),
+            None => Err((pos, "unexpected end of the document".to_string()))
+        };
+
+        value.map(|v| Node{ value: v, position: pos })
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, ParseError> {
+        let pos = self.position();
+        let mut word = String::new();
+        while let Some(c) = self.peek() {
+            if !c.is_alphanumeric() { break; }
+            word.push(c);
+            self.bump();
+        }
+
+        match word.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "null" => Ok(Value::Null),
+            _ => Err((pos, format!("unknown literal '{}'", word)))
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ParseError> {
+        let pos = self.position();
+        let mut number = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_digit(10) || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E') { break; }
+            number.push(c);
+            self.bump();
+        }
+
+        match number.parse::<f64>() {
+            Ok(n) => Ok(Value::Number(n)),
+            Err(_) => Err((pos, format!("invalid number '{}'", number)))
+        }
+    }
+
+    fn parse_hex(&mut self) -> Result<u32, ParseError> {
+        let pos = self.position();
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.bump().and_then(|c| c.to_digit(16)) {
+                Some(d) => { code = code * 16 + d; },
+                None => { return Err((pos, "invalid unicode escape".to_string())); }
+            }
+        }
+        Ok(code)
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        try!(self.expect('"'));
+
+        let mut text = String::new();
+        loop {
+            let pos = self.position();
+            match self.bump() {
+                Some('"') => { return Ok(text); },
+                Some('\\') => {
+                    let c = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let mut code = try!(self.parse_hex());
+
+                            // Characters outside the basic plane are written as a surrogate pair
+                            if code >= 0xD800 && code < 0xDC00 {
+                                try!(self.expect('\\'));
+                                try!(self.expect('u'));
+                                let low = try!(self.parse_hex());
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+
+                            match ::std::char::from_u32(code) {
+                                Some(c) => c,
+                                None => { return Err((pos, "invalid unicode escape".to_string())); }
+                            }
+                        },
+                        _ => { return Err((pos, "invalid escape sequence".to_string())); }
+                    };
+                    text.push(c);
+                },
+                Some(c) => { text.push(c); },
+                None => { return Err((pos, "unterminated string".to_string())); }
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ParseError> {
+        try!(self.expect('['));
+
+        let mut values = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.bump();
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(try!(self.parse_value()));
+            self.skip_whitespace();
+
+            let pos = self.position();
+            match self.bump() {
+                Some(',') => {},
+                Some(']') => { return Ok(Value::Array(values)); },
+                Some(c) => { return Err((pos, format!("expected ',' or ']', found '{}'", c))); },
+                None => { return Err((pos, "unterminated array".to_string())); }
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ParseError> {
+        try!(self.expect('{'));
+
+        let mut members: Vec<(Node, Node)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.bump();
+            return Ok(Value::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key_pos = self.position();
+            let key = try!(self.parse_string());
+            if members.iter().any(|&(ref k, _)| k.as_str() == Some(&key)) {
+                return Err((key_pos, format!("duplicate key \"{}\"", key)));
+            }
+
+            self.skip_whitespace();
+            try!(self.expect(':'));
+            let value = try!(self.parse_value());
+            members.push((Node{ value: Value::String(key), position: key_pos }, value));
+
+            self.skip_whitespace();
+            let pos = self.position();
+            match self.bump() {
+                Some(',') => {},
+                Some('}') => { return Ok(Value::Object(members)); },
+                Some(c) => { return Err((pos, format!("expected ',' or '}}', found '{}'", c))); },
+                None => { return Err((pos, "unterminated object".to_string())); }
+            }
+        }
+    }
+
+}
+
+/**
+    Parse a JSON document. Return the position and the description of the first error found.
+*/
+pub fn parse(text: &str) -> Result<Node, ParseError> {
+    let mut parser = Parser{ chars: text.chars().peekable(), line: 1, column: 1 };
+    let node = try!(parser.parse_value());
+
+    parser.skip_whitespace();
+    let pos = parser.position();
+    match parser.bump() {
+        Some(c) => Err((pos, format!("unexpected character '{}' after the document", c))),
+        None => Ok(node)
+    }
+}
@@ -0,0 +1,730 @@
+/*!
+    Ui descriptions loaded at runtime. A description is a JSON document that lists the values, the resources
+    and the controls of a Ui. It is parsed and validated without creating any window and can then be packed
+    into a `Ui<String>`.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+mod json;
+
+use std::collections::{HashSet, HashMap};
+
+use error::Error;
+#[cfg(windows)] use ui::Ui;
+#[cfg(windows)]
+use defs::{HTextAlign, CheckState, ProgressBarState, ImageType, FONT_WEIGHT_NORMAL, FONT_DECO_NORMAL, FONT_DECO_ITALIC, FONT_DECO_UNDERLINE,
+ FONT_DECO_STRIKEOUT};
+#[cfg(windows)]
+use controls::{WindowT, ButtonT, LabelT, CheckBoxT, RadioButtonT, TextInputT, TextBoxT, GroupBoxT, ListBoxT, ComboBoxT, ProgressBarT,
+ NumericInputT, TabsContainerT, TabT, MenuT, MenuItemT, SeparatorT, TimerT, AcceleratorT};
+#[cfg(windows)] use resources::{FontT, ImageT, ImageSource};
+use self::json::{Node, Value};
+
+/**
+    The expected type of a property value
+*/
+#[derive(Clone, Copy, PartialEq)]
+enum PropertyType {
+    Text,
+    Texts,
+    Bool,
+    BoolPair,
+    Int,
+    IntPair,
+    UInt,
+    UIntPair,
+    Parent,
+    Control,
+    Font,
+    Image,
+    Align,
+    Check,
+    BarState,
+    Decoration,
+    ImageKind
+}
+
+impl PropertyType {
+    fn describe(&self) -> &'static str {
+        match *self {
+            PropertyType::Text => "a string",
+            PropertyType::Texts => "an array of strings",
+            PropertyType::Bool => "a boolean",
+            PropertyType::BoolPair => "an array of two booleans",
+            PropertyType::Int => "an integer",
+            PropertyType::IntPair => "an array of two integers",
+            PropertyType::UInt => "a positive integer",
+            PropertyType::UIntPair => "an array of two positive integers",
+            PropertyType::Parent | PropertyType::Control => "the id of a control",
+            PropertyType::Font => "the id of a Font resource",
+            PropertyType::Image => "the id of an Image resource",
+            PropertyType::Align => "one of \"Left\", \"Center\" or \"Right\"",
+            PropertyType::Check => "one of \"Checked\", \"Unchecked\" or \"Indeterminate\"",
+            PropertyType::BarState => "one of \"Normal\", \"Paused\" or \"Error\"",
+            PropertyType::Decoration => "an array containing \"Italic\", \"Underline\" or \"Strikeout\"",
+            PropertyType::ImageKind => "one of \"Bitmap\", \"Icon\" or \"Cursor\""
+        }
+    }
+}
+
+/**
+    A property accepted by a type of control or resource
+*/
+struct Property {
+    name: &'static str,
+    ty: PropertyType,
+    required: bool
+}
+
+const GEOMETRY: &'static [Property] = &[
+    Property{ name: "position", ty: PropertyType::IntPair, required: false },
+    Property{ name: "size", ty: PropertyType::UIntPair, required: false },
+    Property{ name: "visible", ty: PropertyType::Bool, required: false },
+    Property{ name: "disabled", ty: PropertyType::Bool, required: false },
+];
+
+const PARENT: &'static [Property] = &[
+    Property{ name: "parent", ty: PropertyType::Parent, required: true },
+];
+
+const FONT: &'static [Property] = &[
+    Property{ name: "font", ty: PropertyType::Font, required: false },
+];
+
+/**
+    Return the properties accepted by a type of control, or None if the type is not supported
+*/
+fn control_schema(kind: &str) -> Option<Vec<&'static Property>> {
+    use self::PropertyType::*;
+
+    let child = || GEOMETRY.iter().chain(PARENT.iter()).chain(FONT.iter());
+    let own: &'static [Property] = match kind {
+        "Window" => &[
+            Property{ name: "title", ty: Text, required: false }, Property{ name: "resizable", ty: Bool, required: false },
+            Property{ name: "exit_on_close", ty: Bool, required: false }, Property{ name: "accept_files", ty: Bool, required: false },
+            Property{ name: "icon", ty: Image, required: false }
+        ],
+        "Button" => &[ Property{ name: "text", ty: Text, required: false } ],
+        "Label" | "GroupBox" => &[ Property{ name: "text", ty: Text, required: false }, Property{ name: "align", ty: Align, required: false } ],
+        "CheckBox" => &[
+            Property{ name: "text", ty: Text, required: false }, Property{ name: "checkstate", ty: Check, required: false },
+            Property{ name: "tristate", ty: Bool, required: false }
+        ],
+        "RadioButton" => &[ Property{ name: "text", ty: Text, required: false }, Property{ name: "checkstate", ty: Check, required: false } ],
+        "TextInput" => &[
+            Property{ name: "text", ty: Text, required: false }, Property{ name: "readonly", ty: Bool, required: false },
+            Property{ name: "password", ty: Bool, required: false }, Property{ name: "placeholder", ty: Text, required: false },
+            Property{ name: "limit", ty: UInt, required: false }
+        ],
+        "TextBox" => &[
+            Property{ name: "text", ty: Text, required: false }, Property{ name: "readonly", ty: Bool, required: false },
+            Property{ name: "limit", ty: UInt, required: false }, Property{ name: "scrollbars", ty: BoolPair, required: false }
+        ],
+        "ListBox" => &[
+            Property{ name: "collection", ty: Texts, required: false }, Property{ name: "readonly", ty: Bool, required: false },
+            Property{ name: "multi_select", ty: Bool, required: false }
+        ],
+        "ComboBox" => &[ Property{ name: "collection", ty: Texts, required: false }, Property{ name: "placeholder", ty: Text, required: false } ],
+        "ProgressBar" => &[
+            Property{ name: "range", ty: UIntPair, required: false }, Property{ name: "value", ty: UInt, required: false },
+            Property{ name: "step", ty: UInt, required: false }, Property{ name: "state", ty: BarState, required: false },
+            Property{ name: "vertical", ty: Bool, required: false }
+        ],
+        "NumericInput" => &[
+            Property{ name: "value", ty: Int, required: false }, Property{ name: "range", ty: IntPair, required: false },
+            Property{ name: "step", ty: Int, required: false }, Property{ name: "decimals", ty: UInt, required: false },
+            Property{ name: "readonly", ty: Bool, required: false }
+        ],
+        "TabsContainer" => &[],
+        "Tab" => &[ Property{ name: "text", ty: Text, required: false } ],
+        "Menu" => &[ Property{ name: "text", ty: Text, required: false }, Property{ name: "disabled", ty: Bool, required: false } ],
+        "MenuItem" => &[
+            Property{ name: "text", ty: Text, required: false }, Property{ name: "disabled", ty: Bool, required: false },
+            Property{ name: "checked", ty: Bool, required: false }, Property{ name: "radio", ty: Bool, required: false },
+            Property{ name: "default", ty: Bool, required: false }, Property{ name: "image", ty: Image, required: false }
+        ],
+        "Separator" => &[],
+        "Timer" => &[ Property{ name: "interval", ty: UInt, required: false } ],
+        "Accelerator" => &[ Property{ name: "shortcut", ty: Text, required: true }, Property{ name: "item", ty: Control, required: false } ],
+        _ => { return None; }
+    };
+
+    let schema: Vec<&'static Property> = match kind {
+        "Window" => GEOMETRY.iter().chain(own.iter()).collect(),
+        "Timer" => own.iter().collect(),
+        "Tab" | "Menu" | "MenuItem" | "Separator" | "Accelerator" => PARENT.iter().chain(own.iter()).collect(),
+        "ProgressBar" => GEOMETRY.iter().chain(PARENT.iter()).chain(own.iter()).collect(),
+        _ => child().chain(own.iter()).collect()
+    };
+
+    Some(schema)
+}
+
+/**
+    Return the properties accepted by a type of resource, or None if the type is not supported
+*/
+fn resource_schema(kind: &str) -> Option<Vec<&'static Property>> {
+    use self::PropertyType::*;
+
+    const FONT_PROPERTIES: &'static [Property] = &[
+        Property{ name: "family", ty: Text, required: false }, Property{ name: "size", ty: Int, required: false },
+        Property{ name: "weight", ty: Int, required: false }, Property{ name: "decoration", ty: Decoration, required: false }
+    ];
+
+    const IMAGE_PROPERTIES: &'static [Property] = &[
+        Property{ name: "file", ty: Text, required: false }, Property{ name: "resource", ty: Text, required: false },
+        Property{ name: "image_type", ty: ImageKind, required: false }, Property{ name: "size", ty: UIntPair, required: false }
+    ];
+
+    match kind {
+        "Font" => Some(FONT_PROPERTIES.iter().collect()),
+        "Image" => Some(IMAGE_PROPERTIES.iter().collect()),
+        _ => None
+    }
+}
+
+/**
+    A control or a resource of a description. The properties were validated against the schema of its type.
+*/
+struct Element {
+    id: String,
+    kind: String,
+    #[cfg_attr(not(windows), allow(dead_code))]
+    properties: Vec<(String, Node)>
+}
+
+#[cfg(windows)]
+impl Element {
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.properties.iter().find(|&&(ref n, _)| n == name).map(|&(_, ref v)| &v.value)
+    }
+
+    fn text(&self, name: &str, default: &str) -> String {
+        match self.get(name) {
+            Some(&Value::String(ref s)) => s.clone(),
+            _ => default.to_string()
+        }
+    }
+
+    fn optional_text(&self, name: &str) -> Option<String> {
+        match self.get(name) {
+            Some(&Value::String(ref s)) => Some(s.clone()),
+            _ => None
+        }
+    }
+
+    fn texts(&self, name: &str) -> Vec<String> {
+        match self.get(name) {
+            Some(&Value::Array(ref a)) => a.iter().filter_map(|n| n.as_str()).map(|s| s.to_string()).collect(),
+            _ => Vec::new()
+        }
+    }
+
+    fn boolean(&self, name: &str, default: bool) -> bool {
+        match self.get(name) {
+            Some(&Value::Bool(b)) => b,
+            _ => default
+        }
+    }
+
+    fn int(&self, name: &str, default: i64) -> i64 {
+        match self.get(name) {
+            Some(&Value::Number(n)) => n as i64,
+            _ => default
+        }
+    }
+
+    fn pair(&self, name: &str, default: (i64, i64)) -> (i64, i64) {
+        match self.get(name) {
+            Some(&Value::Array(ref a)) => match (&a[0].value, &a[1].value) {
+                (&Value::Bool(x), &Value::Bool(y)) => (x as i64, y as i64),
+                (&Value::Number(x), &Value::Number(y)) => (x as i64, y as i64),
+                _ => default
+            },
+            _ => default
+        }
+    }
+
+    fn align(&self) -> HTextAlign {
+        match self.text("align", "Left").as_str() {
+            "Center" => HTextAlign::Center,
+            "Right" => HTextAlign::Right,
+            _ => HTextAlign::Left
+        }
+    }
+
+    fn checkstate(&self) -> CheckState {
+        match self.text("checkstate", "Unchecked").as_str() {
+            "Checked" => CheckState::Checked,
+            "Indeterminate" => CheckState::Indeterminate,
+            _ => CheckState::Unchecked
+        }
+    }
+
+}
+
+/**
+    A Ui description loaded from a JSON document.
+
+    The document is an object with three optional sections. Each section is an object that maps an id to its value.  
+    • `values`: Strings, booleans or numbers packed with `Ui::pack_value`. Integers are packed as `i64` and other numbers as `f64`  
+    • `resources`: Resource templates. The `type` key names the template (ex: `"Font"`), the other keys are the template members  
+    • `controls`: Control templates. The `type` key names the template (ex: `"Button"`), the other keys are the template members  
+
+    Controls are packed in the order they are written, so a parent must be written before its children.
+    The missing members use the same defaults as the `nwg_*` template macros. Enum members are written using the variant name (ex: `"align": "Center"`),
+    tuples are written as arrays (ex: `"size": [100, 30]`) and the font decoration is a list of names (ex: `["Italic", "Underline"]`).
+
+    Supported controls:  
+    Window, Button, Label, CheckBox, RadioButton, TextInput, TextBox, GroupBox, ListBox, ComboBox, ProgressBar,
+    NumericInput, TabsContainer, Tab, Menu, MenuItem, Separator, Timer, Accelerator  
+
+    Supported resources:  
+    Font, Image. An image is loaded from its `file` or, if it has no file, from the `resource` of the executable with this name.
+    The `font` of a control is the id of a Font resource. The `icon` of a window and the `image` of a menu item are the ids of Image resources  
+
+    Usage:  
+    ```text
+    {
+        "resources": { "Title": { "type": "Font", "family": "Arial", "size": 22 } },
+        "controls": {
+            "MainWindow": { "type": "Window", "title": "Hello", "size": [300, 115] },
+            "Name": { "type": "TextInput", "parent": "MainWindow", "position": [5, 15], "size": [280, 35], "font": "Title" }
+        }
+    }
+    ```
+*/
+pub struct UiDescription {
+    values: Vec<(String, Node)>,
+    resources: Vec<Element>,
+    controls: Vec<Element>
+}
+
+impl UiDescription {
+
+    /**
+        Parse and validate a JSON ui description. No windows are created.
+
+        Errors are prefixed by the line and column of the faulty value. A reference to a parent that was not
+        written before the control returns `Error::BadParent`. Any other error (syntax error, unknown type or property,
+        bad property value, unknown font or menu item) returns `Error::BadResource`.
+    */
+    pub fn from_json(text: &str) -> Result<UiDescription, Error> {
+        let root = match json::parse(text) {
+            Ok(root) => root,
+            Err((pos, msg)) => { return Err(Error::BadResource(format!("{}: {}", pos, msg))); }
+        };
+
+        let sections = match root.value {
+            Value::Object(sections) => sections,
+            _ => { return Err(bad_resource(&root, "a ui description must be an object")); }
+        };
+
+        let mut ids: HashSet<String> = HashSet::new();
+        let mut description = UiDescription{ values: Vec::new(), resources: Vec::new(), controls: Vec::new() };
+
+        // Resources are packed first, so the controls can use resources written anywhere in the document.
+        // The type of each resource is kept to check the properties that need a resource of a given type.
+        let mut resources_ids: HashMap<String, String> = HashMap::new();
+        for &(ref key, ref section) in sections.iter() {
+            if key.as_str() == Some("resources") {
+                for &(ref id, ref resource) in try!(members(section)).iter() {
+                    let kind = members(resource).ok()
+                        .and_then(|m| m.into_iter().find(|&(ref k, _)| k.as_str() == Some("type")))
+                        .and_then(|(_, kind)| kind.as_str().map(|k| k.to_string()))
+                        .unwrap_or(String::new());
+                    resources_ids.insert(id.as_str().unwrap().to_string(), kind);
+                }
+            }
+        }
+
+        for (key, section) in sections.into_iter() {
+            let name = key.as_str().unwrap().to_string();
+            if !["values", "resources", "controls"].contains(&name.as_str()) {
+                return Err(bad_resource(&key, &format!("unknown section \"{}\"", name)));
+            }
+
+            for (id_node, value) in try!(members(&section)).into_iter() {
+                let id = id_node.as_str().unwrap().to_string();
+                if !ids.insert(id.clone()) {
+                    return Err(bad_resource(&id_node, &format!("the id \"{}\" is used more than once", id)));
+                }
+
+                match name.as_str() {
+                    "values" => {
+                        match value.value {
+                            Value::String(_) | Value::Bool(_) | Value::Number(_) => {},
+                            _ => { return Err(bad_resource(&value, &format!("the value \"{}\" must be a string, a boolean or a number", id))); }
+                        }
+                        description.values.push((id, value));
+                    },
+                    "resources" => {
+                        let element = try!(validate_element(id, value, false, &description.controls, &resources_ids));
+                        description.resources.push(element);
+                    },
+                    "controls" => {
+                        let element = try!(validate_element(id, value, true, &description.controls, &resources_ids));
+                        description.controls.push(element);
+                    },
+                    _ => unreachable!()
+                }
+            }
+        }
+
+        Ok(description)
+    }
+
+    /// Return the ids of the values in the order they will be packed
+    pub fn values(&self) -> Vec<&str> {
+        self.values.iter().map(|&(ref id, _)| id.as_str()).collect()
+    }
+
+    /// Return the id and the type of the resources in the order they will be packed
+    pub fn resources(&self) -> Vec<(&str, &str)> {
+        self.resources.iter().map(|e| (e.id.as_str(), e.kind.as_str())).collect()
+    }
+
+    /// Return the id and the type of the controls in the order they will be packed
+    pub fn controls(&self) -> Vec<(&str, &str)> {
+        self.controls.iter().map(|e| (e.id.as_str(), e.kind.as_str())).collect()
+    }
+
+    /**
+        Pack the values, the resources and the controls of the description into a Ui and commit the changes.
+        The errors returned are the errors of `Ui::commit`.
+    */
+    #[cfg(windows)]
+    pub fn pack(&self, ui: &Ui<String>) -> Result<(), Error> {
+        for &(ref id, ref node) in self.values.iter() {
+            match node.value {
+                Value::String(ref s) => ui.pack_value(id, s.clone()),
+                Value::Bool(b) => ui.pack_value(id, b),
+                Value::Number(n) if n.fract() == 0.0 => ui.pack_value(id, n as i64),
+                Value::Number(n) => ui.pack_value(id, n),
+                _ => unreachable!()
+            }
+        }
+
+        for r in self.resources.iter() {
+            pack_resource(ui, r);
+        }
+
+        for c in self.controls.iter() {
+            pack_control(ui, c);
+        }
+
+        ui.commit()
+    }
+
+}
+
+fn bad_resource(node: &Node, msg: &str) -> Error {
+    Error::BadResource(format!("{}: {}", node.position, msg))
+}
+
+/**
+    Return the members of a section or of an element. Fails if the node is not an object.
+*/
+fn members(node: &Node) -> Result<Vec<(Node, Node)>, Error> {
+    match node.value {
+        Value::Object(ref m) => Ok(m.clone()),
+        _ => Err(bad_resource(node, "expected an object"))
+    }
+}
+
+/**
+    Check that a control or a resource has a known type, known properties and valid property values.
+    `controls` are the controls written before the element and `resources` are all the resources of the document.
+*/
+fn validate_element(id: String, node: Node, control: bool, controls: &[Element], resources: &HashMap<String, String>) -> Result<Element, Error> {
+    let mut members = try!(members(&node));
+
+    let kind = match members.iter().position(|&(ref k, _)| k.as_str() == Some("type")) {
+        Some(i) => {
+            let (_, kind) = members.remove(i);
+            match kind.as_str() {
+                Some(k) => (k.to_string(), kind.clone()),
+                None => { return Err(bad_resource(&kind, "the type must be a string")); }
+            }
+        },
+        None => { return Err(bad_resource(&node, &format!("\"{}\" does not have a type", id))); }
+    };
+
+    let (kind, kind_node) = kind;
+    let schema = if control { control_schema(&kind) } else { resource_schema(&kind) };
+    let schema = match schema {
+        Some(s) => s,
+        None => { return Err(bad_resource(&kind_node, &format!("unknown type \"{}\"", kind))); }
+    };
+
+    for &(ref name_node, ref value) in members.iter() {
+        let name = name_node.as_str().unwrap();
+        let property = match schema.iter().find(|p| p.name == name) {
+            Some(p) => p,
+            None => { return Err(bad_resource(name_node, &format!("unknown property \"{}\" for {} \"{}\"", name, kind, id))); }
+        };
+
+        if !valid_value(property.ty, value) {
+            let msg = format!("the property \"{}\" of {} \"{}\" must be {}", name, kind, id, property.ty.describe());
+            return Err(match property.ty {
+                PropertyType::Parent => Error::BadParent(format!("{}: {}", value.position, msg)),
+                _ => bad_resource(value, &msg)
+            });
+        }
+
+        let reference = value.as_str().unwrap_or("");
+        match property.ty {
+            PropertyType::Parent if !controls.iter().any(|c| c.id == reference) => {
+                return Err(Error::BadParent(format!("{}: the parent \"{}\" of {} \"{}\" must be a control written before it", value.position, reference, kind, id)));
+            },
+            PropertyType::Control if !controls.iter().any(|c| c.id == reference) => {
+                return Err(bad_resource(value, &format!("the control \"{}\" used by {} \"{}\" must be written before it", reference, kind, id)));
+            },
+            PropertyType::Font | PropertyType::Image if !resources.contains_key(reference) => {
+                return Err(bad_resource(value, &format!("unknown resource \"{}\" used by {} \"{}\"", reference, kind, id)));
+            },
+            PropertyType::Font if resources[reference] != "Font" => {
+                return Err(bad_resource(value, &format!("the resource \"{}\" used by {} \"{}\" must be a Font", reference, kind, id)));
+            },
+            PropertyType::Image if resources[reference] != "Image" => {
+                return Err(bad_resource(value, &format!("the resource \"{}\" used by {} \"{}\" must be an Image", reference, kind, id)));
+            },
+            _ => {}
+        }
+    }
+
+    for p in schema.iter().filter(|p| p.required) {
+        if !members.iter().any(|&(ref k, _)| k.as_str() == Some(p.name)) {
+            let msg = format!("{} \"{}\" requires the property \"{}\"", kind, id, p.name);
+            return Err(match p.ty {
+                PropertyType::Parent => Error::BadParent(format!("{}: {}", node.position, msg)),
+                _ => bad_resource(&node, &msg)
+            });
+        }
+    }
+
+    let properties = members.into_iter().map(|(k, v)| (k.as_str().unwrap().to_string(), v)).collect();
+    Ok(Element{ id: id, kind: kind, properties: properties })
+}
+
+/**
+    Check if the value of a property matches its type
+*/
+fn valid_value(ty: PropertyType, node: &Node) -> bool {
+    use self::PropertyType::*;
+
+    let is_int = |n: &Node| match n.value { Value::Number(x) => x.fract() == 0.0 && x.abs() <= (i64::max_value() as f64), _ => false };
+    let is_uint = |n: &Node| match n.value { Value::Number(x) => x.fract() == 0.0 && x >= 0.0 && x <= (u32::max_value() as f64), _ => false };
+    let is_bool = |n: &Node| match n.value { Value::Bool(_) => true, _ => false };
+    let is_one_of = |n: &Node, names: &[&str]| n.as_str().map(|s| names.contains(&s)).unwrap_or(false);
+    let pair = |n: &Node, f: &Fn(&Node) -> bool| match n.value { Value::Array(ref a) => a.len() == 2 && f(&a[0]) && f(&a[1]), _ => false };
+    let all = |n: &Node, f: &Fn(&Node) -> bool| match n.value { Value::Array(ref a) => a.iter().all(|x| f(x)), _ => false };
+
+    match ty {
+        Text | Parent | Control | Font | Image => node.as_str().is_some(),
+        Texts => all(node, &|n: &Node| n.as_str().is_some()),
+        Bool => is_bool(node),
+        BoolPair => pair(node, &is_bool),
+        Int => is_int(node),
+        IntPair => pair(node, &is_int),
+        UInt => is_uint(node),
+        UIntPair => pair(node, &is_uint),
+        Align => is_one_of(node, &["Left", "Center", "Right"]),
+        Check => is_one_of(node, &["Checked", "Unchecked", "Indeterminate"]),
+        BarState => is_one_of(node, &["Normal", "Paused", "Error"]),
+        Decoration => all(node, &|n: &Node| is_one_of(n, &["Italic", "Underline", "Strikeout"])),
+        ImageKind => is_one_of(node, &["Bitmap", "Icon", "Cursor"])
+    }
+}
+
+#[cfg(windows)]
+fn pack_resource(ui: &Ui<String>, r: &Element) {
+    match r.kind.as_str() {
+        "Font" => {
+            let decoration = r.texts("decoration").iter().fold(FONT_DECO_NORMAL, |d, name| d | match name.as_str() {
+                "Italic" => FONT_DECO_ITALIC,
+                "Underline" => FONT_DECO_UNDERLINE,
+                _ => FONT_DECO_STRIKEOUT
+            });
+
+            ui.pack_resource(&r.id, FontT{
+                family: r.text("family", "Arial"),
+                size: r.int("size", 12) as i32,
+                weight: r.int("weight", FONT_WEIGHT_NORMAL as i64) as i32,
+                decoration: decoration
+            });
+        },
+        "Image" => {
+            // An image without a file is loaded from the executable resources
+            let source = match r.optional_text("file") {
+                Some(path) => ImageSource::File(path),
+                None => ImageSource::Resource(r.text("resource", ""))
+            };
+
+            let image_type = match r.text("image_type", "Icon").as_str() {
+                "Bitmap" => ImageType::Bitmap,
+                "Cursor" => ImageType::Cursor,
+                _ => ImageType::Icon
+            };
+
+            let size = r.pair("size", (0, 0));
+            ui.pack_resource(&r.id, ImageT{
+                source: source,
+                image_type: image_type,
+                size: (size.0 as u32, size.1 as u32)
+            });
+        },
+        _ => unreachable!()
+    }
+}
+
+#[cfg(windows)]
+fn pack_control(ui: &Ui<String>, c: &Element) {
+    let id = &c.id;
+    let parent = c.optional_text("parent").unwrap_or(String::new());
+    let font = c.optional_text("font");
+    let pos = c.pair("position", (0, 0));
+    let position = (pos.0 as i32, pos.1 as i32);
+    let s = c.pair("size", (100, 30));
+    let size = (s.0 as u32, s.1 as u32);
+    let visible = c.boolean("visible", true);
+    let disabled = c.boolean("disabled", false);
+
+    match c.kind.as_str() {
+        "Window" => {
+            let pos = c.pair("position", (100, 100));
+            let size = c.pair("size", (800, 600));
+            ui.pack_control(id, WindowT{
+                title: c.text("title", "Native Windows GUI"),
+                position: (pos.0 as i32, pos.1 as i32), size: (size.0 as u32, size.1 as u32),
+                resizable: c.boolean("resizable", false), visible: visible, disabled: disabled,
+                exit_on_close: c.boolean("exit_on_close", true), accept_files: c.boolean("accept_files", false),
+                icon: c.optional_text("icon")
+            });
+        },
+        "Button" => ui.pack_control(id, ButtonT{
+            text: c.text("text", ""),
+            position: position, size: size, visible: visible, disabled: disabled,
+            parent: parent, font: font
+        }),
+        "Label" => ui.pack_control(id, LabelT{
+            text: c.text("text", ""),
+            position: position, size: size, visible: visible, disabled: disabled,
+            align: c.align(),
+            parent: parent, font: font
+        }),
+        "CheckBox" => ui.pack_control(id, CheckBoxT{
+            text: c.text("text", ""),
+            position: position, size: size, visible: visible, disabled: disabled,
+            checkstate: c.checkstate(), tristate: c.boolean("tristate", false),
+            parent: parent, font: font
+        }),
+        "RadioButton" => ui.pack_control(id, RadioButtonT{
+            text: c.text("text", ""),
+            position: position, size: size, visible: visible, disabled: disabled,
+            checkstate: c.checkstate(),
+            parent: parent, font: font
+        }),
+        "TextInput" => ui.pack_control(id, TextInputT{
+            text: c.text("text", ""),
+            position: position, size: size, visible: visible, disabled: disabled,
+            readonly: c.boolean("readonly", false), password: c.boolean("password", false),
+            placeholder: c.optional_text("placeholder"),
+            limit: c.int("limit", 32_767) as u32,
+            parent: parent, font: font
+        }),
+        "TextBox" => {
+            let scrollbars = c.pair("scrollbars", (0, 0));
+            ui.pack_control(id, TextBoxT{
+                text: c.text("text", ""),
+                position: position, size: size, visible: visible, disabled: disabled,
+                readonly: c.boolean("readonly", false),
+                limit: c.int("limit", 32_767) as u32,
+                scrollbars: (scrollbars.0 != 0, scrollbars.1 != 0),
+                parent: parent, font: font
+            });
+        },
+        "GroupBox" => {
+            let s = c.pair("size", (100, 100));
+            ui.pack_control(id, GroupBoxT{
+                text: c.text("text", ""),
+                position: position, size: (s.0 as u32, s.1 as u32), visible: visible, disabled: disabled,
+                align: c.align(),
+                parent: parent, font: font
+            });
+        },
+        "ListBox" => ui.pack_control(id, ListBoxT{
+            collection: c.texts("collection"),
+            position: position, size: size, visible: visible, disabled: disabled,
+            readonly: c.boolean("readonly", false), multi_select: c.boolean("multi_select", false),
+            parent: parent, font: font
+        }),
+        "ComboBox" => ui.pack_control(id, ComboBoxT{
+            collection: c.texts("collection"),
+            position: position, size: size, visible: visible, disabled: disabled,
+            placeholder: c.optional_text("placeholder"),
+            parent: parent, font: font
+        }),
+        "ProgressBar" => {
+            let range = c.pair("range", (0, 100));
+            let state = match c.text("state", "Normal").as_str() {
+                "Paused" => ProgressBarState::Paused,
+                "Error" => ProgressBarState::Error,
+                _ => ProgressBarState::Normal
+            };
+
+            ui.pack_control(id, ProgressBarT{
+                position: position, size: size, visible: visible, disabled: disabled,
+                range: (range.0 as u32, range.1 as u32),
+                value: c.int("value", 0) as u32, step: c.int("step", 10) as u32,
+                state: state, vertical: c.boolean("vertical", false),
+                parent: parent
+            });
+        },
+        "NumericInput" => ui.pack_control(id, NumericInputT{
+            value: c.int("value", 0),
+            range: c.pair("range", (i64::min_value(), i64::max_value())),
+            step: c.int("step", 1), decimals: c.int("decimals", 0) as u32,
+            position: position, size: size, visible: visible, disabled: disabled,
+            readonly: c.boolean("readonly", false),
+            parent: parent, font: font
+        }),
+        "TabsContainer" => {
+            let s = c.pair("size", (200, 150));
+            ui.pack_control(id, TabsContainerT{
+                position: position, size: (s.0 as u32, s.1 as u32), visible: visible, disabled: disabled,
+                parent: parent, font: font
+            });
+        },
+        "Tab" => ui.pack_control(id, TabT{ text: c.text("text", "Tab"), parent: parent }),
+        "Menu" => ui.pack_control(id, MenuT{ text: c.text("text", "Menu"), parent: parent, disabled: disabled }),
+        "MenuItem" => ui.pack_control(id, MenuItemT{
+            text: c.text("text", "Menuitem"), parent: parent, disabled: disabled,
+            checked: c.boolean("checked", false), radio: c.boolean("radio", false), default: c.boolean("default", false),
+            image: c.optional_text("image")
+        }),
+        "Separator" => ui.pack_control(id, SeparatorT{ parent: parent }),
+        "Timer" => ui.pack_control(id, TimerT{ interval: c.int("interval", 1000) as u32 }),
+        "Accelerator" => ui.pack_control(id, AcceleratorT{
+            shortcut: c.text("shortcut", ""),
+            parent: parent,
+            item: c.optional_text("item")
+        }),
+        _ => unreachable!()
+    }
+}
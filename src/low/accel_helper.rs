@@ -0,0 +1,138 @@
+/*!
+    Low level keyboard accelerators functions
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::ptr;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use winapi::{HWND, HACCEL, ACCEL, MSG, BYTE, WORD, c_int};
+
+/**
+    The accelerators bound to a window. The system table is rebuilt every time an accelerator is added or removed.
+*/
+struct AcceleratorTable {
+    handle: HACCEL,
+    entries: Vec<ACCEL>
+}
+
+thread_local!(
+    static TABLES: RefCell<HashMap<usize, AcceleratorTable>> = RefCell::new(HashMap::new())
+);
+
+/**
+    Destroy the current system table of a window and create a new one from its entries.
+*/
+unsafe fn rebuild_table(table: &mut AcceleratorTable) {
+    use user32::{CreateAcceleratorTableW, DestroyAcceleratorTable};
+
+    if !table.handle.is_null() {
+        DestroyAcceleratorTable(table.handle);
+        table.handle = ptr::null_mut();
+    }
+
+    if table.entries.len() > 0 {
+        table.handle = CreateAcceleratorTableW(table.entries.as_mut_ptr(), table.entries.len() as c_int);
+    }
+}
+
+/**
+    Return the smallest command id not used by any accelerator of the current thread.
+    Command ids start at 1 because 0 is never sent by the system.
+*/
+fn next_command_id(tables: &HashMap<usize, AcceleratorTable>) -> WORD {
+    let mut id: WORD = 1;
+    while tables.values().any(|t| t.entries.iter().any(|e| e.cmd == id)) {
+        id += 1;
+    }
+    id
+}
+
+/**
+    Bind a new accelerator to a window. `virt` is a combination of the `FVIRTKEY`, `FCONTROL`, `FSHIFT` and `FALT` flags.
+    Return the command id that will be sent in the `WM_COMMAND` message when the accelerator is used.
+*/
+pub unsafe fn add_accelerator(window: HWND, virt: BYTE, key: WORD) -> WORD {
+    TABLES.with(|tables| {
+        let mut tables = tables.borrow_mut();
+        let cmd = next_command_id(&tables);
+
+        let table = tables.entry(window as usize).or_insert(AcceleratorTable{ handle: ptr::null_mut(), entries: Vec::new() });
+        table.entries.push(ACCEL{ fVirt: virt, key: key, cmd: cmd });
+        rebuild_table(table);
+
+        cmd
+    })
+}
+
+/**
+    Remove an accelerator from a window. If the window do not have any accelerators left, its table is freed.
+*/
+pub unsafe fn remove_accelerator(window: HWND, cmd: WORD) {
+    use user32::DestroyAcceleratorTable;
+
+    TABLES.with(|tables| {
+        let mut tables = tables.borrow_mut();
+        let empty = match tables.get_mut(&(window as usize)) {
+            Some(table) => {
+                table.entries.retain(|e| e.cmd != cmd);
+                rebuild_table(table);
+                table.entries.len() == 0
+            },
+            None => false
+        };
+
+        if empty {
+            let table = tables.remove(&(window as usize)).unwrap();
+            if !table.handle.is_null() { DestroyAcceleratorTable(table.handle); }
+        }
+    });
+}
+
+/**
+    Return the command ids of the accelerators bound to a window
+*/
+pub fn list_accelerators(window: HWND) -> Vec<WORD> {
+    TABLES.with(|tables| match tables.borrow().get(&(window as usize)) {
+        Some(table) => table.entries.iter().map(|e| e.cmd).collect(),
+        None => Vec::new()
+    })
+}
+
+/**
+    Translate a keyboard message into a `WM_COMMAND` message if it matches an accelerator bound to the
+    target window or one of its ancestors. Return `true` if the message was translated, in which case it
+    must not be dispatched.
+*/
+pub unsafe fn translate_accelerator(msg: &mut MSG) -> bool {
+    use user32::GetParent;
+    use low::defs::TranslateAcceleratorW;
+
+    let mut window = msg.hwnd;
+    while !window.is_null() {
+        // The table handle is copied because the accelerator callbacks might add or remove accelerators
+        let handle = TABLES.with(|tables| tables.borrow().get(&(window as usize)).map(|t| t.handle) );
+        if let Some(handle) = handle {
+            return TranslateAcceleratorW(window, handle, msg) != 0;
+        }
+
+        window = GetParent(window);
+    }
+
+    false
+}
@@ -0,0 +1,1073 @@
+/*!
+    Pluggable system backend used by the low level helpers.
+
+    By default, every Ui talks to the system through `SystemBackend`. A `MockBackend` can be used
+    instead to run the Ui logic (pack, bind, trigger, unpack, commit) without creating any system window.
+    Outside of Windows, only the `MockBackend` is available.
+
+    Each Ui keeps its own backend. It is installed on the thread while the Ui executes its commands and its events.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+
+use std::ptr;
+#[cfg(windows)] use std::mem;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use winapi::{HWND, HMENU, UINT, WPARAM, LPARAM, LRESULT, UINT_PTR, DWORD_PTR, LONG_PTR, DWORD, WNDPROC, SUBCLASSPROC, c_int};
+
+use low::window_helper::{SysclassParams, WindowParams};
+use error::SystemError;
+
+/**
+    The functions used by NWG to create and manipulate the system windows.
+
+    Every function is unsafe because the system implementation directly forwards its arguments to the WINAPI.
+*/
+pub trait Backend {
+    /// Called once when a new Ui is created
+    unsafe fn init(&self);
+
+    /// Create a system class. Must not fail if the class already exists.
+    unsafe fn build_sysclass(&self, p: SysclassParams<String>) -> Result<(), SystemError>;
+
+    /// Try to unregister a system class. Errors are ignored.
+    unsafe fn unregister_sysclass(&self, class_name: &str);
+
+    /// Create a window using the parameters in `p`
+    unsafe fn build_window(&self, p: WindowParams<String, String>) -> Result<HWND, SystemError>;
+
+    /// Create a message-only window of the class `class_name`
+    unsafe fn build_message_window(&self, class_name: &str) -> Result<HWND, SystemError>;
+
+    /// Destroy a window
+    unsafe fn destroy_window(&self, handle: HWND);
+
+    unsafe fn get_window_text(&self, handle: HWND) -> String;
+    unsafe fn set_window_text(&self, handle: HWND, text: &str);
+    unsafe fn get_window_position(&self, handle: HWND) -> (i32, i32);
+    unsafe fn set_window_position(&self, handle: HWND, x: i32, y: i32);
+    unsafe fn get_window_size(&self, handle: HWND) -> (u32, u32);
+    unsafe fn set_window_size(&self, handle: HWND, w: u32, h: u32, fix: bool);
+    unsafe fn get_window_enabled(&self, handle: HWND) -> bool;
+    unsafe fn set_window_enabled(&self, handle: HWND, enabled: bool);
+    unsafe fn get_window_visibility(&self, handle: HWND) -> bool;
+    unsafe fn set_window_visibility(&self, handle: HWND, visible: bool);
+    unsafe fn get_window_long(&self, handle: HWND, index: c_int) -> LONG_PTR;
+    unsafe fn set_window_long(&self, handle: HWND, index: c_int, value: LONG_PTR);
+
+    /// Return the parent of a window. Can be null.
+    unsafe fn window_parent(&self, handle: HWND) -> HWND;
+
+    /// Return every child window of `handle` (recursively)
+    unsafe fn window_children(&self, handle: HWND) -> Vec<HWND>;
+
+    /// Return the menubar of a window. Can be null.
+    unsafe fn window_menu(&self, handle: HWND) -> HMENU;
+
+    /// Add a subclass to a window
+    unsafe fn set_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR, data: DWORD_PTR);
+
+    /// Return the data associated with a window subclass, or `None` if the window was not subclassed
+    unsafe fn get_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR) -> Option<DWORD_PTR>;
+
+    /// Remove a subclass from a window
+    unsafe fn remove_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR);
+
+    /// Call the next subclass proc of a window. Used by the subclass procs for the messages they do not handle.
+    unsafe fn def_subclass_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT;
+
+    /// Call the default window proc. Used by the window procs of the NWG classes for the messages they do not handle.
+    unsafe fn def_window_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT;
+
+    /// Send a message to a window and return the value returned by the window proc
+    unsafe fn send_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT;
+
+    /// Post a message in the message queue of a window
+    unsafe fn post_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM);
+
+    /// Post a quit message in the message queue of the current thread
+    unsafe fn post_quit_message(&self, code: c_int);
+
+    /// Remove and return the first message in the `[min, max]` range waiting in the queue of a window
+    unsafe fn peek_message(&self, handle: HWND, min: UINT, max: UINT) -> Option<(UINT, WPARAM, LPARAM)>;
+
+    /// Return an object that can post messages to the windows of this backend from any thread
+    fn remote_poster(&self) -> Arc<RemotePoster>;
+}
+
+/**
+    Post messages to the windows of a backend from any thread. Returned by `Backend::remote_poster`.
+
+    Window handles are passed as `usize` because a `HWND` cannot be sent between threads.
+*/
+pub trait RemotePoster: Send + Sync {
+
+    /// Post a message in the message queue of a window. Return `false` if the message could not be posted.
+    unsafe fn post_message(&self, handle: usize, msg: UINT, w: WPARAM, l: LPARAM) -> bool;
+}
+
+/**
+    The default backend. Forwards everything to the system. Only available on Windows.
+*/
+#[cfg(windows)]
+pub struct SystemBackend;
+
+#[cfg(windows)]
+impl Backend for SystemBackend {
+
+    unsafe fn init(&self) {
+        use low::other_helper::{enable_visual_styles, enable_com};
+        enable_visual_styles();
+        enable_com();
+    }
+
+    unsafe fn build_sysclass(&self, p: SysclassParams<String>) -> Result<(), SystemError> {
+        use kernel32::{GetModuleHandleW, GetLastError};
+        use user32::{LoadCursorW, RegisterClassExW};
+        use winapi::{WNDCLASSEXW, CS_HREDRAW, CS_VREDRAW, IDC_ARROW, COLOR_WINDOW, HBRUSH, ERROR_CLASS_ALREADY_EXISTS};
+        use low::other_helper::to_utf16;
+
+        let hmod = GetModuleHandleW(ptr::null_mut());
+        if hmod.is_null() { return Err(SystemError::SystemClassCreation); }
+
+        let class_name = to_utf16(&p.class_name);
+
+        let background: HBRUSH = match p.background {
+            Some(bg) => bg,
+            None => mem::transmute(COLOR_WINDOW as usize)
+        };
+
+        let style: UINT = match p.style {
+            Some(s) => s as UINT,
+            None=> CS_HREDRAW | CS_VREDRAW
+        };
+
+        let class =
+        WNDCLASSEXW {
+            cbSize: mem::size_of::<WNDCLASSEXW>() as UINT,
+            style: style,
+            lpfnWndProc: p.sysproc,
+            cbClsExtra: 0,
+            cbWndExtra: 0,
+            hInstance: hmod,
+            hIcon: ptr::null_mut(),
+            hCursor: LoadCursorW(ptr::null_mut(), IDC_ARROW),
+            hbrBackground: background,
+            lpszMenuName: ptr::null(),
+            lpszClassName: class_name.as_ptr(),
+            hIconSm: ptr::null_mut()
+        };
+
+        let class_token = RegisterClassExW(&class);
+        if class_token == 0 && GetLastError() != ERROR_CLASS_ALREADY_EXISTS {
+            Err(SystemError::SystemClassCreation)
+        } else {
+            Ok(())
+        }
+    }
+
+    unsafe fn unregister_sysclass(&self, class_name: &str) {
+        use kernel32::GetModuleHandleW;
+        use user32::UnregisterClassW;
+        use low::other_helper::to_utf16;
+
+        let class_name = to_utf16(class_name);
+        UnregisterClassW(class_name.as_ptr(), GetModuleHandleW(ptr::null_mut()));
+    }
+
+    unsafe fn build_window(&self, p: WindowParams<String, String>) -> Result<HWND, SystemError> {
+        use kernel32::GetModuleHandleW;
+        use user32::{CreateWindowExW, GetDesktopWindow, GetWindowRect};
+        use winapi::{WS_EX_COMPOSITED, RECT};
+        use low::other_helper::to_utf16;
+        use low::window_helper::fix_overlapped_window_size;
+
+        let hmod = GetModuleHandleW(ptr::null_mut());
+        if hmod.is_null() { return Err(SystemError::WindowCreationFail); }
+
+        let class_name = to_utf16(&p.class_name);
+        let window_name = to_utf16(&p.title);
+
+        let px = match p.position.0 {
+            ::defs::CENTER_POSITION => {
+                let mut rect: RECT = mem::uninitialized();
+                let parent = if p.parent.is_null() { GetDesktopWindow() } else {p.parent};
+                GetWindowRect(parent, &mut rect);
+                (rect.right/2) - ((p.size.0/2) as i32)
+            },
+            x => x
+        };
+
+        let py = match p.position.1 {
+            ::defs::CENTER_POSITION => {
+                let mut rect: RECT = mem::uninitialized();
+                let parent = if p.parent.is_null() { GetDesktopWindow() } else {p.parent};
+                GetWindowRect(parent, &mut rect);
+                (rect.bottom/2) - ((p.size.1/2) as i32)
+            },
+            y => y
+        };
+
+        let ex_flags = match p.ex_flags {
+            Some(ex) => ex,
+            None => WS_EX_COMPOSITED
+        };
+
+        let handle = CreateWindowExW (
+            ex_flags,
+            class_name.as_ptr(), window_name.as_ptr(),
+            p.flags,
+            px, py,
+            p.size.0 as i32, p.size.1 as i32,
+            p.parent,
+            ptr::null_mut(),
+            hmod,
+            ptr::null_mut()
+        );
+
+        if handle.is_null() {
+            Err(SystemError::WindowCreationFail)
+        } else {
+            fix_overlapped_window_size(handle, p.size);
+            Ok(handle)
+        }
+    }
+
+    unsafe fn build_message_window(&self, class_name: &str) -> Result<HWND, SystemError> {
+        use kernel32::GetModuleHandleW;
+        use user32::CreateWindowExW;
+        use winapi::HWND_MESSAGE;
+        use low::other_helper::to_utf16;
+
+        let hmod = GetModuleHandleW(ptr::null_mut());
+        if hmod.is_null() { return Err(SystemError::UiCreation); }
+
+        let class_name = to_utf16(class_name);
+        let window_name = to_utf16("");
+
+        let handle = CreateWindowExW (
+            0,
+            class_name.as_ptr(), window_name.as_ptr(),
+            0, 0, 0, 0, 0,
+            HWND_MESSAGE,
+            ptr::null_mut(),
+            hmod,
+            ptr::null_mut()
+        );
+
+        if handle.is_null() {
+            Err(SystemError::UiCreation)
+        } else {
+            Ok(handle)
+        }
+    }
+
+    unsafe fn destroy_window(&self, handle: HWND) {
+        use user32::DestroyWindow;
+        DestroyWindow(handle);
+    }
+
+    unsafe fn get_window_text(&self, handle: HWND) -> String {
+        use user32::{GetWindowTextW, GetWindowTextLengthW};
+        use winapi::c_int;
+        use low::other_helper::from_utf16;
+
+        let mut buffer_size = GetWindowTextLengthW(handle) as usize;
+        if buffer_size == 0 { return String::new(); }
+
+        buffer_size += 1;
+        let mut buffer: Vec<u16> = Vec::with_capacity(buffer_size);
+        buffer.set_len(buffer_size);
+
+        if GetWindowTextW(handle, buffer.as_mut_ptr(), buffer_size as c_int) == 0 {
+            String::new()
+        } else {
+            from_utf16(&buffer[..])
+        }
+    }
+
+    unsafe fn set_window_text(&self, handle: HWND, text: &str) {
+        use user32::SetWindowTextW;
+        use low::other_helper::to_utf16;
+
+        let text = to_utf16(text);
+        SetWindowTextW(handle, text.as_ptr());
+    }
+
+    unsafe fn get_window_position(&self, handle: HWND) -> (i32, i32) {
+        use user32::{GetWindowRect, ScreenToClient, GetParent};
+        use winapi::{RECT, POINT};
+
+        let mut r: RECT = mem::uninitialized();
+        GetWindowRect(handle, &mut r);
+
+        let parent = GetParent(handle);
+        if !parent.is_null() {
+            let mut pt = POINT{x: r.left, y: r.top};
+            ScreenToClient(parent, &mut pt);
+            (pt.x as i32, pt.y as i32)
+        } else {
+            (r.left as i32, r.top as i32)
+        }
+    }
+
+    unsafe fn set_window_position(&self, handle: HWND, x: i32, y: i32) {
+        use user32::SetWindowPos;
+        use winapi::{SWP_NOZORDER, SWP_NOSIZE, SWP_NOACTIVATE, c_int};
+
+        SetWindowPos(handle, ptr::null_mut(), x as c_int, y as c_int, 0, 0, SWP_NOZORDER|SWP_NOSIZE|SWP_NOACTIVATE);
+    }
+
+    unsafe fn get_window_size(&self, handle: HWND) -> (u32, u32) {
+        use user32::GetClientRect;
+        use winapi::RECT;
+
+        let mut r: RECT = mem::uninitialized();
+        GetClientRect(handle, &mut r);
+
+        (r.right as u32, r.bottom as u32)
+    }
+
+    unsafe fn set_window_size(&self, handle: HWND, w: u32, h: u32, fix: bool) {
+        use user32::SetWindowPos;
+        use winapi::{SWP_NOZORDER, SWP_NOMOVE, SWP_NOACTIVATE, c_int};
+        use low::window_helper::fix_overlapped_window_size;
+
+        SetWindowPos(handle, ptr::null_mut(), 0, 0, w as c_int, h as c_int, SWP_NOZORDER|SWP_NOMOVE|SWP_NOACTIVATE);
+
+        if fix { fix_overlapped_window_size(handle, (w, h)); }
+    }
+
+    unsafe fn get_window_enabled(&self, handle: HWND) -> bool {
+        use winapi::{GWL_STYLE, WS_DISABLED};
+
+        let style = self.get_window_long(handle, GWL_STYLE) as UINT;
+        (style & WS_DISABLED) != WS_DISABLED
+    }
+
+    unsafe fn set_window_enabled(&self, handle: HWND, enabled: bool) {
+        use winapi::{GWL_STYLE, WS_DISABLED};
+        use user32::{UpdateWindow, InvalidateRect};
+
+        let old_style = self.get_window_long(handle, GWL_STYLE) as usize;
+        if enabled {
+            self.set_window_long(handle, GWL_STYLE, (old_style&(!WS_DISABLED as usize)) as LONG_PTR);
+        } else {
+            self.set_window_long(handle, GWL_STYLE, (old_style|(WS_DISABLED as usize)) as LONG_PTR);
+        }
+
+        // Tell the control to redraw itself to show the new style.
+        InvalidateRect(handle, ptr::null(), 1);
+        UpdateWindow(handle);
+    }
+
+    unsafe fn get_window_visibility(&self, handle: HWND) -> bool {
+        use user32::IsWindowVisible;
+        IsWindowVisible(handle) != 0
+    }
+
+    unsafe fn set_window_visibility(&self, handle: HWND, visible: bool) {
+        use user32::ShowWindow;
+        use winapi::{SW_HIDE, SW_SHOW};
+
+        let visible = if visible { SW_SHOW } else { SW_HIDE };
+        ShowWindow(handle, visible);
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn get_window_long(&self, handle: HWND, index: c_int) -> LONG_PTR {
+        use user32::GetWindowLongPtrW;
+        GetWindowLongPtrW(handle, index)
+    }
+
+    #[cfg(target_arch = "x86")]
+    unsafe fn get_window_long(&self, handle: HWND, index: c_int) -> LONG_PTR {
+        use user32::GetWindowLongW;
+        GetWindowLongW(handle, index) as LONG_PTR
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn set_window_long(&self, handle: HWND, index: c_int, value: LONG_PTR) {
+        use user32::SetWindowLongPtrW;
+        SetWindowLongPtrW(handle, index, value);
+    }
+
+    #[cfg(target_arch = "x86")]
+    unsafe fn set_window_long(&self, handle: HWND, index: c_int, value: LONG_PTR) {
+        use user32::SetWindowLongW;
+        SetWindowLongW(handle, index, value as ::winapi::LONG);
+    }
+
+    unsafe fn window_parent(&self, handle: HWND) -> HWND {
+        use user32::GetParent;
+        GetParent(handle)
+    }
+
+    unsafe fn window_children(&self, handle: HWND) -> Vec<HWND> {
+        use user32::EnumChildWindows;
+        use winapi::BOOL;
+
+        unsafe extern "system" fn push_child(handle: HWND, params: LPARAM) -> BOOL {
+            let children: &mut Vec<HWND> = mem::transmute(params);
+            children.push(handle);
+            1
+        }
+
+        let mut children: Vec<HWND> = Vec::new();
+        EnumChildWindows(handle, Some(push_child), mem::transmute(&mut children));
+        children
+    }
+
+    unsafe fn window_menu(&self, handle: HWND) -> HMENU {
+        use user32::GetMenu;
+        GetMenu(handle)
+    }
+
+    unsafe fn set_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR, data: DWORD_PTR) {
+        use comctl32::SetWindowSubclass;
+        SetWindowSubclass(handle, proc_, id, data);
+    }
+
+    unsafe fn get_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR) -> Option<DWORD_PTR> {
+        use comctl32::GetWindowSubclass;
+        use winapi::TRUE;
+
+        let mut data: DWORD_PTR = 0;
+        if GetWindowSubclass(handle, proc_, id, &mut data) == TRUE {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    unsafe fn remove_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR) {
+        use comctl32::RemoveWindowSubclass;
+        RemoveWindowSubclass(handle, proc_, id);
+    }
+
+    unsafe fn def_subclass_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        use comctl32::DefSubclassProc;
+        DefSubclassProc(handle, msg, w, l)
+    }
+
+    unsafe fn def_window_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        use user32::DefWindowProcW;
+        DefWindowProcW(handle, msg, w, l)
+    }
+
+    unsafe fn send_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        use user32::SendMessageW;
+        SendMessageW(handle, msg, w, l)
+    }
+
+    unsafe fn post_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) {
+        use user32::PostMessageW;
+        PostMessageW(handle, msg, w, l);
+    }
+
+    unsafe fn post_quit_message(&self, code: c_int) {
+        use user32::PostQuitMessage;
+        PostQuitMessage(code);
+    }
+
+    unsafe fn peek_message(&self, handle: HWND, min: UINT, max: UINT) -> Option<(UINT, WPARAM, LPARAM)> {
+        use user32::PeekMessageW;
+        use winapi::{MSG, PM_REMOVE};
+
+        let mut msg: MSG = mem::uninitialized();
+        if PeekMessageW(&mut msg, handle, min, max, PM_REMOVE) != 0 {
+            Some((msg.message, msg.wParam, msg.lParam))
+        } else {
+            None
+        }
+    }
+
+    fn remote_poster(&self) -> Arc<RemotePoster> {
+        Arc::new(SystemPoster)
+    }
+}
+
+/**
+    The remote poster of the system backend. Forwards the messages to `PostMessageW`.
+*/
+#[cfg(windows)]
+pub struct SystemPoster;
+
+#[cfg(windows)]
+impl RemotePoster for SystemPoster {
+
+    unsafe fn post_message(&self, handle: usize, msg: UINT, w: WPARAM, l: LPARAM) -> bool {
+        use user32::PostMessageW;
+        PostMessageW(handle as HWND, msg, w, l) != 0
+    }
+}
+
+/**
+    A window created by a `MockBackend`
+
+    Members:  
+    • `class_name`: The system class of the window  
+    • `text`: The window text  
+    • `position`: The window position  
+    • `size`: The window size  
+    • `flags`: The window creation flags  
+    • `visible`: If the window is visible  
+    • `enabled`: If the window is enabled  
+    • `parent`: The window parent. Can be null.  
+    • `message_only`: If the window is a message-only window  
+*/
+#[derive(Clone, Debug)]
+pub struct MockWindow {
+    pub class_name: String,
+    pub text: String,
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub flags: DWORD,
+    pub visible: bool,
+    pub enabled: bool,
+    pub parent: HWND,
+    pub message_only: bool
+}
+
+/**
+    A message posted through a `MockBackend`
+*/
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MockMessage {
+    pub handle: HWND,
+    pub msg: UINT,
+    pub w: WPARAM,
+    pub l: LPARAM
+}
+
+/**
+    The remote poster of a `MockBackend`. Messages are kept aside until the thread that owns the backend reads its queue.
+*/
+struct MockPoster {
+    queue: Mutex<Vec<(usize, UINT, WPARAM, LPARAM)>>
+}
+
+impl RemotePoster for MockPoster {
+
+    unsafe fn post_message(&self, handle: usize, msg: UINT, w: WPARAM, l: LPARAM) -> bool {
+        match self.queue.lock() {
+            Ok(mut queue) => { queue.push((handle, msg, w, l)); true },
+            Err(_) => false
+        }
+    }
+}
+
+/**
+    A subclass installed on a mock window
+*/
+#[derive(Clone, Copy)]
+struct MockSubclass {
+    handle: usize,
+    proc_: SUBCLASSPROC,
+    id: UINT_PTR,
+    data: DWORD_PTR
+}
+
+/**
+    The next procedure called with a message sent to a mock window
+*/
+enum MockProc {
+    Subclass(MockSubclass),
+    Reply(Rc<Fn(WPARAM, LPARAM) -> LRESULT>),
+    Class(WNDPROC)
+}
+
+struct MockState {
+    next_handle: usize,
+    classes: Vec<String>,
+    procs: HashMap<String, WNDPROC>,
+    windows: BTreeMap<usize, MockWindow>,
+    longs: HashMap<(usize, c_int), LONG_PTR>,
+    subclasses: Vec<MockSubclass>,
+    dispatch: Vec<(usize, Vec<MockSubclass>)>,
+    replies: HashMap<(usize, UINT), Rc<Fn(WPARAM, LPARAM) -> LRESULT>>,
+    queue: VecDeque<MockMessage>,
+    posted: Vec<MockMessage>,
+    sent: Vec<MockMessage>,
+}
+
+/**
+    An in-memory backend that records the windows created, their properties and the posted messages.
+    No system window is ever created.
+
+    Handles returned by the mock are fake and must never be passed to a system function.
+*/
+pub struct MockBackend {
+    state: RefCell<MockState>,
+    remote: Arc<MockPoster>
+}
+
+impl MockBackend {
+
+    pub fn new() -> MockBackend {
+        MockBackend {
+            state: RefCell::new(MockState {
+                next_handle: 0x100,
+                classes: Vec::new(),
+                procs: HashMap::new(),
+                windows: BTreeMap::new(),
+                longs: HashMap::new(),
+                subclasses: Vec::new(),
+                dispatch: Vec::new(),
+                replies: HashMap::new(),
+                queue: VecDeque::new(),
+                posted: Vec::new(),
+                sent: Vec::new()
+            }),
+            remote: Arc::new(MockPoster{ queue: Mutex::new(Vec::new()) })
+        }
+    }
+
+    /// Return a copy of the window identified by `handle`, or `None` if the window does not exist (or was destroyed)
+    pub fn window(&self, handle: HWND) -> Option<MockWindow> {
+        self.state.borrow().windows.get(&(handle as usize)).cloned()
+    }
+
+    /// Return a copy of every window alive, in the order they were created. Message-only windows are included.
+    pub fn windows(&self) -> Vec<(HWND, MockWindow)> {
+        self.state.borrow().windows.iter().map(|(h, w)| (*h as HWND, w.clone())).collect()
+    }
+
+    /// Return the system classes registered
+    pub fn classes(&self) -> Vec<String> {
+        self.state.borrow().classes.clone()
+    }
+
+    /// Return every message posted since the backend creation, including the ones that were already processed.
+    /// A quit message is recorded as a `WM_QUIT` with a null handle.
+    pub fn posted_messages(&self) -> Vec<MockMessage> {
+        self.state.borrow().posted.clone()
+    }
+
+    /// Return every message sent since the backend creation.
+    /// Just like the system, the sent messages go through the subclasses of the window and then its class procedure.
+    /// Messages sent to a window of a class without a procedure (ex: a built-in control) return 0.
+    pub fn sent_messages(&self) -> Vec<MockMessage> {
+        self.state.borrow().sent.clone()
+    }
+
+    /// Return the number of messages waiting in the queue, including the ones posted from other threads
+    pub fn pending_messages(&self) -> usize {
+        self.receive_remote();
+        self.state.borrow().queue.len()
+    }
+
+    /**
+        Answer the messages `msg` sent to `handle` with `reply`, in place of the class procedure of the window.
+        The built-in controls have no procedure in the mock. Use this to stand in for them.
+    */
+    pub fn set_reply<F>(&self, handle: HWND, msg: UINT, reply: F) where F: Fn(WPARAM, LPARAM) -> LRESULT + 'static {
+        self.state.borrow_mut().replies.insert((handle as usize, msg), Rc::new(reply));
+    }
+
+    /// Return `true` if the window has at least one subclass
+    pub fn is_subclassed(&self, handle: HWND) -> bool {
+        let handle = handle as usize;
+        self.state.borrow().subclasses.iter().any(|s| s.handle == handle)
+    }
+
+    /// Call the next procedure of the message being sent to `handle`: its next subclass or, at the end of the chain, its reply or its class procedure
+    unsafe fn next_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        let h = handle as usize;
+        let next = {
+            let mut state = self.state.borrow_mut();
+            match state.dispatch.iter_mut().rev().find(|&&mut (dh, _)| dh == h) {
+                Some(&mut (_, ref mut chain)) if chain.len() > 0 => MockProc::Subclass(chain.remove(0)),
+                _ => match state.replies.get(&(h, msg)) {
+                    Some(reply) => MockProc::Reply(reply.clone()),
+                    None => {
+                        let class_name = state.windows.get(&h).map(|w| w.class_name.clone());
+                        MockProc::Class(class_name.and_then(|c| state.procs.get(&c).cloned()).unwrap_or(None))
+                    }
+                }
+            }
+        };
+
+        // The state must not be borrowed while the procedures run, they will most likely call the backend
+        match next {
+            MockProc::Subclass(s) => match s.proc_ {
+                Some(p) => p(handle, msg, w, l, s.id, s.data),
+                None => 0
+            },
+            MockProc::Reply(reply) => reply(w, l),
+            MockProc::Class(Some(p)) => p(handle, msg, w, l),
+            MockProc::Class(None) => 0
+        }
+    }
+
+    fn new_window(&self, w: MockWindow) -> HWND {
+        let mut state = self.state.borrow_mut();
+        let handle = state.next_handle;
+        state.next_handle += 4;
+        state.windows.insert(handle, w);
+        handle as HWND
+    }
+
+    /// Move the messages posted from other threads to the queue
+    fn receive_remote(&self) {
+        let remote = match self.remote.queue.lock() {
+            Ok(mut queue) => queue.drain(..).collect::<Vec<_>>(),
+            Err(_) => { return; }
+        };
+
+        let mut state = self.state.borrow_mut();
+        for (handle, msg, w, l) in remote {
+            let message = MockMessage{ handle: handle as HWND, msg: msg, w: w, l: l };
+            state.queue.push_back(message);
+            state.posted.push(message);
+        }
+    }
+
+    fn with_window<F: FnOnce(&mut MockWindow)>(&self, handle: HWND, f: F) {
+        if let Some(w) = self.state.borrow_mut().windows.get_mut(&(handle as usize)) {
+            f(w);
+        }
+    }
+
+}
+
+impl Backend for MockBackend {
+
+    unsafe fn init(&self) {}
+
+    unsafe fn build_sysclass(&self, p: SysclassParams<String>) -> Result<(), SystemError> {
+        let mut state = self.state.borrow_mut();
+        if !state.classes.contains(&p.class_name) {
+            state.procs.insert(p.class_name.clone(), p.sysproc);
+            state.classes.push(p.class_name);
+        }
+        Ok(())
+    }
+
+    unsafe fn unregister_sysclass(&self, class_name: &str) {
+        let mut state = self.state.borrow_mut();
+        let in_use = state.windows.values().any(|w| w.class_name == class_name);
+        if !in_use {
+            state.classes.retain(|c| c != class_name);
+            state.procs.remove(class_name);
+        }
+    }
+
+    unsafe fn build_window(&self, p: WindowParams<String, String>) -> Result<HWND, SystemError> {
+        use winapi::{WS_VISIBLE, WS_DISABLED};
+
+        if !p.parent.is_null() && self.window(p.parent).is_none() {
+            return Err(SystemError::WindowCreationFail);
+        }
+
+        let w = MockWindow {
+            class_name: p.class_name,
+            text: p.title,
+            position: p.position,
+            size: p.size,
+            flags: p.flags,
+            visible: (p.flags & WS_VISIBLE) == WS_VISIBLE,
+            enabled: (p.flags & WS_DISABLED) != WS_DISABLED,
+            parent: p.parent,
+            message_only: false
+        };
+
+        Ok(self.new_window(w))
+    }
+
+    unsafe fn build_message_window(&self, class_name: &str) -> Result<HWND, SystemError> {
+        let w = MockWindow {
+            class_name: class_name.to_string(),
+            text: String::new(),
+            position: (0, 0),
+            size: (0, 0),
+            flags: 0,
+            visible: false,
+            enabled: true,
+            parent: ptr::null_mut(),
+            message_only: true
+        };
+
+        Ok(self.new_window(w))
+    }
+
+    unsafe fn destroy_window(&self, handle: HWND) {
+        // Children are destroyed with their parent, just like the system does it
+        let mut children = self.window_children(handle);
+        children.push(handle);
+
+        let mut state = self.state.borrow_mut();
+        for h in children {
+            let h = h as usize;
+            state.windows.remove(&h);
+            state.longs.retain(|&(lh, _), _| lh != h);
+            state.subclasses.retain(|s| s.handle != h);
+            state.replies.retain(|&(rh, _), _| rh != h);
+            state.queue.retain(|m| m.handle as usize != h);
+        }
+    }
+
+    unsafe fn get_window_text(&self, handle: HWND) -> String {
+        self.window(handle).map(|w| w.text).unwrap_or(String::new())
+    }
+
+    unsafe fn set_window_text(&self, handle: HWND, text: &str) {
+        self.with_window(handle, |w| w.text = text.to_string());
+    }
+
+    unsafe fn get_window_position(&self, handle: HWND) -> (i32, i32) {
+        self.window(handle).map(|w| w.position).unwrap_or((0, 0))
+    }
+
+    unsafe fn set_window_position(&self, handle: HWND, x: i32, y: i32) {
+        self.with_window(handle, |w| w.position = (x, y));
+    }
+
+    unsafe fn get_window_size(&self, handle: HWND) -> (u32, u32) {
+        self.window(handle).map(|w| w.size).unwrap_or((0, 0))
+    }
+
+    #[allow(unused_variables)]
+    unsafe fn set_window_size(&self, handle: HWND, width: u32, height: u32, fix: bool) {
+        self.with_window(handle, |w| w.size = (width, height));
+    }
+
+    unsafe fn get_window_enabled(&self, handle: HWND) -> bool {
+        self.window(handle).map(|w| w.enabled).unwrap_or(false)
+    }
+
+    unsafe fn set_window_enabled(&self, handle: HWND, enabled: bool) {
+        self.with_window(handle, |w| w.enabled = enabled);
+    }
+
+    unsafe fn get_window_visibility(&self, handle: HWND) -> bool {
+        self.window(handle).map(|w| w.visible).unwrap_or(false)
+    }
+
+    unsafe fn set_window_visibility(&self, handle: HWND, visible: bool) {
+        self.with_window(handle, |w| w.visible = visible);
+    }
+
+    unsafe fn get_window_long(&self, handle: HWND, index: c_int) -> LONG_PTR {
+        self.state.borrow().longs.get(&(handle as usize, index)).cloned().unwrap_or(0)
+    }
+
+    unsafe fn set_window_long(&self, handle: HWND, index: c_int, value: LONG_PTR) {
+        if self.window(handle).is_some() {
+            self.state.borrow_mut().longs.insert((handle as usize, index), value);
+        }
+    }
+
+    unsafe fn window_parent(&self, handle: HWND) -> HWND {
+        self.window(handle).map(|w| w.parent).unwrap_or(ptr::null_mut())
+    }
+
+    unsafe fn window_children(&self, handle: HWND) -> Vec<HWND> {
+        let state = self.state.borrow();
+        let mut children: Vec<HWND> = Vec::new();
+        let mut parents: Vec<HWND> = vec![handle];
+
+        while let Some(parent) = parents.pop() {
+            for (h, w) in state.windows.iter() {
+                if w.parent == parent {
+                    children.push(*h as HWND);
+                    parents.push(*h as HWND);
+                }
+            }
+        }
+
+        children
+    }
+
+    #[allow(unused_variables)]
+    unsafe fn window_menu(&self, handle: HWND) -> HMENU {
+        ptr::null_mut()
+    }
+
+    unsafe fn set_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR, data: DWORD_PTR) {
+        let subclass = MockSubclass{ handle: handle as usize, proc_: proc_, id: id, data: data };
+        let mut state = self.state.borrow_mut();
+
+        // Like the system, setting an existing subclass only updates its data
+        match state.subclasses.iter().position(|s| same_subclass(s, &subclass)) {
+            Some(i) => { state.subclasses[i].data = data; },
+            None => { state.subclasses.push(subclass); }
+        }
+    }
+
+    unsafe fn get_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR) -> Option<DWORD_PTR> {
+        let subclass = MockSubclass{ handle: handle as usize, proc_: proc_, id: id, data: 0 };
+        self.state.borrow().subclasses.iter().find(|s| same_subclass(s, &subclass)).map(|s| s.data)
+    }
+
+    unsafe fn remove_subclass(&self, handle: HWND, proc_: SUBCLASSPROC, id: UINT_PTR) {
+        let subclass = MockSubclass{ handle: handle as usize, proc_: proc_, id: id, data: 0 };
+        let mut state = self.state.borrow_mut();
+        state.subclasses.retain(|s| !same_subclass(s, &subclass));
+        for &mut (_, ref mut chain) in state.dispatch.iter_mut() {
+            chain.retain(|s| !same_subclass(s, &subclass));
+        }
+    }
+
+    unsafe fn def_subclass_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        self.next_proc(handle, msg, w, l)
+    }
+
+    #[allow(unused_variables)]
+    unsafe fn def_window_proc(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        0
+    }
+
+    unsafe fn send_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+        let message = MockMessage{ handle: handle, msg: msg, w: w, l: l };
+
+        {
+            let mut state = self.state.borrow_mut();
+            state.sent.push(message);
+            if !state.windows.contains_key(&(handle as usize)) {
+                return 0;
+            }
+
+            // The last subclass installed is the first one called
+            let h = handle as usize;
+            let chain = state.subclasses.iter().rev().filter(|s| s.handle == h).cloned().collect();
+            state.dispatch.push((h, chain));
+        }
+
+        let result = self.next_proc(handle, msg, w, l);
+
+        let mut state = self.state.borrow_mut();
+        if let Some(i) = state.dispatch.iter().rposition(|&(dh, _)| dh == handle as usize) {
+            state.dispatch.remove(i);
+        }
+
+        result
+    }
+
+    unsafe fn post_message(&self, handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) {
+        let message = MockMessage{ handle: handle, msg: msg, w: w, l: l };
+        let mut state = self.state.borrow_mut();
+        state.queue.push_back(message);
+        state.posted.push(message);
+    }
+
+    unsafe fn post_quit_message(&self, code: c_int) {
+        use winapi::WM_QUIT;
+
+        // The quit message is not tied to a window and is never returned by `peek_message`
+        let message = MockMessage{ handle: ptr::null_mut(), msg: WM_QUIT, w: code as WPARAM, l: 0 };
+        self.state.borrow_mut().posted.push(message);
+    }
+
+    unsafe fn peek_message(&self, handle: HWND, min: UINT, max: UINT) -> Option<(UINT, WPARAM, LPARAM)> {
+        self.receive_remote();
+
+        let mut state = self.state.borrow_mut();
+        let index = state.queue.iter().position(|m| m.handle == handle && m.msg >= min && m.msg <= max);
+        match index {
+            Some(i) => state.queue.remove(i).map(|m| (m.msg, m.w, m.l)),
+            None => None
+        }
+    }
+
+    fn remote_poster(&self) -> Arc<RemotePoster> {
+        self.remote.clone()
+    }
+}
+
+#[inline(always)]
+fn subclass_key(proc_: SUBCLASSPROC) -> usize {
+    match proc_ {
+        Some(p) => p as usize,
+        None => 0
+    }
+}
+
+/// Subclasses are identified by their window, their procedure and their id
+fn same_subclass(a: &MockSubclass, b: &MockSubclass) -> bool {
+    a.handle == b.handle && a.id == b.id && subclass_key(a.proc_) == subclass_key(b.proc_)
+}
+
+thread_local!(
+    static CURRENT_BACKEND: RefCell<Option<Rc<Backend>>> = RefCell::new(None)
+);
+
+/// The backend used when none was installed. Outside of Windows, there is no system to talk to.
+#[cfg(windows)]
+fn default_backend() -> Rc<Backend> { Rc::new(SystemBackend) }
+
+#[cfg(not(windows))]
+fn default_backend() -> Rc<Backend> { Rc::new(MockBackend::new()) }
+
+/**
+    Return the backend used by the current thread. If no backend was installed, install and return the default backend
+    (a `SystemBackend` on Windows, a `MockBackend` elsewhere).
+*/
+pub fn current() -> Rc<Backend> {
+    CURRENT_BACKEND.with(|b| {
+        let mut b = b.borrow_mut();
+        if b.is_none() {
+            *b = Some(default_backend());
+        }
+        b.as_ref().unwrap().clone()
+    })
+}
+
+/**
+    Set the backend used by the current thread and return the backend that was installed before.
+    Every window created after this call will use the new backend.
+*/
+pub fn install(backend: Rc<Backend>) -> Option<Rc<Backend>> {
+    CURRENT_BACKEND.with(|b| b.borrow_mut().replace(backend))
+}
+
+/**
+    Put back a backend returned by `install`. If `backend` is `None`, the default backend will be installed on the next call to `current`.
+*/
+pub fn restore(backend: Option<Rc<Backend>>) {
+    CURRENT_BACKEND.with(|b| { *b.borrow_mut() = backend; });
+}
+
+/**
+    Return `true` if `backend` is the backend used by the current thread
+*/
+pub fn is_current(backend: &Rc<Backend>) -> bool {
+    CURRENT_BACKEND.with(|b| match *b.borrow() {
+        Some(ref b) => Rc::ptr_eq(b, backend),
+        None => false
+    })
+}
+
+/**
+    Execute `f` with `backend` installed. The backend that was installed before is restored when `f` returns, even if it panics.
+    Used by the Ui to run its commands and its events over its own backend.
+*/
+pub fn with<R, F: FnOnce() -> R>(backend: &Rc<Backend>, f: F) -> R {
+    struct Restore(Option<Option<Rc<Backend>>>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            if let Some(previous) = self.0.take() { restore(previous); }
+        }
+    }
+
+    let _restore = Restore(Some(install(backend.clone())));
+    f()
+}
@@ -20,4 +20,8 @@ define_guid!(CLSID_FileOpenDialog, 3692845724, 59530, 19934, [165, 161, 96, 248,
 define_guid!(CLSID_FileSaveDialog, 3233080051, 47649, 18291, [141, 186, 51, 94, 201, 70, 235, 139]);
 define_guid!(UUIDOF_IFileDialog, 1123569974, 56190, 17308, [133, 241, 228, 7, 93, 19, 95, 200]);
 define_guid!(UUIDOF_IFileOpenDialog, 3581702792, 54445, 18280, [190, 2, 157, 150, 149, 50, 217, 96]);
-define_guid!(IID_IShellItem, 1132621086, 59160, 17134, [188, 85, 161, 226, 97, 195, 123, 254]);
\ No newline at end of file
+define_guid!(IID_IShellItem, 1132621086, 59160, 17134, [188, 85, 161, 226, 97, 195, 123, 254]);
+define_guid!(IID_IDWriteFactory, 3092901466, 55352, 19291, [162, 232, 26, 220, 125, 147, 219, 72]);
+define_guid!(CLSID_WICImagingFactory, 3402297954, 37744, 17941, [161, 59, 159, 85, 57, 218, 76, 10]);
+define_guid!(IID_IWICImagingFactory, 3965634729, 50069, 17172, [156, 119, 84, 215, 169, 53, 255, 112]);
+define_guid!(GUID_WICPixelFormat32bppPBGRA, 1876804388, 19971, 19454, [177, 133, 61, 119, 118, 141, 201, 16]);
\ No newline at end of file
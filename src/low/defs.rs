@@ -23,12 +23,16 @@
 use std::hash::Hash;
 use std::any::{Any, TypeId};
 
-use winapi::{UINT, LRESULT, DWORD, HBRUSH, ULONG_PTR, HMENU, BOOL, c_int, MENUITEMINFOW, IShellItem, HRESULT, IUnknownVtbl,
- IUnknown, PCWSTR, IBindCtx, REFIID, D2D1_FACTORY_TYPE, D2D1_FACTORY_OPTIONS, ID2D1Factory,c_void};
-use std::ops::{Deref, DerefMut};
+use winapi::{UINT, LRESULT, DWORD, BYTE};
+#[cfg(windows)]
+use winapi::{HBRUSH, ULONG_PTR, HMENU, BOOL, c_int, MENUITEMINFOW, HWND, HACCEL, MSG, IShellItem, HRESULT, IUnknownVtbl,
+ IUnknown, PCWSTR, IBindCtx, REFIID, D2D1_FACTORY_TYPE, D2D1_FACTORY_OPTIONS, ID2D1Factory, DWRITE_FACTORY_TYPE, c_void,
+ GUID, LPCWSTR, IStream, IStreamVtbl, IWICBitmapSource, IWICFormatConverter, WICDecodeOptions, HDROP, LPWSTR, POINT, NOTIFYICONDATAW};
+#[cfg(windows)] use std::ops::{Deref, DerefMut};
 
 
 use events::{Event, EventCallback, EventArgs};
+use ui::Ui;
 use controls::ControlT;
 use resources::ResourceT;
 
@@ -42,7 +46,11 @@ pub const NWG_BIND:              UINT = 0x403;  /// Message sent when binding an
 pub const NWG_UNBIND:            UINT = 0x404;  /// Message sent when unbinding an event from a control
 pub const NWG_PACK_RESOURCE:     UINT = 0x405;  /// Message sent when packing a resource
 pub const NWG_TRIGGER:           UINT = 0x406;  /// Message sent when triggering an event
-pub const NWG_CUSTOM_MAX:        UINT = 0x407;  /// Maximum custom event value
+pub const NWG_EXECUTE:           UINT = 0x407;  /// Message sent when executing a function sent from another thread
+pub const NWG_WAKE:              UINT = 0x408;  /// Message sent when a local task is woken
+pub const NWG_CUSTOM_MAX:        UINT = 0x409;  /// Maximum custom event value
+
+pub const NWG_TRAY:              UINT = 0x410;  /// Message sent by the system to the Ui when the user interacts with a tray icon
 
 // Value returned by a window proc if the message execution failed/succeeded
 
@@ -55,15 +63,47 @@ pub const MIM_STYLE: DWORD = 0x00000010;
 
 pub const MIIM_STATE: DWORD = 0x00000001;
 pub const MIIM_ID: DWORD = 0x00000002;
+pub const MIIM_STRING: DWORD = 0x00000040;
+pub const MIIM_BITMAP: DWORD = 0x00000080;
+pub const MIIM_FTYPE: DWORD = 0x00000100;
+
+pub const MFT_RADIOCHECK: DWORD = 0x00000200;
 
 pub const MNS_NOTIFYBYPOS: DWORD = 0x08000000;
 
 pub const MFS_DISABLED: DWORD = 0x00000003;
 pub const MFS_ENABLED: DWORD = 0x00000000;
+pub const MFS_CHECKED: DWORD = 0x00000008;
+pub const MFS_DEFAULT: DWORD = 0x00001000;
+
+pub const GMDI_USEDISABLED: UINT = 0x0001;
 
 pub const MF_BYPOSITION: UINT = 0x00000400;
 pub const MF_SEPARATOR: UINT = 0x00000800;
 
+pub const FVIRTKEY: BYTE = 0x01;
+pub const FSHIFT: BYTE = 0x04;
+pub const FCONTROL: BYTE = 0x08;
+pub const FALT: BYTE = 0x10;
+
+pub const DLGC_WANTALLKEYS: LRESULT = 0x0004;
+
+pub const GMEM_MOVEABLE: UINT = 0x0002;
+
+pub const NIS_HIDDEN: DWORD = 0x00000001;
+pub const NIN_SELECT: UINT = 0x0400;
+pub const NIN_KEYSELECT: UINT = 0x0401;
+
+pub const NIIF_NONE: DWORD = 0x00000000;
+pub const NIIF_INFO: DWORD = 0x00000001;
+pub const NIIF_WARNING: DWORD = 0x00000002;
+pub const NIIF_ERROR: DWORD = 0x00000003;
+pub const NIIF_USER: DWORD = 0x00000004;
+
+pub const TPM_RIGHTBUTTON: UINT = 0x0002;
+
+pub const USER_TIMER_MAXIMUM: UINT = 0x7FFFFFFF;
+
 pub const ACTCTX_FLAG_RESOURCE_NAME_VALID: u32 = 0x008;
 pub const ACTCTX_FLAG_SET_PROCESS_DEFAULT: u32 = 0x010;
 pub const ACTCTX_FLAG_ASSEMBLY_DIRECTORY_VALID: u32 = 0x004;
@@ -129,6 +169,11 @@ pub const CB_SETCURSEL: UINT = 334;
 pub const CB_SHOWDROPDOWN: UINT = 335;
 pub const CB_SETCUEBANNER: UINT = 5891;
 
+pub const CBN_SELCHANGE: UINT = 1;
+pub const CBN_DBLCLK: UINT = 2;
+pub const CBN_SETFOCUS: UINT = 3;
+pub const CBN_KILLFOCUS: UINT = 4;
+
 pub const ES_AUTOHSCROLL: UINT = 128;
 pub const ES_AUTOVSCROLL: UINT = 64;
 pub const ES_PASSWORD: UINT = 32;
@@ -137,6 +182,7 @@ pub const ES_MULTILINE: UINT = 4;
 
 pub const EM_LIMITTEXT: UINT = 197;
 pub const EM_GETLIMITTEXT: UINT = 213;
+pub const EM_SETREADONLY: UINT = 207;
 
 pub const EN_SETFOCUS: UINT = 256;
 pub const EN_KILLFOCUS: UINT = 512;
@@ -162,6 +208,7 @@ pub const STATE_SYSTEM_CHECKED: u32 = 0x10;
 pub const STATE_SYSTEM_INVISIBLE: u32 = 0x8000;
 
 // System structs
+#[cfg(windows)]
 #[repr(C)]
 #[allow(non_snake_case)]
 pub struct MENUINFO {
@@ -178,6 +225,7 @@ pub struct MENUINFO {
 // Unused functions have an empty signature
 
 // MACRO taken from winapi. Original author: Peter Atashian (retep998)
+#[cfg(windows)]
 macro_rules! RIDL {
     (interface $interface:ident ($vtbl:ident) {$(
         fn $method:ident(&mut self $(,$p:ident : $t:ty)*) -> $rtr:ty
@@ -261,6 +309,7 @@ macro_rules! RIDL {
     );
 }
 
+#[cfg(windows)]
 RIDL!(
 interface IShellItemArray(IShellItemArrayVtbl): IUnknown(IUnknownVtbl) {
     fn BindToHandler(&mut self) -> (),
@@ -273,7 +322,56 @@ interface IShellItemArray(IShellItemArrayVtbl): IUnknown(IUnknownVtbl) {
 }
 );
 
+// The WIC interfaces are incomplete in winapi. Only the methods up to the last one used are declared.
+
+#[cfg(windows)]
+RIDL!(
+interface IWICImagingFactory(IWICImagingFactoryVtbl): IUnknown(IUnknownVtbl) {
+    fn CreateDecoderFromFilename(&mut self, wzFilename: LPCWSTR, pguidVendor: *const GUID, dwDesiredAccess: DWORD,
+      metadataOptions: WICDecodeOptions, ppIDecoder: *mut *mut IWICBitmapDecoder) -> HRESULT,
+    fn CreateDecoderFromStream(&mut self, pIStream: *mut IStream, pguidVendor: *const GUID, metadataOptions: WICDecodeOptions,
+      ppIDecoder: *mut *mut IWICBitmapDecoder) -> HRESULT,
+    fn CreateDecoderFromFileHandle(&mut self) -> (),
+    fn CreateComponentInfo(&mut self) -> (),
+    fn CreateDecoder(&mut self) -> (),
+    fn CreateEncoder(&mut self) -> (),
+    fn CreatePalette(&mut self) -> (),
+    fn CreateFormatConverter(&mut self, ppIFormatConverter: *mut *mut IWICFormatConverter) -> HRESULT,
+    fn CreateBitmapScaler(&mut self) -> (),
+    fn CreateBitmapClipper(&mut self) -> (),
+    fn CreateBitmapFlipRotator(&mut self) -> (),
+    fn CreateStream(&mut self, ppIWICStream: *mut *mut IWICStream) -> HRESULT
+}
+);
+
+#[cfg(windows)]
+RIDL!(
+interface IWICBitmapDecoder(IWICBitmapDecoderVtbl): IUnknown(IUnknownVtbl) {
+    fn QueryCapability(&mut self) -> (),
+    fn Initialize(&mut self) -> (),
+    fn GetContainerFormat(&mut self) -> (),
+    fn GetDecoderInfo(&mut self) -> (),
+    fn CopyPalette(&mut self) -> (),
+    fn GetMetadataQueryReader(&mut self) -> (),
+    fn GetPreview(&mut self) -> (),
+    fn GetColorContexts(&mut self) -> (),
+    fn GetThumbnail(&mut self) -> (),
+    fn GetFrameCount(&mut self, pCount: *mut UINT) -> HRESULT,
+    fn GetFrame(&mut self, index: UINT, ppIBitmapFrame: *mut *mut IWICBitmapSource) -> HRESULT
+}
+);
+
+#[cfg(windows)]
+RIDL!(
+interface IWICStream(IWICStreamVtbl): IStream(IStreamVtbl) {
+    fn InitializeFromIStream(&mut self) -> (),
+    fn InitializeFromFilename(&mut self) -> (),
+    fn InitializeFromMemory(&mut self, pbBuffer: *mut BYTE, cbBufferSize: DWORD) -> HRESULT
+}
+);
+
 // System extern
+#[cfg(windows)]
 extern "system" {
     pub fn GetMenuItemCount(menu: HMENU) -> c_int;
     pub fn GetSubMenu(hMenu: HMENU, nPos: c_int) -> HMENU;
@@ -282,8 +380,17 @@ extern "system" {
     pub fn GetMenuItemID(menu: HMENU, index: c_int) -> UINT;
     pub fn SetMenuItemInfoW(hMenu: HMENU, uItem: UINT, gByPosition: BOOL, lpmii: &mut MENUITEMINFOW) -> BOOL;
     pub fn GetMenuItemInfoW(hMenu: HMENU, uItem: UINT, gByPosition: BOOL, lpmii: &mut MENUITEMINFOW) -> BOOL;
+    pub fn TranslateAcceleratorW(hWnd: HWND, hAccTable: HACCEL, lpMsg: &mut MSG) -> c_int;
+    pub fn RemoveClipboardFormatListener(hWnd: HWND) -> BOOL;
+    pub fn SetMenuDefaultItem(hMenu: HMENU, uItem: UINT, fByPos: UINT) -> BOOL;
+    pub fn GetMenuDefaultItem(hMenu: HMENU, fByPos: UINT, gmdiFlags: UINT) -> UINT;
+    pub fn TrackPopupMenuEx(hMenu: HMENU, uFlags: UINT, x: c_int, y: c_int, hWnd: HWND, lptpm: *mut c_void) -> BOOL;
 
     pub fn SHCreateItemFromParsingName(pszPath: PCWSTR, pbc: *mut IBindCtx, riid: REFIID, ppv: *mut *mut c_void) -> HRESULT;
+    pub fn DragQueryFileW(hDrop: HDROP, iFile: UINT, lpszFile: LPWSTR, cch: UINT) -> UINT;
+    pub fn DragQueryPoint(hDrop: HDROP, lppt: *mut POINT) -> BOOL;
+    pub fn DragFinish(hDrop: HDROP);
+    pub fn Shell_NotifyIconW(dwMessage: DWORD, lpData: *mut NOTIFYICONDATAW) -> BOOL;
 
     pub fn D2D1CreateFactory(
         factoryType: D2D1_FACTORY_TYPE,
@@ -291,6 +398,8 @@ extern "system" {
 		pFactoryOptions: *const D2D1_FACTORY_OPTIONS,
         ppIFactory: *mut *mut ID2D1Factory
     ) -> HRESULT;
+
+    pub fn DWriteCreateFactory(factoryType: DWRITE_FACTORY_TYPE, iid: REFIID, factory: *mut *mut IUnknown) -> HRESULT;
 }
 
 // Arguments passed to the NWG custom events 
@@ -332,4 +441,12 @@ pub struct TriggerArgs {
     pub id: u64,
     pub event: Event,
     pub args: EventArgs
+}
+
+pub struct ExecuteArgs<ID: Hash+Clone+'static> {
+    pub cb: Box<FnMut(&Ui<ID>) + Send>
+}
+
+pub struct WakeArgs {
+    pub task: u64
 }
\ No newline at end of file
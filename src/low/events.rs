@@ -21,112 +21,150 @@
 use std::mem;
 use std::ptr;
 use std::hash::Hash;
-use std::any::TypeId;
+#[cfg(windows)] use std::any::TypeId;
 
-use winapi::{HWND, HMENU, UINT, WPARAM, LPARAM, UINT_PTR, DWORD_PTR, LRESULT, DWORD};
+use winapi::{HWND, UINT, WPARAM, LPARAM, UINT_PTR, DWORD_PTR, LRESULT, DWORD, NMHDR};
+#[cfg(windows)] use winapi::{HMENU, HDROP, WORD};
 
 use ui::UiInner;
 use events::{Event, EventArgs};
-use controls::{ControlType, AnyHandle, Timer};
+use controls::AnyHandle;
+#[cfg(windows)] use controls::{Timer, Accelerator, TrayIcon};
+#[cfg(windows)] use defs::MouseState;
 
 /// A magic number to identify the NWG subclass that dispatches events
 const EVENTS_DISPATCH_ID: UINT_PTR = 2465;
 
-// WARNING! This WHOLE section (from parse_listbox_command to parse_command) will be replaced with the events overhaul in NWG BETA2
-
-fn parse_listbox_command(id: u64, ncode: u32) -> Option<(u64, Event, EventArgs)> {
-  use low::defs::{LBN_SELCHANGE, LBN_DBLCLK, LBN_SETFOCUS, LBN_KILLFOCUS};
-
-  match ncode {
-    LBN_SELCHANGE => Some((id, Event::SelectionChanged, EventArgs::None)),
-    LBN_DBLCLK => Some((id, Event::DoubleClick, EventArgs::None)),
-    LBN_SETFOCUS | LBN_KILLFOCUS => Some((id, Event::Focus, EventArgs::Focus(ncode==LBN_SETFOCUS))),
-    _ => None
-  }
-}
+/**
+  Decode a `WM_COMMAND` notification using the decoders registered for the type of the control that sent it.
+*/
+#[inline(always)]
+unsafe fn decode_command<ID: Hash+Clone+'static>(inner: &UiInner<ID>, handle: HWND, w: WPARAM) -> Option<(u64, Event, EventArgs)> {
+  use winapi::HIWORD;
 
-fn parse_button_command(id: u64, ncode: u32) -> Option<(u64, Event, EventArgs)> {
-  use low::defs::{BN_CLICKED, BN_DBLCLK, BN_SETFOCUS, BN_KILLFOCUS};
-  match ncode {
-    BN_CLICKED => Some((id, Event::Click, EventArgs::None)),
-    BN_DBLCLK => Some((id, Event::DoubleClick, EventArgs::None)),
-    BN_SETFOCUS | BN_KILLFOCUS => Some((id, Event::Focus, EventArgs::Focus(ncode==BN_SETFOCUS))),
-    _ => None
-  }
-}
+  // Somehow, WM_COMMAND messages get sent while freeing and so inner_id_from_handle can fail...
+  let id = match inner.inner_id_from_handle( &AnyHandle::HWND(handle) ) {
+    Some(id) => id,
+    None => { return None; }
+  };
 
-fn parse_edit_command(id: u64, ncode: u32) -> Option<(u64, Event, EventArgs)> {
-  use low::defs::{EN_SETFOCUS, EN_KILLFOCUS, EN_UPDATE, EN_MAXTEXT};
-  match ncode {
-    EN_UPDATE => Some((id, Event::ValueChanged, EventArgs::None)),
-    EN_MAXTEXT => Some((id, Event::LimitReached, EventArgs::None)),
-    EN_SETFOCUS | EN_KILLFOCUS => Some((id, Event::Focus, EventArgs::Focus(ncode==EN_SETFOCUS))),
-    _ => None
+  let ncode = HIWORD(w as DWORD) as u32;
+  match inner.decoders_of(&id).and_then(|d| d.command) {
+    Some(decode) => decode(handle, ncode).map(|(evt, args)| (id, evt, args)),
+    None => None
   }
 }
 
-fn parse_static_command(id: u64, ncode: u32) -> Option<(u64, Event, EventArgs)> {
-  use low::defs::{STN_CLICKED, STN_DBLCLK};
-  match ncode {
-    STN_CLICKED => Some((id, Event::Click, EventArgs::None)),
-    STN_DBLCLK => Some((id, Event::DoubleClick, EventArgs::None)),
-    _ => None
-  }
-}
+/**
+  Decode a `WM_NOTIFY` notification using the decoders registered for the type of the control that sent it.
+*/
+#[inline(always)]
+unsafe fn decode_notify<ID: Hash+Clone+'static>(inner: &UiInner<ID>, nmhdr: *const NMHDR) -> Option<(u64, Event, EventArgs)> {
+  let handle = (&*nmhdr).hwndFrom;
+  let id = match inner.inner_id_from_handle( &AnyHandle::HWND(handle) ) {
+    Some(id) => id,
+    None => { return None; }
+  };
 
-fn parse_datepicker_command(id: u64, ncode: u32) -> Option<(u64, Event, EventArgs)> {
-  use winapi::DTN_CLOSEUP;
-  match ncode {
-    DTN_CLOSEUP => {  // DTN_DATETIMECHANGE is sent twice so instead we catch DTN_CLOSEUP ¯\_(ツ)_/¯
-      Some((id, Event::DateChanged, EventArgs::None))
-    },
-    _ => None
+  match inner.decoders_of(&id).and_then(|d| d.notify) {
+    Some(decode) => decode(handle, nmhdr).map(|(evt, args)| (id, evt, args)),
+    None => None
   }
 }
 
 /**
-  Parse the common controls notification passed through the `WM_COMMAND` message.
+  Decode a `WM_COMMAND` sent by an accelerator. If the accelerator is bound to an enabled menu item, the accelerator
+  event is raised here and the menu item event is returned.
 */
 #[inline(always)]
-fn parse_notify(id: u64, control_type: ControlType, w: WPARAM) -> Option<(u64, Event, EventArgs)> {
-  match control_type {
-    ControlType::DatePicker => parse_datepicker_command(id, w as u32),
+#[cfg(windows)]
+unsafe fn decode_accelerator<ID: Hash+Clone+'static>(inner: &mut UiInner<ID>, cmd: WORD) -> Option<(u64, Event, EventArgs)> {
+  use low::menu_helper::is_menuitem_enabled;
+
+  let id = match inner.inner_id_from_handle( &AnyHandle::Custom(TypeId::of::<Accelerator>(), cmd as usize) ) {
+    Some(id) => id,
+    None => { return None; }
+  };
+
+  let accel: &mut Box<Accelerator> = mem::transmute( inner.controls.get(&id).unwrap().as_ptr() );
+  let item = match accel.get_item() {
+    Some(AnyHandle::HMENU_ITEM(menu, uid)) if is_menuitem_enabled(menu, None, Some(uid)) => inner.inner_id_from_handle( &AnyHandle::HMENU_ITEM(menu, uid) ),
     _ => None
+  };
+
+  match item {
+    Some(item_id) => {
+      inner.trigger(id, Event::Triggered, EventArgs::None);
+      Some( (item_id, Event::Triggered, EventArgs::None) )
+    },
+    None => Some( (id, Event::Triggered, EventArgs::None) )
   }
 }
 
 /**
-  Parse the common controls notification passed through the `WM_COMMAND` message.
+  Decode a notification sent by a tray icon to the Ui message window. On right click, the context menu event
+  is raised here and then the menu attached to the icon is shown.
 */
 #[inline(always)]
-fn parse_command(id: u64, control_type: ControlType, w: WPARAM) -> Option<(u64, Event, EventArgs)> {
-  use winapi::HIWORD;
+#[cfg(windows)]
+unsafe fn decode_tray<ID: Hash+Clone+'static>(inner: &mut UiInner<ID>, hwnd: HWND, w: WPARAM, l: LPARAM) -> Option<(u64, Event, EventArgs)> {
+  use winapi::{WM_LBUTTONDBLCLK, WM_CONTEXTMENU, GET_X_LPARAM, GET_Y_LPARAM, LOWORD, HIWORD};
+  use low::defs::{NIN_SELECT, NIN_KEYSELECT};
+  use low::menu_helper::popup_menu;
+  use controls::tray::tray_menu_id;
+
+  let id = match inner.inner_id_from_handle( &AnyHandle::Custom(TypeId::of::<TrayIcon>(), HIWORD(l as DWORD) as usize) ) {
+    Some(id) => id,
+    None => { return None; }
+  };
 
-  let ncode = HIWORD(w as DWORD) as u32;
-  match control_type {
-    ControlType::ListBox => parse_listbox_command(id, ncode),
-    ControlType::Button => parse_button_command(id, ncode),
-    ControlType::TextInput | ControlType::TextBox => parse_edit_command(id, ncode),
-    ControlType::Label => parse_static_command(id, ncode),
-    ControlType::DatePicker => parse_datepicker_command(id, ncode),
+  match LOWORD(l as DWORD) as UINT {
+    NIN_SELECT | NIN_KEYSELECT => Some( (id, Event::Click, EventArgs::None) ),
+    WM_LBUTTONDBLCLK => Some( (id, Event::DoubleClick, EventArgs::None) ),
+    WM_CONTEXTMENU => {
+      // With NOTIFYICON_VERSION_4, the cursor position is sent in WPARAM
+      let (x, y) = (GET_X_LPARAM(w as LPARAM), GET_Y_LPARAM(w as LPARAM));
+      inner.trigger(id, Event::ContextMenu, EventArgs::Position(x, y));
+
+      // The callbacks might have removed the icon or its menu. The menu handle is resolved from its id.
+      let menu = match inner.controls.get(&id) {
+        Some(tray) => { let tray: &mut Box<TrayIcon> = mem::transmute(tray.as_ptr()); tray_menu_id(tray) },
+        None => None
+      };
+
+      if let Some(Ok(AnyHandle::HMENU(menu))) = menu.map(|m| inner.handle_of(m)) {
+        popup_menu(menu, hwnd, x, y);
+      }
+
+      None
+    },
     _ => None
   }
 }
 
 /**
-  Proc that dispatches the NWG events
+  Proc that dispatches the NWG events. The events are processed over the backend of the Ui that owns the window.
 */
 #[allow(unused_variables)]
 unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM, id: UINT_PTR, data: DWORD_PTR) -> LRESULT {
-  use comctl32::DefSubclassProc;
-  use user32::GetClientRect;
-  use winapi::{WM_KEYDOWN, WM_KEYUP, WM_UNICHAR, WM_CHAR, UNICODE_NOCHAR, WM_MENUCOMMAND, WM_CLOSE, WM_LBUTTONUP, WM_LBUTTONDOWN, 
-    WM_RBUTTONUP, WM_RBUTTONDOWN, WM_MBUTTONUP, WM_MBUTTONDOWN, WM_COMMAND, WM_TIMER, WM_MOVE, WM_SIZING, WM_EXITSIZEMOVE, WM_SIZE,
-    WM_PAINT, WM_NOTIFY, c_int, LOWORD, HIWORD, RECT, NMHDR};
-  use low::menu_helper::get_menu_id;
-  use low::defs::{NWG_CUSTOM_MIN, NWG_CUSTOM_MAX};
+  use low::backend;
 
   let inner: &mut UiInner<ID> = mem::transmute(data);
+  let backend = inner.messages.backend.clone();
+  backend::with(&backend, || handle_event(inner, hwnd, msg, w, l))
+}
+
+unsafe fn handle_event<ID: Hash+Clone+'static>(inner: &mut UiInner<ID>, hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+  use low::window_helper::def_subclass_proc;
+  use winapi::{WM_KEYDOWN, WM_KEYUP, WM_UNICHAR, WM_CHAR, UNICODE_NOCHAR, WM_CLOSE, WM_LBUTTONUP, WM_LBUTTONDOWN, 
+    WM_RBUTTONUP, WM_RBUTTONDOWN, WM_MBUTTONUP, WM_MBUTTONDOWN, WM_MOUSELEAVE, WM_COMMAND, WM_MOVE, WM_SIZING, WM_EXITSIZEMOVE, WM_SIZE,
+    WM_PAINT, WM_NOTIFY, WM_CLIPBOARDUPDATE, LOWORD, HIWORD};
+  #[cfg(windows)]
+  use winapi::{WM_MENUCOMMAND, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_TIMER, WM_DROPFILES, WM_CONTEXTMENU, WM_SETCURSOR, HTCLIENT, c_int};
+  use low::window_helper::get_window_size;
+  use low::defs::{NWG_CUSTOM_MIN, NWG_CUSTOM_MAX};
+  #[cfg(windows)] use low::defs::NWG_TRAY;
+
   let inner_id: u64;
 
   let callback_data = match msg {
@@ -134,29 +172,15 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_PAINT)");;
       Some( (inner_id, Event::Paint, EventArgs::None) )
     },
-    WM_COMMAND => {
-      if l == 0 { 
-        None 
-      } else {
-        // Somehow, WM_COMMAND messages get sent while freeing and so inner_id_from_handle can fail...
-        let nhandle: HWND = mem::transmute(l);
-        if let Some(id) = inner.inner_id_from_handle( &AnyHandle::HWND(nhandle) ) {
-          let control_type = (&mut *inner.controls.get(&id).expect("Could not find a control with with the specified type ID").as_ptr()).control_type();
-          parse_command(id, control_type, w)
-        } else {
-          None
-        }
-      }
+    #[cfg(windows)]
+    WM_COMMAND if l == 0 => {
+      // Accelerators send a notification code of 1
+      if HIWORD(w as DWORD) == 1 { decode_accelerator(inner, LOWORD(w as DWORD)) } else { None }
     },
+    WM_COMMAND if l != 0 => decode_command(inner, mem::transmute(l), w),
     WM_NOTIFY => {
       // WM_NOTIFY is the new WM_COMMAND for the new windows controls
-      let nmdr: &NMHDR = mem::transmute(l);
-      if let Some(id) = inner.inner_id_from_handle( &AnyHandle::HWND(nmdr.hwndFrom) ) {
-        let control_type = (&mut *inner.controls.get(&id).expect("Could not find a control with with the specified type ID").as_ptr()).control_type();
-        parse_notify(id, control_type, nmdr.code as WPARAM)
-      } else {
-        None
-      }
+      decode_notify(inner, mem::transmute(l))
     },
     WM_LBUTTONUP | WM_RBUTTONUP  | WM_MBUTTONUP => {
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_LBUTTONUP | WM_RBUTTONUP  | WM_MBUTTONUP)");;
@@ -166,12 +190,33 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN)");;
       Some( (inner_id, Event::MouseDown, parse_mouse_click(msg, l)) )
     },
+    #[cfg(windows)]
+    WM_MOUSEMOVE => {
+      inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_MOUSEMOVE)");
+      // The system does not send a message when the mouse enters a window. The first move raises the enter event.
+      if track_mouse_leave(hwnd) {
+        inner.trigger(inner_id, Event::MouseEnter, EventArgs::None);
+      }
+      Some( (inner_id, Event::MouseMove, parse_mouse_move(w, l)) )
+    },
+    WM_MOUSELEAVE => {
+      inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_MOUSELEAVE)");
+      Some( (inner_id, Event::MouseLeave, EventArgs::None) )
+    },
+    #[cfg(windows)]
+    WM_MOUSEWHEEL => {
+      inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_MOUSEWHEEL)");
+      Some( (inner_id, Event::MouseWheel, parse_mouse_wheel(hwnd, w, l)) )
+    },
     WM_KEYDOWN | WM_KEYUP => {
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_KEYDOWN | WM_KEYUP)");;
       let evt = if msg == WM_KEYDOWN { Event::KeyDown } else { Event::KeyUp };
       Some( (inner_id, evt, EventArgs::Key(w as u32)) )
     },
+    #[cfg(windows)]
     WM_MENUCOMMAND => {
+      use low::menu_helper::get_menu_id;
+
       let parent_menu: HMENU = mem::transmute(l);
       let handle = AnyHandle::HMENU_ITEM(parent_menu, get_menu_id(parent_menu, w as c_int));
 
@@ -191,6 +236,21 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
         None
       }
     },
+    #[cfg(windows)]
+    WM_SETCURSOR => {
+      use user32::SetCursor;
+      use low::window_helper::get_window_cursor;
+
+      // The message is forwarded to the parents of the window under the mouse. Only the window under the mouse sets its cursor.
+      if w as HWND == hwnd && LOWORD(l as DWORD) as c_int == HTCLIENT {
+        if let Some(cursor) = get_window_cursor(hwnd) {
+          SetCursor(cursor);
+          return 1;
+        }
+      }
+      None
+    },
+    #[cfg(windows)]
     WM_TIMER => {
       let handle = AnyHandle::Custom(TypeId::of::<Timer>(), w as usize);
 
@@ -210,24 +270,39 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
     },
     WM_SIZING | WM_SIZE => {
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_SIZING)");
-      let mut r: RECT = mem::uninitialized();
-      GetClientRect(hwnd, &mut r);
-      let w: u32 = (r.right-r.left) as u32;
-      let h: u32 = (r.bottom-r.top) as u32;
+      let (w, h) = get_window_size(hwnd);
       Some( (inner_id, Event::Resized, EventArgs::Size(w, h)) )
     },
     WM_EXITSIZEMOVE => {
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_SIZING)");
-      let mut r: RECT = mem::uninitialized();
-      GetClientRect(hwnd, &mut r);
-      let w: u32 = (r.right-r.left) as u32;
-      let h: u32 = (r.bottom-r.top) as u32;
+      let (w, h) = get_window_size(hwnd);
       Some( (inner_id, Event::Resized, EventArgs::Size(w, h)) )
     }
     WM_CLOSE => {
       inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_CLOSE)");
       Some( (inner_id, Event::Closed, EventArgs::None) )
     },
+    #[cfg(windows)]
+    WM_DROPFILES => {
+      inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_DROPFILES)");
+      Some( (inner_id, Event::FilesDropped, parse_dropped_files(mem::transmute(w))) )
+    },
+    WM_CLIPBOARDUPDATE => {
+      inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_CLIPBOARDUPDATE)");
+      Some( (inner_id, Event::ClipboardChanged, EventArgs::None) )
+    },
+    #[cfg(windows)]
+    WM_CONTEXTMENU => {
+      // The message is forwarded to the parent if the child does not handle it. Only the control that was clicked raises the event.
+      if w as HWND == hwnd {
+        inner_id = inner.inner_id_from_handle( &AnyHandle::HWND(hwnd) ).expect("Could not match system handle to ui control (msg: WM_CONTEXTMENU)");
+        parse_context_menu(hwnd, l).map(|args| (inner_id, Event::ContextMenu, args))
+      } else {
+        None
+      }
+    },
+    #[cfg(windows)]
+    NWG_TRAY => decode_tray(inner, hwnd, w, l),
     _ => { None }
   };
 
@@ -242,32 +317,31 @@ unsafe extern "system" fn process_events<ID: Hash+Clone+'static>(hwnd: HWND, msg
     }
   }
 
-  DefSubclassProc(hwnd, msg, w, l)
+  def_subclass_proc(hwnd, msg, w, l)
 }
 
 /**
     Add a subclass that dispatches the system event to the application callbacks to a window control.
 */
 pub fn hook_window_events<ID: Hash+Clone+'static>(uiinner: &mut UiInner<ID>, handle: HWND) { unsafe {
-  use comctl32::SetWindowSubclass;
+  use low::backend;
 
   // While definitely questionable in term of safety, the reference to the UiInner is actually (always)
   // a raw pointer belonging to a Ui. Also, when the Ui goes out of scope, every window control
   // gets destroyed BEFORE the UiInner, this guarantees that uinner lives long enough.
   let ui_inner_raw: *mut UiInner<ID> = uiinner as *mut UiInner<ID>;
-  SetWindowSubclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID, mem::transmute(ui_inner_raw));
+  backend::current().set_subclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID, mem::transmute(ui_inner_raw));
 }}
 
 /**
   Remove a subclass and free the associated data
 */
 pub fn unhook_window_events<ID: Hash+Clone+'static>(handle: HWND) { unsafe {
-  use comctl32::{RemoveWindowSubclass, GetWindowSubclass};
-  use winapi::{TRUE};
+  use low::backend;
 
-  let mut data: DWORD_PTR = 0;
-  if GetWindowSubclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID, &mut data) == TRUE {
-    RemoveWindowSubclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID);
+  let backend = backend::current();
+  if backend.get_subclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID).is_some() {
+    backend.remove_subclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID);
   }
 }}
 
@@ -275,19 +349,18 @@ pub fn unhook_window_events<ID: Hash+Clone+'static>(handle: HWND) { unsafe {
   Check if a window is hooked by nwg. If it is, return its ID, if not return None
 */
 pub unsafe fn window_id<ID: Clone+Hash>(handle: HWND, inner_ref: *mut UiInner<ID>) -> Option<u64> {
-  use comctl32::GetWindowSubclass;
-  use winapi::{TRUE};
-
-  let mut data: DWORD_PTR = 0;
-  if GetWindowSubclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID, &mut data) == TRUE {
-    let data: *mut UiInner<ID> = mem::transmute(data);
-    if data == inner_ref {
-      (&*data).inner_id_from_handle( &AnyHandle::HWND(handle) )
-    } else {
-      None
-    }
-  } else {
-    None
+  use low::backend;
+
+  match backend::current().get_subclass(handle, Some(process_events::<ID>), EVENTS_DISPATCH_ID) {
+    Some(data) => {
+      let data: *mut UiInner<ID> = mem::transmute(data);
+      if data == inner_ref {
+        (&*data).inner_id_from_handle( &AnyHandle::HWND(handle) )
+      } else {
+        None
+      }
+    },
+    None => None
   }
 }
 
@@ -297,27 +370,66 @@ pub unsafe fn window_id<ID: Clone+Hash>(handle: HWND, inner_ref: *mut UiInner<ID
     Return once a quit event was received.
 */
 #[inline(always)]
+#[cfg(windows)]
 pub unsafe fn dispatch_events() {
   use winapi::MSG;
   use user32::{GetMessageW, TranslateMessage, DispatchMessageW};
+  use low::accel_helper::translate_accelerator;
 
   let mut msg: MSG = mem::uninitialized();
   while GetMessageW(&mut msg, ptr::null_mut(), 0, 0) != 0 {
+      if translate_accelerator(&mut msg) { continue; }
       TranslateMessage(&msg); 
       DispatchMessageW(&msg); 
       // TODO dispatch events sent from other thread / other processes ( after first stable release )
   }
 }
 
+/**
+    Dispatch the messages of a modal dialog until `running` returns false or until the dialog is hidden.
+
+    When Enter or Esc is pressed in the dialog, the key code is sent to `on_key` instead of the control that has the focus,
+    unless this control wants the key (ex: a multiline TextBox). If a quit message is received, it is posted again so that
+    the parent event loop also returns.
+*/
+#[cfg(windows)]
+pub unsafe fn dispatch_modal_events<F1: Fn() -> bool, F2: FnMut(u32)>(dialog: HWND, running: F1, mut on_key: F2) {
+  use winapi::{MSG, WM_KEYDOWN, WM_GETDLGCODE, VK_RETURN, VK_ESCAPE, c_int};
+  use user32::{GetMessageW, TranslateMessage, DispatchMessageW, IsWindowVisible, IsChild, SendMessageW, PostQuitMessage};
+  use low::accel_helper::translate_accelerator;
+  use low::defs::DLGC_WANTALLKEYS;
+
+  let mut msg: MSG = mem::uninitialized();
+  while running() && IsWindowVisible(dialog) != 0 {
+      if GetMessageW(&mut msg, ptr::null_mut(), 0, 0) == 0 {
+          PostQuitMessage(msg.wParam as c_int);
+          break;
+      }
+
+      let dialog_key = msg.message == WM_KEYDOWN && (msg.wParam == VK_RETURN as WPARAM || msg.wParam == VK_ESCAPE as WPARAM);
+      if dialog_key && (msg.hwnd == dialog || IsChild(dialog, msg.hwnd) != 0) {
+          let code = SendMessageW(msg.hwnd, WM_GETDLGCODE, msg.wParam, mem::transmute(&mut msg));
+          if code & DLGC_WANTALLKEYS == 0 {
+              on_key(msg.wParam as u32);
+              continue;
+          }
+      }
+
+      if translate_accelerator(&mut msg) { continue; }
+      TranslateMessage(&msg);
+      DispatchMessageW(&msg);
+  }
+}
+
 /**
     Send a WM_QUIT to the system queue. Breaks the dispatch_events loop.
 */
 #[inline(always)]
 pub unsafe fn exit() {
-  use user32::PostMessageW;
+  use low::backend;
   use winapi::WM_QUIT;
 
-  PostMessageW(ptr::null_mut(), WM_QUIT, 0, 0);
+  backend::current().post_message(ptr::null_mut(), WM_QUIT, 0, 0);
 }
 
 fn parse_mouse_click(msg: UINT, l: LPARAM) -> EventArgs {
@@ -336,4 +448,119 @@ fn parse_mouse_click(msg: UINT, l: LPARAM) -> EventArgs {
   let y = GET_Y_LPARAM(l) as i32;
 
   EventArgs::MouseClick{btn: btn, pos: (x, y)}
-}
\ No newline at end of file
+}
+
+/**
+  Ask the system to send a `WM_MOUSELEAVE` when the mouse leaves the window. The request ends once the message is sent.
+  Return `false` if the window is already tracked.
+*/
+#[cfg(windows)]
+unsafe fn track_mouse_leave(hwnd: HWND) -> bool {
+  use user32::TrackMouseEvent;
+  use winapi::{TRACKMOUSEEVENT, TME_LEAVE, TME_QUERY};
+
+  let mut tme = TRACKMOUSEEVENT{ cbSize: mem::size_of::<TRACKMOUSEEVENT>() as DWORD, dwFlags: TME_QUERY, hwndTrack: ptr::null_mut(), dwHoverTime: 0 };
+  TrackMouseEvent(&mut tme);
+  if tme.dwFlags & TME_LEAVE == TME_LEAVE && tme.hwndTrack == hwnd {
+    return false;
+  }
+
+  tme = TRACKMOUSEEVENT{ cbSize: mem::size_of::<TRACKMOUSEEVENT>() as DWORD, dwFlags: TME_LEAVE, hwndTrack: hwnd, dwHoverTime: 0 };
+  TrackMouseEvent(&mut tme) != 0
+}
+
+/**
+  Read the mouse buttons and the modifier keys from the flags sent with the mouse messages.
+  Alt is not in the flags, so its state is read from the keyboard state.
+*/
+#[cfg(windows)]
+unsafe fn parse_mouse_state(flags: WPARAM) -> MouseState {
+  use user32::GetKeyState;
+  use winapi::{MK_LBUTTON, MK_RBUTTON, MK_MBUTTON, MK_CONTROL, MK_SHIFT, VK_MENU};
+
+  MouseState {
+    left: flags & MK_LBUTTON != 0,
+    right: flags & MK_RBUTTON != 0,
+    middle: flags & MK_MBUTTON != 0,
+    ctrl: flags & MK_CONTROL != 0,
+    shift: flags & MK_SHIFT != 0,
+    alt: GetKeyState(VK_MENU) < 0
+  }
+}
+
+#[cfg(windows)]
+unsafe fn parse_mouse_move(w: WPARAM, l: LPARAM) -> EventArgs {
+  use winapi::{GET_X_LPARAM, GET_Y_LPARAM};
+
+  let pos = (GET_X_LPARAM(l) as i32, GET_Y_LPARAM(l) as i32);
+  EventArgs::MouseMove{pos: pos, state: parse_mouse_state(w)}
+}
+
+#[cfg(windows)]
+unsafe fn parse_mouse_wheel(hwnd: HWND, w: WPARAM, l: LPARAM) -> EventArgs {
+  use user32::ScreenToClient;
+  use winapi::{POINT, LOWORD, GET_X_LPARAM, GET_Y_LPARAM, GET_WHEEL_DELTA_WPARAM};
+
+  // The position of a wheel message is in screen coordinates
+  let mut pt = POINT{ x: GET_X_LPARAM(l), y: GET_Y_LPARAM(l) };
+  ScreenToClient(hwnd, &mut pt);
+
+  let state = parse_mouse_state(LOWORD(w as DWORD) as WPARAM);
+  EventArgs::MouseWheel{pos: (pt.x as i32, pt.y as i32), delta: GET_WHEEL_DELTA_WPARAM(w) as i32, state: state}
+}
+
+/**
+  Return the position of a context menu request in screen coordinates. A request sent from the keyboard (Shift+F10 or
+  the menu key) has no position, so the cursor position is used. Return `None` if the request comes from outside the client area,
+  because the system shows the window menu when the title bar is right clicked.
+*/
+#[cfg(windows)]
+unsafe fn parse_context_menu(hwnd: HWND, l: LPARAM) -> Option<EventArgs> {
+  use user32::{GetCursorPos, ScreenToClient, GetClientRect};
+  use winapi::{POINT, RECT, GET_X_LPARAM, GET_Y_LPARAM};
+
+  let (x, y) = (GET_X_LPARAM(l), GET_Y_LPARAM(l));
+  if x == -1 && y == -1 {
+    let mut pt = POINT{ x: 0, y: 0 };
+    GetCursorPos(&mut pt);
+    return Some(EventArgs::Position(pt.x as i32, pt.y as i32));
+  }
+
+  let mut pt = POINT{ x: x, y: y };
+  let mut r: RECT = mem::zeroed();
+  ScreenToClient(hwnd, &mut pt);
+  GetClientRect(hwnd, &mut r);
+
+  if pt.x >= r.left && pt.x < r.right && pt.y >= r.top && pt.y < r.bottom {
+    Some(EventArgs::Position(x as i32, y as i32))
+  } else {
+    None
+  }
+}
+
+/**
+  Read the paths and the drop point of the files dropped on a window. The drop handle is freed.
+*/
+#[cfg(windows)]
+unsafe fn parse_dropped_files(drop: HDROP) -> EventArgs {
+  use winapi::POINT;
+  use low::defs::{DragQueryFileW, DragQueryPoint, DragFinish};
+  use low::other_helper::from_utf16;
+
+  // Passing 0xFFFFFFFF as the file index returns the number of files
+  let count = DragQueryFileW(drop, 0xFFFFFFFF, ptr::null_mut(), 0);
+  let mut paths = Vec::with_capacity(count as usize);
+  for i in 0..count {
+    // The returned length does not include the terminating null character
+    let len = DragQueryFileW(drop, i, ptr::null_mut(), 0) + 1;
+    let mut buffer: Vec<u16> = vec![0; len as usize];
+    DragQueryFileW(drop, i, buffer.as_mut_ptr(), len);
+    paths.push(from_utf16(&buffer));
+  }
+
+  let mut pt = POINT{ x: 0, y: 0 };
+  DragQueryPoint(drop, &mut pt);
+  DragFinish(drop);
+
+  EventArgs::FilesDropped{paths: paths, pos: (pt.x as i32, pt.y as i32)}
+}
@@ -20,12 +20,19 @@
 use std::ptr;
 use std::mem;
 use std::hash::Hash;
+use std::cell::RefCell;
+use std::collections::HashMap;
 
-use winapi::{HMENU, DWORD, HBRUSH, c_int, UINT, BOOL};
+use winapi::{HMENU, HWND, HBITMAP, DWORD, HBRUSH, c_int, UINT, BOOL, MENUITEMINFOW};
 
 use ui::UiInner;
 use controls::AnyHandle;
 
+thread_local!(
+    /// The popup menus owned by each window. A popup menu is freed with its owner.
+    static POPUP_MENUS: RefCell<HashMap<usize, Vec<usize>>> = RefCell::new(HashMap::new())
+);
+
 /**
     List the children of a menu and return a list of their IDs. The function is recursive and so 
     it list the ids for the whole menu tree.
@@ -152,7 +159,7 @@ pub unsafe fn use_menu_command(h: HMENU) {
 #[inline(always)]
 pub unsafe fn enable_menuitem(h: HMENU, pos: Option<UINT>, id: Option<UINT>, enabled: bool) {
     use winapi::MENUITEMINFOW;
-    use low::defs::{SetMenuItemInfoW, GetMenuItemCount, MIIM_STATE, MFS_DISABLED, MFS_ENABLED};
+    use low::defs::{SetMenuItemInfoW, GetMenuItemInfoW, GetMenuItemCount, MIIM_STATE, MFS_DISABLED, MFS_ENABLED};
     
     let use_position = id.is_none();
     let choice = if use_position { pos } else { id };
@@ -168,12 +175,16 @@ pub unsafe fn enable_menuitem(h: HMENU, pos: Option<UINT>, id: Option<UINT>, ena
 
     let mut info = MENUITEMINFOW { 
         cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
-        fMask: MIIM_STATE, fType: 0, fState: state,
+        fMask: MIIM_STATE, fType: 0, fState: 0,
         wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
         hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
         cch: 0, hbmpItem: ptr::null_mut()
     };
 
+    // Keep the other states (checked, default) of the item
+    GetMenuItemInfoW(h, value, use_position as BOOL, &mut info);
+    info.fState = (info.fState & !MFS_DISABLED) | state;
+
     SetMenuItemInfoW(h, value, use_position as BOOL, &mut info);
 }
 
@@ -233,4 +244,213 @@ pub unsafe fn is_menu_enabled(menu: HMENU, parent: &AnyHandle) -> bool {
 #[inline(always)]
 pub unsafe fn get_menu_id(parent_h: HMENU, index: c_int) -> UINT {
     ::low::defs::GetMenuItemID(parent_h, index)
-}
\ No newline at end of file
+}
+
+/**
+    Return the text of a menuitem using its ID.
+*/
+pub unsafe fn get_menuitem_text(h: HMENU, id: UINT) -> String {
+    use winapi::MENUITEMINFOW;
+    use low::defs::{GetMenuItemInfoW, MIIM_STRING};
+    use low::other_helper::from_utf16;
+
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_STRING, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: ptr::null_mut(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    // First call to get the text length, second call to get the text
+    if GetMenuItemInfoW(h, id, false as BOOL, &mut info) == 0 { return String::new(); }
+
+    let mut buffer: Vec<u16> = vec![0; (info.cch + 1) as usize];
+    info.cch += 1;
+    info.dwTypeData = buffer.as_mut_ptr();
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+
+    from_utf16(&buffer)
+}
+
+/**
+    Set the text of a menuitem using its ID.
+*/
+pub unsafe fn set_menuitem_text(h: HMENU, id: UINT, text: &str) {
+    use winapi::MENUITEMINFOW;
+    use low::defs::{SetMenuItemInfoW, MIIM_STRING};
+    use low::other_helper::to_utf16;
+
+    let mut text = to_utf16(text);
+    let mut info = MENUITEMINFOW { 
+        cbSize: mem::size_of::<MENUITEMINFOW>() as UINT,
+        fMask: MIIM_STRING, fType: 0, fState: 0,
+        wID: 0, hSubMenu: ptr::null_mut(), hbmpChecked: ptr::null_mut(),
+        hbmpUnchecked: ptr::null_mut(), dwItemData: 0, dwTypeData: text.as_mut_ptr(),
+        cch: 0, hbmpItem: ptr::null_mut()
+    };
+
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Return an empty menuitem info struct that reads or writes the members selected by `mask`.
+*/
+#[inline(always)]
+unsafe fn menuitem_info(mask: UINT) -> MENUITEMINFOW {
+    let mut info: MENUITEMINFOW = mem::zeroed();
+    info.cbSize = mem::size_of::<MENUITEMINFOW>() as UINT;
+    info.fMask = mask;
+    info
+}
+
+/**
+    Return the state flags (MFS_*) of a menuitem using its ID.
+*/
+pub unsafe fn get_menuitem_state(h: HMENU, id: UINT) -> UINT {
+    use low::defs::{GetMenuItemInfoW, MIIM_STATE};
+
+    let mut info = menuitem_info(MIIM_STATE);
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+    info.fState
+}
+
+/**
+    Set or clear the state flags (MFS_*) in `flags` of a menuitem using its ID. The other states are kept.
+*/
+pub unsafe fn set_menuitem_state(h: HMENU, id: UINT, flags: UINT, set: bool) {
+    use low::defs::{SetMenuItemInfoW, MIIM_STATE};
+
+    let mut info = menuitem_info(MIIM_STATE);
+    let state = get_menuitem_state(h, id);
+    info.fState = if set { state | flags } else { state & !flags };
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Return `true` if a menuitem shows a radio bullet instead of a check mark when it is checked.
+*/
+pub unsafe fn is_menuitem_radio(h: HMENU, pos: UINT) -> bool {
+    use low::defs::{GetMenuItemInfoW, MIIM_FTYPE, MFT_RADIOCHECK};
+
+    let mut info = menuitem_info(MIIM_FTYPE);
+    GetMenuItemInfoW(h, pos, true as BOOL, &mut info);
+    info.fType & MFT_RADIOCHECK == MFT_RADIOCHECK
+}
+
+/**
+    Make a menuitem show a radio bullet instead of a check mark when it is checked.
+*/
+pub unsafe fn set_menuitem_radio(h: HMENU, id: UINT, radio: bool) {
+    set_menuitem_radio_type(h, id, false, radio);
+}
+
+unsafe fn set_menuitem_radio_type(h: HMENU, item: UINT, by_position: bool, radio: bool) {
+    use low::defs::{GetMenuItemInfoW, SetMenuItemInfoW, MIIM_FTYPE, MFT_RADIOCHECK};
+
+    let mut info = menuitem_info(MIIM_FTYPE);
+    GetMenuItemInfoW(h, item, by_position as BOOL, &mut info);
+    info.fType = if radio { info.fType | MFT_RADIOCHECK } else { info.fType & !MFT_RADIOCHECK };
+    SetMenuItemInfoW(h, item, by_position as BOOL, &mut info);
+}
+
+/**
+    Check a radio menuitem and uncheck the other items of its group. A radio group is a run of
+    radio items that are next to each other in the same menu.
+*/
+pub unsafe fn check_menuitem_radio(h: HMENU, id: UINT) {
+    use user32::CheckMenuRadioItem;
+    use low::defs::{GetMenuItemCount, GetMenuItemID, MF_BYPOSITION};
+
+    let count = GetMenuItemCount(h) as UINT;
+    let pos = match (0..count).find(|&i| GetMenuItemID(h, i as c_int) == id) {
+        Some(p) => p,
+        None => { return; }
+    };
+
+    let mut first = pos;
+    while first > 0 && is_menuitem_radio(h, first - 1) { first -= 1; }
+
+    let mut last = pos;
+    while last + 1 < count && is_menuitem_radio(h, last + 1) { last += 1; }
+
+    CheckMenuRadioItem(h, first, last, pos, MF_BYPOSITION);
+
+    // The system also clears the radio type of the unchecked items. It is set back so that the group can be found next time.
+    for i in first..(last + 1) {
+        if i != pos { set_menuitem_radio_type(h, i, true, true); }
+    }
+}
+
+/**
+    Return the bitmap shown beside a menuitem using its ID. The handle is null if the item does not have a bitmap.
+*/
+pub unsafe fn get_menuitem_bitmap(h: HMENU, id: UINT) -> HBITMAP {
+    use low::defs::{GetMenuItemInfoW, MIIM_BITMAP};
+
+    let mut info = menuitem_info(MIIM_BITMAP);
+    GetMenuItemInfoW(h, id, false as BOOL, &mut info);
+    info.hbmpItem
+}
+
+/**
+    Set the bitmap shown beside a menuitem using its ID. If `bitmap` is null, the bitmap is removed.
+    The bitmap is not copied and must live as long as the item.
+*/
+pub unsafe fn set_menuitem_bitmap(h: HMENU, id: UINT, bitmap: HBITMAP) {
+    use low::defs::{SetMenuItemInfoW, MIIM_BITMAP};
+
+    let mut info = menuitem_info(MIIM_BITMAP);
+    info.hbmpItem = bitmap;
+    SetMenuItemInfoW(h, id, false as BOOL, &mut info);
+}
+
+/**
+    Show a menu as a popup at the (`x`, `y`) screen position and return when the menu is closed.
+    The menu commands are sent to `owner` as `WM_MENUCOMMAND`.
+*/
+pub unsafe fn popup_menu(h: HMENU, owner: HWND, x: c_int, y: c_int) {
+    use user32::{SetForegroundWindow, PostMessageW};
+    use winapi::WM_NULL;
+    use low::defs::{TrackPopupMenuEx, TPM_RIGHTBUTTON};
+
+    // If the owner is not in the foreground, the menu is not closed when the user clicks outside of it.
+    // The dummy message makes the owner process its queue so that the next popup opens correctly.
+    SetForegroundWindow(owner);
+    TrackPopupMenuEx(h, TPM_RIGHTBUTTON, x, y, owner, ptr::null_mut());
+    PostMessageW(owner, WM_NULL, 0, 0);
+}
+
+/**
+    Register a popup menu as a child of its owner window
+*/
+pub fn add_popup_menu(owner: HWND, menu: HMENU) {
+    POPUP_MENUS.with(|popups| {
+        popups.borrow_mut().entry(owner as usize).or_insert(Vec::new()).push(menu as usize);
+    });
+}
+
+/**
+    Remove a popup menu from the children of its owner window
+*/
+pub fn remove_popup_menu(owner: HWND, menu: HMENU) {
+    POPUP_MENUS.with(|popups| {
+        let mut popups = popups.borrow_mut();
+        let empty = match popups.get_mut(&(owner as usize)) {
+            Some(menus) => { menus.retain(|&m| m != menu as usize); menus.len() == 0 },
+            None => false
+        };
+
+        if empty { popups.remove(&(owner as usize)); }
+    });
+}
+
+/**
+    Return the handles of the popup menus owned by a window
+*/
+pub fn list_popup_menus(owner: HWND) -> Vec<HMENU> {
+    POPUP_MENUS.with(|popups| match popups.borrow().get(&(owner as usize)) {
+        Some(menus) => menus.iter().map(|&m| m as HMENU).collect(),
+        None => Vec::new()
+    })
+}
@@ -23,11 +23,12 @@ use std::ptr;
 use std::hash::Hash;
 use std::marker::PhantomData;
 use std::any::Any;
+use std::rc::Rc;
 
 use winapi::{HWND, UINT, WPARAM, LPARAM, LRESULT};
 
 use ui::UiInner;
-use low::other_helper::to_utf16;
+use low::backend::Backend;
 use error::{Error, SystemError};
 
 /// Unique class name that identify the nwg message-only windows.
@@ -40,6 +41,7 @@ const MESSAGE_HANDLE_CLASS_NAME: &'static str = "NWG_MESSAGE";
 */
 pub struct MessageHandler<ID: Hash+Clone+'static> {
     pub hwnd: HWND,
+    pub backend: Rc<Backend>,
     pub last_error: Option<Error>,
     pub p: PhantomData<ID>
 }
@@ -47,18 +49,19 @@ pub struct MessageHandler<ID: Hash+Clone+'static> {
 impl<ID: Hash+Clone+'static> MessageHandler<ID> {
 
     /**
-        Create a new message handle. 
+        Create a new message handle using `backend`.
 
         * If the window creation was successful, returns the new message handler
         * If the system was not capable to create the window, returns a `Error::System`
     */
-    pub fn new() -> Result<MessageHandler<ID>, Error> {
-        let hwnd_result = unsafe{ create_message_only_window::<ID>() };
+    pub fn new(backend: Rc<Backend>) -> Result<MessageHandler<ID>, Error> {
+        let hwnd_result = unsafe{ create_message_only_window::<ID>(&*backend) };
         match hwnd_result {
             Ok(h) => 
             Ok( 
                 MessageHandler::<ID>{ 
                     hwnd: h, 
+                    backend: backend,
                     last_error: None,
                     p: PhantomData,
                 } 
@@ -75,15 +78,16 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
           The following events will not be touched.
     */
     pub fn commit(&mut self) -> Result<(), Error> {
-        use winapi::{MSG, PM_REMOVE};
-        use user32::{PeekMessageW, DispatchMessageW};
         use low::defs::{NWG_CUSTOM_MAX, NWG_CUSTOM_MIN, COMMIT_FAILED};
 
+        let backend = self.backend.clone();
+        let hwnd = self.hwnd;
+
         let ok = unsafe{
             let mut ok = true;
-            let mut msg: MSG = mem::uninitialized();
-            while PeekMessageW(&mut msg, self.hwnd, NWG_CUSTOM_MIN, NWG_CUSTOM_MAX, PM_REMOVE) != 0 {
-                if DispatchMessageW(&msg) == COMMIT_FAILED {
+            while let Some((msg, w, l)) = backend.peek_message(hwnd, NWG_CUSTOM_MIN, NWG_CUSTOM_MAX) {
+                // Only NWG commands are peeked, so the message can be sent directly to the message window proc
+                if message_window_proc::<ID>(hwnd, msg, w, l) == COMMIT_FAILED {
                     ok = false;
                     break;
                 }
@@ -104,14 +108,9 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
         Post a message to the message only queue.
     */
     pub fn post(&self, ui: *mut UiInner<ID>, msg: UINT, data: Box<Any>) {
-        use user32::PostMessageW;
-
         unsafe {
             let ui_wparam: WPARAM = mem::transmute(ui);
-            let data_ptr: *mut Any = Box::into_raw(data);
-            let data_ptr: *mut *mut Any = Box::into_raw(Box::new(data_ptr));
-            let data_lparam: LPARAM = mem::transmute(data_ptr);
-            PostMessageW(self.hwnd, msg, ui_wparam, data_lparam);
+            self.backend.post_message(self.hwnd, msg, ui_wparam, pack_message_data(data));
         }
     }
 
@@ -122,14 +121,27 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
         The class will be freed when the last Ui is freed.
     */
     pub fn free(&self) {
-        use kernel32::GetModuleHandleW;
-        use user32::{DestroyWindow, UnregisterClassW};
+        unsafe{ self.backend.destroy_window(self.hwnd); }
+        unsafe{ self.backend.unregister_sysclass(MESSAGE_HANDLE_CLASS_NAME); }
+    }
+}
 
-        let class_name = to_utf16(MESSAGE_HANDLE_CLASS_NAME);
+/**
+    Pack the parameters of a NWG command in a LPARAM. The parameters must be freed by the message window proc
+    or, if the message could not be posted, by `free_message_data`.
+*/
+pub unsafe fn pack_message_data(data: Box<Any>) -> LPARAM {
+    let data_ptr: *mut Any = Box::into_raw(data);
+    let data_ptr: *mut *mut Any = Box::into_raw(Box::new(data_ptr));
+    mem::transmute(data_ptr)
+}
 
-        unsafe{ DestroyWindow(self.hwnd); }
-        unsafe{ UnregisterClassW(class_name.as_ptr(), GetModuleHandleW(ptr::null_mut())); }
-    }
+/**
+    Free the parameters of a NWG command that could not be posted.
+*/
+pub unsafe fn free_message_data(l: LPARAM) {
+    let args: *mut *mut Any = mem::transmute::<LPARAM, *mut *mut Any>(l);
+    drop(Box::from_raw(*Box::from_raw(args)));
 }
 
 /** 
@@ -141,9 +153,24 @@ impl<ID: Hash+Clone+'static> MessageHandler<ID> {
 */
 #[allow(unused_variables)]
 unsafe extern "system" fn message_window_proc<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
-    use user32::{DefWindowProcW};
-    use low::defs::{NWG_PACK_USER_VALUE, NWG_PACK_CONTROL, NWG_UNPACK, NWG_BIND, NWG_UNBIND, NWG_TRIGGER, NWG_PACK_RESOURCE, COMMIT_SUCCESS, COMMIT_FAILED};
-    use low::defs::{PackUserValueArgs, PackControlArgs, UnpackArgs, BindArgs, UnbindArgs, PackResourceArgs, TriggerArgs};
+    use low::window_helper::def_window_proc;
+    use low::defs::{NWG_CUSTOM_MIN, NWG_CUSTOM_MAX};
+    use low::backend;
+
+    if msg < NWG_CUSTOM_MIN || msg > NWG_CUSTOM_MAX {
+        return def_window_proc(hwnd, msg, w, l);
+    }
+
+    // The commands are executed over the backend of the Ui that sent them
+    let ui: &UiInner<ID> = mem::transmute(w);
+    let backend = ui.messages.backend.clone();
+    backend::with(&backend, || execute_command::<ID>(hwnd, msg, w, l))
+}
+
+unsafe fn execute_command<ID: Hash+Clone+'static>(hwnd: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    use low::window_helper::def_window_proc;
+    use low::defs::{NWG_PACK_USER_VALUE, NWG_PACK_CONTROL, NWG_UNPACK, NWG_BIND, NWG_UNBIND, NWG_TRIGGER, NWG_PACK_RESOURCE, NWG_EXECUTE, NWG_WAKE, COMMIT_SUCCESS, COMMIT_FAILED};
+    use low::defs::{PackUserValueArgs, PackControlArgs, UnpackArgs, BindArgs, UnbindArgs, PackResourceArgs, TriggerArgs, ExecuteArgs, WakeArgs};
 
     let ui: &mut UiInner<ID> = mem::transmute(w);
     let args: *mut *mut Any = mem::transmute::<LPARAM, *mut *mut Any>(l);
@@ -207,6 +234,22 @@ unsafe extern "system" fn message_window_proc<ID: Hash+Clone+'static>(hwnd: HWND
                 panic!("Could not downcast command NWG_UNBIND args into a UnbindArgs struct.");
             }
         },
+        NWG_EXECUTE => {
+            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
+            if let Ok(params) = args.downcast::<ExecuteArgs<ID>>() {
+                (true, ui.execute(*params))
+            } else {
+                panic!("Could not downcast command NWG_EXECUTE args into a ExecuteArgs struct.");
+            }
+        },
+        NWG_WAKE => {
+            let args: Box<Any> = Box::from_raw(*Box::from_raw(args));
+            if let Ok(params) = args.downcast::<WakeArgs>() {
+                (true, ui.poll_task(*params))
+            } else {
+                panic!("Could not downcast command NWG_WAKE args into a WakeArgs struct.");
+            }
+        },
         _ => (false, None)
     };
 
@@ -229,7 +272,7 @@ unsafe extern "system" fn message_window_proc<ID: Hash+Clone+'static>(hwnd: HWND
             COMMIT_SUCCESS
         }
     } else {
-        DefWindowProcW(hwnd, msg, w, l)
+        def_window_proc(hwnd, msg, w, l)
     }
 }
 
@@ -240,15 +283,15 @@ unsafe extern "system" fn message_window_proc<ID: Hash+Clone+'static>(hwnd: HWND
     * If the class creation is successful or the class already exists, returns `Ok`
     * If there was an error while creating the class, returns a `Err(SystemError::UiCreation)`
 */
-unsafe fn setup_class<ID: Hash+Clone+'static>() -> Result<(), SystemError> {
-    use low::window_helper::{SysclassParams, build_sysclass};
+unsafe fn setup_class<ID: Hash+Clone+'static>(backend: &Backend) -> Result<(), SystemError> {
+    use low::window_helper::SysclassParams;
     let params = SysclassParams{ 
-        class_name: MESSAGE_HANDLE_CLASS_NAME, 
+        class_name: MESSAGE_HANDLE_CLASS_NAME.to_string(), 
         sysproc: Some(message_window_proc::<ID>),
         background: Some(ptr::null_mut()), style: None
     };
     
-    if let Err(_) = build_sysclass(params) {
+    if let Err(_) = backend.build_sysclass(params) {
         Err(SystemError::UiCreation)
     } else {
         Ok(())
@@ -256,45 +299,11 @@ unsafe fn setup_class<ID: Hash+Clone+'static>() -> Result<(), SystemError> {
 }
 
 /**
-    Create a NWG message-only window.
-
-    * If the window creation is successful, returns `Ok(window_handle)`  
-    * If the window creation fails, returns `Err(SystemError::UiCreation)`
-*/
-unsafe fn create_window<ID: Hash+Clone>() -> Result<HWND, SystemError> {
-    use kernel32::GetModuleHandleW;
-    use user32::CreateWindowExW;
-    use winapi::HWND_MESSAGE;
-
-    let hmod = GetModuleHandleW(ptr::null_mut());
-    if hmod.is_null() { return Err(SystemError::UiCreation); }
-
-    let class_name = to_utf16(MESSAGE_HANDLE_CLASS_NAME);
-    let window_name = to_utf16("");
-
-    let handle = CreateWindowExW (
-        0, 
-        class_name.as_ptr(), window_name.as_ptr(), 
-        0, 0, 0, 0, 0,
-        HWND_MESSAGE,
-        ptr::null_mut(),
-        hmod,
-        ptr::null_mut()
-    );
-
-    if handle.is_null() {
-        Err(SystemError::UiCreation)
-    } else {
-        Ok(handle)
-    }
-}
-
-/**
-    Create a message only window for an UI. See `setup_class` && `create_window` docs for more info.
+    Create a message only window for an UI. See `setup_class` && `Backend::build_message_window` docs for more info.
 */
-unsafe fn create_message_only_window<ID: Hash+Clone+'static>() -> Result<HWND, SystemError> {
-    match setup_class::<ID>() {
-        Ok(_) => create_window::<ID>(),
+unsafe fn create_message_only_window<ID: Hash+Clone+'static>(backend: &Backend) -> Result<HWND, SystemError> {
+    match setup_class::<ID>(backend) {
+        Ok(_) => backend.build_message_window(MESSAGE_HANDLE_CLASS_NAME),
         Err(e) => Err(e)
     }
-}
\ No newline at end of file
+}
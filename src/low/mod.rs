@@ -20,9 +20,12 @@
 */
 
 pub mod defs;
-pub mod clsid;
+pub mod backend;
 pub mod events;
 pub mod message_handler;
-pub mod menu_helper;
 pub mod window_helper;
-pub mod other_helper;
\ No newline at end of file
+pub mod other_helper;
+#[cfg(windows)] pub mod clsid;
+#[cfg(windows)] pub mod menu_helper;
+#[cfg(windows)] pub mod accel_helper;
+#[cfg(not(windows))] pub mod types;
\ No newline at end of file
@@ -19,22 +19,18 @@
     along with this program.  If not, see <http://www.gnu.org/licenses/>.
 */
 
-use std::ptr;
-use std::mem;
+#[cfg(windows)] use std::ptr;
+#[cfg(windows)] use std::mem;
 
 use winapi::DWORD;
 
-use defs::{MessageParams, MessageButtons, MessageIcons, MessageChoice};
+#[cfg(windows)] use defs::{MessageParams, MessageButtons, MessageIcons, MessageChoice};
 
 /**
     Encode a string value into a utf16 string. Adds a null char at the end of the string.
 */
 pub fn to_utf16<'a>(s: &'a str) -> Vec<u16> {
-    use std::ffi::OsStr;
-    use std::os::windows::ffi::OsStrExt;
-
-    OsStr::new(s)
-      .encode_wide()
+    s.encode_utf16()
       .chain(Some(0u16).into_iter())
       .collect()
 }
@@ -43,18 +39,14 @@ pub fn to_utf16<'a>(s: &'a str) -> Vec<u16> {
     Decode a raw utf16 string. Should be null terminated.
 */
 pub fn from_utf16(s: &[u16]) -> String {
-    use std::ffi::OsString;
-    use std::os::windows::ffi::OsStringExt;
-
     let null_index = s.iter().position(|&i| i==0).unwrap_or(s.len());
-    let os_string = OsString::from_wide(&s[0..null_index]);
-
-    os_string.into_string().unwrap_or("Decoding error".to_string())
+    String::from_utf16(&s[0..null_index]).unwrap_or("Decoding error".to_string())
 }
 
 /**
     Read a string from a wide char pointer. Undefined behaviour if [ptr] is not null terminated.
 */
+#[cfg(windows)]
 pub unsafe fn from_wide_ptr(ptr: *mut u16) -> String {
     use std::slice::from_raw_parts;
 
@@ -67,11 +59,21 @@ pub unsafe fn from_wide_ptr(ptr: *mut u16) -> String {
     from_utf16(array)
 }
 
+/*
+    Little endian readers and writers for the binary formats of the system (bitmaps, icons, clipboard data).
+    The readers panic if the data is too short: the caller must check the length first.
+*/
+#[cfg(windows)] pub fn read_u16(b: &[u8], at: usize) -> u32 { (b[at] as u32) | ((b[at+1] as u32) << 8) }
+#[cfg(windows)] pub fn read_u32(b: &[u8], at: usize) -> u32 { read_u16(b, at) | (read_u16(b, at+2) << 16) }
+#[cfg(windows)] pub fn push_u16(b: &mut Vec<u8>, v: u16) { b.push(v as u8); b.push((v >> 8) as u8); }
+#[cfg(windows)] pub fn push_u32(b: &mut Vec<u8>, v: u32) { push_u16(b, v as u16); push_u16(b, (v >> 16) as u16); }
+
 /**
     Return a formatted output of the last system error that was raised.
 
     (ERROR ID, Error message localized)
 */
+#[cfg(windows)]
 pub unsafe fn get_system_error() -> (DWORD, String) { 
   use kernel32::{GetLastError, FormatMessageW};
   use winapi::{FORMAT_MESSAGE_FROM_SYSTEM, MAKELANGID, LANG_NEUTRAL, SUBLANG_DEFAULT};
@@ -92,9 +94,18 @@ pub unsafe fn get_system_error() -> (DWORD, String) {
   (code, error_message)
 }
 
+/**
+    Outside of Windows, the errors are raised by the mock backend and there is no system error to report.
+*/
+#[cfg(not(windows))]
+pub unsafe fn get_system_error() -> (DWORD, String) {
+    (0, "No system error".to_string())
+}
+
 /**
   Enable the Windows visual style in the application without having to use a manifest
 */
+#[cfg(windows)]
 pub unsafe fn enable_visual_styles() {
     use kernel32::{ActivateActCtx, CreateActCtxW, GetSystemDirectoryW};
     use winapi::{MAX_PATH, ULONG, ACTCTXW, ULONG_PTR, ICC_STANDARD_CLASSES, ICC_DATE_CLASSES, ICC_PROGRESS_CLASS, ICC_WIN95_CLASSES, INITCOMMONCONTROLSEX};
@@ -134,6 +145,7 @@ pub unsafe fn enable_visual_styles() {
 /**
    Initializes the COM library for use by the calling thread,
 */
+#[cfg(windows)]
 pub unsafe fn enable_com() {
     use ole32::CoInitializeEx;
     use winapi::{COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE};
@@ -146,6 +158,7 @@ pub unsafe fn enable_com() {
     Parameters:  
     * params: A `MessageParams` structure that defines how the message box should look
 */
+#[cfg(windows)]
 pub fn message<'a>(params: &MessageParams) -> MessageChoice {
     use winapi::{MB_ABORTRETRYIGNORE, MB_CANCELTRYCONTINUE, MB_OK, MB_OKCANCEL, MB_RETRYCANCEL, MB_YESNO,
      MB_YESNOCANCEL, MB_ICONSTOP, MB_ICONINFORMATION, MB_ICONQUESTION, MB_ICONEXCLAMATION};
@@ -195,6 +208,7 @@ pub fn message<'a>(params: &MessageParams) -> MessageChoice {
     * title: The message box title
     * content: The message box message
 */
+#[cfg(windows)]
 pub fn fatal_message<'a>(title: &'a str, content: &'a str) -> ! {
     error_message(title, content);
     panic!("{} - {}", title, content);
@@ -207,6 +221,7 @@ pub fn fatal_message<'a>(title: &'a str, content: &'a str) -> ! {
     * title: The message box title
     * content: The message box message
 */
+#[cfg(windows)]
 pub fn error_message<'a>(title: &'a str, content: &'a str) -> MessageChoice {
     let params = MessageParams {
         title: title,
@@ -225,6 +240,7 @@ pub fn error_message<'a>(title: &'a str, content: &'a str) -> MessageChoice {
     * title: The message box title
     * content: The message box message
 */
+#[cfg(windows)]
 pub fn simple_message<'a>(title: &'a str, content: &'a str) -> MessageChoice {
     let params = MessageParams {
         title: title,
@@ -0,0 +1,512 @@
+/*!
+    The WINAPI types and constants used by the platform independent part of NWG.
+
+    `winapi` is empty outside of Windows. On these platforms, this module is used in its place
+    so that the Ui can run over a `MockBackend`. The definitions match the ones of winapi-rs.
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(dead_code)]
+
+pub use std::os::raw::{c_void, c_int, c_uint, c_short, c_ushort};
+
+// Handles
+
+macro_rules! DECLARE_HANDLE {
+    ($name:ident, $inner:ident) => {
+        pub enum $inner { }
+        pub type $name = *mut $inner;
+    };
+}
+
+DECLARE_HANDLE!(HWND, HWND__);
+DECLARE_HANDLE!(HMENU, HMENU__);
+DECLARE_HANDLE!(HFONT, HFONT__);
+DECLARE_HANDLE!(HBITMAP, HBITMAP__);
+DECLARE_HANDLE!(HICON, HICON__);
+DECLARE_HANDLE!(HBRUSH, HBRUSH__);
+DECLARE_HANDLE!(HDROP, HDROP__);
+
+pub type HANDLE = *mut c_void;
+pub type HCURSOR = HICON;
+
+pub enum TREEITEM { }
+pub type HTREEITEM = *mut TREEITEM;
+
+// Basic types. `DWORD` is 32 bits on Windows, even on 64 bits systems.
+
+pub type BYTE = u8;
+pub type WORD = c_ushort;
+pub type DWORD = u32;
+pub type UINT = c_uint;
+pub type BOOL = c_int;
+pub type INT = c_int;
+pub type FLOAT = f32;
+pub type LONG = i32;
+pub type WCHAR = u16;
+pub type LPWSTR = *mut WCHAR;
+pub type PUINT = *mut UINT;
+
+#[cfg(target_pointer_width = "32")] pub type UINT_PTR = u32;
+#[cfg(target_pointer_width = "64")] pub type UINT_PTR = u64;
+#[cfg(target_pointer_width = "32")] pub type LONG_PTR = i32;
+#[cfg(target_pointer_width = "64")] pub type LONG_PTR = i64;
+pub type DWORD_PTR = UINT_PTR;
+pub type ULONG_PTR = UINT_PTR;
+
+pub type WPARAM = UINT_PTR;
+pub type LPARAM = LONG_PTR;
+pub type LRESULT = LONG_PTR;
+
+pub type WNDPROC = Option<unsafe extern "system" fn(HWND, UINT, WPARAM, LPARAM) -> LRESULT>;
+pub type SUBCLASSPROC = Option<unsafe extern "system" fn(HWND, UINT, WPARAM, LPARAM, UINT_PTR, DWORD_PTR) -> LRESULT>;
+
+// Structures
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMHDR {
+    pub hwndFrom: HWND,
+    pub idFrom: UINT_PTR,
+    pub code: UINT,
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct SYSTEMTIME {
+    pub wYear: WORD,
+    pub wMonth: WORD,
+    pub wDayOfWeek: WORD,
+    pub wDay: WORD,
+    pub wHour: WORD,
+    pub wMinute: WORD,
+    pub wSecond: WORD,
+    pub wMilliseconds: WORD,
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct RECT {
+    pub left: LONG,
+    pub top: LONG,
+    pub right: LONG,
+    pub bottom: LONG
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct DATETIMEPICKERINFO {
+    pub cbSize: UINT,
+    pub rcCheck: RECT,
+    pub stateCheck: DWORD,
+    pub rcButton: RECT,
+    pub stateButton: DWORD,
+    pub hwndEdit: HWND,
+    pub hwndUD: HWND,
+    pub hwndDropDown: HWND
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct LVITEMW {
+    pub mask: UINT,
+    pub iItem: c_int,
+    pub iSubItem: c_int,
+    pub state: UINT,
+    pub stateMask: UINT,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iImage: c_int,
+    pub lParam: LPARAM,
+    pub iIndent: c_int,
+    pub iGroupId: c_int,
+    pub cColumns: UINT,
+    pub puColumns: PUINT,
+    pub piColFmt: *mut c_int,
+    pub iGroup: c_int
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct LVCOLUMNW {
+    pub mask: UINT,
+    pub fmt: c_int,
+    pub cx: c_int,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iSubItem: c_int,
+    pub iImage: c_int,
+    pub iOrder: c_int,
+    pub cxMin: c_int,
+    pub cxDefault: c_int,
+    pub cxIdeal: c_int
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct POINT {
+    pub x: LONG,
+    pub y: LONG
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMITEMACTIVATE {
+    pub hdr: NMHDR,
+    pub iItem: c_int,
+    pub iSubItem: c_int,
+    pub uNewState: UINT,
+    pub uOldState: UINT,
+    pub uChanged: UINT,
+    pub ptAction: POINT,
+    pub lParam: LPARAM,
+    pub uKeyFlags: UINT
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMLISTVIEW {
+    pub hdr: NMHDR,
+    pub iItem: c_int,
+    pub iSubItem: c_int,
+    pub uNewState: UINT,
+    pub uOldState: UINT,
+    pub uChanged: UINT,
+    pub ptAction: POINT,
+    pub lParam: LPARAM
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMLVDISPINFOW {
+    pub hdr: NMHDR,
+    pub item: LVITEMW
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct TVITEMW {
+    pub mask: UINT,
+    pub hItem: HTREEITEM,
+    pub state: UINT,
+    pub stateMask: UINT,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iImage: c_int,
+    pub iSelectedImage: c_int,
+    pub cChildren: c_int,
+    pub lParam: LPARAM
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMTREEVIEWW {
+    pub hdr: NMHDR,
+    pub action: UINT,
+    pub itemOld: TVITEMW,
+    pub itemNew: TVITEMW,
+    pub ptDrag: POINT
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct NMUPDOWN {
+    pub hdr: NMHDR,
+    pub iPos: c_int,
+    pub iDelta: c_int
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct PBRANGE {
+    pub iLow: c_int,
+    pub iHigh: c_int
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct TCITEMW {
+    pub mask: UINT,
+    pub dwState: DWORD,
+    pub dwStateMask: DWORD,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iImage: c_int,
+    pub lParam: LPARAM
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct TVITEMEXW {
+    pub mask: UINT,
+    pub hItem: HTREEITEM,
+    pub state: UINT,
+    pub stateMask: UINT,
+    pub pszText: LPWSTR,
+    pub cchTextMax: c_int,
+    pub iImage: c_int,
+    pub iSelectedImage: c_int,
+    pub cChildren: c_int,
+    pub lParam: LPARAM,
+    pub iIntegral: c_int,
+    pub uStateEx: UINT,
+    pub hwnd: HWND,
+    pub iExpandedImage: c_int,
+    pub iReserved: c_int
+}
+
+#[repr(C)] #[derive(Clone, Copy, Debug)]
+pub struct TVINSERTSTRUCTW {
+    pub hParent: HTREEITEM,
+    pub hInsertAfter: HTREEITEM,
+    pub itemex: TVITEMEXW
+}
+
+impl TVINSERTSTRUCTW {
+    #[inline]
+    pub unsafe fn item(&self) -> &TVITEMW { ::std::mem::transmute(&self.itemex) }
+
+    #[inline]
+    pub unsafe fn item_mut(&mut self) -> &mut TVITEMW { ::std::mem::transmute(&mut self.itemex) }
+}
+
+// Constants
+
+pub const CW_USEDEFAULT: c_int = 0x80000000u32 as c_int;
+pub const BS_AUTO3STATE: DWORD = 0x00000006;
+pub const BS_AUTOCHECKBOX: DWORD = 0x00000003;
+pub const BS_AUTORADIOBUTTON: DWORD = 0x00000009;
+pub const BS_BITMAP: DWORD = 0x00000080;
+pub const BS_CENTER: DWORD = 0x00000300;
+pub const BS_GROUPBOX: DWORD = 0x00000007;
+pub const BS_LEFT: DWORD = 0x00000100;
+pub const BS_NOTIFY: DWORD = 0x00004000;
+pub const BS_RIGHT: DWORD = 0x00000200;
+pub const BS_TEXT: DWORD = 0x00000000;
+pub const BS_TOP: DWORD = 0x00000400;
+
+pub const CS_HREDRAW: DWORD = 0x0002;
+pub const CS_VREDRAW: DWORD = 0x0001;
+
+pub const DTM_FIRST: UINT = 0x1000;
+pub const DTM_CLOSEMONTHCAL: UINT = DTM_FIRST + 13;
+pub const DTM_GETDATETIMEPICKERINFO: UINT = DTM_FIRST + 14;
+pub const DTM_GETSYSTEMTIME: UINT = DTM_FIRST + 1;
+pub const DTM_SETFORMATW: UINT = DTM_FIRST + 50;
+pub const DTM_SETSYSTEMTIME: UINT = DTM_FIRST + 2;
+
+pub const DTN_FIRST2: UINT = -753i32 as UINT;
+pub const DTN_CLOSEUP: UINT = DTN_FIRST2;
+
+pub const DTS_RIGHTALIGN: DWORD = 0x0020;
+pub const DTS_SHOWNONE: DWORD = 0x0002;
+
+pub const ECM_FIRST: UINT = 0x1500;
+
+pub const EM_SETCUEBANNER: UINT = ECM_FIRST + 1;
+
+pub const GDT_NONE: LRESULT = 1;
+pub const GDT_VALID: LRESULT = 0;
+
+pub const GWL_STYLE: c_int = -16;
+pub const GWL_USERDATA: c_int = -21;
+
+pub const ICON_BIG: UINT = 1;
+pub const ICON_SMALL: UINT = 0;
+
+pub const LVCFMT_LEFT: c_int = 0x0000;
+
+pub const LVCF_FMT: UINT = 0x0001;
+pub const LVCF_TEXT: UINT = 0x0004;
+pub const LVCF_WIDTH: UINT = 0x0002;
+
+pub const LVIF_STATE: UINT = 0x00000008;
+pub const LVIF_TEXT: UINT = 0x00000001;
+
+pub const LVIS_SELECTED: UINT = 0x0002;
+pub const LVIS_STATEIMAGEMASK: UINT = 0xF000;
+
+pub const LVM_FIRST: UINT = 0x1000;
+pub const LVM_DELETEALLITEMS: UINT = LVM_FIRST + 9;
+pub const LVM_DELETEITEM: UINT = LVM_FIRST + 8;
+pub const LVM_GETITEMSTATE: UINT = LVM_FIRST + 44;
+pub const LVM_GETNEXTITEM: UINT = LVM_FIRST + 12;
+pub const LVM_GETSELECTEDCOUNT: UINT = LVM_FIRST + 50;
+pub const LVM_INSERTCOLUMNW: UINT = LVM_FIRST + 97;
+pub const LVM_INSERTITEMW: UINT = LVM_FIRST + 77;
+pub const LVM_REDRAWITEMS: UINT = LVM_FIRST + 21;
+pub const LVM_SETEXTENDEDLISTVIEWSTYLE: UINT = LVM_FIRST + 54;
+pub const LVM_SETITEMCOUNT: UINT = LVM_FIRST + 47;
+pub const LVM_SETITEMSTATE: UINT = LVM_FIRST + 43;
+pub const LVM_SETITEMTEXTW: UINT = LVM_FIRST + 116;
+
+pub const LVNI_SELECTED: LPARAM = 0x0002;
+
+pub const LVN_FIRST: UINT = -100i32 as UINT;
+pub const LVN_COLUMNCLICK: UINT = LVN_FIRST - 8;
+pub const LVN_GETDISPINFOW: UINT = LVN_FIRST - 77;
+pub const LVN_ITEMCHANGED: UINT = LVN_FIRST - 1;
+pub const LVN_ODSTATECHANGED: UINT = LVN_FIRST - 15;
+
+pub const LVS_EX_CHECKBOXES: DWORD = 0x00000004;
+pub const LVS_EX_DOUBLEBUFFER: DWORD = 0x00010000;
+pub const LVS_EX_FULLROWSELECT: DWORD = 0x00000020;
+pub const LVS_ICON: DWORD = 0x0000;
+pub const LVS_LIST: DWORD = 0x0003;
+pub const LVS_OWNERDATA: DWORD = 0x1000;
+pub const LVS_REPORT: DWORD = 0x0001;
+pub const LVS_SHOWSELALWAYS: DWORD = 0x0008;
+pub const LVS_SINGLESEL: DWORD = 0x0004;
+pub const LVS_SMALLICON: DWORD = 0x0002;
+pub const LVS_TYPEMASK: DWORD = 0x0003;
+
+pub const NM_FIRST: UINT = 0;
+pub const NM_DBLCLK: UINT = (NM_FIRST as INT - 3) as UINT;
+pub const NM_KILLFOCUS: UINT = (NM_FIRST as INT - 8) as UINT;
+pub const NM_SETFOCUS: UINT = (NM_FIRST as INT - 7) as UINT;
+
+pub const WM_USER: UINT = 0x0400;
+
+pub const PBM_DELTAPOS: UINT = WM_USER + 3;
+pub const PBM_GETPOS: UINT = WM_USER + 8;
+pub const PBM_GETRANGE: UINT = WM_USER + 7;
+pub const PBM_GETSTATE: UINT = WM_USER + 17;
+pub const PBM_GETSTEP: UINT = WM_USER + 13;
+pub const PBM_SETPOS: UINT = WM_USER + 2;
+pub const PBM_SETRANGE32: UINT = WM_USER + 6;
+pub const PBM_SETSTATE: UINT = WM_USER + 16;
+pub const PBM_SETSTEP: UINT = WM_USER + 4;
+pub const PBM_STEPIT: UINT = WM_USER + 5;
+
+pub const PBST_ERROR: c_int = 0x0002;
+pub const PBST_NORMAL: c_int = 0x0001;
+pub const PBST_PAUSED: c_int = 0x0003;
+
+pub const PBS_VERTICAL: DWORD = 0x04;
+
+pub const TCIF_TEXT: UINT = 0x0001;
+
+pub const TCM_FIRST: UINT = 0x1300;
+pub const TCM_ADJUSTRECT: UINT = TCM_FIRST + 40;
+pub const TCM_DELETEITEM: UINT = TCM_FIRST + 8;
+pub const TCM_GETCURSEL: UINT = TCM_FIRST + 11;
+pub const TCM_INSERTITEMW: UINT = TCM_FIRST + 62;
+pub const TCM_SETCURSEL: UINT = TCM_FIRST + 12;
+pub const TCM_SETITEMW: UINT = TCM_FIRST + 61;
+
+pub const TCN_FIRST: UINT = -550i32 as UINT;
+pub const TCN_SELCHANGE: UINT = TCN_FIRST - 1;
+
+pub const TVE_COLLAPSE: WPARAM = 0x0001;
+pub const TVE_EXPAND: WPARAM = 0x0002;
+
+pub const TVGN_CARET: WPARAM = 0x0009;
+
+pub const TVIF_CHILDREN: UINT = 0x0040;
+pub const TVIF_HANDLE: UINT = 0x0010;
+pub const TVIF_STATE: UINT = 0x0008;
+pub const TVIF_TEXT: UINT = 0x0001;
+
+pub const TVIS_EXPANDED: UINT = 0x0020;
+pub const TVIS_STATEIMAGEMASK: UINT = 0xF000;
+
+pub const TVI_FIRST: HTREEITEM = (0 - 0x0FFFF) as HTREEITEM;
+pub const TVI_ROOT: HTREEITEM = (0 - 0x10000) as HTREEITEM;
+
+pub const TV_FIRST: UINT = 0x1100;
+
+pub const TVM_DELETEITEM: UINT = TV_FIRST + 1;
+pub const TVM_EXPAND: UINT = TV_FIRST + 2;
+pub const TVM_GETITEMSTATE: UINT = TV_FIRST + 39;
+pub const TVM_GETNEXTITEM: UINT = TV_FIRST + 10;
+pub const TVM_INSERTITEMW: UINT = TV_FIRST + 50;
+pub const TVM_SELECTITEM: UINT = TV_FIRST + 11;
+pub const TVM_SETITEMW: UINT = TV_FIRST + 63;
+
+pub const TVN_FIRST: UINT = -400i32 as UINT;
+pub const TVN_ITEMCHANGEDW: UINT = TVN_FIRST - 19;
+pub const TVN_ITEMEXPANDEDW: UINT = TVN_FIRST - 55;
+pub const TVN_ITEMEXPANDINGW: UINT = TVN_FIRST - 54;
+pub const TVN_SELCHANGEDW: UINT = TVN_FIRST - 51;
+
+pub const TVS_CHECKBOXES: DWORD = 0x0100;
+pub const TVS_HASBUTTONS: DWORD = 0x0001;
+pub const TVS_HASLINES: DWORD = 0x0002;
+pub const TVS_LINESATROOT: DWORD = 0x0004;
+pub const TVS_SHOWSELALWAYS: DWORD = 0x0020;
+
+pub const UDM_SETBUDDY: UINT = WM_USER + 105;
+pub const UDM_SETRANGE32: UINT = WM_USER + 111;
+
+pub const UDN_FIRST: UINT = -721i32 as UINT;
+pub const UDN_DELTAPOS: UINT = UDN_FIRST - 1;
+
+pub const UDS_ARROWKEYS: DWORD = 0x0020;
+pub const UDS_HOTTRACK: DWORD = 0x0100;
+
+pub const UNICODE_NOCHAR: WPARAM = 0xffff;
+
+pub const WM_CHAR: UINT = 0x0102;
+pub const WM_CLIPBOARDUPDATE: UINT = 0x031D;
+pub const WM_CLOSE: UINT = 0x0010;
+pub const WM_COMMAND: UINT = 0x0111;
+pub const WM_CREATE: UINT = 0x0001;
+pub const WM_EXITSIZEMOVE: UINT = 0x0232;
+pub const WM_KEYDOWN: UINT = 0x0100;
+pub const WM_KEYUP: UINT = 0x0101;
+pub const WM_LBUTTONDOWN: UINT = 0x0201;
+pub const WM_LBUTTONUP: UINT = 0x0202;
+pub const WM_MBUTTONDOWN: UINT = 0x0207;
+pub const WM_MBUTTONUP: UINT = 0x0208;
+pub const WM_MOUSELEAVE: UINT = 0x02A3;
+pub const WM_MOVE: UINT = 0x0003;
+pub const WM_NOTIFY: UINT = 0x004E;
+pub const WM_PAINT: UINT = 0x000F;
+pub const WM_QUIT: UINT = 0x0012;
+pub const WM_RBUTTONDOWN: UINT = 0x0204;
+pub const WM_RBUTTONUP: UINT = 0x0205;
+pub const WM_SETFONT: UINT = 0x0030;
+pub const WM_SETICON: UINT = 0x0080;
+pub const WM_SIZE: UINT = 0x0005;
+pub const WM_SIZING: UINT = 0x0214;
+pub const WM_UNICHAR: UINT = 0x0109;
+
+pub const WS_BORDER: DWORD = 0x00800000;
+pub const WS_CAPTION: DWORD = 0x00C00000;
+pub const WS_CHILD: DWORD = 0x40000000;
+pub const WS_CLIPCHILDREN: DWORD = 0x02000000;
+pub const WS_CLIPSIBLINGS: DWORD = 0x04000000;
+pub const WS_EX_ACCEPTFILES: DWORD = 0x00000010;
+pub const WS_HSCROLL: DWORD = 0x00100000;
+pub const WS_MAXIMIZEBOX: DWORD = 0x00010000;
+pub const WS_MINIMIZEBOX: DWORD = 0x00020000;
+pub const WS_OVERLAPPED: DWORD = 0x00000000;
+pub const WS_SYSMENU: DWORD = 0x00080000;
+pub const WS_THICKFRAME: DWORD = 0x00040000;
+pub const WS_OVERLAPPEDWINDOW: DWORD = WS_OVERLAPPED | WS_CAPTION | WS_SYSMENU | WS_THICKFRAME | WS_MINIMIZEBOX | WS_MAXIMIZEBOX;
+pub const WS_TABSTOP: DWORD = 0x00010000;
+pub const WS_VSCROLL: DWORD = 0x00200000;
+pub const WS_VISIBLE: DWORD = 0x10000000;
+pub const WS_DISABLED: DWORD = 0x08000000;
+
+// Macros
+
+#[inline]
+pub fn LOWORD(l: DWORD) -> WORD { (l & 0xffff) as WORD }
+
+#[inline]
+pub fn HIWORD(l: DWORD) -> WORD { ((l >> 16) & 0xffff) as WORD }
+
+#[inline]
+pub fn GET_X_LPARAM(lp: LPARAM) -> c_int { LOWORD(lp as DWORD) as c_short as c_int }
+
+#[inline]
+pub fn GET_Y_LPARAM(lp: LPARAM) -> c_int { HIWORD(lp as DWORD) as c_short as c_int }
+
+#[inline]
+pub fn INDEXTOSTATEIMAGEMASK(i: UINT) -> UINT { i << 12 }
+
+#[inline]
+pub fn MAKELONG(a: WORD, b: WORD) -> LONG {
+    ((a as DWORD) | ((b as DWORD) << 16)) as LONG
+}
@@ -22,11 +22,12 @@ use std::ptr;
 use std::mem;
 use std::hash::Hash;
 
-use winapi::{HWND, HFONT, HBRUSH, WNDPROC, DWORD, LPARAM, BOOL, c_int};
+use winapi::{HWND, HFONT, HBRUSH, HICON, UINT, WNDPROC, DWORD, WPARAM, LPARAM, LRESULT, c_int};
+#[cfg(windows)] use winapi::{HCURSOR, HANDLE};
 
 use ui::{UiInner, Ui};
 use controls::{AnyHandle};
-use low::other_helper::to_utf16;
+use low::backend;
 use error::{Error, SystemError};
 
 /**
@@ -75,47 +76,14 @@ pub struct WindowParams<S1: Into<String>, S2: Into<String>> {
     Note that if the system class window proc used is malformed, the program will most likely segfault.
 */
 pub unsafe fn build_sysclass<S: Into<String>>(p: SysclassParams<S>) -> Result<(), SystemError> {
-    use kernel32::{GetModuleHandleW, GetLastError};
-    use user32::{LoadCursorW, RegisterClassExW};
-    use winapi::{WNDCLASSEXW, CS_HREDRAW, CS_VREDRAW, IDC_ARROW, COLOR_WINDOW, UINT, ERROR_CLASS_ALREADY_EXISTS};
-
-    let hmod = GetModuleHandleW(ptr::null_mut());
-    if hmod.is_null() { return Err(SystemError::SystemClassCreation); }
-
-    let class_name = to_utf16(p.class_name.into().as_ref());
-
-    let background: HBRUSH = match p.background {
-        Some(bg) => bg,
-        None => mem::transmute(COLOR_WINDOW as usize)
-    };
-
-    let style: UINT = match p.style {
-        Some(s) => s as UINT,
-        None=> CS_HREDRAW | CS_VREDRAW
+    let params = SysclassParams{
+        class_name: p.class_name.into(),
+        sysproc: p.sysproc,
+        background: p.background,
+        style: p.style
     };
 
-    let class =
-    WNDCLASSEXW {
-        cbSize: mem::size_of::<WNDCLASSEXW>() as UINT,
-        style: style,
-        lpfnWndProc: p.sysproc, 
-        cbClsExtra: 0,
-        cbWndExtra: 0,
-        hInstance: hmod,
-        hIcon: ptr::null_mut(),
-        hCursor: LoadCursorW(ptr::null_mut(), IDC_ARROW),
-        hbrBackground: background,
-        lpszMenuName: ptr::null(),
-        lpszClassName: class_name.as_ptr(),
-        hIconSm: ptr::null_mut()
-    };
-
-    let class_token = RegisterClassExW(&class);
-    if class_token == 0 && GetLastError() != ERROR_CLASS_ALREADY_EXISTS { 
-        Err(SystemError::SystemClassCreation)
-    } else {
-        Ok(())
-    }
+    backend::current().build_sysclass(params)
 }
 
 /**
@@ -127,66 +95,31 @@ pub unsafe fn build_sysclass<S: Into<String>>(p: SysclassParams<S>) -> Result<()
     Note that if the system class window proc used is malformed, the program will most likely segfault.
 */
 pub unsafe fn build_window<S1: Into<String>, S2: Into<String>>(p: WindowParams<S1, S2>) -> Result<HWND, SystemError>{
-    use kernel32::GetModuleHandleW;
-    use user32::{CreateWindowExW, GetDesktopWindow, GetWindowRect};
-    use winapi::{WS_EX_COMPOSITED, RECT};
-
-    let hmod = GetModuleHandleW(ptr::null_mut());
-    if hmod.is_null() { return Err(SystemError::WindowCreationFail); }
-
-    let class_name = to_utf16(p.class_name.into().as_ref());
-    let window_name = to_utf16(p.title.into().as_ref());
-
-    let px = match p.position.0 { 
-        ::defs::CENTER_POSITION => {
-            let mut rect: RECT = mem::uninitialized();
-            let parent = if p.parent.is_null() { GetDesktopWindow() } else {p.parent};
-            GetWindowRect(parent, &mut rect);
-            (rect.right/2) - ((p.size.0/2) as i32)
-        },
-        x => x
-    };
-
-    let py = match p.position.1 { 
-        ::defs::CENTER_POSITION => {
-            let mut rect: RECT = mem::uninitialized();
-            let parent = if p.parent.is_null() { GetDesktopWindow() } else {p.parent};
-            GetWindowRect(parent, &mut rect);
-            (rect.bottom/2) - ((p.size.1/2) as i32)
-        },
-        y => y
+    let params = WindowParams{
+        title: p.title.into(),
+        class_name: p.class_name.into(),
+        position: p.position,
+        size: p.size,
+        flags: p.flags,
+        ex_flags: p.ex_flags,
+        parent: p.parent
     };
 
-    let ex_flags = match p.ex_flags {
-        Some(ex) => ex,
-        None => WS_EX_COMPOSITED
-    };
+    backend::current().build_window(params)
+}
 
-    let handle = CreateWindowExW (
-        ex_flags,
-        class_name.as_ptr(), window_name.as_ptr(),
-        p.flags,
-        px, py,
-        p.size.0 as i32, p.size.1 as i32,
-        p.parent,
-        ptr::null_mut(),
-        hmod,
-        ptr::null_mut()
-    );
-
-    if handle.is_null() {
-        Err(SystemError::WindowCreationFail)
-    } else {
-        fix_overlapped_window_size(handle, p.size);
-        Ok(handle)
-    }
+/// Destroy a window
+#[inline(always)]
+pub unsafe fn destroy_window(handle: HWND) {
+    backend::current().destroy_window(handle);
 }
 
 /** 
     Fix: Window size include the non client area. This behaviour is not wanted
     Resize the client area to match the "true" size. 
 */
-unsafe fn fix_overlapped_window_size(handle: HWND, size: (u32, u32)) {
+#[cfg(windows)]
+pub unsafe fn fix_overlapped_window_size(handle: HWND, size: (u32, u32)) {
     use winapi::{RECT, SWP_NOMOVE, SWP_NOZORDER};
     use user32::{GetClientRect, SetWindowPos};
 
@@ -203,164 +136,180 @@ unsafe fn fix_overlapped_window_size(handle: HWND, size: (u32, u32)) {
 }
 
 
-unsafe extern "system" fn list_children_window<ID: Clone+Hash+'static>(handle: HWND, params: LPARAM) -> BOOL {
-    let &mut (inner, ref mut ids): &mut (*mut UiInner<ID>, Vec<u64>) = mem::transmute(params);
-
-    // Check if the window belongs to the ui
-    if let Some(id) = ::low::events::window_id(handle, inner) {
-        ids.push(id)
-    }
-
-    1
-}
-
 /**
-    Return the children control found in the window. Includes the window menubar if one is present.
+    Return the children control found in the window. Includes the window menubar if one is present
+    and the accelerators bound to the window or to its children.
 */
-#[allow(unused_variables)]
 pub unsafe fn list_window_children<ID: Clone+Hash>(handle: HWND, ui: *mut UiInner<ID>) -> Vec<u64> {
-    use user32::{GetMenu, EnumChildWindows};
-    use low::menu_helper::list_menu_children;
+    use low::events::window_id;
 
+    let backend = backend::current();
     let mut children = Vec::new();
 
-    let menu = GetMenu(handle);
-    if !menu.is_null() {
-        children.append(&mut list_menu_children(&*ui, menu) );
+    #[cfg(windows)]
+    {
+        use low::menu_helper::list_menu_children;
+        let menu = backend.window_menu(handle);
+        if !menu.is_null() {
+            children.append(&mut list_menu_children(&*ui, menu) );
+        }
     }
 
-    let mut params: (*mut UiInner<ID>, Vec<u64>) = (ui, children);
-    EnumChildWindows(handle, Some(list_children_window::<ID>), mem::transmute(&mut params));
+    let windows = backend.window_children(handle);
+    for &child in windows.iter() {
+        // Check if the window belongs to the ui
+        if let Some(id) = window_id(child, ui) {
+            children.push(id);
+        }
+    }
 
-    params.1
+    // The popup menus are freed with their owner, so that they never send their commands to a destroyed window
+    #[cfg(windows)]
+    {
+        use low::menu_helper::{list_menu_children, list_popup_menus};
+
+        for &window in Some(&handle).into_iter().chain(windows.iter()) {
+            for menu in list_popup_menus(window) {
+                if let Some(id) = (&*ui).inner_id_from_handle(&AnyHandle::HMENU(menu)) {
+                    children.push(id);
+                    children.append(&mut list_menu_children(&*ui, menu));
+                }
+            }
+        }
+    }
+
+    // The accelerators are listed last, so they are freed before the menu items they are bound to
+    #[cfg(windows)]
+    {
+        use std::any::TypeId;
+        use controls::Accelerator;
+        use low::accel_helper::list_accelerators;
+
+        for &window in Some(&handle).into_iter().chain(windows.iter()) {
+            for cmd in list_accelerators(window) {
+                let accel_handle = AnyHandle::Custom(TypeId::of::<Accelerator>(), cmd as usize);
+                if let Some(id) = (&*ui).inner_id_from_handle(&accel_handle) {
+                    children.push(id);
+                }
+            }
+        }
+    }
+
+    children
 }
 
 /// Set the font of a window
 pub unsafe fn set_window_font(handle: HWND, font_handle: Option<HFONT>, redraw: bool) {
-    use user32::SendMessageW;
     use winapi::{WM_SETFONT};
 
     let font_handle = font_handle.unwrap_or(ptr::null_mut());
 
-    SendMessageW(handle, WM_SETFONT, mem::transmute(font_handle), redraw as LPARAM);
+    send_message(handle, WM_SETFONT, mem::transmute(font_handle), redraw as LPARAM);
 }
 
-/// Get the window text
-#[inline(always)]
-pub unsafe fn get_window_text(handle: HWND) -> String {
-    use user32::{GetWindowTextW, GetWindowTextLengthW};
-    use low::other_helper::from_utf16;
+/// Set the big icon (taskbar, alt-tab) and the small icon (title bar) of a window. The system scales the icon if needed.
+pub unsafe fn set_window_icon(handle: HWND, icon: Option<HICON>) {
+    use winapi::{WM_SETICON, ICON_BIG, ICON_SMALL};
 
-    let mut buffer_size = GetWindowTextLengthW(handle) as usize;
-    if buffer_size == 0 { return String::new(); }
+    let icon = icon.unwrap_or(ptr::null_mut());
+    send_message(handle, WM_SETICON, ICON_BIG as WPARAM, icon as LPARAM);
+    send_message(handle, WM_SETICON, ICON_SMALL as WPARAM, icon as LPARAM);
+}
+
+/// Return the big and the small icons set with `set_window_icon`. A null handle means the default icon is used.
+#[cfg(windows)]
+pub unsafe fn get_window_icons(handle: HWND) -> (HICON, HICON) {
+    use winapi::{WM_GETICON, ICON_BIG, ICON_SMALL};
 
-    buffer_size += 1;
-    let mut buffer: Vec<u16> = Vec::with_capacity(buffer_size);
-    buffer.set_len(buffer_size);
+    let big = send_message(handle, WM_GETICON, ICON_BIG as WPARAM, 0);
+    let small = send_message(handle, WM_GETICON, ICON_SMALL as WPARAM, 0);
+    (big as HICON, small as HICON)
+}
 
-    if GetWindowTextW(handle, buffer.as_mut_ptr(), buffer_size as c_int) == 0 {
-        String::new()
-    } else {
-        from_utf16(&buffer[..])
+/// The name of the window property that holds the cursor of a window
+#[cfg(windows)]
+const CURSOR_PROPERTY: &'static str = "NWG_CURSOR";
+
+/**
+    Set the cursor shown when the mouse is over the client area of a window. If `cursor` is None, the class cursor is used.
+    The cursor is applied by the events dispatcher when the window receives `WM_SETCURSOR`.
+*/
+#[cfg(windows)]
+pub unsafe fn set_window_cursor(handle: HWND, cursor: Option<HCURSOR>) {
+    use user32::{SetPropW, RemovePropW};
+    use low::other_helper::to_utf16;
+
+    let name = to_utf16(CURSOR_PROPERTY);
+    match cursor {
+        Some(c) => { SetPropW(handle, name.as_ptr(), c as HANDLE); },
+        None => { RemovePropW(handle, name.as_ptr()); }
     }
 }
 
+/// Return the cursor set with `set_window_cursor`, if any
+#[cfg(windows)]
+pub unsafe fn get_window_cursor(handle: HWND) -> Option<HCURSOR> {
+    use user32::GetPropW;
+    use low::other_helper::to_utf16;
+
+    let name = to_utf16(CURSOR_PROPERTY);
+    let cursor = GetPropW(handle, name.as_ptr());
+    if cursor.is_null() { None } else { Some(cursor as HCURSOR) }
+}
+
+/// Get the window text
+#[inline(always)]
+pub unsafe fn get_window_text(handle: HWND) -> String {
+    backend::current().get_window_text(handle)
+}
+
 /// Set the window text
 #[inline(always)]
 pub unsafe fn set_window_text<'a>(handle: HWND, text: &'a str) {
-    use user32::SetWindowTextW;
-
-    let text = to_utf16(text);
-    SetWindowTextW(handle, text.as_ptr());
+    backend::current().set_window_text(handle, text);
 }
 
 
 /// Set window position
 #[inline(always)]
 pub unsafe fn set_window_position(handle: HWND, x: i32, y: i32) {
-    use user32::SetWindowPos;
-    use winapi::{SWP_NOZORDER, SWP_NOSIZE, SWP_NOACTIVATE};
-
-    SetWindowPos(handle, ptr::null_mut(), x as c_int, y as c_int, 0, 0, SWP_NOZORDER|SWP_NOSIZE|SWP_NOACTIVATE);
+    backend::current().set_window_position(handle, x, y);
 }
 
 /// Get window position
 #[inline(always)]
 pub unsafe fn get_window_position(handle: HWND) -> (i32, i32) {
-    use user32::{GetWindowRect, ScreenToClient, GetParent};
-    use winapi::{RECT, POINT};
-    
-    let mut r: RECT = mem::uninitialized();
-    GetWindowRect(handle, &mut r);
-
-    let parent = GetParent(handle);
-    if !parent.is_null() {
-        let mut pt = POINT{x: r.left, y: r.top};
-        ScreenToClient(parent, &mut pt);
-        (pt.x as i32, pt.y as i32)
-    } else {
-        (r.left as i32, r.top as i32)
-    }
+    backend::current().get_window_position(handle)
 }
 
 /// Set window size
 #[inline(always)]
 pub unsafe fn set_window_size(handle: HWND, w: u32, h: u32, fix: bool) {
-    use user32::SetWindowPos;
-    use winapi::{SWP_NOZORDER, SWP_NOMOVE, SWP_NOACTIVATE};
-
-    SetWindowPos(handle, ptr::null_mut(), 0, 0, w as c_int, h as c_int, SWP_NOZORDER|SWP_NOMOVE|SWP_NOACTIVATE);
-
-    if fix { fix_overlapped_window_size(handle, (w, h)); }
+    backend::current().set_window_size(handle, w, h, fix);
 }
 
 /// Get window size
 #[inline(always)]
 pub unsafe fn get_window_size(handle: HWND) -> (u32, u32) {
-    use user32::GetClientRect;
-    use winapi::RECT;
-    
-    let mut r: RECT = mem::uninitialized();
-    GetClientRect(handle, &mut r);
-
-    (r.right as u32, r.bottom as u32)
+    backend::current().get_window_size(handle)
 }
 
 /// Get the window enabled state
 #[inline(always)]
 pub unsafe fn get_window_enabled(handle: HWND) -> bool {
-    use winapi::{GWL_STYLE, WS_DISABLED, UINT};
-
-    let style = get_window_long(handle, GWL_STYLE) as UINT;
-    (style & WS_DISABLED) != WS_DISABLED
+    backend::current().get_window_enabled(handle)
 }
 
 /// Set the window enabled state
 #[inline(always)]
 pub unsafe fn set_window_enabled(handle: HWND, enabled: bool) {
-    use winapi::{GWL_STYLE, WS_DISABLED};
-    use user32::{UpdateWindow, InvalidateRect};
-
-    let old_style = get_window_long(handle, GWL_STYLE) as usize;
-    if enabled {
-        set_window_long(handle, GWL_STYLE, old_style&(!WS_DISABLED as usize) );
-    } else {
-        set_window_long(handle, GWL_STYLE, old_style|(WS_DISABLED as usize));
-    }
-
-    // Tell the control to redraw itself to show the new style.
-    InvalidateRect(handle, ptr::null(), 1);
-    UpdateWindow(handle);
+    backend::current().set_window_enabled(handle, enabled);
 }
 
 /// Set window visibility
 #[inline(always)]
 pub unsafe fn set_window_visibility(handle: HWND, visible: bool) {
-    use user32::ShowWindow;
-    use winapi::{SW_HIDE, SW_SHOW};
-
-    let visible = if visible { SW_SHOW } else { SW_HIDE };
-    ShowWindow(handle, visible);
+    backend::current().set_window_visibility(handle, visible);
 }
 
 /**
@@ -368,8 +317,43 @@ pub unsafe fn set_window_visibility(handle: HWND, visible: bool) {
 */
 #[inline(always)]
 pub unsafe fn get_window_visibility(handle: HWND) -> bool {
-    use user32::IsWindowVisible;
-    IsWindowVisible(handle) != 0
+    backend::current().get_window_visibility(handle)
+}
+
+/// Get the parent of a window. Can be null.
+#[inline(always)]
+pub unsafe fn get_window_parent(handle: HWND) -> HWND {
+    backend::current().window_parent(handle)
+}
+
+/// Send a message to a window and return the result
+#[inline(always)]
+pub unsafe fn send_message(handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    backend::current().send_message(handle, msg, w, l)
+}
+
+/// Post a message in the message queue of a window
+#[inline(always)]
+pub unsafe fn post_message(handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) {
+    backend::current().post_message(handle, msg, w, l);
+}
+
+/// Post a quit message. Breaks the events loop of the current thread.
+#[inline(always)]
+pub unsafe fn post_quit_message(code: c_int) {
+    backend::current().post_quit_message(code);
+}
+
+/// Default processing of a message. Must be returned by the window procs for the messages they do not handle.
+#[inline(always)]
+pub unsafe fn def_window_proc(handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    backend::current().def_window_proc(handle, msg, w, l)
+}
+
+/// Forward a message to the next subclass. Must be returned by the subclass procs for the messages they do not handle.
+#[inline(always)]
+pub unsafe fn def_subclass_proc(handle: HWND, msg: UINT, w: WPARAM, l: LPARAM) -> LRESULT {
+    backend::current().def_subclass_proc(handle, msg, w, l)
 }
 
 
@@ -397,27 +381,23 @@ pub fn handle_of_font<ID: Clone+Hash>(ui: &Ui<ID>, id: &ID, err: &'static str) -
 #[inline(always)]
 #[cfg(target_arch = "x86_64")]
 pub fn get_window_long(handle: HWND, index: c_int) -> LONG_PTR {
-    use user32::GetWindowLongPtrW;
-    unsafe{ GetWindowLongPtrW(handle, index) }
+    unsafe{ backend::current().get_window_long(handle, index) }
 }
 
 #[inline(always)]
 #[cfg(target_arch = "x86")]
 pub fn get_window_long(handle: HWND, index: c_int) -> LONG {
-    use user32::GetWindowLongW;
-    unsafe { GetWindowLongW(handle, index) }
+    unsafe { backend::current().get_window_long(handle, index) as LONG }
 }
 
 #[inline(always)]
 #[cfg(target_arch = "x86_64")]
 pub fn set_window_long(handle: HWND, index: c_int, v: usize) {
-    use user32::SetWindowLongPtrW;
-    unsafe{ SetWindowLongPtrW(handle, index, v as LONG_PTR); }
+    unsafe{ backend::current().set_window_long(handle, index, v as LONG_PTR); }
 }
 
 #[inline(always)]
 #[cfg(target_arch = "x86")]
 pub fn set_window_long(handle: HWND, index: c_int, v: usize) {
-    use user32::SetWindowLongW;
-    unsafe { SetWindowLongW(handle, index, v as LONG); }
-}
\ No newline at end of file
+    unsafe { backend::current().set_window_long(handle, index, v as ::winapi::LONG_PTR); }
+}
@@ -0,0 +1,435 @@
+/*!
+    Image resources: icons, cursors and bitmaps
+*/
+/*
+    Copyright (C) 2016  Gabriel Dubé
+
+    This program is free software: you can redistribute it and/or modify
+    it under the terms of the GNU General Public License as published by
+    the Free Software Foundation, either version 3 of the License, or
+    (at your option) any later version.
+
+    This program is distributed in the hope that it will be useful,
+    but WITHOUT ANY WARRANTY; without even the implied warranty of
+    MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+    GNU General Public License for more details.
+
+    You should have received a copy of the GNU General Public License
+    along with this program.  If not, see <http://www.gnu.org/licenses/>.
+*/
+use std::any::TypeId;
+use std::hash::Hash;
+use std::ptr;
+
+use winapi::{HANDLE, HINSTANCE, HBITMAP, HICON, HCURSOR, HGDIOBJ, LPCWSTR, UINT, DWORD, BOOL, c_int, c_void};
+
+use ui::{Ui, UiInner};
+use controls::AnyHandle;
+use resources::{ResourceT, Resource};
+use defs::ImageType;
+use error::Error;
+use low::other_helper::{to_utf16, read_u16, read_u32, push_u16};
+
+/// The version of the icon format expected by `CreateIconFromResourceEx`
+const ICON_VERSION: DWORD = 0x00030000;
+
+/**
+    The location of the data of an image resource
+
+    • `File`: The path of a `.ico`, `.cur` or `.bmp` file  
+    • `Bytes`: The content of a `.ico`, `.cur` or `.bmp` file. Ex: `ImageSource::Bytes(include_bytes!("app.ico").to_vec())`  
+    • `Resource`: The name of an image in the resource section of the executable  
+    • `ResourceId`: The numeric id of an image in the resource section of the executable  
+*/
+#[derive(Clone, Debug)]
+pub enum ImageSource<S: Clone+Into<String>> {
+    File(S),
+    Bytes(Vec<u8>),
+    Resource(S),
+    ResourceId(u16)
+}
+
+/**
+    A template that can create an image resource. An icon can be used as the icon of a window (`WindowT::icon`, `set_control_icon`)
+    or of a tray icon, a cursor can be used as the cursor of a control (`set_control_cursor`) and a bitmap can be shown beside a menuitem.
+
+    The images are not owned by the controls that show them: an image cannot be removed from the Ui while a control uses it
+    (`Error::ResourceInUse`). The controls must be removed or the image detached first.
+
+    Params:  
+    • `source`: Where the image is loaded from. See `ImageSource`  
+    • `image_type`: The type of the image. Must match the format of the data: `.ico` for `Icon`, `.cur` for `Cursor` and `.bmp` for `Bitmap`  
+    • `size`: The size of the image. If the data holds many images, the closest one is picked. `(0, 0)` means the system size for icons and cursors and the real size for bitmaps. Bitmaps loaded from bytes always keep their real size  
+*/
+#[derive(Clone)]
+pub struct ImageT<S: Clone+Into<String>> {
+    pub source: ImageSource<S>,
+    pub image_type: ImageType,
+    pub size: (u32, u32)
+}
+
+impl<ID: Clone+Hash, S: Clone+Into<String>> ResourceT<ID> for ImageT<S> {
+    fn resource_type_id(&self) -> TypeId { TypeId::of::<Image>() }
+
+    #[allow(unused_variables)]
+    fn build(&self, ui: &Ui<ID>) -> Result<Box<Resource>, Error> {
+        let handle = unsafe{ match self.source {
+            ImageSource::File(ref path) => {
+                let path: String = path.clone().into();
+                load_file(&path, self.image_type, self.size)
+            },
+            ImageSource::Bytes(ref data) => load_bytes(data, self.image_type, self.size),
+            ImageSource::Resource(ref name) => {
+                let name: String = name.clone().into();
+                let name_w = to_utf16(&name);
+                load_resource(name_w.as_ptr(), &name, self.image_type, self.size)
+            },
+            ImageSource::ResourceId(id) => load_resource(id as usize as LPCWSTR, &id.to_string(), self.image_type, self.size)
+        }};
+
+        match handle {
+            Ok(h) => Ok( Box::new( Image{ handle: h, image_type: self.image_type } ) ),
+            Err(e) => Err(e)
+        }
+    }
+}
+
+/**
+    An image resource
+*/
+pub struct Image {
+    handle: HANDLE,
+    image_type: ImageType
+}
+
+impl Image {
+
+    /// Return the type of the image
+    pub fn get_type(&self) -> ImageType { self.image_type }
+
+}
+
+impl Resource for Image {
+    fn handle(&self) -> AnyHandle {
+        match self.image_type {
+            ImageType::Bitmap => AnyHandle::HBITMAP(self.handle as HBITMAP),
+            ImageType::Icon => AnyHandle::HICON(self.handle as HICON),
+            ImageType::Cursor => AnyHandle::HCURSOR(self.handle as HCURSOR)
+        }
+    }
+
+    fn free(&mut self) {
+        use user32::{DestroyIcon, DestroyCursor};
+        use gdi32::DeleteObject;
+
+        unsafe{ match self.image_type {
+            ImageType::Bitmap => { DeleteObject(self.handle as HGDIOBJ); },
+            ImageType::Icon => { DestroyIcon(self.handle as HICON); },
+            ImageType::Cursor => { DestroyCursor(self.handle as HCURSOR); }
+        } }
+    }
+}
+
+/**
+    Set the big and the small icons of a window. If `icon` is `None`, the default icon is restored.
+
+    Arguments:  
+    • `ui`: The Ui holding the window and the icon  
+    • `window`: The id of a window-like control  
+    • `icon`: The id of an `Image` resource of type `Icon`. The icon is not owned by the window: it must stay in the Ui while the window uses it  
+*/
+pub fn set_control_icon<ID: Clone+Hash>(ui: &Ui<ID>, window: &ID, icon: Option<&ID>) -> Result<(), Error> {
+    use low::window_helper::{handle_of_window, set_window_icon};
+
+    let handle = match handle_of_window(ui, window, "The icon can only be set on a window-like control.") {
+        Ok(h) => h,
+        Err(e) => { return Err(e); }
+    };
+
+    let icon = match icon.map(|id| ui.handle_of(id)) {
+        Some(Ok(AnyHandle::HICON(h))) => Some(h),
+        Some(Ok(h)) => { return Err(Error::BadResource(format!("The icon of a window must be an Image of type Icon. Got {:?}", h))); },
+        Some(Err(e)) => { return Err(e); },
+        None => None
+    };
+
+    unsafe{ set_window_icon(handle, icon); }
+
+    Ok(())
+}
+
+/**
+    Set the cursor shown when the mouse is over a control. If `cursor` is `None`, the default cursor of the control is restored.
+
+    Arguments:  
+    • `ui`: The Ui holding the control and the cursor  
+    • `control`: The id of a window-like control  
+    • `cursor`: The id of an `Image` resource of type `Cursor`. The cursor is not owned by the control: it must stay in the Ui while the control uses it  
+*/
+pub fn set_control_cursor<ID: Clone+Hash>(ui: &Ui<ID>, control: &ID, cursor: Option<&ID>) -> Result<(), Error> {
+    use low::window_helper::{handle_of_window, set_window_cursor};
+
+    let handle = match handle_of_window(ui, control, "The cursor can only be set on a window-like control.") {
+        Ok(h) => h,
+        Err(e) => { return Err(e); }
+    };
+
+    let cursor = match cursor.map(|id| ui.handle_of(id)) {
+        Some(Ok(AnyHandle::HCURSOR(h))) => Some(h),
+        Some(Ok(h)) => { return Err(Error::BadResource(format!("The cursor of a control must be an Image of type Cursor. Got {:?}", h))); },
+        Some(Err(e)) => { return Err(e); },
+        None => None
+    };
+
+    unsafe{ set_window_cursor(handle, cursor); }
+
+    Ok(())
+}
+
+
+/**
+    Return `true` if a control of the Ui shows the image: as the icon of a window, the cursor of a control,
+    the bitmap of a menuitem or the icon of a tray icon. Used to refuse to free an image that is still in use.
+*/
+pub unsafe fn image_in_use<ID: Clone+Hash>(ui: &UiInner<ID>, image: &AnyHandle) -> bool {
+    use std::mem;
+    use low::window_helper::{get_window_icons, get_window_cursor};
+    use low::menu_helper::get_menuitem_bitmap;
+    use controls::TrayIcon;
+    use controls::tray::tray_icon_handle;
+
+    // The controls are read without being borrowed, because the user might hold a reference to one of them
+    ui.controls.values().any(|control| match ((*control.as_ptr()).handle(), image) {
+        (AnyHandle::HWND(h), &AnyHandle::HICON(icon)) => {
+            let (big, small) = get_window_icons(h);
+            big == icon || small == icon
+        },
+        (AnyHandle::HWND(h), &AnyHandle::HCURSOR(cursor)) => get_window_cursor(h) == Some(cursor),
+        (AnyHandle::HMENU_ITEM(parent, id), &AnyHandle::HBITMAP(bitmap)) => get_menuitem_bitmap(parent, id) == bitmap,
+        (AnyHandle::Custom(t, _), &AnyHandle::HICON(icon)) if t == TypeId::of::<TrayIcon>() => {
+            let tray: &Box<TrayIcon> = mem::transmute(control.as_ptr());
+            tray_icon_handle(tray) == icon
+        },
+        _ => false
+    })
+}
+
+
+/*
+    Private unsafe resource methods
+*/
+
+unsafe fn load_image(module: HINSTANCE, name: LPCWSTR, image_type: ImageType, size: (u32, u32), flags: UINT) -> HANDLE {
+    use user32::LoadImageW;
+    use winapi::{IMAGE_BITMAP, IMAGE_ICON, IMAGE_CURSOR, LR_DEFAULTSIZE, LR_CREATEDIBSECTION};
+
+    // Without LR_SHARED, the image is a copy that must be destroyed when the resource is freed
+    let (ty, flags) = match image_type {
+        ImageType::Bitmap => (IMAGE_BITMAP, flags | LR_CREATEDIBSECTION),
+        ImageType::Icon => (IMAGE_ICON, flags | LR_DEFAULTSIZE),
+        ImageType::Cursor => (IMAGE_CURSOR, flags | LR_DEFAULTSIZE)
+    };
+
+    LoadImageW(module, name, ty, size.0 as c_int, size.1 as c_int, flags)
+}
+
+unsafe fn load_file(path: &str, image_type: ImageType, size: (u32, u32)) -> Result<HANDLE, Error> {
+    use winapi::LR_LOADFROMFILE;
+
+    let path_w = to_utf16(path);
+    let handle = load_image(ptr::null_mut(), path_w.as_ptr(), image_type, size, LR_LOADFROMFILE);
+
+    if handle.is_null() {
+        Err(Error::BadResource(format!("Could not load the image {:?}", path)))
+    } else {
+        Ok(handle)
+    }
+}
+
+unsafe fn load_resource(name: LPCWSTR, display_name: &str, image_type: ImageType, size: (u32, u32)) -> Result<HANDLE, Error> {
+    use kernel32::GetModuleHandleW;
+
+    let module = GetModuleHandleW(ptr::null());
+    let handle = load_image(module, name, image_type, size, 0);
+
+    if handle.is_null() {
+        Err(Error::BadResource(format!("Could not load the image {:?} from the executable resources", display_name)))
+    } else {
+        Ok(handle)
+    }
+}
+
+unsafe fn load_bytes(data: &[u8], image_type: ImageType, size: (u32, u32)) -> Result<HANDLE, Error> {
+    let handle = match image_type {
+        ImageType::Bitmap => match find_bitmap_layout(data) {
+            Some(layout) => create_bitmap(data, &layout),
+            None => ptr::null_mut()
+        },
+        ImageType::Icon | ImageType::Cursor => create_icon(data, image_type, size)
+    };
+
+    if handle.is_null() {
+        Err(Error::BadResource(format!("Could not load the {:?} image from bytes. The data must be the content of a file of the same type.", image_type)))
+    } else {
+        Ok(handle)
+    }
+}
+
+unsafe fn create_icon(data: &[u8], image_type: ImageType, size: (u32, u32)) -> HANDLE {
+    use user32::{CreateIconFromResourceEx, GetSystemMetrics};
+    use winapi::{SM_CXICON, SM_CYICON, SM_CXCURSOR, SM_CYCURSOR, LR_DEFAULTCOLOR};
+
+    let cursor = image_type == ImageType::Cursor;
+    let (mx, my) = if cursor { (SM_CXCURSOR, SM_CYCURSOR) } else { (SM_CXICON, SM_CYICON) };
+    let w = if size.0 == 0 { GetSystemMetrics(mx) } else { size.0 as c_int };
+    let h = if size.1 == 0 { GetSystemMetrics(my) } else { size.1 as c_int };
+
+    let entry = match find_icon_entry(data, cursor, (w as u32, h as u32)) {
+        Some(e) => e,
+        None => { return ptr::null_mut(); }
+    };
+
+    // A cursor resource starts with the position of its hotspot
+    let mut res: Vec<u8> = Vec::with_capacity(entry.len + 4);
+    if cursor {
+        push_u16(&mut res, entry.hotspot.0);
+        push_u16(&mut res, entry.hotspot.1);
+    }
+    res.extend_from_slice(&data[entry.offset..(entry.offset + entry.len)]);
+
+    CreateIconFromResourceEx(res.as_mut_ptr(), res.len() as DWORD, !cursor as BOOL, ICON_VERSION, w, h, LR_DEFAULTCOLOR) as HANDLE
+}
+
+unsafe fn create_bitmap(data: &[u8], layout: &BitmapLayout) -> HANDLE {
+    use gdi32::CreateDIBSection;
+    use winapi::{BITMAPINFO, DIB_RGB_COLORS};
+
+    // The header is copied in an aligned buffer. It includes the color masks and the color table, if any.
+    let mut info: Vec<u32> = vec![0; (layout.info_len + 3) / 4];
+    ptr::copy_nonoverlapping(data[layout.info..].as_ptr(), info.as_mut_ptr() as *mut u8, layout.info_len);
+
+    let mut bits: *mut c_void = ptr::null_mut();
+    let bitmap = CreateDIBSection(ptr::null_mut(), info.as_ptr() as *const BITMAPINFO, DIB_RGB_COLORS, &mut bits, ptr::null_mut(), 0);
+    if !bitmap.is_null() {
+        ptr::copy_nonoverlapping(data[layout.bits..].as_ptr(), bits as *mut u8, layout.bits_len);
+    }
+
+    bitmap as HANDLE
+}
+
+/**
+    An image of a `.ico` or `.cur` file
+*/
+struct IconEntry {
+    offset: usize,
+    len: usize,
+    hotspot: (u16, u16)
+}
+
+/**
+    Find the image of a `.ico` or `.cur` file that is the closest to `size`. Between images of the same size, the one with the most colors is picked.
+    Return `None` if the data is not an icon file (or a cursor file if `cursor` is true) or if it is truncated.
+*/
+fn find_icon_entry(data: &[u8], cursor: bool, size: (u32, u32)) -> Option<IconEntry> {
+    // ICONDIR: reserved (0), type (1 for icons, 2 for cursors), count. Followed by `count` 16 bytes ICONDIRENTRY.
+    let file_type = if cursor { 2 } else { 1 };
+    if data.len() < 6 || read_u16(data, 0) != 0 || read_u16(data, 2) != file_type {
+        return None;
+    }
+
+    let count = read_u16(data, 4) as usize;
+    if data.len() < 6 + count * 16 {
+        return None;
+    }
+
+    let mut best: Option<(u64, u32, IconEntry)> = None;
+    for i in 0..count {
+        let e = &data[(6 + i * 16)..(6 + (i + 1) * 16)];
+
+        let (len, offset) = (read_u32(e, 8) as usize, read_u32(e, 12) as usize);
+        if len == 0 || offset > data.len() || len > data.len() - offset {
+            continue;
+        }
+
+        // A width or a height of 0 means 256 pixels
+        let w = if e[0] == 0 { 256 } else { e[0] as i64 };
+        let h = if e[1] == 0 { 256 } else { e[1] as i64 };
+        let distance = ((w - size.0 as i64).abs() + (h - size.1 as i64).abs()) as u64;
+
+        // In a cursor file, the planes and the bit count fields hold the hotspot
+        let (planes, bit_count) = (read_u16(e, 4) as u16, read_u16(e, 6) as u16);
+        let depth = if cursor { 0 } else { bit_count as u32 };
+
+        let better = match best {
+            Some((d, b, _)) => distance < d || (distance == d && depth > b),
+            None => true
+        };
+
+        if better {
+            best = Some((distance, depth, IconEntry{ offset: offset, len: len, hotspot: (planes, bit_count) }));
+        }
+    }
+
+    best.map(|(_, _, entry)| entry)
+}
+
+/**
+    The position of the header and of the pixels of a `.bmp` file
+*/
+struct BitmapLayout {
+    info: usize,
+    info_len: usize,
+    bits: usize,
+    bits_len: usize
+}
+
+/**
+    Find the header and the pixels of a `.bmp` file.
+    Return `None` if the data is not an uncompressed bitmap or if it is truncated.
+*/
+fn find_bitmap_layout(data: &[u8]) -> Option<BitmapLayout> {
+    use winapi::{BI_RGB, BI_BITFIELDS};
+
+    // BITMAPFILEHEADER (14 bytes) followed by a BITMAPINFOHEADER (40 bytes or more)
+    const FILE_HEADER_SIZE: usize = 14;
+    if data.len() < FILE_HEADER_SIZE + 40 || &data[0..2] != b"BM" {
+        return None;
+    }
+
+    let bits = read_u32(data, 10) as usize;
+    let header_size = read_u32(data, 14) as usize;
+    let width = read_u32(data, 18) as i32 as i64;
+    let height = read_u32(data, 22) as i32 as i64;
+    let bit_count = read_u16(data, 28) as u64;
+    let compression = read_u32(data, 30);
+
+    if header_size < 40 || bits < FILE_HEADER_SIZE + header_size || bits > data.len() || width <= 0 || height == 0 {
+        return None;
+    }
+
+    match (bit_count, compression) {
+        (1, BI_RGB) | (4, BI_RGB) | (8, BI_RGB) | (24, BI_RGB) | (16, BI_RGB) | (32, BI_RGB) | (16, BI_BITFIELDS) | (32, BI_BITFIELDS) => {},
+        _ => { return None; }
+    }
+
+    // The header is followed by the color masks if they are not part of it (BI_BITFIELDS with a 40 bytes header)
+    // and by the color table. `biClrUsed` is the size of the table, 0 meaning the full table for the images of 8 bits or less.
+    let masks: u64 = if compression == BI_BITFIELDS && header_size == 40 { 12 } else { 0 };
+    let colors = match read_u32(data, 46) as u64 {
+        0 if bit_count <= 8 => 1 << bit_count,
+        n => n
+    };
+
+    if ((bits - FILE_HEADER_SIZE) as u64) < header_size as u64 + masks + colors * 4 {
+        return None;
+    }
+
+    // Each row is aligned on 4 bytes
+    let stride = ((width as u64 * bit_count + 31) / 32) * 4;
+    let bits_len = match stride.checked_mul(height.abs() as u64) {
+        Some(len) if len <= (data.len() - bits) as u64 => len as usize,
+        _ => { return None; }
+    };
+
+    Some(BitmapLayout{ info: FILE_HEADER_SIZE, info_len: bits - FILE_HEADER_SIZE, bits: bits, bits_len: bits_len })
+}
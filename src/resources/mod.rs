@@ -18,7 +18,8 @@
     along with this program.  If not, see <http://www.gnu.org/licenses/>.
 */
 
-pub mod font;
+#[cfg(windows)] pub mod font;
+#[cfg(windows)] pub mod image;
 
 use std::any::TypeId;
 use std::hash::Hash;
@@ -27,7 +28,8 @@ use ui::Ui;
 use controls::AnyHandle;
 use error::Error;
 
-pub use resources::font::{FontT, Font};
+#[cfg(windows)] pub use resources::font::{FontT, Font};
+#[cfg(windows)] pub use resources::image::{ImageT, Image, ImageSource, set_control_icon, set_control_cursor};
 
 /**
     Structures implementing this trait can be used by a Ui to build a Resource
@@ -43,6 +45,13 @@ pub trait ResourceT<ID: Clone+Hash> {
         Should instance the resource and return it as a Box<Resource>. If an error is raised, it will be returned by `ui.commit`.
     */
     fn build(&self, ui: &Ui<ID>) -> Result<Box<Resource>, Error>;
+
+    /**
+        Called once the resource was added to the ui under `id`. Resources that react to the events of a control
+        bind their callbacks here, using `id` as callback ID. Does nothing by default.
+    */
+    #[allow(unused_variables)]
+    fn packed(&self, ui: &Ui<ID>, id: &ID) { }
 }
 
 /**
@@ -140,7 +140,9 @@ pub fn $n(ui: &$crate::Ui<$t>) -> Result<(), $crate::Error> {
     • resizable: `false`  
     • visible: `true`  
     • disabled: `false`  
-    • exit_on_close: `true`
+    • exit_on_close: `true`  
+    • accept_files: `false`  
+    • icon: `None`  
 
     Usage:  
     `nwg_window!()`  
@@ -155,7 +157,40 @@ macro_rules! nwg_window {
             title: "Native Windows GUI", 
             position: (100, 100), size: (800, 600), 
             resizable: false, visible: true, disabled: false, 
-            exit_on_close: true
+            exit_on_close: true, accept_files: false,
+            icon: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the Dialog control.
+
+    Defaults:  
+    • title: `"Native Windows GUI"`  
+    • position: `(200, 200)`  
+    • size: `(400, 200)`  
+    • resizable: `false`  
+    • owner: `None`  
+    • accept_files: `false`  
+
+    Usage:  
+    `nwg_dialog!()`  
+    `nwg_dialog!(owner=Some("MainWindow"); title="Settings")`  
+    `nwg_dialog!(\* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_dialog {
+    ( $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::DialogT{ 
+            title: "Native Windows GUI", 
+            position: (200, 200), size: (400, 200), 
+            resizable: false, owner: None, accept_files: false
         };
         
         $( t.$i = $v; );*
@@ -394,12 +429,35 @@ macro_rules! nwg_menu {
     }}
 }
 
+/**
+    Sane defaults for the PopupMenu control.
+
+    Defaults:  
+    • owner: `None` (the menu commands are sent to the Ui)  
+
+    Usage:  
+    `nwg_popup_menu!()`  
+    `nwg_popup_menu!(owner=Some("MyWindow"))`  
+*/
+#[macro_export]
+macro_rules! nwg_popup_menu {
+    ($( $i:ident=$v:expr );*) => { {
+        let mut t = $crate::PopupMenuT{ owner: None };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
 /**
     Sane defaults for the MenuItem control. Requires a menu parent.
 
     Defaults:  
     • text: `"Menuitem"`  
     • disabled: `false`  
+    • checked: `false`  
+    • radio: `false`  
+    • default: `false`  
+    • image: `None`  
 
     Usage:  
     `nwg_menuitem!(parent="MyParent";)`  
@@ -409,7 +467,7 @@ macro_rules! nwg_menu {
 #[macro_export]
 macro_rules! nwg_menuitem {
     (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
-        let mut t =  $crate::MenuItemT{  text: "Menuitem", parent: $p, disabled: false };
+        let mut t =  $crate::MenuItemT{  text: "Menuitem", parent: $p, disabled: false, checked: false, radio: false, default: false, image: None };
         $( t.$i = $v; );*
         t
     }}
@@ -673,6 +731,266 @@ macro_rules! nwg_datepicker {
     }}
 }
 
+/**
+    Sane defaults for the NumericInput control. Requires a parent.
+
+    Defaults:  
+    • value: `0`  
+    • range: `(i64::min_value(), i64::max_value())`  
+    • step: `1`  
+    • decimals: `0`  
+    • position: `(0, 0)`  
+    • size: `(100, 30)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • readonly: `false`  
+    • font: `None`  
+
+    Usage:  
+    `nwg_numinput!(parent="MyParent";)`  
+    `nwg_numinput!(parent="MyParent"; range=(0, 100); step=5)`  
+    `nwg_numinput!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_numinput {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::NumericInputT{ 
+            value: 0,
+            range: (i64::min_value(), i64::max_value()),
+            step: 1, decimals: 0,
+            position: (0, 0), size: (100, 30), 
+            visible: true, disabled: false, readonly: false,
+            parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the ListView control. Requires a parent.
+
+    Defaults:  
+    • collection: `vec![]`  
+    • columns: `vec![]`  
+    • style: `ListViewStyle::Report`  
+    • position: `(0, 0)`  
+    • size: `(200, 150)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • multi_select: `false`  
+    • checkboxes: `false`  
+    • sortable: `true`  
+    • virtual_mode: `false`  
+    • font: `None`  
+
+    Usage:  
+    `nwg_listview!(data=String; parent="MyParent";)`  
+    `nwg_listview!(parent="MyParent"; collection=vec!["Hello", "World"]; columns=vec![Rc::new(my_column)])`  
+    `nwg_listview!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_listview {
+    (data=$t:ty; parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::ListViewT::<$t, _>{ 
+            collection: vec![], columns: vec![],
+            style: $crate::constants::ListViewStyle::Report,
+            position: (0, 0), size: (200, 150), 
+            visible: true, disabled: false, multi_select: false,
+            checkboxes: false, sortable: true, virtual_mode: false,
+            parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }};
+
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::ListViewT::<_, _>{ 
+            collection: vec![], columns: vec![],
+            style: $crate::constants::ListViewStyle::Report,
+            position: (0, 0), size: (200, 150), 
+            visible: true, disabled: false, multi_select: false,
+            checkboxes: false, sortable: true, virtual_mode: false,
+            parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the TreeView control. Requires a parent.
+
+    Defaults:  
+    • nodes: `vec![]`  
+    • position: `(0, 0)`  
+    • size: `(200, 150)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • checkboxes: `false`  
+    • loader: `None`  
+    • font: `None`  
+
+    Usage:  
+    `nwg_treeview!(data=String; parent="MyParent";)`  
+    `nwg_treeview!(parent="MyParent"; nodes=vec![(None, TreeNode{id: "Root", data: "Root", lazy: true})])`  
+    `nwg_treeview!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_treeview {
+    (data=$t:ty; parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::TreeViewT::<$t, _>{ 
+            nodes: vec![],
+            position: (0, 0), size: (200, 150), 
+            visible: true, disabled: false, checkboxes: false,
+            loader: None, parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }};
+
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::TreeViewT::<_, _>{ 
+            nodes: vec![],
+            position: (0, 0), size: (200, 150), 
+            visible: true, disabled: false, checkboxes: false,
+            loader: None, parent: $p, font: None
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the TabsContainer control. Requires a parent.
+
+    Defaults:  
+    • position: `(0, 0)`  
+    • size: `(200, 150)`  
+    • visible: `true`  
+    • disabled: `false`  
+    • font: `None`  
+
+    Usage:  
+    `nwg_tabs_container!(parent="MyParent";)`  
+    `nwg_tabs_container!(parent="MyParent"; size=(400, 300))`  
+    `nwg_tabs_container!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_tabs_container {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::TabsContainerT {
+            position: (0, 0), size: (200, 150), 
+            visible: true, disabled: false,
+            parent: $p, font: None
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the Tab control. Requires a tabs container parent.
+
+    Defaults:  
+    • text: `"Tab"`  
+
+    Usage:  
+    `nwg_tab!(parent="MyTabs";)`  
+    `nwg_tab!(parent="MyTabs"; text="Settings")`  
+*/
+#[macro_export]
+macro_rules! nwg_tab {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = $crate::TabT { text: "Tab", parent: $p };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the Accelerator control. Requires a window parent and a shortcut.
+
+    Defaults:  
+    • item: `None`  
+
+    Usage:  
+    `nwg_accelerator!(parent="MyWindow"; shortcut="Ctrl+S";)`  
+    `nwg_accelerator!(parent="MyWindow"; shortcut="Ctrl+S"; item=Some("SaveItem"))`  
+*/
+#[macro_export]
+macro_rules! nwg_accelerator {
+    (parent=$p:expr; shortcut=$s:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = $crate::AcceleratorT { shortcut: $s, parent: $p, item: None };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the TrayIcon control.
+
+    Defaults:  
+    • tooltip: `""`  
+    • icon: `None` (the default application icon)  
+    • menu: `None`  
+    • visible: `true`  
+
+    Usage:  
+    `nwg_tray_icon!()`  
+    `nwg_tray_icon!(tooltip="Sync"; menu=Some("TrayMenu"))`  
+*/
+#[macro_export]
+macro_rules! nwg_tray_icon {
+    ($( $i:ident=$v:expr );*) => { {
+        let mut t = 
+        $crate::TrayIconT{
+            tooltip: "",
+            icon: None,
+            menu: None,
+            visible: true
+        };
+        $( t.$i = $v; );*
+        t
+    }}
+}
+
+/**
+    Sane defaults for the Binding control. Requires an observable value, a control and a property.
+    The value and the property must have the same type. Use `BindingT` directly to set the converters.
+
+    Defaults:  
+    • validator: `None`  
+
+    Usage:  
+    `nwg_binding!(value="Name"; control="NameInput"; property=TextInputText;)`  
+    `nwg_binding!(value="Volume"; control="VolumeInput"; property=NumericInputValue; validator=Some(valid_volume))`  
+*/
+#[macro_export]
+macro_rules! nwg_binding {
+    (value=$v:expr; control=$c:expr; property=$p:expr; $( $i:ident=$x:expr );* ) => { {
+        let mut t = $crate::BindingT::new($v, $c, $p);
+        $( t.$i = $x; );*
+        t
+    }}
+}
+
 /**
     Sane defaults for the FileDialog control.
 
@@ -716,6 +1034,7 @@ macro_rules! nwg_filedialog {
     • size: `(100, 30)`  
     • visible: `true`  
     • disabled: `false`  
+    • accept_files: `false`  
 
     Usage:  
     `nwg_canvas!(parent="MyParent";)`  
@@ -729,7 +1048,7 @@ macro_rules! nwg_canvas {
         $crate::CanvasT {
             position: (0, 0), size: (100, 100), 
             visible: true, disabled: false,
-            parent: $p,
+            accept_files: false, parent: $p,
         };
         $( t.$i = $v; );*
         t
@@ -767,4 +1086,122 @@ macro_rules! nwg_font {
 
         t
     }}
-}
\ No newline at end of file
+}
+
+/**
+    Sane defaults for the Image resource. Requires a source.
+
+    Defaults:  
+    • image_type: `ImageType::Icon`  
+    • size: `(0, 0)`  
+
+    Usage:  
+    `nwg_image!(source=ImageSource::File("app.ico");)`  
+    `nwg_image!(source=ImageSource::File("hand.cur"); image_type=ImageType::Cursor)`  
+    `nwg_image!(source=ImageSource::Resource("AppIcon"); \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_image {
+    (source=$s:expr; $( $i:ident=$v:expr );*) => { {
+        let mut t =
+        $crate::ImageT{
+            source: $s,
+            image_type: $crate::constants::ImageType::Icon,
+            size: (0, 0)
+        };
+
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+//---- Layouts ----//
+
+/**
+    Sane defaults for the BoxLayout resource. Requires a parent.
+
+    Defaults:  
+    • orientation: `Orientation::Vertical`  
+    • margin: `(5, 5, 5, 5)`  
+    • spacing: `5`  
+    • children: `vec![]`  
+
+    Usage:  
+    `nwg_boxlayout!(parent="MyParent";)`  
+    `nwg_boxlayout!(parent="MyParent"; children=vec![("Button", LayoutTrack{weight: 1, min: 0, max: None})])`  
+    `nwg_boxlayout!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_boxlayout {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::BoxLayoutT{ 
+            parent: $p,
+            orientation: $crate::constants::Orientation::Vertical,
+            margin: (5, 5, 5, 5), spacing: 5,
+            children: vec![]
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the GridLayout resource. Requires a parent.
+
+    Defaults:  
+    • margin: `(5, 5, 5, 5)`  
+    • spacing: `5`  
+    • columns: `vec![]`  
+    • rows: `vec![]`  
+    • children: `vec![]`  
+
+    Usage:  
+    `nwg_gridlayout!(parent="MyParent";)`  
+    `nwg_gridlayout!(parent="MyParent"; spacing=10)`  
+    `nwg_gridlayout!(parent="MyParent"; \* Any combinations of the template properties*\)`    
+*/
+#[macro_export]
+macro_rules! nwg_gridlayout {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::GridLayoutT{ 
+            parent: $p,
+            margin: (5, 5, 5, 5), spacing: 5,
+            columns: vec![], rows: vec![],
+            children: vec![]
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
+
+/**
+    Sane defaults for the AnchorLayout resource. Requires a parent.
+
+    Defaults:  
+    • children: `vec![]`  
+
+    Usage:  
+    `nwg_anchorlayout!(parent="MyParent";)`  
+    `nwg_anchorlayout!(parent="MyParent"; children=vec![("Button", AnchorItem::Dock{dock: Dock::Fill, size: 0})])`  
+*/
+#[macro_export]
+macro_rules! nwg_anchorlayout {
+    (parent=$p:expr; $( $i:ident=$v:expr );* ) => { {
+        let mut t = 
+        $crate::AnchorLayoutT{ 
+            parent: $p,
+            children: vec![]
+        };
+        
+        $( t.$i = $v; );*
+
+        t
+    }}
+}
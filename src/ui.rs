@@ -25,13 +25,22 @@ use std::collections::HashMap;
 use std::any::{Any, TypeId};
 use std::cell::{RefCell, Ref, RefMut};
 use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::marker::PhantomData;
+use std::future::Future;
+
+use winapi::{UINT, WPARAM};
 
 use low::message_handler::MessageHandler;
-use low::defs::{PackUserValueArgs, PackControlArgs, PackResourceArgs, UnpackArgs, BindArgs, UnbindArgs, TriggerArgs};
+use low::backend::{self, Backend, RemotePoster};
+use low::defs::{PackUserValueArgs, PackControlArgs, PackResourceArgs, UnpackArgs, BindArgs, UnbindArgs, TriggerArgs, ExecuteArgs, WakeArgs};
 use controls::{ControlT, Control, AnyHandle};
 use resources::{ResourceT, Resource};
-use events::{Event, EventCallback, EventArgs};
+use events::{Event, EventCallback, EventArgs, EventDecoders};
 use error::Error;
+use bindings::Observable;
+use executor::{LocalTask, TaskWaker, BlockingTask};
 
 type InnerId = u64;
 type HandleHash = u64;
@@ -60,22 +69,31 @@ pub struct UiInner<ID: Hash+Clone+'static> {
     pub inner_public_map: HashMap<InnerId, (ID, TypeId)>,
 
     // Map the handle of the controls to its ui inner id. Used when matching controls from within the events proc
-    pub handle_inner_map: HashMap<HandleHash, InnerId>
+    pub handle_inner_map: HashMap<HandleHash, InnerId>,
+
+    // Map the type of the controls to their notifications decoders. Used when decoding WM_COMMAND and WM_NOTIFY
+    pub decoders: HashMap<TypeId, EventDecoders>,
+
+    // Local tasks spawned with `Ui::spawn_local` that are not completed. Indexed by task id.
+    pub tasks: HashMap<u64, LocalTask>,
+    pub next_task: u64,
+
+    // Set to false when the ui is dropped. Shared with the UiSender created from the ui
+    pub alive: Arc<AtomicBool>,
+
+    // The backend that was installed on the thread before the ui. Restored when the ui is dropped.
+    pub previous_backend: Option<Rc<Backend>>
 }
 
 impl<ID: Hash+Clone> UiInner<ID> {
 
-    pub fn new() -> Result<UiInner<ID>, Error> {
-        use low::other_helper::{enable_visual_styles, enable_com};
-        let messages: MessageHandler<ID> = match MessageHandler::new() {
+    pub fn new(backend: Rc<Backend>) -> Result<UiInner<ID>, Error> {
+        let messages: MessageHandler<ID> = match MessageHandler::new(backend.clone()) {
             Ok(msg) => msg,
             Err(e) => { return Err(e); }
         };
 
-        unsafe{ 
-            enable_visual_styles(); 
-            enable_com();
-        }
+        unsafe{ backend.init(); }
 
         Ok(UiInner{
             messages: messages,
@@ -84,7 +102,12 @@ impl<ID: Hash+Clone> UiInner<ID> {
             control_events: HashMap::with_capacity(32),
             resources: HashMap::with_capacity(16),
             inner_public_map: HashMap::with_capacity(64),
-            handle_inner_map: HashMap::with_capacity(32) })
+            handle_inner_map: HashMap::with_capacity(32),
+            decoders: HashMap::with_capacity(16),
+            tasks: HashMap::with_capacity(16),
+            next_task: 0,
+            alive: Arc::new(AtomicBool::new(true)),
+            previous_backend: None })
     }
 
     pub fn pack_user_value(&mut self, params: PackUserValueArgs<ID>) -> Option<Error> {
@@ -122,11 +145,19 @@ impl<ID: Hash+Clone> UiInner<ID> {
                         event_collection.insert(e, Rc::new(Vec::new()));
                     }
 
-                    self.inner_public_map.insert(inner_id, (params.id, params.value.resource_type_id()));
+                    // Register the notifications decoders of the control type
+                    let type_id = params.value.resource_type_id();
+                    if !self.decoders.contains_key(&type_id) {
+                        self.decoders.insert(type_id, params.value.decoders());
+                    }
+
+                    self.inner_public_map.insert(inner_id, (params.id.clone(), type_id));
                     self.controls.insert(inner_id, RefCell::new(control) );
                     self.control_events.insert(inner_id, event_collection);
                     self.handle_inner_map.insert(handle_hash, inner_id);
 
+                    params.value.packed(&tmp_ui, &params.id);
+
                     ::std::mem::forget(tmp_ui);
 
                     None
@@ -146,10 +177,12 @@ impl<ID: Hash+Clone> UiInner<ID> {
                 Ok(resource) => {
                     let handle_hash = UiInner::<ID>::hash_handle(&resource.handle());
 
-                    self.inner_public_map.insert(inner_id, (params.id, params.value.resource_type_id()));
+                    self.inner_public_map.insert(inner_id, (params.id.clone(), params.value.resource_type_id()));
                     self.resources.insert(inner_id, RefCell::new(resource) );
                     self.handle_inner_map.insert(handle_hash, inner_id);
 
+                    params.value.packed(&tmp_ui, &params.id);
+
                     ::std::mem::forget(tmp_ui);
                     None
                 },
@@ -160,7 +193,8 @@ impl<ID: Hash+Clone> UiInner<ID> {
 
     fn unpack_control(&mut self, id: InnerId) -> Option<Error> {
         use low::events::unhook_window_events;
-        use low::menu_helper::{list_menu_children};
+        #[cfg(windows)] use low::menu_helper::{list_menu_children};
+        #[cfg(windows)] use low::window_helper::set_window_cursor;
         use low::window_helper::list_window_children;
        
 
@@ -184,6 +218,7 @@ impl<ID: Hash+Clone> UiInner<ID> {
         if handle.is_err() { return Some(handle.err().unwrap()); }
 
         let children_ids: Vec<u64> = match handle.unwrap() {
+            #[cfg(windows)]
             AnyHandle::HMENU(h) => unsafe {
                 let mut children = vec![id];
                 children.append( &mut list_menu_children(self, h) );
@@ -194,7 +229,10 @@ impl<ID: Hash+Clone> UiInner<ID> {
                 children.append( &mut list_window_children(h, self as *mut UiInner<ID>) );
                 children
             },
-            AnyHandle::HMENU_ITEM(_, _) | AnyHandle::HFONT(_) | AnyHandle::Custom(_, _) => vec![id], // These handle can't have children
+            #[cfg(not(windows))]
+            AnyHandle::HMENU(_) => vec![id],
+            AnyHandle::HMENU_ITEM(_, _) | AnyHandle::HFONT(_) | AnyHandle::HBITMAP(_) | AnyHandle::HICON(_) |
+            AnyHandle::HCURSOR(_) | AnyHandle::Custom(_, _) => vec![id], // These handle can't have children
         };
        
         for id in children_ids.iter().rev() {
@@ -211,9 +249,12 @@ impl<ID: Hash+Clone> UiInner<ID> {
             let handle_hash = *self.handle_inner_map.iter().find(|&(_, ref v)| *v == id).expect("Could not match handle while unpacking control").0;
             self.handle_inner_map.remove(&handle_hash);
 
-            // Unhook the events dispatcher if its a window
+            // Unhook the events dispatcher and remove the cursor property if its a window
             match control.handle() {
-                AnyHandle::HWND(h) => unhook_window_events::<ID>(h),
+                AnyHandle::HWND(h) => unsafe {
+                    unhook_window_events::<ID>(h);
+                    #[cfg(windows)] set_window_cursor(h, None);
+                },
                 _ => {/* Nothing to free here */}
             };
             
@@ -231,10 +272,23 @@ impl<ID: Hash+Clone> UiInner<ID> {
             return Some(Error::ResourceInUse);
         }
 
+        // Check if the resource is an image shown by a control. The controls do not own their images.
+        #[cfg(windows)]
+        {
+            use resources::image::image_in_use;
+            let handle = self.resources.get(&id).unwrap().borrow().handle();
+            if unsafe{ image_in_use(self, &handle) } {
+                return Some(Error::ResourceInUse);
+            }
+        }
+
          // Removes stuffs
         self.inner_public_map.remove(&id).unwrap();
         let resource = self.resources.remove(&id).unwrap();
         let mut resource = resource.into_inner();
+
+        let handle_hash = UiInner::<ID>::hash_handle(&resource.handle());
+        self.handle_inner_map.remove(&handle_hash);
         
         // Free the control custom resources
         resource.free();
@@ -363,6 +417,55 @@ impl<ID: Hash+Clone> UiInner<ID> {
         None
     }
 
+    pub fn execute(&mut self, params: ExecuteArgs<ID>) -> Option<Error> {
+        let mut cb = params.cb;
+
+        let tmp_ui: Ui<ID> = Ui{inner: self as *mut UiInner<ID>};
+        (cb)(&tmp_ui);
+
+        ::std::mem::forget(tmp_ui);
+        None
+    }
+
+    pub fn spawn_task(&mut self, task: LocalTask) {
+        use low::defs::NWG_WAKE;
+
+        self.next_task += 1;
+        let task_id = self.next_task;
+        self.tasks.insert(task_id, task);
+
+        // The task is polled for the first time when the wake command is executed
+        let inner = self as *mut UiInner<ID>;
+        self.messages.post(inner, NWG_WAKE, Box::new(WakeArgs{ task: task_id }) as Box<Any>);
+    }
+
+    pub fn poll_task(&mut self, params: WakeArgs) -> Option<Error> {
+        use std::task::{Context, Waker};
+
+        // A task can be woken many times before being polled, or after being completed
+        let task_id = params.task;
+        let mut task = match self.tasks.remove(&task_id) {
+            Some(t) => t,
+            None => { return None; }
+        };
+
+        let waker = Waker::from(Arc::new(TaskWaker{
+            task: task_id,
+            inner: self as *mut UiInner<ID> as usize,
+            hwnd: self.messages.hwnd as usize,
+            poster: self.messages.backend.remote_poster(),
+            alive: self.alive.clone()
+        }));
+
+        // The task is removed from the map while it is polled, because it may spawn other tasks
+        let mut cx = Context::from_waker(&waker);
+        if task.as_mut().poll(&mut cx).is_pending() {
+            self.tasks.insert(task_id, task);
+        }
+
+        None
+    }
+
     pub fn handle_of(&self, id: InnerId) -> Result<AnyHandle, Error> {
         if !self.inner_public_map.contains_key(&id) {
             return Err(Error::KeyNotFound);
@@ -397,13 +500,21 @@ impl<ID: Hash+Clone> UiInner<ID> {
         }
     }
 
+    #[inline(always)]
+    pub fn decoders_of(&self, id: &InnerId) -> Option<EventDecoders> {
+        match self.inner_public_map.get(id) {
+            Some(&(_, ref type_id)) => self.decoders.get(type_id).cloned(),
+            None => None
+        }
+    }
+
     #[inline(always)]
     pub fn types_matches(&self, id: &InnerId, tid: TypeId) -> bool {
         self.inner_public_map.get(id).unwrap().1 == tid
     }
 
     #[inline(always)]
-    fn hash_id(id: &ID) -> InnerId {
+    pub fn hash_id(id: &ID) -> InnerId {
         use std::hash::Hasher;
         use std::collections::hash_map::DefaultHasher;
         let mut s1 = DefaultHasher::new();
@@ -426,6 +537,12 @@ impl<ID: Hash+Clone> Drop for UiInner<ID> {
 
     fn drop(&mut self) {
         use low::events::unhook_window_events;
+
+        // Commands sent from other threads after this point are refused
+        self.alive.store(false, Ordering::SeqCst);
+
+        // Pending tasks are dropped before the controls they might reference
+        self.tasks.clear();
         
         let controls_ids: Vec<u64> = self.controls.keys().map(|k| *k).collect();
         for id in controls_ids {
@@ -460,12 +577,35 @@ impl<ID:Hash+Clone> Ui<ID> {
         Returns `Ok(ui)` if the initialization was successful  
         Returns `Err(Error::System)` if the system could not initialize the ui
     */
+    #[cfg(windows)]
     pub fn new() -> Result<Ui<ID>, Error> {
+        use low::backend::SystemBackend;
+        Ui::with_backend(Rc::new(SystemBackend))
+    }
+
+    /**
+        Create a new Ui that uses `backend` to talk to the system.  
+        The Ui commands and the events of its controls are always executed over its own backend. The other calls
+        (ex: the control methods) use the backend of the last Ui created on the thread. When the Ui is dropped,
+        the backend that was used before its creation is restored.
+
+        Returns `Ok(ui)` if the initialization was successful  
+        Returns `Err(Error::System)` if the backend could not initialize the ui
+    */
+    pub fn with_backend(backend: Rc<Backend>) -> Result<Ui<ID>, Error> {
         use low::events::hook_window_events;
 
-        let inner = match UiInner::new() {
-            Ok(inner) => Box::into_raw(Box::new(inner)),
-            Err(e) => { return Err(e); }
+        let previous = backend::install(backend.clone());
+
+        let inner = match UiInner::new(backend) {
+            Ok(mut inner) => {
+                inner.previous_backend = previous;
+                Box::into_raw(Box::new(inner))
+            },
+            Err(e) => {
+                backend::restore(previous);
+                return Err(e);
+            }
         };
 
         // Hook the inner message window. This is basically a SAFE hack to process non nwg events that are sent to ui (ie: WM_TIMER)
@@ -507,6 +647,19 @@ impl<ID:Hash+Clone> Ui<ID> {
         inner.messages.post(self.inner, NWG_PACK_USER_VALUE, Box::new(data) as Box<Any> );
     }
 
+    /**
+        Add an observable user value to the Ui. This is a shortcut for `ui.pack_value(id, Observable::new(value))`.  
+        The value can be bound to the controls with a `BindingT` and is accessed with `ui.get::<Observable<T>>`.  
+        Delayed, this only registers the command in the ui message queue.  
+        Either call `ui.commit` to execute it now or wait for the command to be executed in the main event loop.  
+
+        Commit returns  
+        • `Error::KeyExist` if the key already exists in the ui  
+    */
+    pub fn pack_observable<T: 'static>(&self, id: &ID, value: T) {
+        self.pack_value(id, Observable::new(value));
+    }
+
     /**
         Add a control to the Ui.  
         Delayed, this only registers the command in the ui message queue.  
@@ -549,6 +702,7 @@ impl<ID:Hash+Clone> Ui<ID> {
         Commit may returns:  
           • `Error::ControlInUse` if the control callbacks are being executed  
           • `Error::ControlInUse` if the object is currently borrowed (using ui.get or ui.get_mut)  
+          • `Error::ResourceInUse` if the resource is borrowed or if it is an image shown by a control  
           • `Error::KeyNotFound` if the id do not exists in the Ui  
     */
     pub fn unpack(&self, id: &ID) {
@@ -769,6 +923,66 @@ impl<ID:Hash+Clone> Ui<ID> {
         inner.inner_public_map.contains_key(&UiInner::hash_id(id))
     }
 
+    /**
+        Return a handle that can send commands to the ui from other threads. See `UiSender`.
+    */
+    pub fn sender(&self) -> UiSender<ID> {
+        let inner = unsafe{ &*self.inner };
+        UiSender {
+            inner: self.inner as usize,
+            hwnd: inner.messages.hwnd as usize,
+            poster: inner.messages.backend.remote_poster(),
+            alive: inner.alive.clone(),
+            p: PhantomData
+        }
+    }
+
+    /**
+        Return a handle to the ui that can be moved in the local tasks. See `LocalUi`.
+    */
+    pub fn local(&self) -> LocalUi<ID> {
+        let inner = unsafe{ &*self.inner };
+        LocalUi{ inner: self.inner, alive: inner.alive.clone() }
+    }
+
+    /**
+        Spawn a task on the thread that owns the Ui. The task is polled by the event loop each time it is woken,
+        so it can wait on the futures of the `tasks` module without blocking the windows. Use a `LocalUi` to access
+        the ui from the task.  
+        Delayed, the task is polled for the first time when the command is executed by `ui.commit` or by the main event loop.  
+
+        Tasks that are still pending when the Ui is dropped are dropped with it.
+    */
+    pub fn spawn_local<F: Future<Output=()>+'static>(&self, task: F) {
+        let inner = unsafe{ &mut *self.inner };
+        inner.spawn_task(Box::pin(task));
+    }
+
+    /**
+        Execute `f` on a worker thread and return a future that resolves to its result.  
+        Await the future in a local task to receive the result on the thread that owns the Ui.
+    */
+    pub fn spawn_blocking<T: Send+'static, F: FnOnce() -> T + Send + 'static>(&self, f: F) -> BlockingTask<T> {
+        BlockingTask::spawn(f)
+    }
+
+    /**
+        Show a dialog and dispatch the events until the dialog is closed. The owner of the dialog is disabled in the meantime.
+        Pressing Enter in the dialog raises `Event::Accepted`, pressing Esc or closing the dialog cancels it.
+
+        Returns:  
+          • `Ok(Some(T))` if the dialog was closed with `Dialog::accept`  
+          • `Ok(None)` if the dialog was cancelled  
+          • `Error::KeyNotFound` if the id is not in the Ui.  
+          • `Error::BadType` if the id do not identify a dialog or if the accepted value is not a `T`  
+          • `Error::BorrowError` if the dialog was already borrowed mutably  
+          • `Error::ControlInUse` if the dialog is already running  
+    */
+    #[cfg(windows)]
+    pub fn run_modal<T: 'static>(&self, id: &ID) -> Result<Option<T>, Error> {
+        ::controls::dialog::run_modal::<ID, T>(self, id)
+    }
+
     /**
         Return the message window handle of the ui. Useful for controls or functions that requires a window (such as timers)
     */
@@ -781,8 +995,193 @@ impl<ID:Hash+Clone> Ui<ID> {
 
 impl<ID: Hash+Clone> Drop for Ui<ID> {
     fn drop(&mut self) {
-        unsafe{ drop(Box::from_raw(self.inner)); }
+        let mut inner = unsafe{ Box::from_raw(self.inner) };
         self.inner = ptr::null_mut();
+
+        let backend = inner.messages.backend.clone();
+        let previous = inner.previous_backend.take();
+        backend::with(&backend, move || drop(inner));
+
+        // Another ui might have installed its backend after this one
+        if backend::is_current(&backend) {
+            backend::restore(previous);
+        }
+    }
+}
+
+/**
+    A non owning reference to a Ui. Used by the controls that must access their Ui outside of the events callbacks
+    (ex: the bindings). A UiRef must not be used after the Ui is freed. Controls holding one must release it in `Control::free`.
+*/
+#[derive(Clone)]
+pub struct UiRef<ID: Hash+Clone+'static> {
+    inner: *mut UiInner<ID>
+}
+
+impl<ID: Hash+Clone> UiRef<ID> {
+
+    /// Create a reference to `ui`
+    pub fn new(ui: &Ui<ID>) -> UiRef<ID> {
+        UiRef{ inner: ui.inner }
+    }
+
+    /// Execute `f` with a temporary Ui
+    pub unsafe fn with<R, F: FnOnce(&Ui<ID>) -> R>(&self, f: F) -> R {
+        let tmp_ui = Ui{inner: self.inner};
+        let r = f(&tmp_ui);
+        ::std::mem::forget(tmp_ui);
+        r
+    }
+
+    /// Bind a callback right away, using an inner callback id. Unlike `Ui::bind`, this is not delayed.
+    pub unsafe fn bind<T>(&self, id: &ID, cb_id: u64, event: Event, cb: T) -> Option<Error> where
+      T: Fn(&Ui<ID>, &ID, &Event, &EventArgs) -> ()+'static {
+        let inner = &mut *self.inner;
+        inner.bind(BindArgs{ id: UiInner::hash_id(id), cb_id: cb_id, event: event, cb: Box::new(cb) })
+    }
+
+    /// Unbind a callback right away, using an inner callback id. Unlike `Ui::unbind`, this is not delayed.
+    pub unsafe fn unbind(&self, id: &ID, cb_id: u64, event: Event) -> Option<Error> {
+        let inner = &mut *self.inner;
+        inner.unbind(UnbindArgs{ id: UiInner::hash_id(id), cb_id: cb_id, event: event })
+    }
+
+}
+
+/**
+    A handle that sends commands to a Ui from any thread. Created with `Ui::sender`.
+
+    The commands are posted in the Ui command queue and are executed on the thread that owns the Ui,
+    either by the event loop (`dispatch_events`) or by `ui.commit`. This is the only safe way to update
+    the controls of a Ui (ex: a progress bar) from a worker thread.
+
+    Once the Ui is dropped, the sender refuses any new command. Commands still waiting in the queue when the Ui
+    is dropped are never executed.
+*/
+pub struct UiSender<ID: Hash+Clone+'static> {
+    inner: usize,   // *mut UiInner<ID>. Only dereferenced by the thread that owns the Ui.
+    hwnd: usize,    // The ui message window
+    poster: Arc<RemotePoster>,
+    alive: Arc<AtomicBool>,
+    p: PhantomData<ID>
+}
+
+unsafe impl<ID: Hash+Clone+'static> Send for UiSender<ID> {}
+
+impl<ID: Hash+Clone+'static> UiSender<ID> {
+
+    /**
+        Execute `f` on the thread that owns the Ui.  
+        Delayed, this only registers the command in the ui message queue.  
+
+        Returns `Err(Error::UserError)` if the Ui was dropped or if the command could not be posted.
+    */
+    pub fn execute<F: FnOnce(&Ui<ID>) -> () + Send + 'static>(&self, f: F) -> Result<(), Error> {
+        use low::defs::NWG_EXECUTE;
+
+        // A FnOnce cannot be called from a box, so it is wrapped in a FnMut that can only be called once.
+        let mut f = Some(f);
+        let cb = move |ui: &Ui<ID>| { if let Some(f) = f.take() { f(ui); } };
+
+        let data = ExecuteArgs{ cb: Box::new(cb) };
+        self.post(NWG_EXECUTE, Box::new(data) as Box<Any>)
+    }
+
+    /**
+        Trigger an event on a control of the Ui. See `Ui::trigger`.  
+        Delayed, this only registers the command in the ui message queue.  
+
+        Returns `Err(Error::UserError)` if the Ui was dropped or if the command could not be posted.  
+        Errors raised while the command is executed are returned by `ui.commit`.
+    */
+    pub fn trigger(&self, id: &ID, event: Event, event_arg: EventArgs) -> Result<(), Error> {
+        use low::defs::NWG_TRIGGER;
+
+        let inner_id = UiInner::hash_id(id);
+        let data = TriggerArgs{ id: inner_id, event: event, args: event_arg};
+        self.post(NWG_TRIGGER, Box::new(data) as Box<Any>)
+    }
+
+    /**
+        Return `true` if the Ui still exists
+    */
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+    fn post(&self, msg: UINT, data: Box<Any>) -> Result<(), Error> {
+        use low::message_handler::{pack_message_data, free_message_data};
+
+        if !self.is_alive() {
+            return Err(Error::UserError("The Ui was dropped".to_string()));
+        }
+
+        unsafe {
+            let data = pack_message_data(data);
+            if self.poster.post_message(self.hwnd, msg, self.inner as WPARAM, data) {
+                Ok(())
+            } else {
+                free_message_data(data);
+                Err(Error::UserError("The command could not be posted to the Ui".to_string()))
+            }
+        }
+    }
+}
+
+impl<ID: Hash+Clone+'static> Clone for UiSender<ID> {
+    fn clone(&self) -> UiSender<ID> {
+        UiSender {
+            inner: self.inner,
+            hwnd: self.hwnd,
+            poster: self.poster.clone(),
+            alive: self.alive.clone(),
+            p: PhantomData
+        }
+    }
+}
+
+/**
+    A handle to a Ui that can be moved in the tasks spawned with `Ui::spawn_local`. Created with `Ui::local`.
+
+    Unlike a `UiSender`, a `LocalUi` cannot be sent to other threads and its functions are executed right away.
+    Once the Ui is dropped, `with` returns an error instead of executing the function.
+*/
+pub struct LocalUi<ID: Hash+Clone+'static> {
+    inner: *mut UiInner<ID>,
+    alive: Arc<AtomicBool>
+}
+
+impl<ID: Hash+Clone+'static> LocalUi<ID> {
+
+    /**
+        Execute `f` with the Ui.  
+
+        Returns `Err(Error::UserError)` if the Ui was dropped.
+    */
+    pub fn with<R, F: FnOnce(&Ui<ID>) -> R>(&self, f: F) -> Result<R, Error> {
+        if !self.is_alive() {
+            return Err(Error::UserError("The Ui was dropped".to_string()));
+        }
+
+        let tmp_ui: Ui<ID> = Ui{inner: self.inner};
+        let r = f(&tmp_ui);
+        ::std::mem::forget(tmp_ui);
+
+        Ok(r)
+    }
+
+    /**
+        Return `true` if the Ui still exists
+    */
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+}
+
+impl<ID: Hash+Clone+'static> Clone for LocalUi<ID> {
+    fn clone(&self) -> LocalUi<ID> {
+        LocalUi{ inner: self.inner, alive: self.alive.clone() }
     }
 }
 
@@ -792,6 +1191,7 @@ impl<ID: Hash+Clone> Drop for Ui<ID> {
 
     Return once a quit event was received.
 */
+#[cfg(windows)]
 pub fn dispatch_events() {
     // Actual code is located under the low module because that's where most of the unsafe code should be
     unsafe{ ::low::events::dispatch_events(); }
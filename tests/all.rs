@@ -1,13 +1,31 @@
 #![allow(unused_must_use)]
 #![allow(unused_variables)]
 
-extern crate native_windows_gui as nwg;
+#[cfg_attr(windows, macro_use)] extern crate native_windows_gui as nwg;
+#[cfg(windows)] extern crate kernel32;
+#[cfg(windows)] extern crate user32;
+
+use std::rc::Rc;
 
 use nwg::*;
 use nwg::constants::*;
+use nwg::backend::MockBackend;
+use nwg::custom::{AnyHandle, ControlT};
+use nwg::geometry::*;
 
+#[cfg(windows)]
 fn setup_ui() -> Ui<u64> { Ui::new().unwrap() }
-fn window() -> WindowT<&'static str> {  WindowT{title: "", position:(-600,-600), size:(100, 100), resizable:true, visible:true, disabled:false, exit_on_close:true} }
+#[cfg(not(windows))]
+fn setup_ui() -> Ui<u64> { Ui::with_backend(Rc::new(MockBackend::new())).unwrap() }
+fn setup_mock_ui() -> (Ui<u64>, Rc<MockBackend>) { 
+    let backend = Rc::new(MockBackend::new());
+    (Ui::with_backend(backend.clone()).unwrap(), backend)
+}
+fn window() -> WindowT<&'static str, u64> {  WindowT{title: "", position:(-600,-600), size:(100, 100), resizable:true, visible:true, disabled:false, exit_on_close:true, accept_files:false, icon: None} }
+fn track(weight: u32, min: u32, max: Option<u32>) -> LayoutTrack { LayoutTrack{weight: weight, min: min, max: max} }
+#[cfg(windows)]
+fn point(x: i32, y: i32) -> isize { ((x as u16 as u32) | ((y as u16 as u32) << 16)) as isize }
+#[cfg(windows)]
 fn default_font() -> FontT<&'static str> { FontT{ family: "Arial", size: 10, weight: FONT_WEIGHT_BOLD, decoration: FONT_DECO_ITALIC|FONT_DECO_STRIKEOUT } }
 
 macro_rules! test_visibility {
@@ -67,6 +85,7 @@ macro_rules! test_enabled {
 }
 
 
+#[cfg(windows)]
 #[test]
 fn test_ui_new() {
     match Ui::<u64>::new() {
@@ -145,7 +164,7 @@ fn test_ui_pack_control() {
     let ui = setup_ui();
 
     ui.pack_control(&1000, window());
-    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1001, nwg_button!(parent=1000; text="TEST"));
 
     assert!(!ui.has_id(&1000), "ID 1000 was found in ui before commit");
     ui.commit().expect("Commit was not successful");
@@ -155,13 +174,14 @@ fn test_ui_pack_control() {
     { let w = ui.get::<Window>(&1000); w.expect("Failed to get control"); }
 
     // Id already exists
-    ui.pack_control(&1000, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1000, nwg_button!(parent=1000; text="TEST"));
     let r = ui.commit();
     assert!(r.is_err() && r.err().unwrap() == Error::KeyExists, "Commit was successful");
 
 }
 
 #[test]
+#[cfg(windows)]
 fn test_ui_pack_resource() {
     let ui = setup_ui();
 
@@ -179,6 +199,7 @@ fn test_ui_pack_resource() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_ui_unpack() {
     let ui = setup_ui();
     let mut free_count: u8 = 0;
@@ -229,13 +250,14 @@ fn test_ui_unpack() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_ui_bind() {
     let ui = setup_ui();
 
     ui.pack_value(&1000, 5u32);
     ui.pack_control(&1001, window());
     ui.pack_control(&1002, window());
-    ui.pack_control(&1003, MenuItemT{text: "", parent: 1001, disabled: false});
+    ui.pack_control(&1003, nwg_menuitem!(parent=1001;));
 
     // Binding successful
     ui.bind(&1001, &5000, Event::Destroyed, |ui, id, _, _|{
@@ -284,11 +306,12 @@ fn test_ui_bind() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_ui_unbind() {
     let ui = setup_ui();
     
     ui.pack_control(&1000, window());
-    ui.pack_control(&1002, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1002, nwg_menuitem!(parent=1000;));
     ui.pack_value(&1001, 5u32);
 
     ui.bind(&1000, &5000, Event::Destroyed, |_, _, _, _|{});
@@ -371,6 +394,104 @@ fn test_user_trigger() {
 
 
 #[test]
+#[cfg(windows)]
+fn test_mouse_events() {
+    let ui = setup_ui();
+    let mut events: Vec<Event> = Vec::new();
+    let mut moves: Vec<((i32, i32), MouseState)> = Vec::new();
+    let mut wheels: Vec<((i32, i32), i32, MouseState)> = Vec::new();
+    let (e, m, w) = (&mut events as *mut Vec<Event>, &mut moves as *mut Vec<((i32, i32), MouseState)>, &mut wheels as *mut Vec<((i32, i32), i32, MouseState)>);
+
+    ui.pack_control(&1000, window());
+    for evt in [Event::MouseMove, Event::MouseEnter, Event::MouseLeave, Event::MouseWheel].iter() {
+        ui.bind(&1000, &5000, evt.clone(), move |_, _, evt, args|{
+            unsafe{ (&mut *e).push(evt.clone()); }
+            match args {
+                &EventArgs::MouseMove{pos, state} => unsafe{ (&mut *m).push((pos, state)); },
+                &EventArgs::MouseWheel{pos, delta, state} => unsafe{ (&mut *w).push((pos, delta, state)); },
+                _ => {}
+            }
+        });
+    }
+    ui.commit().expect("Commit was not successful");
+
+    let handle = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle is not a HWND") };
+
+    // WM_MOUSEMOVE (0x0200) in client coordinates with MK_LBUTTON|MK_SHIFT (0x0001|0x0004). The first move raises MouseEnter.
+    unsafe{ nwg::custom::send_message(handle, 0x0200, 0x0005, point(15, 25) as _); }
+    assert!(events.len() == 2 && events[0] == Event::MouseEnter && events[1] == Event::MouseMove, "Bad events: {:?}", events);
+    let (pos, state) = moves[0];
+    assert!(pos == (15, 25) && state.left && state.shift && !state.right && !state.middle && !state.ctrl, "Bad move arguments: {:?} {:?}", pos, state);
+
+    unsafe{ nwg::custom::send_message(handle, 0x0200, 0, point(-3, -7) as _); }
+    assert!(moves.len() == 2 && moves[1] == ((-3, -7), MouseState::default()), "Bad move arguments: {:?}", moves[1]);
+
+    // WM_MOUSELEAVE (0x02A3)
+    unsafe{ nwg::custom::send_message(handle, 0x02A3, 0, 0); }
+    assert!(events.last() == Some(&Event::MouseLeave), "Bad events: {:?}", events);
+
+    // WM_MOUSEWHEEL (0x020A): the delta is in the high word of wparam, MK_CONTROL (0x0008) in the low word.
+    // The point is in screen coordinates: the window is at (-600, -600) and its client area is inside the borders and the title bar.
+    let (x, y) = (-600 + 60, -600 + 80);
+    unsafe {
+        nwg::custom::send_message(handle, 0x020A, (((-120i16 as u16 as u32) << 16) | 0x0008) as _, point(x, y) as _);
+        nwg::custom::send_message(handle, 0x020A, (240 << 16) as _, point(x + 10, y + 20) as _);
+    }
+    assert!(wheels.len() == 2, "Wheel events were not raised");
+
+    let ((x0, y0), delta, state) = wheels[0];
+    assert!(delta == -120 && state.ctrl && !state.left && !state.shift, "Bad wheel arguments: {} {:?}", delta, state);
+    assert!(x0 > 0 && x0 <= 60 && y0 > 0 && y0 < 80, "Wheel position was not converted to client coordinates: {:?}", (x0, y0));
+
+    let ((x1, y1), delta, state) = wheels[1];
+    assert!(delta == 240 && state == MouseState::default(), "Bad wheel arguments: {} {:?}", delta, state);
+    assert!((x1 - x0, y1 - y0) == (10, 20), "Bad wheel position: {:?}", (x1, y1));
+}
+
+#[test]
+fn test_ui_sender() {
+    use std::thread;
+
+    let (ui, backend) = setup_mock_ui();
+    let mut triggered: u32 = 0;
+    let x = &mut triggered as *mut u32;
+
+    ui.pack_value(&1000, 0u32);
+    ui.pack_control(&1001, window());
+    ui.bind(&1001, &5000, Event::MouseDown, move |_, _, _, _|{ unsafe{ *(&mut *x) += 1; } });
+    ui.commit().expect("Commit was not successful");
+
+    // Commands sent from a worker thread are executed by the ui thread
+    let sender = ui.sender();
+    let worker_sender = sender.clone();
+    let t = thread::spawn(move || {
+        for _ in 0..10 {
+            worker_sender.execute(|ui| { **ui.get_mut::<u32>(&1000).unwrap() += 1; }).expect("Command was not sent");
+        }
+        worker_sender.trigger(&1001, Event::MouseDown, EventArgs::None).expect("Command was not sent");
+    });
+    t.join().unwrap();
+
+    assert!(**ui.get::<u32>(&1000).unwrap() == 0, "Commands were executed by the worker thread");
+    assert!(backend.pending_messages() == 11, "Commands were not posted");
+    ui.commit().expect("Commit was not successful");
+    assert!(**ui.get::<u32>(&1000).unwrap() == 10, "Commands were not executed");
+    assert!(triggered == 1, "Event was not triggered");
+
+    // Errors are returned by commit
+    sender.trigger(&1030, Event::MouseDown, EventArgs::None).expect("Command was not sent");
+    let r = ui.commit();
+    assert!(r.is_err() && r.err().unwrap() == Error::KeyNotFound, "Commit was successful");
+
+    // Senders refuse commands once the ui is dropped
+    assert!(sender.is_alive());
+    drop(ui);
+    assert!(!sender.is_alive());
+    assert!(sender.execute(|_| {}).is_err(), "Command was sent to a dropped ui");
+}
+
+#[test]
+#[cfg(windows)]
 fn test_window_control_user_close() {
     let ui = setup_ui();
     let mut callback_executed: bool = false;
@@ -405,6 +526,7 @@ fn test_drop_callback() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_menus() {
     let ui = setup_ui();
     
@@ -417,13 +539,13 @@ fn test_menus() {
     ui.pack_control(&1001, MenuT{ text: "Test1", parent: 1000, disabled: false  });
     ui.bind(&1001, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
 
-    ui.pack_control(&2003, MenuItemT{ text: "TestItem4", parent: 1000, disabled: false  });
+    ui.pack_control(&2003, nwg_menuitem!(parent=1000; text="TestItem4"));
     ui.bind(&2003, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     
     ui.pack_control(&1002, MenuT{ text: "Test2", parent: 1000, disabled: false  });
     ui.pack_control(&1003, MenuT{ text: "Test3", parent: 1002, disabled: false  });
     ui.pack_control(&1004, MenuT{ text: "Test4", parent: 1002, disabled: false  });
-    ui.pack_control(&2000, MenuItemT{ text: "TestItem1", parent: 1002, disabled: false  });
+    ui.pack_control(&2000, nwg_menuitem!(parent=1002; text="TestItem1"));
     ui.bind(&1002, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1003, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1004, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
@@ -432,7 +554,7 @@ fn test_menus() {
     ui.pack_control(&1005, MenuT{ text: "Test5", parent: 1000, disabled: false });
     ui.pack_control(&1006, MenuT{ text: "Test6", parent: 1005, disabled: false });
     ui.pack_control(&1007, MenuT{ text: "Test7", parent: 1006, disabled: false });
-    ui.pack_control(&2001, MenuItemT{ text: "TestItem2", parent: 1007, disabled: false  });
+    ui.pack_control(&2001, nwg_menuitem!(parent=1007; text="TestItem2"));
     ui.bind(&1005, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1006, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1007, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
@@ -440,7 +562,7 @@ fn test_menus() {
 
     ui.pack_control(&1008, MenuT{ text: "Test8", parent: 1000, disabled: false });
     ui.pack_control(&1009, MenuT{ text: "Test9", parent: 1008, disabled: false });
-    ui.pack_control(&2002, MenuItemT{ text: "TestItem3", parent: 1000, disabled: false  });
+    ui.pack_control(&2002, nwg_menuitem!(parent=1000; text="TestItem3"));
     ui.bind(&1008, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&1009, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
     ui.bind(&2002, &10_000, Event::Destroyed, move |_,_,_,_|{ unsafe{  *(&mut *x) += 1 } });
@@ -516,6 +638,104 @@ fn test_window() {
 }
 
 #[test]
+#[cfg(windows)]
+fn test_file_drop() {
+    let ui = setup_ui();
+    let mut dropped: Vec<(Vec<String>, (i32, i32))> = Vec::new();
+    let x = &mut dropped as *mut Vec<(Vec<String>, (i32, i32))>;
+
+    ui.pack_control(&1000, nwg_window!(accept_files=true; visible=false));
+    ui.pack_control(&1001, nwg_canvas!(parent=1000; accept_files=true));
+    ui.bind(&1001, &5000, Event::FilesDropped, move |_, _, _, args|{
+        if let &EventArgs::FilesDropped{ref paths, pos} = args {
+            unsafe{ (&mut *x).push((paths.clone(), pos)); }
+        }
+    });
+    ui.commit().expect("Commit was not successful");
+
+    // A drop handle is a global memory block holding a DROPFILES header followed by the paths.
+    // The handle is freed by the Ui once the message is decoded.
+    #[repr(C)] struct Point { x: i32, y: i32 }
+    #[repr(C)] struct DropFiles { files: u32, pt: Point, nc: i32, wide: i32 }
+    let drop_handle = |paths: &[&str], pt: Point| unsafe {
+        use std::{mem, ptr};
+
+        let mut names: Vec<u16> = Vec::new();
+        for p in paths.iter() { names.extend(p.encode_utf16()); names.push(0); }
+        names.push(0);
+
+        let header_size = mem::size_of::<DropFiles>();
+        let header = DropFiles{ files: header_size as u32, pt: pt, nc: 0, wide: 1 };
+
+        // GHND (0x0042): moveable memory initialized to zero
+        let handle = kernel32::GlobalAlloc(0x0042, (header_size + names.len() * 2) as _);
+        let data = kernel32::GlobalLock(handle) as *mut u8;
+        ptr::copy_nonoverlapping(&header as *const DropFiles as *const u8, data, header_size);
+        ptr::copy_nonoverlapping(names.as_ptr() as *const u8, data.offset(header_size as isize), names.len() * 2);
+        kernel32::GlobalUnlock(handle);
+        handle
+    };
+
+    // WM_DROPFILES (0x0233)
+    let canvas = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Canvas handle is not a HWND") };
+    unsafe{ nwg::custom::send_message(canvas, 0x0233, drop_handle(&["C:\\a.txt", "C:\\Dossier é\\b.txt"], Point{x: 5, y: 7}) as _, 0); }
+    let paths = vec!["C:\\a.txt".to_string(), "C:\\Dossier é\\b.txt".to_string()];
+    assert!(dropped == vec![(paths, (5, 7))], "Bad dropped files: {:?}", dropped);
+
+    unsafe{ nwg::custom::send_message(canvas, 0x0233, drop_handle(&["C:\\c.txt"], Point{x: 0, y: 90}) as _, 0); }
+    assert!(dropped.len() == 2 && dropped[1] == (vec!["C:\\c.txt".to_string()], (0, 90)), "Bad dropped files: {:?}", dropped);
+
+    // Files dropped on the window do not raise the event of the canvas
+    let window = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle is not a HWND") };
+    unsafe{ nwg::custom::send_message(window, 0x0233, drop_handle(&["C:\\d.txt"], Point{x: 5, y: 5}) as _, 0); }
+    assert!(dropped.len() == 2, "The window drop was sent to the canvas");
+}
+
+#[test]
+#[cfg(windows)]
+fn test_clipboard() {
+    use nwg::clipboard::{self, ClipboardFormat, ClipboardImage};
+
+    let ui = setup_ui();
+    ui.pack_value(&1001, 5u32);
+    ui.pack_control(&1000, window());
+    ui.bind(&1000, &5000, Event::ClipboardChanged, |_, _, _, _|{});
+    ui.commit().expect("Commit was not successful");
+
+    clipboard::listen(&ui, &1000, true).expect("Could not listen to the clipboard");
+
+    clipboard::set_text(&ui, &1000, "Hello Клавиатура").expect("Could not set the text");
+    assert!(clipboard::has_format(ClipboardFormat::Text));
+    assert!(clipboard::get_text() == Ok(Some("Hello Клавиатура".to_string())), "Text was not copied");
+
+    let image = ClipboardImage{ width: 2, height: 2, pixels: vec![255, 0, 0, 255,  0, 255, 0, 255,  0, 0, 255, 255,  10, 20, 30, 255] };
+    clipboard::set_image(&ui, &1000, &image).expect("Could not set the image");
+    assert!(clipboard::get_image() == Ok(Some(image)), "Image was not copied");
+    assert!(clipboard::get_text() == Ok(None), "Setting the image did not replace the text");
+
+    let format = clipboard::register_format("NWG test format").expect("Could not register the format");
+    assert!(clipboard::register_format("NWG test format") == Ok(format));
+    clipboard::set_data(&ui, &1000, format, &[1, 2, 3, 4]).expect("Could not set the data");
+    assert!(clipboard::get_data(format).unwrap().map(|d| d[0..4].to_vec()) == Some(vec![1, 2, 3, 4]));
+
+    // Images written by other applications can hold any size: a size that overflows is refused
+    let mut huge_dib = vec![40, 0, 0, 0,  255, 255, 255, 127,  255, 255, 255, 127,  1, 0, 32, 0,  0, 0, 0, 0];
+    huge_dib.extend_from_slice(&[0; 12]);
+    huge_dib.extend_from_slice(&[255, 255, 255, 255,  0, 0, 0, 0,  1, 2, 3, 4]);
+    clipboard::set_data(&ui, &1000, ClipboardFormat::Image, &huge_dib).expect("Could not set the data");
+    match clipboard::get_image() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    let bad_image = ClipboardImage{ width: 2, height: 2, pixels: vec![0; 4] };
+    assert!(clipboard::set_image(&ui, &1000, &bad_image).is_err(), "Bad image was accepted");
+    assert!(clipboard::set_text(&ui, &1001, "").err() == Some(Error::ControlOrResourceRequired), "User value was accepted as owner");
+
+    clipboard::clear(&ui, &1000).expect("Could not clear the clipboard");
+    assert!(!clipboard::has_format(format));
+    clipboard::listen(&ui, &1000, false).expect("Could not stop listening to the clipboard");
+}
+
+#[test]
+#[cfg(windows)]
 fn test_buttons() {
     let ui = setup_ui();
 
@@ -524,7 +744,7 @@ fn test_buttons() {
 
     ui.pack_resource(&10_000, default_font());
     ui.pack_control(&1000, window());
-    ui.pack_control(&1001, MenuItemT{text: "", parent: 1000, disabled: false});
+    ui.pack_control(&1001, nwg_menuitem!(parent=1000;));
 
     // pack test
     ui.pack_control(&1002, btn_t.clone());
@@ -567,6 +787,7 @@ fn test_buttons() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_listbox() {
     let ui = setup_ui();
 
@@ -687,6 +908,7 @@ fn test_timer() {
 }
 
 #[test]
+#[cfg(windows)]
 fn test_combobox() {
     let ui = setup_ui();
 
@@ -754,6 +976,7 @@ fn test_combobox() {
 }
 
 #[test]
+#[cfg(windows)]
 fn text_textinput() {
     let ui = setup_ui();
 
@@ -798,4 +1021,1469 @@ fn text_textinput() {
         tinput.set_limit(10_000);
         assert!(tinput.get_limit() == 10_000);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_numeric_input() {
+    let (ui, backend) = setup_mock_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_numinput!(parent=1000; value=150; range=(100, 0); step=5));
+    ui.pack_control(&1002, nwg_numinput!(parent=1000; value=1234; decimals=2; size=(116, 20)));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let num = ui.get::<NumericInput>(&1001).unwrap();
+        assert!(num.get_range() == (0, 100), "Range was not ordered");
+        assert!(num.get_value() == 100, "Value was not clamped");
+        assert!(num.get_text().as_str() == "100");
+        assert!(num.get_step() == 5 && num.get_decimals() == 0);
+
+        num.set_value(-20);
+        assert!(num.get_value() == 0);
+        num.set_range(10, 20);
+        assert!(num.get_value() == 10 && num.get_text().as_str() == "10");
+    }
+
+    {
+        let num = ui.get::<NumericInput>(&1002).unwrap();
+        assert!(num.get_text().as_str() == "12.34");
+        assert!(num.get_float() == 12.34);
+        num.set_float(-0.456);
+        assert!(num.get_value() == -46 && num.get_text().as_str() == "-0.46");
+
+        // The edit and the up-down control share the client area of the numeric input
+        let handle = match ui.handle_of(&1002) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("NumericInput handle is not a HWND") };
+        let children: Vec<_> = backend.windows().into_iter().filter(|&(_, ref w)| w.parent == handle).collect();
+        assert!(children.len() == 2, "NumericInput should have two children");
+        let edit = children.iter().find(|&&(_, ref w)| w.class_name.as_str() == "EDIT").expect("Edit was not created");
+        let updown = children.iter().find(|&&(_, ref w)| w.class_name.as_str() == "msctls_updown32").expect("Up-down was not created");
+        assert!(edit.1.size == (100, 20) && updown.1.position == (100, 0) && updown.1.size == (16, 20));
+    }
+
+    // ValueChanged is raised when the value changes, no matter where the change comes from
+    let mut values: Vec<i64> = Vec::new();
+    let v = &mut values as *mut Vec<i64>;
+    ui.bind(&1001, &5000, Event::ValueChanged, move |_, _, _, args|{
+        if let &EventArgs::Value(x) = args { unsafe{ (&mut *v).push(x); } }
+    });
+    ui.commit().expect("Commit was not successful");
+
+    let handle = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("NumericInput handle is not a HWND") };
+    let children: Vec<_> = backend.windows().into_iter().filter(|&(_, ref w)| w.parent == handle).collect();
+    let edit = children.iter().find(|&&(_, ref w)| w.class_name.as_str() == "EDIT").expect("Edit was not created").0;
+    let updown = children.iter().find(|&&(_, ref w)| w.class_name.as_str() == "msctls_updown32").expect("Up-down was not created").0;
+
+    {
+        let num = ui.get::<NumericInput>(&1001).unwrap();
+        num.set_value(15);
+        num.set_value(15);
+        num.set_value(500);
+    }
+    assert!(values == vec![15, 20], "ValueChanged was not raised once per change");
+
+    // The user types a value: WM_COMMAND (0x0111) with EN_UPDATE (0x0400). Out of range values are clamped.
+    unsafe {
+        nwg::custom::set_window_text(edit, "12");
+        nwg::custom::send_message(handle, 0x0111, (0x0400 << 16) as _, edit as _);
+        nwg::custom::send_message(handle, 0x0111, (0x0400 << 16) as _, edit as _);
+        nwg::custom::set_window_text(edit, "abc");
+        nwg::custom::send_message(handle, 0x0111, (0x0400 << 16) as _, edit as _);
+    }
+    assert!(values == vec![15, 20, 12], "EN_UPDATE did not raise ValueChanged");
+
+    // The user clicks on the arrows: WM_NOTIFY (0x004E) with UDN_DELTAPOS
+    #[repr(C)] struct NmHdr { hwnd_from: *mut (), id_from: usize, code: u32 }
+    #[repr(C)] struct NmUpDown { hdr: NmHdr, pos: i32, delta: i32 }
+    let mut nmud = NmUpDown{ hdr: NmHdr{ hwnd_from: updown as *mut (), id_from: 0, code: (-722i32) as u32 }, pos: 0, delta: -1 };
+    unsafe{ nwg::custom::send_message(handle, 0x004E, 0, &mut nmud as *mut NmUpDown as _); }
+    assert!(values == vec![15, 20, 12, 10], "UDN_DELTAPOS did not raise ValueChanged");
+    assert!(ui.get::<NumericInput>(&1001).unwrap().get_text().as_str() == "10");
+
+    ui.unpack(&1002);
+    ui.commit().expect("Commit was not successful");
+    assert!(backend.windows().iter().filter(|&&(_, ref w)| w.class_name.as_str() == "EDIT").count() == 1, "Children were not destroyed");
+}
+
+#[test]
+fn test_listview() {
+    let (ui, backend) = setup_mock_ui();
+
+    let name = TextColumn{header: "Name".to_string(), width: 150, cell: |x: &(&'static str, u32)| x.0.to_string()};
+    let age = TextColumn{header: "Age".to_string(), width: 50, cell: |x: &(&'static str, u32)| x.1.to_string()};
+    let columns: Vec<Rc<ListViewColumn<(&'static str, u32)>>> = vec![Rc::new(name), Rc::new(age)];
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_listview!(parent=1000; collection=vec![("Bob", 30), ("Alice", 9), ("Carl", 100)]; columns=columns; checkboxes=true));
+    ui.commit().expect("Commit was not successful");
+
+    let handle = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("ListView handle is not a HWND") };
+    assert!(backend.window(handle).expect("ListView window was not recorded").class_name.as_str() == "SysListView32");
+
+    {
+        let mut list = ui.get_mut::<ListView<(&'static str, u32)>>(&1001).unwrap();
+        assert!(list.len() == 3 && list.columns().len() == 2);
+        assert!(list.columns()[1].header().as_str() == "Age");
+        assert!(list.get_sort().is_none());
+
+        // Cells are compared as text by default
+        list.sort(1, true);
+        assert!(list.collection().iter().map(|x| x.0).collect::<Vec<_>>() == vec!["Carl", "Bob", "Alice"]);
+        list.sort(0, true);
+        assert!(list.collection().iter().map(|x| x.0).collect::<Vec<_>>() == vec!["Alice", "Bob", "Carl"]);
+        assert!(list.get_sort() == Some((0, true)));
+        list.sort(1, false);
+        assert!(list.collection().iter().map(|x| x.0).collect::<Vec<_>>() == vec!["Alice", "Bob", "Carl"]);
+        assert!(list.get_sort() == Some((1, false)));
+
+        list.push(("Dave", 41));
+        list.insert(0, ("Eve", 22));
+        assert!(list.len() == 5 && list.collection()[0].0 == "Eve");
+        assert!(list.remove(4) == ("Dave", 41));
+
+        list.collection_mut().clear();
+        list.sync();
+        assert!(list.len() == 0);
+    }
+
+    // Clicking on a column header sends WM_NOTIFY (0x004E) with LVN_COLUMNCLICK to the parent. Only sortable list views are sorted.
+    #[repr(C)] struct NmHdr { hwnd_from: *mut (), id_from: usize, code: u32 }
+    #[repr(C)] struct NmListView { hdr: NmHdr, item: i32, sub_item: i32, new_state: u32, old_state: u32, changed: u32, action: (i32, i32), param: isize }
+
+    let name = TextColumn{header: "Name".to_string(), width: 150, cell: |x: &(&'static str, u32)| x.0.to_string()};
+    let columns: Vec<Rc<ListViewColumn<(&'static str, u32)>>> = vec![Rc::new(name)];
+    ui.pack_control(&1002, nwg_listview!(parent=1000; collection=vec![("Bob", 30), ("Alice", 9), ("Carl", 100)]; columns=columns.clone(); sortable=true));
+    ui.pack_control(&1003, nwg_listview!(parent=1000; collection=vec![("Bob", 30), ("Alice", 9), ("Carl", 100)]; columns=columns; sortable=false));
+    ui.commit().expect("Commit was not successful");
+
+    let mut clicked: Vec<usize> = Vec::new();
+    let c = &mut clicked as *mut Vec<usize>;
+    ui.bind(&1002, &5000, Event::ColumnClick, move |_, _, _, args|{
+        if let &EventArgs::Column(x) = args { unsafe{ (&mut *c).push(x); } }
+    });
+    ui.commit().expect("Commit was not successful");
+
+    let parent = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle is not a HWND") };
+    let click = |id: u64| {
+        let handle = match ui.handle_of(&id) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("ListView handle is not a HWND") };
+        let mut nmlv = NmListView{ hdr: NmHdr{ hwnd_from: handle as *mut (), id_from: 0, code: (-108i32) as u32 },
+          item: -1, sub_item: 0, new_state: 0, old_state: 0, changed: 0, action: (0, 0), param: 0 };
+        unsafe{ nwg::custom::send_message(parent, 0x004E, 0, &mut nmlv as *mut NmListView as _); }
+    };
+    let names = |id: u64| ui.get::<ListView<(&'static str, u32)>>(&id).unwrap().collection().iter().map(|x| x.0).collect::<Vec<_>>();
+
+    click(1002);
+    assert!(names(1002) == vec!["Alice", "Bob", "Carl"], "The column click did not sort the list view");
+    click(1002);
+    assert!(names(1002) == vec!["Carl", "Bob", "Alice"], "The second click did not reverse the order");
+    assert!(ui.get::<ListView<(&'static str, u32)>>(&1002).unwrap().get_sort() == Some((0, false)));
+    assert!(clicked == vec![0, 0], "ColumnClick was not raised");
+
+    click(1003);
+    assert!(names(1003) == vec!["Bob", "Alice", "Carl"], "A list view that is not sortable was sorted");
+}
+
+struct NumberLoader;
+
+impl TreeViewLoader<String, u64> for NumberLoader {
+    fn children(&self, id: &u64, data: &String) -> Vec<TreeNode<String, u64>> {
+        (1..4).map(|i| TreeNode{id: id*10+i, data: format!("{}.{}", data, i), lazy: false}).collect()
+    }
+}
+
+#[test]
+fn test_treeview() {
+    let (ui, backend) = setup_mock_ui();
+
+    let nodes = vec![
+        (None, TreeNode{id: 1, data: "1".to_string(), lazy: false}),
+        (None, TreeNode{id: 2, data: "2".to_string(), lazy: true}),
+        (Some(1), TreeNode{id: 11, data: "1.1".to_string(), lazy: false}),
+    ];
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_treeview!(data=String; parent=1000; nodes=nodes; loader=Some(Rc::new(NumberLoader))));
+    ui.pack_control(&1002, nwg_treeview!(data=String; parent=1000; nodes=vec![(Some(5), TreeNode{id: 6, data: "6".to_string(), lazy: false})]));
+    match ui.commit() { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    let handle = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("TreeView handle is not a HWND") };
+    assert!(backend.window(handle).expect("TreeView window was not recorded").class_name.as_str() == "SysTreeView32");
+
+    let mut tree = ui.get_mut::<TreeView<String, u64>>(&1001).unwrap();
+    assert!(tree.len() == 3 && tree.roots() == vec![1, 2]);
+    assert!(tree.children(&1) == Some(vec![11]) && tree.parent(&11) == Some(1));
+    assert!(tree.get(&11).map(|d| d.as_str()) == Some("1.1"));
+
+    assert!(tree.push(Some(&1), TreeNode{id: 11, data: "".to_string(), lazy: false}) == Err(Error::KeyExists));
+    assert!(tree.push(Some(&99), TreeNode{id: 99, data: "".to_string(), lazy: false}) == Err(Error::KeyNotFound));
+    tree.insert(Some(&1), 0, TreeNode{id: 10, data: "1.0".to_string(), lazy: false}).expect("Insert failed");
+    assert!(tree.path_of(&11) == Some(vec![0, 1]) && tree.node_at(&[0, 0]) == Some(10));
+
+    // Lazy nodes are populated on the first expand
+    assert!(tree.children(&2) == Some(vec![]));
+    tree.expand(&2).expect("Expand failed");
+    assert!(tree.children(&2) == Some(vec![21, 22, 23]));
+    assert!(tree.get(&22).map(|d| d.as_str()) == Some("2.2") && tree.path_of(&23) == Some(vec![1, 2]));
+    tree.expand(&2).expect("Expand failed");
+    assert!(tree.len() == 7);
+
+    // Nodes are moved with their children
+    tree.push(Some(&21), TreeNode{id: 211, data: "2.1.1".to_string(), lazy: false}).expect("Push failed");
+    match tree.move_node(&2, Some(&211), 0) { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+    tree.move_node(&21, Some(&1), 0).expect("Move failed");
+    assert!(tree.children(&1) == Some(vec![21, 10, 11]) && tree.path_of(&211) == Some(vec![0, 0, 0]));
+    tree.move_node(&21, None, 2).expect("Move failed");
+    assert!(tree.roots() == vec![1, 2, 21] && tree.parent(&21) == None);
+
+    assert!(tree.remove(&21).expect("Remove failed").as_str() == "2.1");
+    assert!(!tree.contains(&211) && tree.len() == 6);
+    assert!(tree.set(&10, "Hello".to_string()).is_ok() && tree.get(&10).map(|d| d.as_str()) == Some("Hello"));
+
+    tree.clear();
+    assert!(tree.len() == 0 && tree.roots().len() == 0);
+    drop(tree);
+
+    // The user expands a node: WM_NOTIFY (0x004E) with TVN_ITEMEXPANDINGW. The lazy node is loaded before the callbacks run.
+    #[repr(C)] struct NmHdr { hwnd_from: *mut (), id_from: usize, code: u32 }
+    #[repr(C)] struct TvItem { mask: u32, item: *mut (), state: u32, state_mask: u32, text: *mut u16, text_max: i32, image: i32, selected_image: i32, children: i32, param: isize }
+    #[repr(C)] struct NmTreeView { hdr: NmHdr, action: u32, item_old: TvItem, item_new: TvItem, drag: (i32, i32) }
+
+    ui.pack_control(&1003, nwg_treeview!(data=String; parent=1000; loader=Some(Rc::new(NumberLoader))));
+    ui.commit().expect("Commit was not successful");
+
+    // The mock has no tree view. TVM_INSERTITEMW (0x1132) returns a new item handle, like the system does.
+    let tree_handle = match ui.handle_of(&1003) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("TreeView handle is not a HWND") };
+    let next_item = ::std::cell::Cell::new(0x10);
+    backend.set_reply(tree_handle, 0x1132, move |_, _| { next_item.set(next_item.get() + 1); next_item.get() as _ });
+    ui.get_mut::<TreeView<String, u64>>(&1003).unwrap().push(None, TreeNode{id: 3, data: "3".to_string(), lazy: true}).expect("Push failed");
+
+    let mut expanding: Vec<(Vec<usize>, usize)> = Vec::new();
+    let e = &mut expanding as *mut Vec<(Vec<usize>, usize)>;
+    ui.bind(&1003, &5000, Event::Expanding, move |ui, _, _, args|{
+        let loaded = ui.get::<TreeView<String, u64>>(&1003).unwrap().len();
+        if let &EventArgs::Node(ref path) = args { unsafe{ (&mut *e).push((path.clone(), loaded)); } }
+    });
+    ui.commit().expect("Commit was not successful");
+
+    let parent = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle is not a HWND") };
+    let item = || TvItem{ mask: 0, item: 0x11 as *mut (), state: 0, state_mask: 0, text: ::std::ptr::null_mut(), text_max: 0, image: 0, selected_image: 0, children: 0, param: 0 };
+    let mut nmtv = NmTreeView{ hdr: NmHdr{ hwnd_from: tree_handle as *mut (), id_from: 0, code: (-454i32) as u32 }, action: 2, item_old: item(), item_new: item(), drag: (0, 0) };
+    unsafe{ nwg::custom::send_message(parent, 0x004E, 0, &mut nmtv as *mut NmTreeView as _); }
+
+    assert!(expanding == vec![(vec![0], 4)], "Expanding was not raised after the lazy node was loaded");
+    assert!(ui.get::<TreeView<String, u64>>(&1003).unwrap().children(&3) == Some(vec![31, 32, 33]));
+}
+
+#[test]
+fn test_tabs() {
+    let (ui, backend) = setup_mock_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_tabs_container!(parent=1000; size=(300, 200)));
+    ui.commit().expect("Commit was not successful");
+
+    // The mock has no tab control. TCM_ADJUSTRECT (0x1328) removes the tabs header and a 2 pixels border from the rectangle.
+    let container = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("TabsContainer handle is not a HWND") };
+    backend.set_reply(container, 0x1328, |_, l| {
+        let r = l as *mut [i32; 4];
+        unsafe{ (*r)[0] += 2; (*r)[1] += 24; (*r)[2] -= 2; (*r)[3] -= 2; }
+        0
+    });
+
+    ui.pack_control(&1002, nwg_tab!(parent=1001; text="General"));
+    ui.pack_control(&1003, nwg_tab!(parent=1001; text="Advanced"));
+    ui.pack_control(&1004, nwg_button!(parent=1003; text="Reset"));
+    ui.pack_control(&1005, nwg_tab!(parent=1000;));
+    match ui.commit() { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    {
+        let tabs = ui.get::<TabsContainer>(&1001).unwrap();
+        let general = ui.get::<Tab>(&1002).unwrap();
+        let advanced = ui.get::<Tab>(&1003).unwrap();
+        assert!(tabs.len() == 2 && tabs.get_selected_index() == Some(0));
+        assert!(general.get_index() == 0 && advanced.get_index() == 1);
+        assert!(advanced.get_text().as_str() == "Advanced");
+
+        // Only the page of the selected tab is visible
+        assert!(general.get_visibility() && !advanced.get_visibility());
+        advanced.select();
+        assert!(!general.get_visibility() && advanced.get_visibility() && advanced.is_selected());
+        tabs.set_selected_index(0);
+        assert!(general.get_visibility() && !advanced.get_visibility() && tabs.get_selected_index() == Some(0));
+
+        // Pages fill the display area of the container
+        assert!(general.get_position() == (2, 24) && general.get_size() == (296, 174));
+        tabs.set_size(400, 250);
+        assert!(advanced.get_position() == (2, 24) && advanced.get_size() == (396, 224));
+    }
+
+    // When the selected tab is removed, the next tab is selected
+    ui.unpack(&1002);
+    ui.commit().expect("Commit was not successful");
+    {
+        let tabs = ui.get::<TabsContainer>(&1001).unwrap();
+        let advanced = ui.get::<Tab>(&1003).unwrap();
+        assert!(tabs.len() == 1 && advanced.get_index() == 0 && advanced.get_visibility() && tabs.get_selected_index() == Some(0));
+    }
+
+    // TCM_SETCURSEL = 0x130C
+    let last_select = backend.sent_messages().into_iter().filter(|m| m.handle == container && m.msg == 0x130C).last();
+    assert!(last_select.map(|m| m.w) == Some(0), "The new selected tab was not selected in the container");
+
+    // The children of a page are destroyed with the page
+    let button = match ui.handle_of(&1004) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Button handle is not a HWND") };
+    ui.unpack(&1003);
+    ui.commit().expect("Commit was not successful");
+    assert!(backend.window(button).is_none() && ui.has_id(&1004) == false);
+
+    // A new tab is selected once the last tab was removed
+    ui.pack_control(&1006, nwg_tab!(parent=1001; text="New"));
+    ui.commit().expect("Commit was not successful");
+    {
+        let tabs = ui.get::<TabsContainer>(&1001).unwrap();
+        assert!(tabs.len() == 1 && tabs.get_selected_index() == Some(0) && ui.get::<Tab>(&1006).unwrap().get_visibility());
+    }
+
+    ui.unpack(&1001);
+    ui.commit().expect("Commit was not successful");
+    assert!(ui.has_id(&1006) == false);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_accelerators() {
+    let ui = setup_ui();
+
+    let s = Shortcut::parse("shift + ctrl + s").expect("Shortcut parsing failed");
+    assert!(s == Shortcut{ ctrl: true, shift: true, alt: false, key: keys::_S } && format!("{}", s) == "Ctrl+Shift+S");
+    assert!(Shortcut::parse("F5").unwrap().key == keys::F5 && format!("{}", Shortcut::parse("alt+pgdn").unwrap()) == "Alt+PageDown");
+    for &bad in ["", "Ctrl+", "Ctrl+Shift", "Ctrl+Ctrl+S", "Hyper+S", "Ctrl+F25"].iter() {
+        match Shortcut::parse(bad) { Err(Error::BadResource(_)) => {}, r => panic!("{:?} should not be a valid shortcut, got {:?}", bad, r) }
+    }
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, MenuT{ text: "File", parent: 1000, disabled: false });
+    ui.pack_control(&1002, nwg_menuitem!(parent=1001; text="Save"));
+    ui.pack_control(&1003, nwg_accelerator!(parent=1000; shortcut="Ctrl+S"; item=Some(1002)));
+    ui.pack_control(&1004, nwg_accelerator!(parent=1000; shortcut="F5";));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let save = ui.get::<Accelerator>(&1003).unwrap();
+        let refresh = ui.get::<Accelerator>(&1004).unwrap();
+        assert!(format!("{}", save.get_shortcut()) == "Ctrl+S" && refresh.get_shortcut().key == keys::F5);
+        assert!(save.get_item().is_some() && refresh.get_item().is_none());
+
+        // The menu item text is "Save\tCtrl+S"
+        let item = ui.get::<MenuItem>(&1002).unwrap();
+        assert!(item.get_text().as_str() == "Save" && item.get_shortcut() == Some("Ctrl+S".to_string()));
+    }
+
+    match (ui.handle_of(&1003), ui.handle_of(&1004)) {
+        (Ok(AnyHandle::Custom(_, a)), Ok(AnyHandle::Custom(_, b))) => assert!(a != b, "Accelerators share the same command id"),
+        r => panic!("Accelerator handles are not custom handles, got {:?}", r)
+    }
+
+    ui.pack_control(&1005, nwg_accelerator!(parent=1000; shortcut="Ctrl+Foo";));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_control(&1005, nwg_accelerator!(parent=1001; shortcut="Ctrl+O";));
+    match ui.commit() { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+    ui.pack_control(&1005, nwg_accelerator!(parent=1000; shortcut="Ctrl+O"; item=Some(1001)));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    ui.unpack(&1003);
+    ui.unpack(&1004);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1003) && !ui.has_id(&1004) && ui.has_id(&1002));
+
+    // The shortcut text is removed from the menu item
+    {
+        let item = ui.get::<MenuItem>(&1002).unwrap();
+        assert!(item.get_text().as_str() == "Save" && item.get_shortcut().is_none());
+    }
+
+    // The accelerators are freed with their parent window
+    ui.pack_control(&1006, nwg_accelerator!(parent=1000; shortcut="Ctrl+S"; item=Some(1002)));
+    ui.pack_control(&2000, window());
+    ui.pack_control(&2001, nwg_accelerator!(parent=2000; shortcut="Ctrl+N";));
+    ui.commit().expect("Commit was not successful");
+    match ui.handle_of(&1006) { Ok(AnyHandle::Custom(_, 1)) => {}, r => panic!("The command ids of the freed accelerators were not reused, got {:?}", r) }
+
+    ui.unpack(&1000);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1006) && !ui.has_id(&1002) && ui.has_id(&2001));
+
+    // The command id of an accelerator is the smallest id not used by the accelerators of the other windows
+    ui.pack_control(&2002, nwg_accelerator!(parent=2000; shortcut="Ctrl+O";));
+    ui.commit().expect("Commit was not successful");
+    match ui.handle_of(&2002) { Ok(AnyHandle::Custom(_, 1)) => {}, r => panic!("The accelerators of the destroyed window were not freed, got {:?}", r) }
+}
+
+#[test]
+#[cfg(windows)]
+fn test_tray_icon() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, MenuT{ text: "Tray", parent: 1000, disabled: false });
+    ui.pack_control(&1002, nwg_menuitem!(parent=1001; text="Quit"));
+    ui.pack_control(&1003, nwg_tray_icon!(tooltip="Sync"; menu=Some(1001)));
+    ui.pack_control(&1004, nwg_tray_icon!(visible=false));
+    ui.commit().expect("Commit was not successful");
+
+    let mut events: Vec<(u64, Event, Option<(i32, i32)>)> = Vec::new();
+    let e = &mut events as *mut Vec<(u64, Event, Option<(i32, i32)>)>;
+    for &id in [1003, 1004].iter() {
+        for evt in [Event::Click, Event::DoubleClick, Event::ContextMenu].iter() {
+            ui.bind(&id, &5000, evt.clone(), move |_, id, evt, args|{
+                let pos = match args { &EventArgs::Position(x, y) => Some((x, y)), _ => None };
+                unsafe{ (&mut *e).push((*id, evt.clone(), pos)); }
+            });
+        }
+    }
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let mut tray = ui.get_mut::<TrayIcon>(&1003).unwrap();
+        assert!(tray.get_tooltip() == "Sync" && tray.get_visibility() && tray.has_menu());
+
+        tray.set_tooltip("Syncing...");
+        tray.set_visibility(false);
+        assert!(tray.get_tooltip() == "Syncing..." && !tray.get_visibility());
+        tray.show_balloon("Sync", "Synchronization done", BalloonIcon::Info);
+
+        tray.remove_menu();
+        assert!(!tray.has_menu());
+        tray.set_menu(&1001);
+        assert!(tray.has_menu());
+        assert!(!ui.get::<TrayIcon>(&1004).unwrap().get_visibility());
+    }
+
+    let (a, b) = match (ui.handle_of(&1003), ui.handle_of(&1004)) {
+        (Ok(AnyHandle::Custom(_, a)), Ok(AnyHandle::Custom(_, b))) => (a, b),
+        r => panic!("Tray icon handles are not custom handles, got {:?}", r)
+    };
+    assert!(a != b && a > 0 && b > 0, "Tray icons share the same id");
+
+    // The system sends NWG_TRAY (0x410) to the Ui with the notification in the low word of lparam and the icon id in the high word.
+    // With a right click (WM_CONTEXTMENU, 0x007B), the cursor position is in wparam.
+    let notify = |icon: usize, code: usize, w: isize| unsafe{ nwg::custom::send_message(ui.message_handle(), 0x410, w as _, ((icon << 16) | code) as _); };
+
+    notify(a, 0x0400, 0);           // NIN_SELECT
+    notify(b, 0x0401, 0);           // NIN_KEYSELECT
+    notify(a, 0x0203, 0);           // WM_LBUTTONDBLCLK
+    notify(a, 0x0200, 0);           // WM_MOUSEMOVE is ignored
+    notify(a + b + 100, 0x0400, 0); // Unknown icons are ignored
+    assert!(events == vec![(1003, Event::Click, None), (1004, Event::Click, None), (1003, Event::DoubleClick, None)], "Bad tray events: {:?}", events);
+
+    // The menu is found from its id when the icon is right clicked: a removed menu is not shown.
+    // Showing a menu would block the test in the menu modal loop.
+    ui.unpack(&1001);
+    ui.commit().expect("Commit was not successful");
+    assert!(ui.get::<TrayIcon>(&1003).unwrap().has_menu());
+    notify(a, 0x007B, point(-20, 300));
+    notify(b, 0x007B, point(40, 50));
+    assert!(events[3..] == [(1003, Event::ContextMenu, Some((-20, 300))), (1004, Event::ContextMenu, Some((40, 50)))], "Bad tray events: {:?}", events);
+
+    // A new control with the id of the menu is not shown either
+    ui.pack_control(&1001, nwg_button!(parent=1000; text="Menu"));
+    ui.commit().expect("Commit was not successful");
+    notify(a, 0x007B, point(1, 2));
+    assert!(events.len() == 6, "Bad tray events: {:?}", events);
+
+    ui.pack_control(&1005, nwg_tray_icon!(menu=Some(1001)));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_control(&1005, nwg_tray_icon!(icon=Some(1000)));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    ui.unpack(&1003);
+    ui.unpack(&1004);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1003) && !ui.has_id(&1004) && ui.has_id(&1001));
+
+    // Notifications of removed icons are ignored
+    notify(a, 0x0400, 0);
+    assert!(events.len() == 6, "Bad tray events: {:?}", events);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_popup_menu() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_popup_menu!(owner=Some(1000)));
+    ui.pack_control(&1002, nwg_menuitem!(parent=1001; text="Copy"));
+    ui.pack_control(&1003, SeparatorT{ parent: 1001 });
+    ui.pack_control(&1004, MenuT{ text: "Sort by", parent: 1001, disabled: false });
+    ui.pack_control(&1005, nwg_menuitem!(parent=1004; text="Name"; disabled=true));
+    ui.pack_control(&1006, nwg_popup_menu!());
+    ui.pack_control(&1008, nwg_textinput!(parent=1000; text="Name"));
+    ui.bind(&1002, &5000, Event::Triggered, |_, _, _, _|{});
+    ui.commit().expect("Commit was not successful");
+
+    // Showing the menu would block the test in the menu modal loop, so the positions are only recorded
+    let mut positions: Vec<(i32, i32)> = Vec::new();
+    let p = &mut positions as *mut Vec<(i32, i32)>;
+    ui.bind(&1000, &5000, Event::ContextMenu, move |_, _, _, args|{
+        if let &EventArgs::Position(x, y) = args { unsafe{ (&mut *p).push((x, y)); } }
+    });
+    ui.commit().expect("Commit was not successful");
+
+    // WM_CONTEXTMENU (0x007B): wparam is the window that was clicked and lparam the cursor position in screen coordinates.
+    // The window is at (-600, -600) and its client area is inside the borders and the title bar.
+    // A position of (-1, -1) means the menu was requested with the keyboard: the cursor position is used instead.
+    let (window, input) = match (ui.handle_of(&1000), ui.handle_of(&1008)) {
+        (Ok(AnyHandle::HWND(w)), Ok(AnyHandle::HWND(i))) => (w, i),
+        r => panic!("Bad window handles, got {:?}", r)
+    };
+
+    unsafe {
+        nwg::custom::send_message(window, 0x007B, window as _, point(-550, -540) as _);
+        nwg::custom::send_message(window, 0x007B, window as _, point(-599, -599) as _); // On the border
+        nwg::custom::send_message(window, 0x007B, input as _, point(-550, -540) as _);  // Forwarded by a child
+        user32::SetCursorPos(30, 40);
+        nwg::custom::send_message(window, 0x007B, window as _, point(-1, -1) as _);
+    }
+    assert!(positions == vec![(-550, -540), (30, 40)], "Bad context menu positions: {:?}", positions);
+
+    match (ui.handle_of(&1001), ui.handle_of(&1002)) {
+        (Ok(AnyHandle::HMENU(menu)), Ok(AnyHandle::HMENU_ITEM(parent, _))) => assert!(menu == parent, "Menu item was not added to the popup menu"),
+        r => panic!("Bad popup menu handles, got {:?}", r)
+    }
+
+    assert!(ui.get::<MenuItem>(&1002).unwrap().get_enabled() && !ui.get::<MenuItem>(&1005).unwrap().get_enabled());
+    assert!(ui.get::<Menu>(&1004).unwrap().get_enabled());
+
+    ui.pack_control(&1007, nwg_popup_menu!(owner=Some(1002)));
+    match ui.commit() { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    ui.unpack(&1001);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1001) && !ui.has_id(&1002) && !ui.has_id(&1005) && ui.has_id(&1006));
+
+    // The popup menus are removed with their owner
+    ui.pack_control(&1001, nwg_popup_menu!(owner=Some(1008)));
+    ui.pack_control(&1002, nwg_menuitem!(parent=1001; text="Paste"));
+    ui.pack_control(&1009, nwg_popup_menu!(owner=Some(1000)));
+    ui.commit().expect("Commit was not successful");
+
+    ui.unpack(&1000);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1001) && !ui.has_id(&1002) && !ui.has_id(&1008) && !ui.has_id(&1009) && ui.has_id(&1006));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_menu_item_states() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_menu!(parent=1000; text="View"));
+    ui.pack_control(&1002, nwg_menuitem!(parent=1001; text="Show toolbar"; checked=true));
+    ui.pack_control(&1003, nwg_separator!(parent=1001));
+    ui.pack_control(&1004, nwg_menuitem!(parent=1001; text="Sort by name"; radio=true; checked=true));
+    ui.pack_control(&1005, nwg_menuitem!(parent=1001; text="Sort by date"; radio=true));
+    ui.pack_control(&1006, nwg_menuitem!(parent=1001; text="Sort by size"; radio=true; default=true));
+    ui.pack_control(&1007, nwg_menuitem!(parent=1001; text="Refresh"));
+    ui.pack_control(&1008, nwg_accelerator!(parent=1000; shortcut="F5"; item=Some(1007)));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let toolbar = ui.get::<MenuItem>(&1002).unwrap();
+        let (name, date, size) = (ui.get::<MenuItem>(&1004).unwrap(), ui.get::<MenuItem>(&1005).unwrap(), ui.get::<MenuItem>(&1006).unwrap());
+        let refresh = ui.get::<MenuItem>(&1007).unwrap();
+
+        assert!(toolbar.get_checked() && !toolbar.is_radio() && name.is_radio());
+        toolbar.set_checked(false);
+        assert!(!toolbar.get_checked());
+
+        // Disabling an item keeps its other states
+        toolbar.set_checked(true);
+        toolbar.set_enabled(false);
+        assert!(toolbar.get_checked() && !toolbar.get_enabled());
+
+        assert!(name.get_checked() && !date.get_checked() && !size.get_checked());
+        date.set_checked(true);
+        assert!(!name.get_checked() && date.get_checked() && !size.get_checked(), "Checking a radio item did not uncheck its group");
+        assert!(toolbar.get_checked(), "The radio group went past the separator");
+        size.set_checked(true);
+        name.set_checked(true);
+        assert!(name.get_checked() && !date.get_checked() && !size.get_checked(), "The radio group was not found again");
+
+        assert!(size.get_default() && !date.get_default());
+        date.set_default(true);
+        assert!(date.get_default() && !size.get_default());
+        date.set_default(false);
+        assert!(!date.get_default());
+
+        // The shortcut of the accelerator is shown after a tab and survives the text changes
+        assert!(refresh.get_text() == "Refresh" && refresh.get_shortcut() == Some("F5".to_string()));
+        refresh.set_text("Reload");
+        assert!(refresh.get_text() == "Reload" && refresh.get_shortcut() == Some("F5".to_string()), "The shortcut was lost");
+    }
+
+    // The images of the menuitems are bitmap resources
+    ui.pack_resource(&2000, nwg_image!(source=ImageSource::Bytes::<&str>(bitmap_file()); image_type=ImageType::Bitmap));
+    ui.pack_resource(&2001, nwg_image!(source=ImageSource::Bytes::<&str>(icon_file(1));));
+    ui.pack_control(&1009, nwg_menuitem!(parent=1001; text="Print"; image=Some(2000)));
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let refresh = ui.get::<MenuItem>(&1007).unwrap();
+        let (bitmap, icon) = (ui.get::<Image>(&2000).unwrap(), ui.get::<Image>(&2001).unwrap());
+        refresh.set_image(Some(&**bitmap)).expect("Setting the image failed");
+        match refresh.set_image(Some(&**icon)) { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+        refresh.set_image(None).expect("Removing the image failed");
+    }
+
+    ui.pack_control(&1010, nwg_menuitem!(parent=1001; image=Some(2001)));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_control(&1010, nwg_menuitem!(parent=1001; image=Some(2002)));
+    match ui.commit() { Err(Error::KeyNotFound) => {}, r => panic!("Should have returned Error::KeyNotFound, got {:?}", r) }
+    assert!(!ui.has_id(&1010));
+}
+
+/// A 1x1 icon file (`file_type` 1) or cursor file (`file_type` 2) with a 32 bits image
+#[cfg(windows)]
+fn icon_file(file_type: u8) -> Vec<u8> {
+    // In a cursor file, the planes and bit count fields of the entry hold the hotspot
+    let (planes, bit_count) = if file_type == 2 { (0, 0) } else { (1, 32) };
+    let mut data = vec![0, 0, file_type, 0, 1, 0, 1, 1, 0, 0, planes, 0, bit_count, 0, 48, 0, 0, 0, 22, 0, 0, 0];
+    // The height of the image header counts the color image and the transparency mask
+    data.extend_from_slice(&[40, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 1, 0, 32, 0]);
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(&[255, 0, 0, 255, 0, 0, 0, 0]);
+    data
+}
+
+/// A 1x1 bitmap file with a 24 bits pixel
+#[cfg(windows)]
+fn bitmap_file() -> Vec<u8> {
+    let mut data = vec![b'B', b'M', 58, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0];
+    data.extend_from_slice(&[40, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 24, 0]);
+    data.extend_from_slice(&[0; 24]);
+    data.extend_from_slice(&[0, 0, 255, 0]);
+    data
+}
+
+/// A 1x1 bitmap file with `bit_count` bits per pixel and `clr_used` colors. `table` holds the color masks and the color table.
+#[cfg(windows)]
+fn bitmap_file_with(bit_count: u8, compression: u8, clr_used: u8, table: &[u8]) -> Vec<u8> {
+    let bits = 54 + table.len() as u8;
+    let mut data = vec![b'B', b'M', bits + 4, 0, 0, 0, 0, 0, 0, 0, bits, 0, 0, 0];
+    data.extend_from_slice(&[40, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, bit_count, 0, compression, 0, 0, 0]);
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&[clr_used, 0, 0, 0, 0, 0, 0, 0]);
+    data.extend_from_slice(table);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data
+}
+
+#[test]
+#[cfg(windows)]
+fn test_images() {
+    let ui = setup_ui();
+
+    ui.pack_resource(&2000, nwg_image!(source=ImageSource::Bytes::<&str>(icon_file(1));));
+    ui.pack_resource(&2001, nwg_image!(source=ImageSource::Bytes::<&str>(icon_file(2)); image_type=ImageType::Cursor));
+    ui.pack_resource(&2002, nwg_image!(source=ImageSource::Bytes::<&str>(bitmap_file()); image_type=ImageType::Bitmap));
+    ui.pack_control(&1000, WindowT{ icon: Some(2000), ..window() });
+    ui.pack_control(&1001, ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1000, font: None});
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let (icon, cursor, bitmap) = nwg_get!(ui; [(2000, Image), (2001, Image), (2002, Image)]);
+        assert!(icon.get_type() == ImageType::Icon && cursor.get_type() == ImageType::Cursor && bitmap.get_type() == ImageType::Bitmap);
+    }
+
+    // Icons
+    set_control_icon(&ui, &1000, None).expect("Could not remove the window icon");
+    set_control_icon(&ui, &1000, Some(&2000)).expect("Could not set the window icon");
+    match set_control_icon(&ui, &1000, Some(&2002)) { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    match set_control_icon(&ui, &2000, None) { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    match (ui.handle_of(&2000), ui.handle_of(&2001), ui.handle_of(&2002)) {
+        (Ok(AnyHandle::HICON(_)), Ok(AnyHandle::HCURSOR(_)), Ok(AnyHandle::HBITMAP(_))) => {},
+        r => panic!("Bad image handles, got {:?}", r)
+    }
+
+    // Cursors
+    set_control_cursor(&ui, &1001, Some(&2001)).expect("Could not set the control cursor");
+    set_control_cursor(&ui, &1001, None).expect("Could not remove the control cursor");
+    set_control_cursor(&ui, &1000, Some(&2001)).expect("Could not set the window cursor");
+    match set_control_cursor(&ui, &1001, Some(&2000)) { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    match set_control_cursor(&ui, &2000, None) { Err(Error::BadParent(_)) => {}, r => panic!("Should have returned Error::BadParent, got {:?}", r) }
+
+    // Bad sources
+    ui.pack_resource(&2003, nwg_image!(source=ImageSource::Bytes::<&str>(bitmap_file());));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_resource(&2003, nwg_image!(source=ImageSource::Bytes::<&str>(icon_file(1)[..30].to_vec());));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_resource(&2003, nwg_image!(source=ImageSource::File("does_not_exist.ico");));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_resource(&2003, nwg_image!(source=ImageSource::ResourceId::<&str>(999); image_type=ImageType::Cursor));
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+    ui.pack_control(&1002, WindowT{ icon: Some(2002), ..window() });
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    // The header of a bitmap is followed by the color masks and the color table: they must not be truncated
+    let masks = [0, 0, 255, 0, 0, 255, 0, 0, 255, 0, 0, 0];
+    let bitmaps = vec![
+        (bitmap_file_with(1, 0, 0, &[0; 8]), true),
+        (bitmap_file_with(1, 0, 0, &[0; 4]), false),       // The table of 1 bit images has 2 colors
+        (bitmap_file_with(8, 0, 2, &[0; 8]), true),
+        (bitmap_file_with(8, 0, 0, &[0; 8]), false),       // The table of 8 bits images has 256 colors
+        (bitmap_file_with(24, 0, 3, &[0; 8]), false),      // A table of 3 colors
+        (bitmap_file_with(32, 3, 0, &masks), true),        // BI_BITFIELDS
+        (bitmap_file_with(32, 3, 0, &masks[..8]), false),
+        (bitmap_file_with(16, 3, 0, &[]), false),
+    ];
+
+    for (i, (data, valid)) in bitmaps.into_iter().enumerate() {
+        ui.pack_resource(&2003, nwg_image!(source=ImageSource::Bytes::<&str>(data); image_type=ImageType::Bitmap));
+        match (ui.commit(), valid) {
+            (Ok(()), true) => { ui.unpack(&2003); ui.commit().expect("Commit was not successful"); },
+            (Err(Error::BadResource(_)), false) => {},
+            (r, _) => panic!("Bitmap {} should be {}, got {:?}", i, if valid { "valid" } else { "refused" }, r)
+        }
+    }
+
+    // The images are not owned by the controls: they cannot be removed while they are shown
+    ui.pack_control(&1003, nwg_menu!(parent=1000; text="File"));
+    ui.pack_control(&1004, nwg_menuitem!(parent=1003; image=Some(2002)));
+    ui.pack_control(&1005, nwg_tray_icon!(icon=Some(2000)));
+    ui.commit().expect("Commit was not successful");
+
+    for &id in [2000, 2001, 2002].iter() {
+        ui.unpack(&id);
+        match ui.commit() { Err(Error::ResourceInUse) => {}, r => panic!("Should have returned Error::ResourceInUse for {}, got {:?}", id, r) }
+    }
+
+    set_control_icon(&ui, &1000, None).expect("Could not remove the window icon");
+    ui.unpack(&2000);
+    match ui.commit() { Err(Error::ResourceInUse) => {}, r => panic!("The tray icon still uses the icon, got {:?}", r) }
+
+    ui.unpack(&1005);
+    ui.unpack(&2000);
+    ui.commit().expect("Commit was not successful");
+    ui.get::<MenuItem>(&1004).unwrap().set_image(None).expect("Removing the image failed");
+    ui.unpack(&2002);
+    ui.commit().expect("Commit was not successful");
+
+    ui.unpack(&1000);
+    ui.unpack(&2001);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&2000) && !ui.has_id(&2001) && !ui.has_id(&2002) && !ui.has_id(&1004));
+
+    ui.pack_resource(&2000, nwg_image!(source=ImageSource::Bytes::<&str>(icon_file(1));));
+    ui.commit().expect("Commit was not successful");
+    assert!(ui.handle_of(&2000).is_ok());
+}
+
+#[test]
+fn test_ui_description() {
+    let doc = r#"{
+        "values": { "Count": 5, "Greeting": "Hello" },
+        "resources": { "Title": { "type": "Font", "family": "Arial", "size": 22, "decoration": ["Italic"] } },
+        "controls": {
+            "MainWindow": { "type": "Window", "title": "Hello", "size": [300, 115] },
+            "Name": { "type": "TextInput", "parent": "MainWindow", "position": [5, 15], "font": "Title" },
+            "Say": { "type": "Button", "parent": "MainWindow", "text": "Say hello", "size": [280, 60] }
+        }
+    }"#;
+
+    let description = UiDescription::from_json(doc).expect("Description is not valid");
+    assert!(description.values() == vec!["Count", "Greeting"]);
+    assert!(description.resources() == vec![("Title", "Font")]);
+    assert!(description.controls() == vec![("MainWindow", "Window"), ("Name", "TextInput"), ("Say", "Button")]);
+
+    // Errors are reported with the position of the faulty value
+    let errors = vec![
+        ("{ \"controls\": { \"A\": { \"type\": \"Button\", \"parent\": \"W\" } } }", Error::BadParent("line 1, column 52: the parent \"W\" of Button \"A\" must be a control written before it".to_string())),
+        ("{ \"controls\": {\n  \"W\": { \"type\": \"Window\", \"colour\": 3 } } }", Error::BadResource("line 2, column 28: unknown property \"colour\" for Window \"W\"".to_string())),
+        ("{ \"controls\": { \"W\": { \"type\": \"Window\", \"size\": [1] } } }", Error::BadResource("line 1, column 50: the property \"size\" of Window \"W\" must be an array of two positive integers".to_string())),
+        ("{ \"controls\": { \"A\": { \"type\": \"Blah\" } } }", Error::BadResource("line 1, column 32: unknown type \"Blah\"".to_string())),
+        ("{ \"controls\": { \"A\": { \"type\": \"Window\", } } }", Error::BadResource("line 1, column 42: expected '\"', found '}'".to_string())),
+    ];
+
+    for (doc, expected) in errors.into_iter() {
+        match UiDescription::from_json(doc) {
+            Err(e) => assert!(e == expected, "Expected {:?}, got {:?}", expected, e),
+            Ok(_) => panic!("{:?} should not be valid", doc)
+        }
+    }
+
+    match UiDescription::from_json("{ \"controls\": { \"W\": { \"type\": \"Window\" }, \"B\": { \"type\": \"Button\", \"parent\": \"W\", \"font\": \"F\" } } }") {
+        Err(Error::BadResource(_)) => {},
+        r => panic!("Should have returned Error::BadResource, got {:?}", r.err())
+    }
+
+    // The image of a menu item is the id of an Image resource, not a path
+    let menu = |image: &str| format!("{{ \"resources\": {{ \"F\": {{ \"type\": \"Font\", \"family\": \"Arial\" }}, \"S\": {{ \"type\": \"Image\", \"file\": \"save.bmp\" }} }}, \
+        \"controls\": {{ \"W\": {{ \"type\": \"Window\" }}, \"M\": {{ \"type\": \"Menu\", \"parent\": \"W\" }}, \"I\": {{ \"type\": \"MenuItem\", \"parent\": \"M\", \"image\": \"{}\" }} }} }}", image);
+    assert!(UiDescription::from_json(&menu("S")).is_ok(), "An Image resource was refused");
+    for &(image, msg) in [("save.bmp", "unknown resource \"save.bmp\" used by MenuItem \"I\""), ("F", "the resource \"F\" used by MenuItem \"I\" must be an Image")].iter() {
+        match UiDescription::from_json(&menu(image)) {
+            Err(Error::BadResource(ref e)) if e.ends_with(msg) => {},
+            r => panic!("Should have returned Error::BadResource({:?}), got {:?}", msg, r.err())
+        }
+    }
+
+    // The font of a control is the id of a Font resource
+    let button = |font: &str| format!("{{ \"resources\": {{ \"F\": {{ \"type\": \"Font\", \"family\": \"Arial\" }}, \"S\": {{ \"type\": \"Image\", \"file\": \"save.bmp\" }} }}, \
+        \"controls\": {{ \"W\": {{ \"type\": \"Window\" }}, \"B\": {{ \"type\": \"Button\", \"parent\": \"W\", \"font\": \"{}\" }} }} }}", font);
+    assert!(UiDescription::from_json(&button("F")).is_ok(), "A Font resource was refused");
+    match UiDescription::from_json(&button("S")) {
+        Err(Error::BadResource(ref e)) if e.ends_with("the resource \"S\" used by Button \"B\" must be a Font") => {},
+        r => panic!("Should have returned Error::BadResource, got {:?}", r.err())
+    }
+
+    // Packing
+    #[cfg(windows)]
+    {
+        let backend = Rc::new(MockBackend::new());
+        let ui: Ui<String> = Ui::with_backend(backend.clone()).unwrap();
+        description.pack(&ui).expect("Pack was not successful");
+        assert!(*ui.get::<i64>(&"Count".to_string()).unwrap() == 5);
+        assert!(ui.get::<String>(&"Greeting".to_string()).unwrap().as_str() == "Hello");
+        assert!(ui.get::<Button>(&"Say".to_string()).unwrap().get_size() == (280, 60));
+        assert!(ui.get::<Window>(&"MainWindow".to_string()).unwrap().get_title().as_str() == "Hello");
+        assert!(ui.has_id(&"Title".to_string()) && ui.has_id(&"Name".to_string()));
+    }
+}
+
+#[test]
+fn test_bindings() {
+    let (ui, _) = setup_mock_ui();
+    let input_t = TextInputT{text: "", position:(0, 0), size: (100, 30), visible: true, disabled: false, readonly: false, password: false, limit: 32_767, placeholder: None::<&str>, parent: 1000, font: None};
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, input_t.clone());
+    ui.pack_control(&1002, input_t);
+    ui.pack_observable(&2000, "Hello".to_string());
+    ui.pack_observable(&2001, 42u32);
+    ui.commit().expect("Commit was not successful");
+
+    ui.pack_control(&3000, nwg_binding!(value=2000; control=1001; property=TextInputText;));
+    ui.pack_control(&3001, BindingT{ value: 2001, control: 1002, property: TextInputText,
+        to_control: |v: &u32| v.to_string(), from_control: |s: &String| s.parse().ok(), validator: Some(|v: &u32| *v <= 100) });
+    ui.commit().expect("Commit was not successful");
+
+    let text_of = |id: u64| ui.get::<TextInput>(&id).unwrap().get_text();
+    let number = || *ui.get::<Observable<u32>>(&2001).unwrap().get();
+
+    // The controls are initialized from the values
+    assert!(text_of(1001) == "Hello" && text_of(1002) == "42", "Controls were not initialized");
+
+    // Value -> Control
+    ui.get_mut::<Observable<String>>(&2000).unwrap().set("World".to_string());
+    ui.get_mut::<Observable<u32>>(&2001).unwrap().set(200);
+    assert!(text_of(1001) == "World", "Control was not updated");
+    assert!(text_of(1002) == "42", "Invalid value was copied to the control");
+
+    // Control -> Value
+    for &(text, expected) in [("7", 7), ("abc", 7), ("500", 7), ("99", 99)].iter() {
+        ui.get::<TextInput>(&1002).unwrap().set_text(text);
+        ui.trigger(&1002, Event::ValueChanged, EventArgs::Text(text.to_string()));
+        ui.commit().expect("Commit was not successful");
+        assert!(number() == expected, "Value is {} after typing {:?}, expected {}", number(), text, expected);
+    }
+
+    // Unpacking the binding stops the synchronization
+    ui.unpack(&3000);
+    ui.commit().expect("Commit was not successful");
+    ui.get_mut::<Observable<String>>(&2000).unwrap().set("Unbound".to_string());
+    assert!(text_of(1001) == "World", "Unpacked binding updated the control");
+    ui.trigger(&1001, Event::ValueChanged, EventArgs::Text("World".to_string()));
+    ui.commit().expect("Commit was not successful");
+    assert!(ui.get::<Observable<String>>(&2000).unwrap().get() == "Unbound", "Unpacked binding updated the value");
+
+    ui.pack_control(&3002, nwg_binding!(value=9999; control=1001; property=TextInputText;));
+    match ui.commit() { Err(Error::KeyNotFound) => {}, r => panic!("Should have returned Error::KeyNotFound, got {:?}", r) }
+    ui.pack_control(&3002, nwg_binding!(value=2000; control=1001; property=TextBoxText;));
+    match ui.commit() { Err(Error::BadType) => {}, r => panic!("Should have returned Error::BadType, got {:?}", r) }
+}
+
+#[test]
+fn test_local_tasks() {
+    use std::cell::Cell;
+    use std::future::{Future, poll_fn};
+    use std::pin::Pin;
+    use std::task::Poll;
+    use std::thread;
+    use std::time::Duration;
+    use nwg::tasks::channel;
+
+    let (ui, _) = setup_mock_ui();
+    let local = ui.local();
+    let (total, closed) = (Rc::new(Cell::new(0u32)), Rc::new(Cell::new(false)));
+
+    // Receive values from a worker thread until the channel is closed
+    let (tx, mut rx) = channel::<u32>();
+    let (task_total, task_closed) = (total.clone(), closed.clone());
+    ui.spawn_local(poll_fn(move |cx| {
+        loop {
+            match rx.poll_recv(cx) {
+                Poll::Ready(Some(v)) => { task_total.set(task_total.get() + v); },
+                Poll::Ready(None) => { task_closed.set(true); return Poll::Ready(()); },
+                Poll::Pending => { return Poll::Pending; }
+            }
+        }
+    }));
+
+    assert!(total.get() == 0, "Task was polled before the commit");
+    ui.commit().expect("Commit was not successful");
+
+    thread::spawn(move || { tx.send(1); tx.send(2); }).join().unwrap();
+    ui.commit().expect("Commit was not successful");
+    assert!(total.get() == 3 && closed.get(), "Task was not woken by the channel");
+
+    // Blocking functions complete back on the ui thread
+    ui.pack_control(&1000, window());
+    let result = Rc::new(Cell::new(None));
+    let (task_result, mut work) = (result.clone(), ui.spawn_blocking(|| 6 * 7));
+    ui.spawn_local(poll_fn(move |cx| {
+        match Pin::new(&mut work).poll(cx) {
+            Poll::Ready(r) => {
+                let has_window = local.with(|ui| ui.has_id(&1000)).unwrap();
+                task_result.set(Some((r.unwrap(), has_window)));
+                Poll::Ready(())
+            },
+            Poll::Pending => Poll::Pending
+        }
+    }));
+
+    for _ in 0..100 {
+        ui.commit().expect("Commit was not successful");
+        if result.get().is_some() { break; }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert!(result.get() == Some((42, true)), "Blocking task result was not received");
+
+    // A LocalUi outlives its Ui without being able to access it
+    let local = ui.local();
+    drop(ui);
+    assert!(!local.is_alive());
+    match local.with(|_| ()) { Err(Error::UserError(_)) => {}, r => panic!("Should have returned Error::UserError, got {:?}", r) }
+}
+
+#[test]
+#[cfg(windows)]
+fn test_dialog() {
+    let ui = setup_ui();
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, nwg_dialog!(owner=Some(1000); title="Dialog"; position=(-600, -600)));
+    ui.bind(&1001, &5000, Event::Accepted, |ui, id, _, _| { ui.get::<Dialog>(id).unwrap().accept(7u32); });
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let dialog = ui.get::<Dialog>(&1001).expect("Control not found!");
+        assert!(dialog.get_title() == "Dialog");
+        assert!(!dialog.running(), "Dialog is running before run_modal");
+
+        // Values accepted outside of run_modal are ignored
+        dialog.accept(1u32);
+    }
+
+    // The triggered event is dispatched by the modal loop and accepts the dialog
+    ui.trigger(&1001, Event::Accepted, EventArgs::None);
+    match ui.run_modal::<u32>(&1001) { Ok(Some(7)) => {}, r => panic!("Should have returned Ok(Some(7)), got {:?}", r) }
+    assert!(ui.get::<Window>(&1000).unwrap().get_enabled(), "Owner was not enabled after the dialog closed");
+    assert!(!ui.get::<Dialog>(&1001).unwrap().running(), "Dialog is running after run_modal");
+
+    ui.trigger(&1001, Event::Accepted, EventArgs::None);
+    match ui.run_modal::<String>(&1001) { Err(Error::BadType) => {}, r => panic!("Should have returned Error::BadType, got {:?}", r) }
+
+    match ui.run_modal::<u32>(&1000) { Err(Error::BadType) => {}, r => panic!("Should have returned Error::BadType, got {:?}", r) }
+    match ui.run_modal::<u32>(&9999) { Err(Error::KeyNotFound) => {}, r => panic!("Should have returned Error::KeyNotFound, got {:?}", r) }
+
+    ui.pack_control(&1002, nwg_dialog!(owner=Some(9999)));
+    match ui.commit() { Err(Error::KeyNotFound) => {}, r => panic!("Should have returned Error::KeyNotFound, got {:?}", r) }
+}
+
+#[test]
+#[cfg(windows)]
+fn test_canvas_paths() {
+    let path = PathBuilder::new((0.0, 0.0))
+        .line_to((10.0, 0.0))
+        .quadratic_to((15.0, 5.0), (10.0, 10.0))
+        .close()
+        .move_to((20.0, 20.0))
+        .move_to((30.0, 30.0))
+        .cubic_to((35.0, 30.0), (40.0, 35.0), (40.0, 40.0))
+        .arc_to((50.0, 50.0), (10.0, 10.0), 0.0, SweepDirection::Clockwise, ArcSize::Small)
+        .fill_mode(FillMode::Winding)
+        .build();
+
+    // Figures without segments are discarded
+    assert!(path.figures.len() == 2 && path.len() == 4, "Path has {} figures and {} segments", path.figures.len(), path.len());
+    assert!(path.fill_mode == FillMode::Winding);
+
+    let (first, second) = (&path.figures[0], &path.figures[1]);
+    assert!(first.closed && first.end() == (0.0, 0.0), "First figure was not closed");
+    assert!(first.segments[1] == PathSegment::QuadraticBezier{ control: (15.0, 5.0), end: (10.0, 10.0) });
+    assert!(!second.closed && second.start == (30.0, 30.0) && second.end() == (50.0, 50.0));
+
+    let points = [(0.0, 0.0), (5.0, 0.0), (5.0, 5.0)];
+    let polygon = Path::polygon(&points);
+    assert!(polygon.figures[0].closed && polygon.len() == 2);
+    assert!(polygon.figures[0].segments == vec![PathSegment::Line{ end: (5.0, 0.0) }, PathSegment::Line{ end: (5.0, 5.0) }]);
+    assert!(!Path::polyline(&points).figures[0].closed);
+    assert!(Path::polyline(&[]).is_empty());
+}
+
+#[test]
+#[cfg(windows)]
+fn test_canvas_text() {
+    let ui = setup_ui();
+    ui.pack_control(&1000, nwg_window!(visible=false));
+    ui.pack_control(&1001, nwg_canvas!(parent=1000; size=(300, 300)));
+    ui.commit().expect("Commit was not successful");
+
+    let mut canvas = ui.get_mut::<Canvas<u64>>(&1001).unwrap();
+    let format = |align, valign, wrapping, trimming| TextFormat{ family: "Arial".to_string(), size: 20.0, weight: FONT_WEIGHT_NORMAL,
+        italic: false, align: align, vertical_align: valign, wrapping: wrapping, trimming: trimming };
+
+    canvas.create_text_format(&1, &format(HTextAlign::Left, VTextAlign::Top, TextWrapping::Wrap, TextTrimming::None)).expect("Failed to create format");
+    canvas.create_text_format(&2, &format(HTextAlign::Right, VTextAlign::Bottom, TextWrapping::NoWrap, TextTrimming::None)).unwrap();
+    canvas.create_text_format(&3, &format(HTextAlign::Center, VTextAlign::Center, TextWrapping::NoWrap, TextTrimming::Character)).unwrap();
+    canvas.create_solid_brush(&4, &SolidBrush{ color: (0.0, 0.0, 0.0, 1.0) }).unwrap();
+    assert!(canvas.create_text_format(&1, &format(HTextAlign::Left, VTextAlign::Top, TextWrapping::Wrap, TextTrimming::None)).err() == Some(Error::KeyExists));
+
+    // Left / Top
+    let m = canvas.measure_text(&1, "Hello", (200.0, 100.0)).expect("Failed to measure text");
+    assert!(m.left == 0.0 && m.top == 0.0 && m.line_count == 1, "Bad metrics: {:?}", m);
+    assert!(m.layout_size == (200.0, 100.0) && m.width > 0.0 && m.width < 200.0 && m.height > 0.0);
+
+    // Right / Bottom
+    let m = canvas.measure_text(&2, "Hello", (200.0, 100.0)).unwrap();
+    assert!((m.left + m.width - 200.0).abs() < 0.5 && (m.top + m.height - 100.0).abs() < 0.5, "Bad metrics: {:?}", m);
+
+    // Center / Center
+    let m = canvas.measure_text(&3, "Hello", (200.0, 100.0)).unwrap();
+    assert!((m.left - (200.0 - m.width) / 2.0).abs() < 0.5 && (m.top - (100.0 - m.height) / 2.0).abs() < 0.5, "Bad metrics: {:?}", m);
+
+    // Wrapping
+    let long = "The quick brown fox jumps over the lazy dog";
+    assert!(canvas.measure_text(&1, long, (100.0, 300.0)).unwrap().line_count > 1);
+    assert!(canvas.measure_text(&2, long, (100.0, 300.0)).unwrap().line_count == 1);
+
+    // Errors
+    assert!(canvas.measure_text(&99, "Hello", (200.0, 100.0)).err() == Some(Error::KeyNotFound));
+    match canvas.measure_text(&4, "Hello", (200.0, 100.0)) {
+        Err(Error::BadResource(_)) => {},
+        r => panic!("Measuring with a brush returned {:?}", r)
+    }
+
+    // Hit testing
+    let layout = canvas.create_text_layout(&1, "Hello", (200.0, 100.0)).unwrap();
+    let h = layout.hit_test_point(1.0, 5.0);
+    assert!(h.text_position == 0 && h.length == 1 && h.inside && !h.trailing && !h.trimmed, "Bad hit test: {:?}", h);
+
+    let ((x, y), h) = layout.hit_test_position(1, false);
+    assert!(h.text_position == 1 && h.inside && x == h.left && y == h.top && x > 0.0, "Bad hit test: {:?}", h);
+
+    let t = layout.hit_test_point(h.left + h.width * 0.75, h.top + 1.0);
+    assert!(t.text_position == 1 && t.inside && t.trailing, "Bad hit test: {:?}", t);
+
+    let t = layout.hit_test_point(190.0, 5.0);
+    assert!(t.text_position == 4 && !t.inside && t.trailing, "Bad hit test: {:?}", t);
+
+    // Trimming
+    let layout = canvas.create_text_layout(&3, long, (50.0, 100.0)).unwrap();
+    assert!(layout.hit_test_position(long.len() as u32 - 1, false).1.trimmed);
+
+    layout.set_max_size(1000.0, 50.0);
+    assert!(layout.get_max_size() == (1000.0, 50.0));
+    assert!(!layout.hit_test_position(long.len() as u32 - 1, false).1.trimmed);
+}
+
+#[test]
+#[cfg(windows)]
+fn test_canvas_rgba_bitmap() {
+    let ui = setup_ui();
+    ui.pack_control(&1000, nwg_window!(visible=false));
+    ui.pack_control(&1001, nwg_canvas!(parent=1000));
+    ui.commit().expect("Commit was not successful");
+
+    // Colors are swapped to BGRA and multiplied by the alpha
+    let pixels = [255, 0, 0, 255,   0, 128, 255, 128,   10, 20, 30, 0];
+    assert!(rgba_to_pbgra(&pixels) == vec![0, 0, 255, 255,   128, 64, 0, 128,   0, 0, 0, 0]);
+    assert!(rgba_to_pbgra(&[1, 2, 3, 4, 5]) == vec![0, 0, 0, 4]);
+
+    let mut canvas = ui.get_mut::<Canvas<u64>>(&1001).unwrap();
+    let pixels: Vec<u8> = (0..(3*2*4)).map(|i| i as u8).collect();
+
+    match canvas.create_bitmap_from_rgba(&1, 3, 2, &pixels[1..]) {
+        Err(Error::BadResource(_)) => {},
+        r => panic!("A bitmap with missing pixels returned {:?}", r)
+    }
+    match canvas.create_bitmap_from_rgba(&1, 2, 2, &pixels) {
+        Err(Error::BadResource(_)) => {},
+        r => panic!("A bitmap with extra pixels returned {:?}", r)
+    }
+    assert!(canvas.get_bitmap_size(&1).err() == Some(Error::KeyNotFound), "A bad bitmap was added to the canvas");
+
+    canvas.create_bitmap_from_rgba(&1, 3, 2, &pixels).expect("Failed to create bitmap");
+    assert!(canvas.get_bitmap_size(&1) == Ok((3, 2)));
+    assert!(canvas.create_bitmap_from_rgba(&1, 3, 2, &pixels).err() == Some(Error::KeyExists));
+}
+
+#[test]
+#[cfg(windows)]
+fn test_canvas_gradients() {
+    let ui = setup_ui();
+    ui.pack_control(&1000, nwg_window!(visible=false));
+    ui.pack_control(&1001, nwg_canvas!(parent=1000));
+    ui.commit().expect("Commit was not successful");
+
+    let mut canvas = ui.get_mut::<Canvas<u64>>(&1001).unwrap();
+    let stops = vec![
+        GradientStop{ position: 0.0, color: (1.0, 0.0, 0.0, 1.0) },
+        GradientStop{ position: 0.5, color: (0.0, 1.0, 0.0, 0.5) },
+        GradientStop{ position: 1.0, color: (0.0, 0.0, 1.0, 0.0) },
+    ];
+
+    let linear = LinearGradientBrush{ start: (0.0, 0.0), end: (100.0, 0.0), stops: stops.clone(), extend_mode: ExtendMode::Mirror };
+    canvas.create_linear_gradient_brush(&1, &linear).expect("Failed to create linear gradient brush");
+    assert!(canvas.get_gradient_stops(&1) == Ok((stops.clone(), ExtendMode::Mirror)));
+
+    let radial = RadialGradientBrush{ center: (50.0, 50.0), origin_offset: (0.0, 0.0), radius: (50.0, 25.0),
+        stops: stops[..2].to_vec(), extend_mode: ExtendMode::Wrap };
+    canvas.create_radial_gradient_brush(&2, &radial).expect("Failed to create radial gradient brush");
+    assert!(canvas.get_gradient_stops(&2) == Ok((stops[..2].to_vec(), ExtendMode::Wrap)));
+
+    let clamp = LinearGradientBrush{ extend_mode: ExtendMode::Clamp, ..linear.clone() };
+    canvas.create_linear_gradient_brush(&3, &clamp).unwrap();
+    assert!(canvas.get_gradient_stops(&3).map(|(_, mode)| mode) == Ok(ExtendMode::Clamp));
+
+    // Errors
+    assert!(canvas.create_linear_gradient_brush(&1, &linear).err() == Some(Error::KeyExists));
+    assert!(canvas.create_radial_gradient_brush(&1, &radial).err() == Some(Error::KeyExists));
+    assert!(canvas.get_gradient_stops(&99).err() == Some(Error::KeyNotFound));
+    canvas.create_solid_brush(&4, &SolidBrush{ color: (0.0, 0.0, 0.0, 1.0) }).unwrap();
+    match canvas.get_gradient_stops(&4) {
+        Err(Error::BadResource(_)) => {},
+        r => panic!("A solid brush returned {:?}", r)
+    }
+}
+
+fn paint_target<R: Renderer<u64>>(r: &mut R) -> Result<(), Error> {
+    let (w, h) = r.get_render_size();
+    r.clear(1.0, 1.0, 1.0, 1.0);
+    r.set_transform(&[[1.0, 0.0], [0.0, 1.0], [w / 2.0, h / 2.0]]);
+    try!(r.fill_ellipse(&1, &Ellipse{ center: (0.0, 0.0), radius: (10.0, 10.0) }));
+    try!(r.draw_rectangle(&2, Some(&3), &Rectangle{ left: -20.0, top: -20.0, right: 20.0, bottom: 20.0 }, 2.0));
+    r.draw_line(&2, None, (-w / 2.0, 0.0), (w / 2.0, 0.0), 1.0)
+}
+
+#[test]
+fn test_recording_renderer() {
+    let mut r = RecordingRenderer::<u64>::new((100.0, 50.0));
+    paint_target(&mut r).expect("Recording failed");
+
+    assert!(r.get_transform() == [[1.0, 0.0], [0.0, 1.0], [50.0, 25.0]], "Transform was not tracked");
+    assert!(r.commands().len() == 5, "{} commands were recorded", r.commands().len());
+    assert!(r.commands()[0] == DrawCommand::Clear{ color: (1.0, 1.0, 1.0, 1.0) });
+    assert!(r.commands()[3] == DrawCommand::DrawRectangle{ brush: 2, pen: Some(3),
+        rect: Rectangle{ left: -20.0, top: -20.0, right: 20.0, bottom: 20.0 }, width: 2.0 });
+
+    let snapshot = r.to_text();
+    assert!(snapshot.lines().count() == 5);
+    assert!(snapshot.lines().next() == Some("clear 1,1,1,1"), "Bad snapshot: {}", snapshot);
+    assert!(snapshot.lines().last() == Some("draw_line 2 - -50,0 50,0 1"), "Bad snapshot: {}", snapshot);
+
+    // Painting again gives the same snapshot
+    let commands = r.take_commands();
+    paint_target(&mut r).unwrap();
+    assert!(r.to_text() == snapshot && r.commands() == &commands[..]);
+}
+
+#[test]
+fn test_recording_renderer_text() {
+    let path = PathBuilder::new((0.0, 0.0))
+        .line_to((10.5, 0.0))
+        .quadratic_to((15.0, 5.0), (10.0, 10.0))
+        .close()
+        .move_to((30.0, 30.0))
+        .cubic_to((35.0, 30.0), (40.0, 35.0), (40.0, 40.0))
+        .arc_to((50.0, 50.0), (10.0, 10.0), 45.0, SweepDirection::CounterClockwise, ArcSize::Large)
+        .fill_mode(FillMode::Winding)
+        .build();
+
+    let mut r = RecordingRenderer::<u64>::new((100.0, 50.0));
+    paint_target(&mut r).unwrap();
+    r.fill_rounded_rectangle(&1, &Rectangle{ left: 0.0, top: 0.0, right: 10.0, bottom: 10.0 }, (2.0, 2.0)).unwrap();
+    r.draw_polyline(&2, Some(&3), &[(0.0, 0.0), (5.0, 0.1), (-5.0, 1e-7)], 0.5).unwrap();
+    r.draw_polygon(&2, None, &[], 1.0).unwrap();
+    r.fill_polygon(&1, &[(1.0, 1.0)]).unwrap();
+    r.draw_path(&2, Some(&3), &path, 3.0).unwrap();
+    r.fill_path(&1, &Path::polyline(&[])).unwrap();
+    r.draw_saved_path(&2, None, &10, 1.0).unwrap();
+    r.fill_saved_path(&1, &10).unwrap();
+    r.draw_text(&2, &5, "Say \"hi\"\n\tto C:\\ ", &Rectangle{ left: 0.0, top: 0.0, right: 100.0, bottom: 20.0 }).unwrap();
+    r.draw_text(&2, &5, "", &Rectangle{ left: 0.0, top: 0.0, right: 100.0, bottom: 20.0 }).unwrap();
+    r.draw_bitmap(&6, &Rectangle{ left: 0.0, top: 0.0, right: 10.0, bottom: 10.0 }, None, 1.0, BitmapInterpolation::Linear).unwrap();
+    r.draw_bitmap(&6, &Rectangle{ left: 0.0, top: 0.0, right: 10.0, bottom: 10.0 }, Some(&Rectangle{ left: 1.0, top: 1.0, right: 2.0, bottom: 2.0 }),
+        0.25, BitmapInterpolation::NearestNeighbor).unwrap();
+
+    // Every command survives the round trip
+    let snapshot = r.to_text();
+    let commands = RecordingRenderer::<u64>::parse_text(&snapshot).expect("Failed to parse snapshot");
+    assert!(commands.len() == 17 && commands == r.commands(), "Bad round trip: {:?}", commands);
+    assert!(snapshot.lines().nth(13) == Some("draw_text 2 5 \"Say \\\"hi\\\"\\n\\tto C:\\\\ \" 0,0,100,20"), "Bad snapshot: {}", snapshot);
+
+    // Text layouts are recorded by their text and their size
+    let text = "\ndraw_text_layout 2 \"Hello world\" 200,40 10,5\n\n";
+    let commands = RecordingRenderer::<u64>::parse_text(text).unwrap();
+    assert!(commands == vec![DrawCommand::DrawTextLayout{ brush: 2, text: "Hello world".to_string(), max_size: (200.0, 40.0), origin: (10.0, 5.0) }]);
+
+    #[cfg(not(windows))]
+    {
+        let layout = TextLayout::new("Hello world", (200.0, 40.0));
+        r.draw_text_layout(&2, &layout, (10.0, 5.0)).unwrap();
+        assert!(r.commands().last() == commands.last(), "Bad text layout command: {:?}", r.commands().last());
+    }
+
+    // Errors
+    let bad = |text: &str| match RecordingRenderer::<u64>::parse_text(text) {
+        Err(Error::BadResource(msg)) => msg,
+        r => panic!("Parsing {:?} returned {:?}", text, r)
+    };
+
+    assert!(bad("clear 1,1,1,1\ndraw_line 2 - 0,0 1,1").starts_with("line 2:"));
+    assert!(bad("draw_line 2 - 0,0 1,1 1 1").starts_with("line 1:"));
+    bad("draw_squiggle 2");
+    bad("fill_rectangle x 0,0,1,1");
+    bad("fill_rectangle 1 0,0,1");
+    bad("draw_text 1 2 \"unterminated 0,0,1,1");
+    bad("fill_path 1 even_odd 0");
+}
+
+#[test]
+fn test_mock_backend() {
+    let (ui, backend) = setup_mock_ui();
+    let mut clicked: bool = false;
+    let x = &mut clicked as *mut bool;
+
+    let btn_t = ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1000, font: None};
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, btn_t);
+    assert!(backend.pending_messages() == 2, "Commands were not posted to the mock backend");
+    ui.commit().expect("Commit was not successful");
+    assert!(backend.pending_messages() == 0, "Commands were not processed");
+    assert!(backend.posted_messages().len() == 2);
+
+    // Windows were recorded by the backend
+    let handle = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Button handle is not a HWND") };
+    let btn = backend.window(handle).expect("Button window was not recorded");
+    assert!(btn.class_name.as_str() == "BUTTON");
+    assert!(btn.text.as_str() == "TEST");
+    assert!(btn.position == (10, 10) && btn.size == (100, 30));
+    assert!(btn.visible && btn.enabled);
+
+    // Methods test
+    test_visibility!(ui, &1001, Button);
+    test_position!(ui, &1001, Button);
+    test_size!(ui, &1001, Button);
+    test_enabled!(ui, &1001, Button);
+    assert!(backend.window(handle).unwrap().position == (-600, -600));
+
+    // Bind & trigger
+    ui.bind(&1001, &5000, Event::Click, move |_, _, _, _|{ unsafe{ *(&mut *x) = true; } });
+    ui.trigger(&1001, Event::Click, EventArgs::None);
+    ui.commit().expect("Commit was not successful");
+    assert!(clicked, "Callback was not called");
+
+    // Unpack destroys the window and its children
+    ui.unpack(&1000);
+    ui.commit().expect("Commit was not successful");
+    assert!(!ui.has_id(&1001), "Child was not unpacked");
+    assert!(backend.window(handle).is_none(), "Button window was not destroyed");
+    assert!(backend.windows().iter().all(|&(_, ref w)| w.message_only), "Some windows were not destroyed");
+}
+
+#[test]
+fn test_backend_per_ui() {
+    let (ui, backend) = setup_mock_ui();
+    let count = |b: &Rc<MockBackend>| b.windows().iter().filter(|&&(_, ref w)| !w.message_only).count();
+
+    ui.pack_control(&1000, window());
+    ui.commit().expect("Commit was not successful");
+
+    {
+        // The commands of a Ui are executed over its own backend, even if another Ui was created after it
+        let (ui2, backend2) = setup_mock_ui();
+        ui2.pack_control(&1000, window());
+        ui2.commit().expect("Commit was not successful");
+
+        ui.pack_control(&1001, window());
+        ui.commit().expect("Commit was not successful");
+        assert!(count(&backend) == 2 && count(&backend2) == 1, "A command was executed over the wrong backend");
+    }
+
+    // The backend of the first Ui is restored when the second Ui is dropped
+    let handle = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle is not a HWND") };
+    ui.get::<Window>(&1000).unwrap().set_title("Restored");
+    assert!(backend.window(handle).unwrap().text.as_str() == "Restored", "The backend was not restored");
+}
+
+#[test]
+fn test_event_decoders() {
+    use std::ptr;
+
+    let btn_t = ButtonT{text: "TEST", position:(10, 10), size: (100, 30), visible: true, disabled: false, parent: 1000, font: None};
+    let input_t = TextInputT{text: "", position:(0, 0), size: (100, 30), visible: true, disabled: false, readonly: false, password: false, limit: 32_767, placeholder: None::<&str>, parent: 1000, font: None};
+    let win_t = window();
+
+    // Windows do not send WM_COMMAND notifications
+    let decoders = ControlT::<u64>::decoders(&win_t);
+    assert!(decoders.command.is_none() && decoders.notify.is_none());
+
+    // BN_CLICKED = 0, BN_SETFOCUS = 6
+    let decode = ControlT::<u64>::decoders(&btn_t).command.expect("Button has no command decoder");
+    match unsafe{ decode(ptr::null_mut(), 0) } { Some((Event::Click, EventArgs::None)) => {}, _ => panic!("BN_CLICKED was not decoded") }
+    match unsafe{ decode(ptr::null_mut(), 6) } { Some((Event::Focus, EventArgs::Focus(true))) => {}, _ => panic!("BN_SETFOCUS was not decoded") }
+    assert!(unsafe{ decode(ptr::null_mut(), 9999) }.is_none());
+
+    // EN_MAXTEXT = 1281
+    let decode = ControlT::<u64>::decoders(&input_t).command.expect("TextInput has no command decoder");
+    match unsafe{ decode(ptr::null_mut(), 1281) } { Some((Event::LimitReached, EventArgs::None)) => {}, _ => panic!("EN_MAXTEXT was not decoded") }
+
+    // Decoders are registered when a control is packed. The notifications sent to the parent are decoded with them.
+    let (ui, backend) = setup_mock_ui();
+    let mut clicks = 0u32;
+    let mut limits = 0u32;
+    let (c, l) = (&mut clicks as *mut u32, &mut limits as *mut u32);
+
+    ui.pack_control(&1000, window());
+    ui.pack_control(&1001, btn_t);
+    ui.pack_control(&1002, input_t);
+    ui.bind(&1001, &5000, Event::Click, move |_, _, _, _|{ unsafe{ *c += 1; } });
+    ui.bind(&1002, &5001, Event::LimitReached, move |_, _, _, _|{ unsafe{ *l += 1; } });
+    ui.commit().expect("Commit was not successful");
+
+    let window_handle = match ui.handle_of(&1000) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Window handle not found") };
+    let btn_handle = match ui.handle_of(&1001) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("Button handle not found") };
+    let input_handle = match ui.handle_of(&1002) { Ok(AnyHandle::HWND(h)) => h, _ => panic!("TextInput handle not found") };
+
+    // WM_COMMAND = 0x0111. The notification code (BN_CLICKED, EN_MAXTEXT) is in the high word of WPARAM and the control handle is in LPARAM.
+    unsafe {
+        nwg::custom::send_message(window_handle, 0x0111, (0 << 16) as _, btn_handle as _);
+        nwg::custom::send_message(window_handle, 0x0111, (1281 << 16) as _, input_handle as _);
+        nwg::custom::send_message(window_handle, 0x0111, (9999 << 16) as _, btn_handle as _);
+    }
+
+    assert!(backend.sent_messages().iter().filter(|m| m.msg == 0x0111).count() == 3);
+    assert!(clicks == 1, "BN_CLICKED was not dispatched to the button callback");
+    assert!(limits == 1, "EN_MAXTEXT was not dispatched to the text input callback");
+
+    // Once the button is removed, its notifications are ignored
+    ui.unpack(&1001);
+    ui.commit().expect("Commit was not successful");
+    unsafe{ nwg::custom::send_message(window_handle, 0x0111, (0 << 16) as _, btn_handle as _); }
+    assert!(clicks == 1);
+}
+
+#[test]
+fn test_layout_solver() {
+    // Weights
+    assert!(distribute(300, 0, &[track(1, 0, None), track(2, 0, None)]) == vec![100, 200]);
+    assert!(distribute(310, 10, &[track(1, 0, None), track(1, 0, None), track(1, 0, None)]) == vec![97, 97, 96]);
+
+    // Min / max constraints
+    assert!(distribute(300, 0, &[track(1, 0, Some(50)), track(1, 0, None)]) == vec![50, 250]);
+    assert!(distribute(300, 0, &[track(1, 200, None), track(1, 0, None)]) == vec![200, 100]);
+    assert!(distribute(300, 0, &[track(0, 40, None), track(1, 0, None)]) == vec![40, 260]);
+    assert!(distribute(10, 0, &[track(1, 20, None), track(1, 20, None)]) == vec![20, 20]);
+
+    // Box
+    let g = solve_box((200, 100), (10, 10, 10, 10), 10, Orientation::Horizontal, &[track(1, 0, None), track(1, 0, None)]);
+    assert!(g == vec![((10, 10), (85, 80)), ((105, 10), (85, 80))]);
+
+    let g = solve_box((200, 100), (0, 0, 0, 0), 0, Orientation::Vertical, &[track(0, 30, None), track(1, 0, None)]);
+    assert!(g == vec![((0, 0), (200, 30)), ((0, 30), (200, 70))]);
+
+    // Grid
+    let cols = [track(1, 0, None), track(1, 0, None)];
+    let rows = [track(0, 20, None), track(1, 0, None)];
+    let cells = [GridCell{col: 0, row: 0, col_span: 2, row_span: 1}, GridCell{col: 1, row: 1, col_span: 1, row_span: 1}];
+    let g = solve_grid((210, 120), (0, 0, 0, 0), 10, &cols, &rows, &cells);
+    assert!(g == vec![((0, 0), (210, 20)), ((110, 30), (100, 90))]);
+
+    // Anchor
+    let items = [
+        AnchorItem::Dock{dock: Dock::Top, size: 20},
+        AnchorItem::Dock{dock: Dock::Left, size: 50},
+        AnchorItem::Dock{dock: Dock::Fill, size: 0},
+        AnchorItem::Anchor{position: (10, 10), size: (50, 50), anchor: ANCHOR_RIGHT|ANCHOR_BOTTOM},
+        AnchorItem::Anchor{position: (10, 10), size: (50, 50), anchor: ANCHOR_ALL},
+    ];
+    let g = solve_anchor((100, 100), (200, 150), &items);
+    assert!(g[0] == ((0, 0), (200, 20)));
+    assert!(g[1] == ((0, 20), (50, 130)));
+    assert!(g[2] == ((50, 20), (150, 130)));
+    assert!(g[3] == ((110, 60), (50, 50)));
+    assert!(g[4] == ((10, 10), (150, 100)));
+}
+
+#[test]
+fn test_layouts() {
+    let (ui, backend) = setup_mock_ui();
+
+    let btn_t = ButtonT{text: "TEST", position:(0, 0), size: (10, 10), visible: true, disabled: false, parent: 1000, font: None};
+    let mut window_t = window();
+    window_t.size = (200, 100);
+
+    ui.pack_control(&1000, window_t);
+    ui.pack_control(&1001, btn_t.clone());
+    ui.pack_control(&1002, btn_t.clone());
+    ui.pack_resource(&2000, BoxLayoutT{
+        parent: 1000, orientation: Orientation::Horizontal, margin: (0, 0, 0, 0), spacing: 0,
+        children: vec![(1001, track(1, 0, None)), (1002, track(1, 0, None))]
+    });
+    ui.commit().expect("Commit was not successful");
+
+    {
+        let (b1, b2) = nwg_get!(ui; [(1001, Button), (1002, Button)]);
+        assert!(b1.get_position() == (0, 0) && b1.get_size() == (100, 100));
+        assert!(b2.get_position() == (100, 0) && b2.get_size() == (100, 100));
+    }
+
+    // Children follow the parent size
+    nwg_get!(ui; (1000, Window)).set_size(400, 50);
+    ui.trigger(&1000, Event::Resized, EventArgs::None);
+    ui.commit().expect("Commit was not successful");
+    assert!(nwg_get!(ui; (1002, Button)).get_position() == (200, 0));
+    assert!(nwg_get!(ui; (1002, Button)).get_size() == (200, 50));
+
+    // The layout callback is identified by the layout ID, so it does not collide with the callbacks of the parent
+    let mut resized = false;
+    let r = &mut resized as *mut bool;
+    ui.bind(&1000, &1000, Event::Resized, move |_, _, _, _| { unsafe{ *r = true; } });
+    ui.pack_resource(&2001, AnchorLayoutT{ parent: 1000, children: vec![] });
+    ui.commit().expect("Commit was not successful");
+
+    nwg_get!(ui; (1000, Window)).set_size(200, 100);
+    ui.trigger(&1000, Event::Resized, EventArgs::None);
+    ui.commit().expect("Commit was not successful");
+    assert!(resized, "The parent callback was not called");
+    assert!(nwg_get!(ui; (1002, Button)).get_position() == (100, 0));
+
+    // Once unpacked, the layout do not move the children anymore
+    ui.unpack(&2000);
+    nwg_get!(ui; (1000, Window)).set_size(400, 50);
+    ui.trigger(&1000, Event::Resized, EventArgs::None);
+    ui.commit().expect("Commit was not successful");
+    assert!(nwg_get!(ui; (1002, Button)).get_position() == (100, 0));
+
+    // Cells outside of the grid
+    ui.pack_resource(&2002, GridLayoutT{ parent: 1001, margin: (0, 0, 0, 0), spacing: 0, columns: vec![track(1, 0, None)], rows: vec![],
+        children: vec![(1002, GridCell{col: 0, row: 0, col_span: 1, row_span: 1})] });
+    match ui.commit() { Err(Error::BadResource(_)) => {}, r => panic!("Should have returned Error::BadResource, got {:?}", r) }
+
+    assert!(backend.windows().len() == 4);
+}